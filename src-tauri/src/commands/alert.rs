@@ -0,0 +1,58 @@
+use crate::models::alert::{AlertFiring, AlertRule, AlertRuleRequest, AlertTestResult};
+use crate::services::global;
+
+fn alert_manager() -> &'static crate::services::server::log_alerts::AlertManager {
+    global::alert_manager()
+}
+
+#[tauri::command]
+pub fn list_alert_rules(server_id: Option<String>) -> Result<Vec<AlertRule>, String> {
+    alert_manager().list_rules(server_id.as_deref())
+}
+
+#[tauri::command]
+pub fn create_alert_rule(rule: AlertRuleRequest) -> Result<AlertRule, String> {
+    alert_manager().create_rule(rule)
+}
+
+#[tauri::command]
+pub fn update_alert_rule(id: String, rule: AlertRuleRequest) -> Result<AlertRule, String> {
+    alert_manager().update_rule(&id, rule)
+}
+
+#[tauri::command]
+pub fn set_alert_rule_enabled(id: String, enabled: bool) -> Result<AlertRule, String> {
+    alert_manager().set_rule_enabled(&id, enabled)
+}
+
+#[tauri::command]
+pub fn delete_alert_rule(id: String) -> Result<(), String> {
+    alert_manager().delete_rule(&id)
+}
+
+/// 用日志数据库中的历史日志试运行规则，`since` 为毫秒时间戳
+#[tauri::command]
+pub async fn test_alert_rule(
+    rule: AlertRuleRequest,
+    since: Option<i64>,
+) -> Result<AlertTestResult, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::services::server::log_alerts::test_rule(&rule, since)
+    })
+    .await
+    .map_err(|e| format!("试运行告警规则任务失败: {}", e))?
+}
+
+#[tauri::command]
+pub fn list_alert_firings(
+    server_id: Option<String>,
+    rule_id: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<AlertFiring>, String> {
+    alert_manager().list_firings(server_id.as_deref(), rule_id.as_deref(), limit)
+}
+
+#[tauri::command]
+pub fn clear_server_unhealthy(server_id: String) {
+    alert_manager().clear_unhealthy(&server_id);
+}
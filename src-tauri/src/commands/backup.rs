@@ -0,0 +1,110 @@
+use crate::models::backup::{
+    BackupEntry, BackupKind, BackupOptions, BackupRetention, SnapshotOptions, SnapshotSummary,
+    SnapshotVerifyReport, StoreGcReport,
+};
+use crate::services::global;
+
+fn backup_manager() -> &'static crate::services::server::backup::BackupManager {
+    global::backup_manager()
+}
+
+#[tauri::command]
+pub fn list_backups(server_id: String) -> Result<Vec<BackupEntry>, String> {
+    backup_manager().list_backups(&server_id)
+}
+
+#[tauri::command]
+pub async fn create_backup(
+    server_id: String,
+    options: Option<BackupOptions>,
+) -> Result<BackupEntry, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        backup_manager().create_backup(&server_id, options.unwrap_or_default(), BackupKind::Manual)
+    })
+    .await
+    .map_err(|e| format!("创建备份任务失败: {}", e))?
+}
+
+#[tauri::command]
+pub async fn restore_backup(
+    server_id: String,
+    backup_id: String,
+) -> Result<Option<BackupEntry>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        backup_manager().restore_backup(&server_id, &backup_id)
+    })
+    .await
+    .map_err(|e| format!("还原备份任务失败: {}", e))?
+}
+
+#[tauri::command]
+pub fn delete_backup(server_id: String, backup_id: String) -> Result<(), String> {
+    backup_manager().delete_backup(&server_id, &backup_id)
+}
+
+#[tauri::command]
+pub fn get_backup_retention(server_id: String) -> Result<BackupRetention, String> {
+    backup_manager().get_retention(&server_id)
+}
+
+#[tauri::command]
+pub fn update_backup_retention(
+    server_id: String,
+    retention: BackupRetention,
+) -> Result<Vec<BackupEntry>, String> {
+    backup_manager().update_retention(&server_id, retention)
+}
+
+#[tauri::command]
+pub fn list_snapshots(server_id: String) -> Result<Vec<SnapshotSummary>, String> {
+    backup_manager().list_snapshots(&server_id)
+}
+
+#[tauri::command]
+pub async fn create_snapshot(
+    server_id: String,
+    options: Option<SnapshotOptions>,
+) -> Result<SnapshotSummary, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        backup_manager().create_snapshot(&server_id, options.unwrap_or_default())
+    })
+    .await
+    .map_err(|e| format!("创建快照任务失败: {}", e))?
+}
+
+#[tauri::command]
+pub fn delete_snapshot(server_id: String, snapshot_id: String) -> Result<(), String> {
+    backup_manager().delete_snapshot(&server_id, &snapshot_id)
+}
+
+#[tauri::command]
+pub async fn verify_backup(
+    server_id: String,
+    snapshot_id: String,
+) -> Result<SnapshotVerifyReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        backup_manager().verify_snapshot(&server_id, &snapshot_id)
+    })
+    .await
+    .map_err(|e| format!("校验快照任务失败: {}", e))?
+}
+
+#[tauri::command]
+pub async fn gc_backup_store(server_id: String) -> Result<StoreGcReport, String> {
+    tauri::async_runtime::spawn_blocking(move || backup_manager().gc_store(&server_id))
+        .await
+        .map_err(|e| format!("回收备份对象任务失败: {}", e))?
+}
+
+#[tauri::command]
+pub async fn restore_snapshot(
+    server_id: String,
+    snapshot_id: String,
+    target_dir: String,
+) -> Result<SnapshotSummary, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        backup_manager().restore_snapshot(&server_id, &snapshot_id, &target_dir)
+    })
+    .await
+    .map_err(|e| format!("还原快照任务失败: {}", e))?
+}
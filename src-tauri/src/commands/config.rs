@@ -1,7 +1,13 @@
-use crate::models::config::ServerProperties;
+use crate::models::config::{
+    ConfigComparison, ConfigPreset, ConfigRevision, ConfigRevisionDiff, ConfigSyncResult,
+    PropertyIssue, PropertyIssueSeverity, PropertyWriteResult, ServerProperties,
+};
 use crate::services::config_parser;
+use crate::services::server::config_history::{self, ConfigOrigin};
+use crate::services::server::config_sync;
+use crate::services::server::properties_schema::validate_properties;
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 fn validate_config_path(path: &str) -> Result<(), String> {
     let path = Path::new(path);
@@ -35,22 +41,79 @@ fn validate_path_within_server(server_path: &str, file_path: &str) -> Result<(),
     Ok(())
 }
 
+/// 读取 .properties / YAML / TOML 配置，嵌套键以点号路径表示
 #[tauri::command]
 pub fn read_config(server_path: String, path: String) -> Result<HashMap<String, String>, String> {
     validate_config_path(&path)?;
     validate_path_within_server(&server_path, &path)?;
-    config_parser::read_properties(&path)
+    config_parser::read_config_file(&path)
 }
 
+/// 写入通用配置；目标为服务器的 server.properties 时与 `write_server_properties` 一样先校验
 #[tauri::command]
 pub fn write_config(
     server_path: String,
     path: String,
     values: HashMap<String, String>,
-) -> Result<(), String> {
+) -> Result<PropertyWriteResult, String> {
+    write_config_with_origin(server_path, path, values, ConfigOrigin::Ui)
+}
+
+pub fn write_config_with_origin(
+    server_path: String,
+    path: String,
+    values: HashMap<String, String>,
+    origin: ConfigOrigin,
+) -> Result<PropertyWriteResult, String> {
+    validate_config_path(&path)?;
+    validate_path_within_server(&server_path, &path)?;
+    if is_server_properties(&server_path, &path) {
+        return write_server_properties_with_origin(server_path, values, origin);
+    }
+    config_parser::write_config_file(&path, &values, &origin)?;
+    Ok(PropertyWriteResult { written: true, issues: Vec::new() })
+}
+
+/// 路径是否指向服务器根目录下的 server.properties
+fn is_server_properties(server_path: &str, path: &str) -> bool {
+    let path = Path::new(path);
+    let is_properties = path.file_name().is_some_and(|name| {
+        name.to_string_lossy()
+            .eq_ignore_ascii_case("server.properties")
+    });
+    is_properties
+        && path
+            .parent()
+            .and_then(|parent| std::fs::canonicalize(parent).ok())
+            .zip(std::fs::canonicalize(server_path).ok())
+            .is_some_and(|(parent, server)| parent == server)
+}
+
+#[tauri::command]
+pub fn preview_config_write(
+    server_path: String,
+    path: String,
+    values: HashMap<String, String>,
+) -> Result<String, String> {
     validate_config_path(&path)?;
     validate_path_within_server(&server_path, &path)?;
-    config_parser::write_properties(&path, &values)
+    config_parser::preview_config_write(&path, &values)
+}
+
+/// 按目录查找服务器的游戏版本，用于 server.properties 的版本相关校验
+fn server_mc_version(server_path: &str) -> Option<String> {
+    let target = std::fs::canonicalize(server_path).ok()?;
+    crate::services::global::server_manager()
+        .get_server_list()
+        .into_iter()
+        .find(|s| std::fs::canonicalize(&s.path).is_ok_and(|path| path == target))
+        .map(|s| s.mc_version)
+}
+
+fn has_errors(issues: &[PropertyIssue]) -> bool {
+    issues
+        .iter()
+        .any(|issue| issue.severity == PropertyIssueSeverity::Error)
 }
 
 #[tauri::command]
@@ -58,18 +121,33 @@ pub fn read_server_properties(server_path: String) -> Result<ServerProperties, S
     validate_config_path(&server_path)?;
     let props_path = format!("{}/server.properties", server_path);
     validate_path_within_server(&server_path, &props_path)?;
-    config_parser::parse_server_properties(&props_path)
+    config_parser::parse_server_properties(&props_path, server_mc_version(&server_path).as_deref())
 }
 
+/// 校验后写入 server.properties；存在错误时不写入，警告随结果返回
 #[tauri::command]
 pub fn write_server_properties(
     server_path: String,
     values: HashMap<String, String>,
-) -> Result<(), String> {
+) -> Result<PropertyWriteResult, String> {
+    write_server_properties_with_origin(server_path, values, ConfigOrigin::Ui)
+}
+
+pub fn write_server_properties_with_origin(
+    server_path: String,
+    values: HashMap<String, String>,
+    origin: ConfigOrigin,
+) -> Result<PropertyWriteResult, String> {
     validate_config_path(&server_path)?;
     let props_path = format!("{}/server.properties", server_path);
     validate_path_within_server(&server_path, &props_path)?;
-    config_parser::write_properties(&props_path, &values)
+
+    let issues = validate_properties(&values, server_mc_version(&server_path).as_deref());
+    let written = !has_errors(&issues);
+    if written {
+        config_parser::write_properties(&props_path, &values, &origin)?;
+    }
+    Ok(PropertyWriteResult { written, issues })
 }
 
 #[tauri::command]
@@ -80,17 +158,44 @@ pub fn read_server_properties_source(server_path: String) -> Result<String, Stri
     config_parser::read_raw_text(&props_path)
 }
 
+/// 校验整份文本后写入 server.properties；存在错误时不写入
 #[tauri::command]
-pub fn write_server_properties_source(server_path: String, source: String) -> Result<(), String> {
+pub fn write_server_properties_source(
+    server_path: String,
+    source: String,
+) -> Result<PropertyWriteResult, String> {
+    write_server_properties_source_with_origin(server_path, source, ConfigOrigin::Ui)
+}
+
+pub fn write_server_properties_source_with_origin(
+    server_path: String,
+    source: String,
+    origin: ConfigOrigin,
+) -> Result<PropertyWriteResult, String> {
     validate_config_path(&server_path)?;
     let props_path = format!("{}/server.properties", server_path);
     validate_path_within_server(&server_path, &props_path)?;
-    config_parser::write_raw_text(&props_path, &source)
+
+    let issues = config_parser::parse_server_properties_from_source(
+        &source,
+        server_mc_version(&server_path).as_deref(),
+    )?
+    .issues;
+    let written = !has_errors(&issues);
+    if written {
+        config_parser::write_raw_text(&props_path, &source, &origin)?;
+    }
+    Ok(PropertyWriteResult { written, issues })
 }
 
+/// 解析 server.properties 文本；给出 `server_path` 时按该服务器的版本校验
 #[tauri::command]
-pub fn parse_server_properties_source(source: String) -> Result<ServerProperties, String> {
-    config_parser::parse_server_properties_from_source(&source)
+pub fn parse_server_properties_source(
+    source: String,
+    server_path: Option<String>,
+) -> Result<ServerProperties, String> {
+    let mc_version = server_path.as_deref().and_then(server_mc_version);
+    config_parser::parse_server_properties_from_source(&source, mc_version.as_deref())
 }
 
 #[tauri::command]
@@ -111,3 +216,107 @@ pub fn preview_server_properties_write_from_source(
 ) -> Result<String, String> {
     config_parser::preview_properties_write_from_source(&source, &values)
 }
+
+fn server_dir(server_id: &str) -> Result<PathBuf, String> {
+    crate::services::global::server_manager()
+        .get_server_list()
+        .into_iter()
+        .find(|s| s.id == server_id)
+        .map(|s| PathBuf::from(s.path))
+        .ok_or_else(|| format!("未找到服务器: {}", server_id))
+}
+
+/// 列出配置修改历史；`file` 为相对服务器目录的路径，留空时列出全部文件
+#[tauri::command]
+pub fn list_config_history(
+    server_id: String,
+    file: Option<String>,
+) -> Result<Vec<ConfigRevision>, String> {
+    config_history::list_history(&server_id, file.as_deref())
+}
+
+#[tauri::command]
+pub fn diff_config_revision(
+    server_id: String,
+    revision_id: String,
+) -> Result<ConfigRevisionDiff, String> {
+    config_history::diff_revision(&server_id, &server_dir(&server_id)?, &revision_id)
+}
+
+#[tauri::command]
+pub fn revert_config_revision(
+    server_id: String,
+    revision_id: String,
+) -> Result<ConfigRevision, String> {
+    revert_config_revision_with_origin(server_id, revision_id, ConfigOrigin::Ui)
+}
+
+pub fn revert_config_revision_with_origin(
+    server_id: String,
+    revision_id: String,
+    origin: ConfigOrigin,
+) -> Result<ConfigRevision, String> {
+    config_history::revert_revision(&server_id, &server_dir(&server_id)?, &revision_id, &origin)
+}
+
+/// 对比多台服务器的配置；`sections` 为配置文件相对路径或 `@instance`，留空时比较
+/// server.properties 与实例设置
+#[tauri::command]
+pub fn compare_server_configs(
+    server_ids: Vec<String>,
+    sections: Option<Vec<String>>,
+) -> Result<ConfigComparison, String> {
+    config_sync::compare_configs(&server_ids, &sections.unwrap_or_default())
+}
+
+/// 把源服务器某个配置文件（或实例设置）中选定的键同步到其他服务器
+#[tauri::command]
+pub fn sync_server_config(
+    source_id: String,
+    target_ids: Vec<String>,
+    section: String,
+    keys: Vec<String>,
+) -> Result<Vec<ConfigSyncResult>, String> {
+    sync_server_config_with_origin(source_id, target_ids, section, keys, ConfigOrigin::Ui)
+}
+
+pub fn sync_server_config_with_origin(
+    source_id: String,
+    target_ids: Vec<String>,
+    section: String,
+    keys: Vec<String>,
+    origin: ConfigOrigin,
+) -> Result<Vec<ConfigSyncResult>, String> {
+    config_sync::sync_config_keys(&source_id, &target_ids, &section, &keys, &origin)
+}
+
+#[tauri::command]
+pub fn list_config_presets() -> Result<Vec<ConfigPreset>, String> {
+    Ok(config_sync::list_presets())
+}
+
+#[tauri::command]
+pub fn save_config_preset(preset: ConfigPreset) -> Result<ConfigPreset, String> {
+    config_sync::save_preset(preset)
+}
+
+#[tauri::command]
+pub fn delete_config_preset(name: String) -> Result<(), String> {
+    config_sync::delete_preset(&name)
+}
+
+#[tauri::command]
+pub fn apply_config_preset(
+    name: String,
+    server_ids: Vec<String>,
+) -> Result<Vec<ConfigSyncResult>, String> {
+    apply_config_preset_with_origin(name, server_ids, ConfigOrigin::Ui)
+}
+
+pub fn apply_config_preset_with_origin(
+    name: String,
+    server_ids: Vec<String>,
+    origin: ConfigOrigin,
+) -> Result<Vec<ConfigSyncResult>, String> {
+    config_sync::apply_preset(&name, &server_ids, &origin)
+}
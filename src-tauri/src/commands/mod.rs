@@ -2,6 +2,8 @@
 #[cfg(debug_assertions)]
 pub mod debug;
 
+pub mod alert;
+pub mod backup;
 pub mod config;
 pub mod downloader;
 pub mod java;
@@ -9,11 +11,13 @@ pub mod logging;
 pub mod mcs_plugin;
 pub mod player;
 pub mod plugin;
+pub mod scheduler;
 pub mod server;
 pub mod settings;
 pub mod system;
 pub mod tunnel;
 pub mod update;
+pub mod world;
 
 // 更新功能子模块
 mod update_arch;
@@ -1,6 +1,14 @@
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+
+use crate::models::world::{ItemRemoval, ItemRemovalResult, PlayerData, PlayerDataSummary};
 use crate::services::global;
 use crate::services::player_manager;
-use crate::services::player_manager::{BanEntry, OpEntry, PlayerEntry};
+use crate::services::player_manager::{
+    BanEntry, BannedIpEntry, OpEntry, PlayerEntry, UuidResolver,
+};
+use crate::services::server::player_sessions::{self, OnlinePlayer, PlayerSession};
+use crate::services::world::playerdata;
 
 fn manager() -> &'static crate::services::server_manager::ServerManager {
     global::server_manager()
@@ -33,85 +41,195 @@ pub fn get_ops(server_path: String) -> Result<Vec<OpEntry>, String> {
     player_manager::read_ops(&server_path)
 }
 
-// ---- Modify via server console commands ----
+// ---- Online players & session history (parsed from server logs) ----
 
 #[tauri::command]
-pub fn add_to_whitelist(server_id: String, name: String) -> Result<String, String> {
-    validate_player_name(&name)?;
-    let cmd = format!("whitelist add {}", name);
-    manager().send_command(&server_id, &cmd)?;
-    // Force save whitelist to file and reload
-    let _ = manager().send_command(&server_id, "whitelist reload");
-    Ok(format!("Sent: {}", cmd))
+pub fn get_online_players(server_id: String) -> Vec<OnlinePlayer> {
+    player_sessions::get_online_players(&server_id)
 }
 
 #[tauri::command]
-pub fn remove_from_whitelist(server_id: String, name: String) -> Result<String, String> {
-    validate_player_name(&name)?;
-    // First, try to remove via command
-    let cmd = format!("whitelist remove {}", name);
-    let _ = manager().send_command(&server_id, &cmd);
-
-    // Also manually remove from file to ensure it's gone
-    let servers = manager().get_server_list();
-    if let Some(server) = servers.iter().find(|s| s.id == server_id) {
-        let whitelist_path = std::path::Path::new(&server.path).join("whitelist.json");
-        if whitelist_path.exists() {
-            if let Ok(content) = std::fs::read_to_string(&whitelist_path) {
-                if let Ok(mut list) =
-                    serde_json::from_str::<Vec<player_manager::PlayerEntry>>(&content)
-                {
-                    // Remove player from list (case-insensitive comparison)
-                    list.retain(|p| !p.name.eq_ignore_ascii_case(&name));
-                    // Write back to file
-                    if let Ok(json) = serde_json::to_string_pretty(&list) {
-                        let _ = std::fs::write(&whitelist_path, json);
-                    }
-                }
+pub fn get_player_sessions(
+    server_id: String,
+    player: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<PlayerSession>, String> {
+    let limit = limit.unwrap_or(200).min(1000);
+    player_sessions::get_player_sessions(&server_id, player.as_deref(), limit)
+}
+
+// ---- Player data files (world/playerdata/<uuid>.dat) ----
+
+#[tauri::command]
+pub fn list_player_data(server_id: String) -> Result<Vec<PlayerDataSummary>, String> {
+    playerdata::list_player_data(&server_dir(&server_id)?)
+}
+
+#[tauri::command]
+pub fn get_player_data(server_id: String, uuid: String) -> Result<PlayerData, String> {
+    playerdata::get_player_data(&server_dir(&server_id)?, &uuid)
+}
+
+#[tauri::command]
+pub async fn remove_player_items(
+    server_id: String,
+    uuid: String,
+    removals: Vec<ItemRemoval>,
+) -> Result<ItemRemovalResult, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let dir = server_dir(&server_id)?;
+        // 运行中的服务端会在玩家下线或自动保存时覆盖玩家文件
+        if manager().is_server_online(&server_id) {
+            return Err("服务器正在运行，请先停止服务器再修改玩家数据".to_string());
+        }
+        playerdata::remove_items(&server_id, &dir, &uuid, &removals)
+    })
+    .await
+    .map_err(|e| format!("删除物品任务失败: {}", e))?
+}
+
+// ---- Modify lists: console commands while running, JSON files while stopped ----
+
+/// 服务器运行中时下发控制台命令（非 SeaLantern 启动的走 RCON），
+/// 已停止时调用 `edit` 直接修改服务器目录下的名单文件。
+///
+/// 端口探测、RCON 与 Mojang 查询都会阻塞，放到阻塞线程池中执行。
+async fn run_or_edit(
+    server_id: String,
+    commands: Vec<String>,
+    edit: impl FnOnce(&Path) -> Result<String, String> + Send + 'static,
+) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        if manager().is_server_online(&server_id) {
+            for cmd in &commands {
+                manager().send_command(&server_id, cmd)?;
             }
+            return Ok(format!("Sent: {}", commands[0]));
         }
-    }
 
-    // Reload whitelist
-    let _ = manager().send_command(&server_id, "whitelist reload");
+        edit(&server_dir(&server_id)?)
+    })
+    .await
+    .map_err(|e| format!("修改名单任务失败: {}", e))?
+}
+
+fn server_dir(server_id: &str) -> Result<PathBuf, String> {
+    manager()
+        .get_server_list()
+        .into_iter()
+        .find(|s| s.id == server_id)
+        .map(|s| PathBuf::from(s.path))
+        .ok_or_else(|| format!("未找到服务器: {}", server_id))
+}
+
+fn validate_ip(ip: &str) -> Result<(), String> {
+    ip.parse::<IpAddr>()
+        .map(|_| ())
+        .map_err(|_| format!("Invalid IP address: {}", ip))
+}
+
+#[tauri::command]
+pub async fn add_to_whitelist(server_id: String, name: String) -> Result<String, String> {
+    validate_player_name(&name)?;
+    let commands = vec![format!("whitelist add {}", name), "whitelist reload".to_string()];
+    run_or_edit(server_id, commands, move |dir| {
+        let player = player_manager::resolver_for_server(dir).resolve(&name)?;
+        if player_manager::add_whitelist_entry(dir, &player)? {
+            Ok(format!("Added to whitelist.json: {}", player.name))
+        } else {
+            Ok(format!("Already whitelisted: {}", player.name))
+        }
+    })
+    .await
+}
 
-    Ok(format!("Removed: {}", name))
+#[tauri::command]
+pub async fn remove_from_whitelist(server_id: String, name: String) -> Result<String, String> {
+    validate_player_name(&name)?;
+    let commands = vec![format!("whitelist remove {}", name), "whitelist reload".to_string()];
+    run_or_edit(server_id, commands, move |dir| {
+        player_manager::remove_whitelist_entry(dir, &name)?;
+        Ok(format!("Removed: {}", name))
+    })
+    .await
 }
 
 #[tauri::command]
-pub fn ban_player(server_id: String, name: String, reason: String) -> Result<String, String> {
+pub async fn ban_player(server_id: String, name: String, reason: String) -> Result<String, String> {
     validate_player_name(&name)?;
     let cmd = if reason.is_empty() {
         format!("ban {}", name)
     } else {
         format!("ban {} {}", name, reason)
     };
-    manager().send_command(&server_id, &cmd)?;
-    Ok(format!("Sent: {}", cmd))
+    run_or_edit(server_id, vec![cmd], move |dir| {
+        let player = player_manager::resolver_for_server(dir).resolve(&name)?;
+        player_manager::add_ban_entry(dir, &player, &reason)?;
+        Ok(format!("Added to banned-players.json: {}", player.name))
+    })
+    .await
 }
 
 #[tauri::command]
-pub fn unban_player(server_id: String, name: String) -> Result<String, String> {
+pub async fn unban_player(server_id: String, name: String) -> Result<String, String> {
     validate_player_name(&name)?;
-    let cmd = format!("pardon {}", name);
-    manager().send_command(&server_id, &cmd)?;
-    Ok(format!("Sent: {}", cmd))
+    run_or_edit(server_id, vec![format!("pardon {}", name)], move |dir| {
+        player_manager::remove_ban_entry(dir, &name)?;
+        Ok(format!("Removed: {}", name))
+    })
+    .await
+}
+
+#[tauri::command]
+pub fn get_banned_ips(server_path: String) -> Result<Vec<BannedIpEntry>, String> {
+    player_manager::read_banned_ips(&server_path)
 }
 
 #[tauri::command]
-pub fn add_op(server_id: String, name: String) -> Result<String, String> {
+pub async fn ban_ip(server_id: String, ip: String, reason: String) -> Result<String, String> {
+    validate_ip(&ip)?;
+    let cmd = if reason.is_empty() {
+        format!("ban-ip {}", ip)
+    } else {
+        format!("ban-ip {} {}", ip, reason)
+    };
+    run_or_edit(server_id, vec![cmd], move |dir| {
+        player_manager::add_ip_ban_entry(dir, &ip, &reason)?;
+        Ok(format!("Added to banned-ips.json: {}", ip))
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn unban_ip(server_id: String, ip: String) -> Result<String, String> {
+    validate_ip(&ip)?;
+    run_or_edit(server_id, vec![format!("pardon-ip {}", ip)], move |dir| {
+        player_manager::remove_ip_ban_entry(dir, &ip)?;
+        Ok(format!("Removed: {}", ip))
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn add_op(server_id: String, name: String) -> Result<String, String> {
     validate_player_name(&name)?;
-    let cmd = format!("op {}", name);
-    manager().send_command(&server_id, &cmd)?;
-    Ok(format!("Sent: {}", cmd))
+    run_or_edit(server_id, vec![format!("op {}", name)], move |dir| {
+        let player = player_manager::resolver_for_server(dir).resolve(&name)?;
+        let level = player_manager::default_op_level(dir);
+        player_manager::add_op_entry(dir, &player, level, false)?;
+        Ok(format!("Added to ops.json: {}", player.name))
+    })
+    .await
 }
 
 #[tauri::command]
-pub fn remove_op(server_id: String, name: String) -> Result<String, String> {
+pub async fn remove_op(server_id: String, name: String) -> Result<String, String> {
     validate_player_name(&name)?;
-    let cmd = format!("deop {}", name);
-    manager().send_command(&server_id, &cmd)?;
-    Ok(format!("Sent: {}", cmd))
+    run_or_edit(server_id, vec![format!("deop {}", name)], move |dir| {
+        player_manager::remove_op_entry(dir, &name)?;
+        Ok(format!("Removed: {}", name))
+    })
+    .await
 }
 
 #[tauri::command]
@@ -0,0 +1,33 @@
+use crate::models::scheduler::{JobRunResult, ScheduledJob, ScheduledJobRequest};
+use crate::services::global;
+
+fn scheduler() -> &'static crate::services::server::scheduler::Scheduler {
+    global::scheduler()
+}
+
+#[tauri::command]
+pub fn list_scheduled_jobs(server_id: Option<String>) -> Result<Vec<ScheduledJob>, String> {
+    scheduler().list_jobs(server_id.as_deref())
+}
+
+#[tauri::command]
+pub fn create_scheduled_job(job: ScheduledJobRequest) -> Result<ScheduledJob, String> {
+    scheduler().create_job(job)
+}
+
+#[tauri::command]
+pub fn update_scheduled_job(id: String, job: ScheduledJobRequest) -> Result<ScheduledJob, String> {
+    scheduler().update_job(&id, job)
+}
+
+#[tauri::command]
+pub fn delete_scheduled_job(id: String) -> Result<(), String> {
+    scheduler().delete_job(&id)
+}
+
+#[tauri::command]
+pub async fn run_scheduled_job(id: String) -> Result<JobRunResult, String> {
+    tauri::async_runtime::spawn_blocking(move || scheduler().run_job_now(&id))
+        .await
+        .map_err(|e| format!("执行定时任务失败: {}", e))?
+}
@@ -1,3 +1,4 @@
+use crate::models::crash::CrashReport;
 use crate::models::server::*;
 use crate::services::global;
 use serde::Serialize;
@@ -570,12 +571,22 @@ pub fn start_server(app: tauri::AppHandle, id: String) -> Result<(), String> {
 }
 
 #[tauri::command]
-pub fn stop_server(id: String) -> Result<(), String> {
-    manager().request_stop_server(&id)
+pub fn stop_server(id: String, countdown: Option<StopCountdown>) -> Result<(), String> {
+    manager().request_stop_server(&id, countdown)
 }
 
+/// 在后台停止所有运行中的服务器，传入 `countdown` 时各服务器同时开始倒计时
 #[tauri::command]
-pub fn send_command(id: String, command: String) -> Result<(), String> {
+pub fn stop_all_servers(countdown: Option<StopCountdown>) -> Result<(), String> {
+    for id in manager().get_running_server_ids() {
+        manager().request_stop_server(&id, countdown.clone())?;
+    }
+    Ok(())
+}
+
+/// 发送控制台命令；通过 RCON 执行时返回命令输出
+#[tauri::command]
+pub fn send_command(id: String, command: String) -> Result<Option<String>, String> {
     manager().send_command(&id, &command)
 }
 
@@ -618,9 +629,45 @@ pub fn delete_server(id: String) -> Result<(), String> {
     manager().delete_server(&id)
 }
 
+/// 读取日志行 ID 大于 `since` 的日志，`max_lines` 限制为其中最新的若干行；
+/// 返回的 `next_since` 作为下一次读取的 `since`
+#[tauri::command]
+pub fn get_server_logs(
+    id: String,
+    since: usize,
+    max_lines: Option<usize>,
+) -> crate::services::server_log_pipeline::LogPage {
+    crate::services::server_log_pipeline::get_log_page(&id, since, max_lines)
+}
+
+/// 按关键词 / 正则、时间范围、来源与级别检索日志，结果按时间倒序分页
+#[tauri::command]
+pub async fn search_server_logs(
+    id: String,
+    query: crate::services::server::log_search::LogSearchQuery,
+) -> Result<crate::services::server::log_search::LogSearchPage, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::services::server::log_search::search_logs(&id, &query)
+    })
+    .await
+    .map_err(|e| format!("检索日志任务失败: {}", e))?
+}
+
 #[tauri::command]
-pub fn get_server_logs(id: String, since: usize, max_lines: Option<usize>) -> Vec<String> {
-    crate::services::server_log_pipeline::get_logs(&id, since, max_lines)
+pub async fn get_log_storage_status(
+    id: String,
+) -> Result<crate::services::server::log_retention::LogStorageStatus, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::services::server::log_retention::get_log_storage_status(&id)
+    })
+    .await
+    .map_err(|e| format!("读取日志存储状态任务失败: {}", e))?
+}
+
+/// 服务器异常退出后的崩溃诊断记录，最新的在前
+#[tauri::command]
+pub fn get_crash_reports(id: String) -> Result<Vec<CrashReport>, String> {
+    crate::services::server::crash_analyzer::get_crash_reports(&id)
 }
 
 #[tauri::command]
@@ -628,6 +675,11 @@ pub fn update_server_name(id: String, name: String) -> Result<(), String> {
     manager().update_server_name(&id, &name)
 }
 
+#[tauri::command]
+pub fn update_restart_policy(id: String, policy: RestartPolicy) -> Result<ServerInstance, String> {
+    manager().update_restart_policy(&id, policy)
+}
+
 #[tauri::command]
 pub fn validate_server_path(new_path: String) -> Result<ValidateServerPathResult, String> {
     let path = std::path::Path::new(&new_path);
@@ -1,5 +1,7 @@
 use tauri::{command, AppHandle};
 
+use crate::models::server::StopCountdown;
+
 #[cfg(target_os = "linux")]
 #[allow(unused_imports)]
 use crate::commands::update_arch;
@@ -162,8 +164,12 @@ pub async fn download_update(
 /// 安装更新
 #[command]
 #[allow(dead_code)]
-pub async fn install_update(file_path: String, version: String) -> Result<(), String> {
-    update_install::execute_install(file_path, version).await
+pub async fn install_update(
+    file_path: String,
+    version: String,
+    stop_countdown: Option<StopCountdown>,
+) -> Result<(), String> {
+    update_install::execute_install(file_path, version, stop_countdown).await
 }
 
 /// 检查待更新状态
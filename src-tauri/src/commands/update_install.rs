@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 
+use crate::models::server::StopCountdown;
 use crate::services;
 
 #[cfg(target_os = "linux")]
@@ -27,7 +28,11 @@ pub fn get_pending_update_file() -> PathBuf {
 
 /// 执行更新安装
 #[allow(dead_code)]
-pub async fn execute_install(file_path: String, version: String) -> Result<(), String> {
+pub async fn execute_install(
+    file_path: String,
+    version: String,
+    stop_countdown: Option<StopCountdown>,
+) -> Result<(), String> {
     if INSTALL_IN_PROGRESS.swap(true, Ordering::SeqCst) {
         return Err("Install is already in progress".to_string());
     }
@@ -54,10 +59,11 @@ pub async fn execute_install(file_path: String, version: String) -> Result<(), S
             }
         }
 
-        // 根据设置决定是否在更新前关闭所有服务器
+        // 根据设置决定是否在更新前关闭所有服务器，传入倒计时则先提醒在线玩家
         let settings = services::global::settings_manager().get();
         if settings.close_servers_on_update {
-            services::global::server_manager().stop_all_servers();
+            services::global::server_manager()
+                .stop_all_servers_with_countdown(stop_countdown.as_ref());
         }
 
         let pending_file = get_pending_update_file();
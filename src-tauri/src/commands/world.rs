@@ -0,0 +1,263 @@
+use std::path::PathBuf;
+
+use crate::models::server::ServerInstance;
+use crate::models::world::{
+    DatapackInfo, LayoutConversion, LevelInfo, LevelUpdate, LevelUpdateResult, MapRenderReport,
+    PruneCriteria, PruneReport, WorldEntry, WorldLayout,
+};
+use crate::services::global;
+use crate::services::world::{datapacks, layout, level, map, prune, worlds};
+
+fn manager() -> &'static crate::services::server_manager::ServerManager {
+    global::server_manager()
+}
+
+/// 世界目录在服务器运行时被服务端占用，所有改动都要求服务器已停止。
+fn stopped_server(server_id: &str) -> Result<ServerInstance, String> {
+    let server = find_server(server_id)?;
+    if manager().is_server_online(server_id) {
+        return Err("服务器正在运行，请先停止服务器再管理世界".to_string());
+    }
+    Ok(server)
+}
+
+fn stopped_server_dir(server_id: &str) -> Result<PathBuf, String> {
+    stopped_server(server_id).map(|s| PathBuf::from(s.path))
+}
+
+fn find_server(server_id: &str) -> Result<ServerInstance, String> {
+    manager()
+        .get_server_list()
+        .into_iter()
+        .find(|s| s.id == server_id)
+        .ok_or_else(|| format!("未找到服务器: {}", server_id))
+}
+
+fn server_dir(server_id: &str) -> Result<PathBuf, String> {
+    find_server(server_id).map(|s| PathBuf::from(s.path))
+}
+
+#[tauri::command]
+pub fn get_level_info(server_id: String) -> Result<LevelInfo, String> {
+    level::read_level_info(&server_dir(&server_id)?)
+}
+
+#[tauri::command]
+pub async fn update_level_info(
+    server_id: String,
+    update: LevelUpdate,
+) -> Result<LevelUpdateResult, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let dir = server_dir(&server_id)?;
+        // 运行中的服务端保存世界时会覆盖 level.dat
+        if manager().is_server_online(&server_id) {
+            return Err("服务器正在运行，请先停止服务器再修改 level.dat".to_string());
+        }
+        level::update_level(&server_id, &dir, &update)
+    })
+    .await
+    .map_err(|e| format!("修改 level.dat 任务失败: {}", e))?
+}
+
+#[tauri::command]
+pub fn list_worlds(server_id: String) -> Result<Vec<WorldEntry>, String> {
+    worlds::list_worlds(&server_dir(&server_id)?)
+}
+
+#[tauri::command]
+pub async fn upload_world(
+    server_id: String,
+    archive_path: String,
+    name: Option<String>,
+    activate: bool,
+) -> Result<WorldEntry, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let dir = stopped_server_dir(&server_id)?;
+        let mut entry =
+            worlds::upload_world(&dir, std::path::Path::new(&archive_path), name.as_deref())?;
+        if activate && !entry.active {
+            worlds::switch_world(&dir, &entry.name)?;
+            entry.active = true;
+        }
+        Ok(entry)
+    })
+    .await
+    .map_err(|e| format!("上传世界任务失败: {}", e))?
+}
+
+/// 导入单人存档，按服务器核心类型转换维度布局（无法判断核心时保持原版布局）。
+#[tauri::command]
+pub async fn import_singleplayer_world(
+    server_id: String,
+    save_path: String,
+    name: Option<String>,
+    activate: bool,
+) -> Result<WorldEntry, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let server = stopped_server(&server_id)?;
+        let dir = PathBuf::from(&server.path);
+        let target_layout =
+            layout::layout_for_core_type(&server.core_type).unwrap_or(WorldLayout::Vanilla);
+        let mut entry = worlds::import_singleplayer_world(
+            &dir,
+            std::path::Path::new(&save_path),
+            name.as_deref(),
+            target_layout,
+        )?;
+        if activate && !entry.active {
+            worlds::switch_world(&dir, &entry.name)?;
+            entry.active = true;
+        }
+        Ok(entry)
+    })
+    .await
+    .map_err(|e| format!("导入存档任务失败: {}", e))?
+}
+
+#[tauri::command]
+pub async fn convert_world_layout(
+    server_id: String,
+    name: String,
+    layout: WorldLayout,
+) -> Result<LayoutConversion, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let dir = stopped_server_dir(&server_id)?;
+        layout::convert_layout(&dir, &name, layout)
+    })
+    .await
+    .map_err(|e| format!("转换世界布局任务失败: {}", e))?
+}
+
+#[tauri::command]
+pub async fn reset_world(
+    server_id: String,
+    name: String,
+    seed: Option<String>,
+) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let dir = stopped_server_dir(&server_id)?;
+        worlds::reset_world(&dir, &name, seed.as_deref())
+    })
+    .await
+    .map_err(|e| format!("重置世界任务失败: {}", e))?
+}
+
+#[tauri::command]
+pub async fn switch_world(server_id: String, name: String) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let dir = stopped_server_dir(&server_id)?;
+        worlds::switch_world(&dir, &name)
+    })
+    .await
+    .map_err(|e| format!("切换世界任务失败: {}", e))?
+}
+
+/// 按条件删除主世界（`level-name`）中的区块；`dry_run` 为 true 时只生成报告。
+#[tauri::command]
+pub async fn prune_world_chunks(
+    server_id: String,
+    criteria: PruneCriteria,
+    dry_run: bool,
+) -> Result<PruneReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let dir = stopped_server_dir(&server_id)?;
+        prune::prune_world(&dir, &criteria, dry_run)
+    })
+    .await
+    .map_err(|e| format!("删除区块任务失败: {}", e))?
+}
+
+/// 增量渲染地图瓦片；渲染只读取世界文件，服务器运行时也可以执行。
+#[tauri::command]
+pub async fn render_world_map(
+    server_id: String,
+    dimension: String,
+    force: bool,
+) -> Result<MapRenderReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let dir = server_dir(&server_id)?;
+        map::render_map(&dir, &map::tiles_dir(&server_id, &dimension)?, &dimension, force)
+    })
+    .await
+    .map_err(|e| format!("渲染地图任务失败: {}", e))?
+}
+
+#[tauri::command]
+pub fn list_map_tiles(server_id: String, dimension: String) -> Result<Vec<[i32; 2]>, String> {
+    Ok(map::list_tiles(&map::tiles_dir(&server_id, &dimension)?))
+}
+
+/// 以 `data:image/png;base64,...` 形式返回瓦片
+#[tauri::command]
+pub fn get_map_tile(
+    server_id: String,
+    dimension: String,
+    x: i32,
+    z: i32,
+) -> Result<String, String> {
+    use base64::Engine;
+
+    let path = map::tiles_dir(&server_id, &dimension)?.join(map::tile_file_name(x, z));
+    let content = std::fs::read(&path).map_err(|e| format!("读取瓦片失败: {}", e))?;
+    Ok(format!(
+        "data:image/png;base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(content)
+    ))
+}
+
+#[tauri::command]
+pub fn list_datapacks(server_id: String) -> Result<Vec<DatapackInfo>, String> {
+    let server = find_server(&server_id)?;
+    datapacks::list_datapacks(std::path::Path::new(&server.path), &server.mc_version)
+}
+
+/// 安装数据包；服务器运行时新数据包需执行 `/reload` 或重启后才会被加载。
+#[tauri::command]
+pub async fn install_datapack(
+    server_id: String,
+    source_path: String,
+) -> Result<DatapackInfo, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let server = find_server(&server_id)?;
+        datapacks::install_datapack(
+            std::path::Path::new(&server.path),
+            std::path::Path::new(&source_path),
+            &server.mc_version,
+        )
+    })
+    .await
+    .map_err(|e| format!("安装数据包任务失败: {}", e))?
+}
+
+/// 服务器运行时下发 `/datapack enable|disable`，否则直接修改 level.dat。
+#[tauri::command]
+pub async fn set_datapack_enabled(
+    server_id: String,
+    name: String,
+    enabled: bool,
+) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let dir = server_dir(&server_id)?;
+        if manager().is_server_online(&server_id) {
+            let command = datapacks::datapack_command(&name, enabled);
+            return manager().send_command(&server_id, &command).map(|_| ());
+        }
+        datapacks::set_datapack_enabled(&server_id, &dir, &name, enabled).map(|_| ())
+    })
+    .await
+    .map_err(|e| format!("修改数据包状态任务失败: {}", e))?
+}
+
+/// 删除数据包；服务器运行时先禁用，避免服务端继续使用已删除的数据包。
+#[tauri::command]
+pub async fn remove_datapack(server_id: String, name: String) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let dir = server_dir(&server_id)?;
+        if manager().is_server_online(&server_id) {
+            manager().send_command(&server_id, &datapacks::datapack_command(&name, false))?;
+        }
+        datapacks::remove_datapack(&dir, &name)
+    })
+    .await
+    .map_err(|e| format!("删除数据包任务失败: {}", e))?
+}
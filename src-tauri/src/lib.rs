@@ -8,6 +8,8 @@ mod utils;
 #[cfg(debug_assertions)]
 use commands::debug as debug_commands;
 
+use commands::alert as alert_commands;
+use commands::backup as backup_commands;
 use commands::config as config_commands;
 use commands::downloader as download_commands;
 use commands::java as java_commands;
@@ -15,11 +17,13 @@ use commands::logging as logging_commands;
 use commands::mcs_plugin as mcs_plugin_commands;
 use commands::player as player_commands;
 use commands::plugin as plugin_commands;
+use commands::scheduler as scheduler_commands;
 use commands::server as server_commands;
 use commands::settings as settings_commands;
 use commands::system as system_commands;
 use commands::tunnel as tunnel_commands;
 use commands::update as update_commands;
+use commands::world as world_commands;
 
 use crate::services::download_manager::DownloadManager;
 use plugins::manager::PluginManager;
@@ -61,6 +65,7 @@ pub fn run() {
                 .exists()
                 .then_some(static_dir);
 
+            services::global::scheduler().start();
             services::http::run_http_server("0.0.0.0:3000", static_dir_opt).await;
         });
         return;
@@ -138,6 +143,7 @@ pub fn run() {
             server_commands::copy_directory_contents,
             server_commands::start_server,
             server_commands::stop_server,
+            server_commands::stop_all_servers,
             server_commands::prepare_force_stop_server,
             server_commands::force_stop_server,
             server_commands::send_command,
@@ -145,7 +151,11 @@ pub fn run() {
             server_commands::get_server_status,
             server_commands::delete_server,
             server_commands::get_server_logs,
+            server_commands::search_server_logs,
+            server_commands::get_log_storage_status,
+            server_commands::get_crash_reports,
             server_commands::update_server_name,
+            server_commands::update_restart_policy,
             server_commands::validate_server_path,
             server_commands::update_server_path,
             java_commands::detect_java,
@@ -154,6 +164,16 @@ pub fn run() {
             java_commands::cancel_java_install,
             config_commands::read_config,
             config_commands::write_config,
+            config_commands::preview_config_write,
+            config_commands::list_config_history,
+            config_commands::diff_config_revision,
+            config_commands::revert_config_revision,
+            config_commands::compare_server_configs,
+            config_commands::sync_server_config,
+            config_commands::list_config_presets,
+            config_commands::save_config_preset,
+            config_commands::delete_config_preset,
+            config_commands::apply_config_preset,
             config_commands::read_server_properties,
             config_commands::write_server_properties,
             config_commands::read_server_properties_source,
@@ -176,17 +196,66 @@ pub fn run() {
             system_commands::get_default_run_path,
             system_commands::get_safe_mode_status,
             system_commands::frontend_heartbeat,
+            scheduler_commands::list_scheduled_jobs,
+            scheduler_commands::create_scheduled_job,
+            scheduler_commands::update_scheduled_job,
+            scheduler_commands::delete_scheduled_job,
+            scheduler_commands::run_scheduled_job,
+            alert_commands::list_alert_rules,
+            alert_commands::create_alert_rule,
+            alert_commands::update_alert_rule,
+            alert_commands::set_alert_rule_enabled,
+            alert_commands::delete_alert_rule,
+            alert_commands::test_alert_rule,
+            alert_commands::list_alert_firings,
+            alert_commands::clear_server_unhealthy,
+            backup_commands::list_backups,
+            backup_commands::create_backup,
+            backup_commands::restore_backup,
+            backup_commands::delete_backup,
+            backup_commands::get_backup_retention,
+            backup_commands::update_backup_retention,
+            backup_commands::list_snapshots,
+            backup_commands::create_snapshot,
+            backup_commands::delete_snapshot,
+            backup_commands::verify_backup,
+            backup_commands::gc_backup_store,
+            backup_commands::restore_snapshot,
             player_commands::get_whitelist,
             player_commands::get_banned_players,
             player_commands::get_ops,
+            player_commands::get_online_players,
+            player_commands::get_player_sessions,
+            player_commands::list_player_data,
+            player_commands::get_player_data,
+            player_commands::remove_player_items,
             player_commands::add_to_whitelist,
             player_commands::remove_from_whitelist,
             player_commands::ban_player,
             player_commands::unban_player,
+            player_commands::get_banned_ips,
+            player_commands::ban_ip,
+            player_commands::unban_ip,
             player_commands::add_op,
             player_commands::remove_op,
             player_commands::kick_player,
             player_commands::export_logs,
+            world_commands::get_level_info,
+            world_commands::update_level_info,
+            world_commands::list_worlds,
+            world_commands::upload_world,
+            world_commands::import_singleplayer_world,
+            world_commands::convert_world_layout,
+            world_commands::reset_world,
+            world_commands::switch_world,
+            world_commands::prune_world_chunks,
+            world_commands::render_world_map,
+            world_commands::list_map_tiles,
+            world_commands::get_map_tile,
+            world_commands::list_datapacks,
+            world_commands::install_datapack,
+            world_commands::set_datapack_enabled,
+            world_commands::remove_datapack,
             settings_commands::get_settings,
             settings_commands::save_settings,
             settings_commands::save_settings_with_diff,
@@ -578,6 +647,8 @@ pub fn run() {
                 },
             ));
 
+            services::global::scheduler().start();
+
             {
                 plugins::api::set_server_ready_handler(Arc::new(move |server_id| {
                     let shared_runtimes = &shared_runtimes_for_server_ready;
@@ -631,6 +702,23 @@ pub fn run() {
                 ));
             }
 
+            {
+                use tauri_plugin_notification::NotificationExt;
+
+                let app_handle = app.handle().clone();
+                let _ = services::server::log_alerts::set_alert_notifier(Arc::new(
+                    move |title, body| {
+                        app_handle
+                            .notification()
+                            .builder()
+                            .title(title)
+                            .body(body)
+                            .show()
+                            .map_err(|e| format!("发送桌面通知失败: {}", e))
+                    },
+                ));
+            }
+
             app.manage(manager.clone());
 
             // 前端心跳看门狗：若长时间未收到心跳则自动退出进程
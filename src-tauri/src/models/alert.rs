@@ -0,0 +1,152 @@
+use serde::{Deserialize, Serialize};
+
+/// 告警规则的匹配条件，多个条件同时满足才算命中
+///
+/// - `pattern`：正则表达式，匹配原始日志行
+/// - `levels`：结构化解析出的日志级别，如 `ERROR`、`WARN`
+/// - `logger`：结构化解析出的 logger 名称（不区分大小写）
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct AlertCondition {
+    #[serde(default)]
+    pub pattern: Option<String>,
+    #[serde(default)]
+    pub levels: Vec<String>,
+    #[serde(default)]
+    pub logger: Option<String>,
+}
+
+/// 触发阈值：`window_secs` 秒内命中 `count` 次才触发
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AlertThreshold {
+    #[serde(default = "default_threshold_count")]
+    pub count: u32,
+    #[serde(default = "default_threshold_window")]
+    pub window_secs: u64,
+}
+
+impl Default for AlertThreshold {
+    fn default() -> Self {
+        Self {
+            count: default_threshold_count(),
+            window_secs: default_threshold_window(),
+        }
+    }
+}
+
+/// 告警触发时执行的动作
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AlertAction {
+    /// 桌面通知
+    Notify,
+    /// 以 JSON POST 到外部地址
+    Webhook { url: String },
+    /// 向服务器发送控制台命令
+    Command { command: String },
+    /// 把服务器标记为不健康，直到下次启动或手动清除
+    MarkUnhealthy,
+}
+
+impl AlertAction {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AlertAction::Notify => "notify",
+            AlertAction::Webhook { .. } => "webhook",
+            AlertAction::Command { .. } => "command",
+            AlertAction::MarkUnhealthy => "mark_unhealthy",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub id: String,
+    pub server_id: String,
+    pub name: String,
+    #[serde(default = "default_rule_enabled")]
+    pub enabled: bool,
+    pub condition: AlertCondition,
+    #[serde(default)]
+    pub threshold: AlertThreshold,
+    /// 两次触发之间的最短间隔（秒），至少为 30
+    #[serde(default = "default_cooldown_secs")]
+    pub cooldown_secs: u64,
+    pub actions: Vec<AlertAction>,
+    pub created_at: u64,
+    #[serde(default)]
+    pub last_fired_at: Option<u64>,
+}
+
+/// 创建 / 更新 / 试运行告警规则时提交的字段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertRuleRequest {
+    pub server_id: String,
+    pub name: String,
+    #[serde(default = "default_rule_enabled")]
+    pub enabled: bool,
+    pub condition: AlertCondition,
+    #[serde(default)]
+    pub threshold: AlertThreshold,
+    #[serde(default = "default_cooldown_secs")]
+    pub cooldown_secs: u64,
+    #[serde(default)]
+    pub actions: Vec<AlertAction>,
+}
+
+/// 单个动作的执行结果
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AlertActionResult {
+    pub action: String,
+    pub success: bool,
+    pub message: String,
+}
+
+/// 一次告警触发记录，时间为毫秒时间戳
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertFiring {
+    pub id: String,
+    pub rule_id: String,
+    pub rule_name: String,
+    pub server_id: String,
+    pub fired_at: i64,
+    /// 窗口内的命中次数
+    pub match_count: usize,
+    /// 触发时的那一行日志
+    pub line: String,
+    pub results: Vec<AlertActionResult>,
+}
+
+/// 试运行中的一次触发，`line_id` 对应日志数据库中的行
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AlertTestFiring {
+    pub fired_at: i64,
+    pub match_count: usize,
+    pub line_id: i64,
+    pub line: String,
+}
+
+/// 用历史日志试运行规则的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertTestResult {
+    pub scanned_lines: usize,
+    pub matched_lines: usize,
+    pub firings: Vec<AlertTestFiring>,
+    /// 触发次数超过返回上限时为 true
+    pub truncated: bool,
+}
+
+fn default_threshold_count() -> u32 {
+    1
+}
+
+fn default_threshold_window() -> u64 {
+    60
+}
+
+fn default_cooldown_secs() -> u64 {
+    60
+}
+
+fn default_rule_enabled() -> bool {
+    true
+}
@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupFormat {
+    #[default]
+    Zip,
+    TarGz,
+}
+
+impl BackupFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            BackupFormat::Zip => "zip",
+            BackupFormat::TarGz => "tar.gz",
+        }
+    }
+}
+
+/// 备份来源：手动创建、定时任务创建、或还原前自动生成的安全快照
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupKind {
+    Manual,
+    Scheduled,
+    Safety,
+}
+
+/// 创建备份时的选项
+///
+/// - `full = false`：只备份世界目录（`level-name` 及其 `_nether` / `_the_end`）
+/// - `full = true`：备份整个服务器目录，`include` 为空表示包含全部文件
+/// - `exclude` 在两种模式下都会生效；glob 以服务器目录为根，`/` 分隔，支持 `*`、`**`、`?`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct BackupOptions {
+    #[serde(default)]
+    pub format: BackupFormat,
+    #[serde(default)]
+    pub full: bool,
+    #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BackupEntry {
+    pub id: String,
+    pub server_id: String,
+    pub file_name: String,
+    pub format: BackupFormat,
+    pub kind: BackupKind,
+    pub full: bool,
+    /// 归档中包含的顶层路径（相对服务器目录），还原时会先清理这些路径
+    pub roots: Vec<String>,
+    pub size_bytes: u64,
+    pub file_count: u64,
+    pub created_at: u64,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+/// 备份保留策略，三项均为 0 时保留全部备份
+///
+/// - `keep_last`：保留最近 N 个
+/// - `keep_daily`：最近 N 天中每天保留最新的一个
+/// - `keep_weekly`：最近 N 周中每周保留最新的一个
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct BackupRetention {
+    #[serde(default)]
+    pub keep_last: u32,
+    #[serde(default)]
+    pub keep_daily: u32,
+    #[serde(default)]
+    pub keep_weekly: u32,
+}
+
+/// 去重快照的创建选项，含义与 [`BackupOptions`] 相同（快照不区分归档格式）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct SnapshotOptions {
+    #[serde(default)]
+    pub full: bool,
+    #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SnapshotSummary {
+    pub id: String,
+    pub server_id: String,
+    pub created_at: u64,
+    pub full: bool,
+    pub roots: Vec<String>,
+    pub file_count: u64,
+    /// 快照引用的全部文件大小
+    pub total_bytes: u64,
+    /// 本次新写入对象库的字节数（未变化的文件不计入）
+    pub new_bytes: u64,
+    pub new_objects: u64,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SnapshotVerifyReport {
+    pub snapshot_id: String,
+    pub ok: bool,
+    pub checked_files: u64,
+    /// 对象文件缺失的路径
+    pub missing: Vec<String>,
+    /// 对象内容与记录的哈希不一致的路径
+    pub corrupted: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StoreGcReport {
+    pub removed_objects: u64,
+    pub freed_bytes: u64,
+    pub remaining_objects: u64,
+}
@@ -9,10 +9,111 @@ pub struct ConfigEntry {
     pub value_type: String,
     pub default_value: String,
     pub category: String,
+    /// 数值类型的取值范围
+    #[serde(default)]
+    pub min: Option<i64>,
+    #[serde(default)]
+    pub max: Option<i64>,
+    /// select 类型的可选值
+    #[serde(default)]
+    pub options: Vec<String>,
+    /// 引入 / 移除该配置项的游戏版本
+    #[serde(default)]
+    pub since: Option<String>,
+    #[serde(default)]
+    pub removed_in: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerProperties {
     pub entries: Vec<ConfigEntry>,
     pub raw: HashMap<String, String>,
+    /// 按服务器版本校验得到的问题
+    #[serde(default)]
+    pub issues: Vec<PropertyIssue>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PropertyIssueSeverity {
+    /// 值不合法，写入会被拒绝
+    Error,
+    /// 未知、已移除或当前版本尚不支持的配置项，不阻止写入
+    Warning,
+}
+
+/// server.properties 的校验问题
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PropertyIssue {
+    pub key: String,
+    pub severity: PropertyIssueSeverity,
+    pub message: String,
+}
+
+/// 配置写入结果；server.properties 存在错误时不写入，其他配置文件不做校验
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PropertyWriteResult {
+    pub written: bool,
+    pub issues: Vec<PropertyIssue>,
+}
+
+/// 配置文件写入前保存的修订版本
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigRevision {
+    pub id: String,
+    /// 相对服务器目录的路径，使用 `/` 分隔
+    pub file: String,
+    pub created_at: u64,
+    /// 写入来源：`ui`、`http`、`plugin:<插件 ID>` 或 `system`
+    pub origin: String,
+    pub size_bytes: u64,
+}
+
+/// 修订版本与当前文件内容的差异
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigRevisionDiff {
+    pub revision: ConfigRevision,
+    pub added: usize,
+    pub removed: usize,
+    /// unified diff 文本，`-` 为修订版本中的行，`+` 为当前文件中的行；无差异时为空
+    pub unified: String,
+}
+
+/// 多台服务器配置中的一个键；`values` 与 [`ConfigComparison::server_ids`] 一一对应，
+/// 服务器缺少该键（或缺少整个文件）时为空
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigComparisonEntry {
+    /// 配置文件相对路径，或表示服务器实例设置的 `@instance`
+    pub section: String,
+    pub key: String,
+    pub values: Vec<Option<String>>,
+    pub differs: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigComparison {
+    pub server_ids: Vec<String>,
+    pub sections: Vec<String>,
+    pub entries: Vec<ConfigComparisonEntry>,
+}
+
+/// 向单台服务器同步配置或应用预设的结果；`error` 不为空时该服务器未做任何修改
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigSyncResult {
+    pub server_id: String,
+    /// 写入的键数量
+    pub applied: usize,
+    pub issues: Vec<PropertyIssue>,
+    pub error: Option<String>,
+}
+
+/// 命名的配置预设：section（配置文件相对路径或 `@instance`）到键值的映射
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigPreset {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub sections: HashMap<String, HashMap<String, String>>,
+    #[serde(default)]
+    pub updated_at: u64,
 }
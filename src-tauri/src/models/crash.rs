@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+/// 崩溃的主要原因
+///
+/// - `out_of_memory`：Java 堆或本地内存耗尽
+/// - `native_crash`：JVM 本地崩溃（存在 hs_err_pid*.log）
+/// - `java_exception`：未捕获的 Java 异常
+/// - `unknown`：未找到崩溃报告、hs_err 或异常堆栈
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CrashCause {
+    OutOfMemory,
+    NativeCrash,
+    JavaException,
+    Unknown,
+}
+
+/// 疑似引起崩溃的模组 / 插件
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CrashSuspect {
+    /// 模组 / 插件名称，取自 mods.toml、fabric.mod.json 或 plugin.yml，缺省为 jar 文件名
+    pub name: String,
+    /// 相对服务器目录的 jar 路径，如 `mods/foo.jar`；仅出现在加载器报告中的模组为 None
+    pub jar: Option<String>,
+    /// 异常堆栈中属于该 jar 的帧数
+    pub frame_count: usize,
+    /// Forge / NeoForge 崩溃报告的 "Suspected Mod" 中列出了该模组
+    pub reported_by_loader: bool,
+}
+
+/// 一次异常退出的诊断结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashReport {
+    pub id: String,
+    pub server_id: String,
+    pub created_at: u64,
+    pub exit_code: Option<i32>,
+    pub cause: CrashCause,
+    /// 一句话结论，供列表与日志展示
+    pub summary: String,
+    /// 崩溃报告中的 "Description" 字段
+    pub description: Option<String>,
+    /// 异常类名，如 `java.lang.NullPointerException`
+    pub exception: Option<String>,
+    pub exception_message: Option<String>,
+    /// JVM 层面的原因，如 `Java heap space`、`SIGSEGV (0xb) at pc=...`
+    pub jvm_cause: Option<String>,
+    /// hs_err 中的 "Problematic frame"
+    pub problematic_frame: Option<String>,
+    pub suspects: Vec<CrashSuspect>,
+    /// 异常堆栈（最多 CRASH_STACK_MAX_LINES 行）
+    pub stack_trace: Vec<String>,
+    /// 相对服务器目录的崩溃报告路径
+    pub crash_report_file: Option<String>,
+    pub hs_err_file: Option<String>,
+}
@@ -1,7 +1,12 @@
+pub mod alert;
+pub mod backup;
 pub mod config;
+pub mod crash;
 pub mod mcs_plugin;
 pub mod plugin;
+pub mod scheduler;
 pub mod server;
 pub mod settings;
+pub mod world;
 
 pub mod download;
@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+
+use super::backup::BackupOptions;
+use super::server::StopCountdown;
+
+/// 定时任务的触发方式
+///
+/// - `cron`：五段式 cron 表达式（按本地时区计算）
+/// - `interval`：固定间隔（秒），从任务创建或上次执行时开始计时
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum JobSchedule {
+    Cron { expression: String },
+    Interval { seconds: u64 },
+}
+
+/// 定时任务触发时执行的动作
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum JobAction {
+    Start,
+    /// `countdown` 为空时立即停止，否则先向玩家广播倒计时
+    Stop {
+        #[serde(default)]
+        countdown: Option<StopCountdown>,
+    },
+    Restart {
+        #[serde(default)]
+        countdown: Option<StopCountdown>,
+    },
+    Command {
+        command: String,
+    },
+    Backup {
+        #[serde(default)]
+        options: BackupOptions,
+    },
+}
+
+/// 单次执行结果
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JobRunResult {
+    pub success: bool,
+    pub message: String,
+    pub started_at: u64,
+    pub finished_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledJob {
+    pub id: String,
+    pub server_id: String,
+    pub name: String,
+    #[serde(default = "default_job_enabled")]
+    pub enabled: bool,
+    pub schedule: JobSchedule,
+    pub action: JobAction,
+    pub created_at: u64,
+    #[serde(default)]
+    pub last_run_at: Option<u64>,
+    #[serde(default)]
+    pub last_result: Option<JobRunResult>,
+    /// 运行期计算得出，不依赖持久化的值
+    #[serde(default)]
+    pub next_run_at: Option<u64>,
+}
+
+/// 创建 / 更新定时任务时提交的字段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledJobRequest {
+    pub server_id: String,
+    pub name: String,
+    #[serde(default = "default_job_enabled")]
+    pub enabled: bool,
+    pub schedule: JobSchedule,
+    pub action: JobAction,
+}
+
+fn default_job_enabled() -> bool {
+    true
+}
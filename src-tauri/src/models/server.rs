@@ -25,6 +25,104 @@ impl ServerStatus {
     }
 }
 
+/// 崩溃自动重启策略（按服务器实例配置）
+///
+/// - `max_restarts` / `window_secs`：窗口期内最多自动重启次数，超出即放弃
+/// - `backoff_initial_secs` / `backoff_max_secs`：指数退避的起始与上限延迟
+/// - `give_up_after`：连续未能进入就绪状态（未输出 `Done`）的崩溃次数阈值
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RestartPolicy {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_max_restarts")]
+    pub max_restarts: u32,
+    #[serde(default = "default_restart_window_secs")]
+    pub window_secs: u64,
+    #[serde(default = "default_backoff_initial_secs")]
+    pub backoff_initial_secs: u64,
+    #[serde(default = "default_backoff_max_secs")]
+    pub backoff_max_secs: u64,
+    #[serde(default = "default_give_up_after")]
+    pub give_up_after: u32,
+}
+
+fn default_max_restarts() -> u32 {
+    5
+}
+
+fn default_restart_window_secs() -> u64 {
+    600
+}
+
+fn default_backoff_initial_secs() -> u64 {
+    5
+}
+
+fn default_backoff_max_secs() -> u64 {
+    300
+}
+
+fn default_give_up_after() -> u32 {
+    3
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        RestartPolicy {
+            enabled: false,
+            max_restarts: default_max_restarts(),
+            window_secs: default_restart_window_secs(),
+            backoff_initial_secs: default_backoff_initial_secs(),
+            backoff_max_secs: default_backoff_max_secs(),
+            give_up_after: default_give_up_after(),
+        }
+    }
+}
+
+/// 倒计时停服选项
+///
+/// - `warnings`：在剩余多少秒时向玩家广播提醒，倒计时总长取其中最大值
+/// - `message`：提醒文本，`{time}` 会替换为剩余时间；填写 `reason` 时附加在末尾
+/// - `use_tellraw`：使用 `tellraw @a` 发送黄色提醒，否则使用 `say`
+/// - `force_stop_after_secs`：发送 `stop` 后等待进程退出的期限，超时强制终止
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StopCountdown {
+    #[serde(default = "default_stop_warnings")]
+    pub warnings: Vec<u64>,
+    #[serde(default = "default_stop_message")]
+    pub message: String,
+    #[serde(default)]
+    pub use_tellraw: bool,
+    #[serde(default = "default_force_stop_after_secs")]
+    pub force_stop_after_secs: u64,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+fn default_stop_warnings() -> Vec<u64> {
+    vec![300, 60, 10]
+}
+
+fn default_stop_message() -> String {
+    "服务器将在 {time}后关闭".to_string()
+}
+
+fn default_force_stop_after_secs() -> u64 {
+    60
+}
+
+impl Default for StopCountdown {
+    fn default() -> Self {
+        StopCountdown {
+            warnings: default_stop_warnings(),
+            message: default_stop_message(),
+            use_tellraw: false,
+            force_stop_after_secs: default_force_stop_after_secs(),
+            reason: None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerInstance {
     pub id: String,
@@ -45,6 +143,8 @@ pub struct ServerInstance {
     pub port: u16,
     pub created_at: u64,
     pub last_started_at: Option<u64>,
+    #[serde(default)]
+    pub restart_policy: RestartPolicy,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -55,6 +155,36 @@ pub struct ServerStatusInfo {
     pub uptime: Option<u64>,
     #[serde(default)]
     pub error_message: Option<String>,
+    #[serde(default)]
+    pub exit_code: Option<i32>,
+    #[serde(default)]
+    pub restart_attempts: u32,
+    #[serde(default)]
+    pub next_restart_at: Option<u64>,
+    /// 最近一次 Server List Ping 的结果，服务器未应答时为空
+    #[serde(default)]
+    pub ping: Option<ServerPingInfo>,
+    /// 被告警规则标记为不健康时的原因，服务器重新启动或手动清除后为空
+    #[serde(default)]
+    pub unhealthy_reason: Option<String>,
+}
+
+/// Server List Ping 查询结果
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ServerPingInfo {
+    pub online_players: u32,
+    pub max_players: u32,
+    /// 服务端提供的部分在线玩家名（原版最多 12 个）
+    pub sample: Vec<String>,
+    pub version: String,
+    pub protocol: i32,
+    /// 已去除格式代码的 MOTD
+    pub motd: String,
+    pub latency_ms: u64,
+    /// `data:image/png;base64,...` 形式的服务器图标
+    pub favicon: Option<String>,
+    /// 是否通过旧版（1.6 及以前）协议获取
+    pub legacy: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,6 +51,18 @@ pub struct AppSettings {
     #[serde(default = "default_log_lines")]
     pub max_log_lines: u32,
 
+    /// 日志数据库保留天数，超出的日志归档为 .log.gz 后删除；0 表示不限制
+    #[serde(default = "default_log_retention_days")]
+    pub log_retention_days: u32,
+
+    /// 日志数据库最多保留的行数；0 表示不限制
+    #[serde(default = "default_log_retention_max_rows")]
+    pub log_retention_max_rows: u64,
+
+    /// 日志数据库大小上限（MB）；0 表示不限制
+    #[serde(default = "default_log_retention_max_db_mb")]
+    pub log_retention_max_db_mb: u32,
+
     #[serde(default)]
     pub cached_java_list: Vec<JavaInfo>,
 
@@ -160,6 +172,15 @@ fn default_console_letter_spacing() -> i32 {
 fn default_log_lines() -> u32 {
     5000
 }
+fn default_log_retention_days() -> u32 {
+    30
+}
+fn default_log_retention_max_rows() -> u64 {
+    1_000_000
+}
+fn default_log_retention_max_db_mb() -> u32 {
+    512
+}
 fn default_bg_opacity() -> f32 {
     0.3
 }
@@ -227,6 +248,9 @@ impl AppSettings {
             || self.console_font_family != other.console_font_family
             || self.console_letter_spacing != other.console_letter_spacing
             || self.max_log_lines != other.max_log_lines
+            || self.log_retention_days != other.log_retention_days
+            || self.log_retention_max_rows != other.log_retention_max_rows
+            || self.log_retention_max_db_mb != other.log_retention_max_db_mb
         {
             changed.push(SettingsGroup::Console);
         }
@@ -305,6 +329,15 @@ impl AppSettings {
         if let Some(v) = partial.max_log_lines {
             self.max_log_lines = v;
         }
+        if let Some(v) = partial.log_retention_days {
+            self.log_retention_days = v;
+        }
+        if let Some(v) = partial.log_retention_max_rows {
+            self.log_retention_max_rows = v;
+        }
+        if let Some(v) = partial.log_retention_max_db_mb {
+            self.log_retention_max_db_mb = v;
+        }
         if let Some(ref v) = partial.cached_java_list {
             self.cached_java_list = v.clone();
         }
@@ -407,6 +440,12 @@ pub struct PartialSettings {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_log_lines: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_retention_days: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_retention_max_rows: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_retention_max_db_mb: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub cached_java_list: Option<Vec<JavaInfo>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub background_image: Option<String>,
@@ -471,6 +510,9 @@ impl Default for AppSettings {
             console_font_family: String::new(),
             console_letter_spacing: 0,
             max_log_lines: 5000,
+            log_retention_days: default_log_retention_days(),
+            log_retention_max_rows: default_log_retention_max_rows(),
+            log_retention_max_db_mb: default_log_retention_max_db_mb(),
             cached_java_list: Vec::new(),
             background_image: String::new(),
             background_opacity: 0.3,
@@ -0,0 +1,244 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// 从 level.dat 读取的世界信息
+///
+/// 字段在不同版本中的位置不同（如种子在 1.16 前为 `RandomSeed`，之后为
+/// `WorldGenSettings.seed`），读取时已做兼容；文件中不存在的字段为 `None`。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LevelInfo {
+    /// 世界目录名（server.properties 中的 `level-name`）
+    pub world: String,
+    /// level.dat 中的 `LevelName`
+    pub level_name: Option<String>,
+    /// 种子以字符串传递，避免超出 JavaScript 安全整数范围后丢失精度
+    pub seed: Option<String>,
+    pub spawn_x: Option<i32>,
+    pub spawn_y: Option<i32>,
+    pub spawn_z: Option<i32>,
+    /// 0 和平 / 1 简单 / 2 普通 / 3 困难
+    pub difficulty: Option<u8>,
+    pub difficulty_locked: bool,
+    pub hardcore: bool,
+    /// 0 生存 / 1 创造 / 2 冒险 / 3 旁观
+    pub game_type: Option<i32>,
+    /// 游戏规则，值统一转为字符串（布尔值为 `true` / `false`）
+    pub game_rules: BTreeMap<String, String>,
+    pub enabled_datapacks: Vec<String>,
+    pub disabled_datapacks: Vec<String>,
+    /// 毫秒时间戳
+    pub last_played: Option<i64>,
+    pub version_name: Option<String>,
+    pub data_version: Option<i32>,
+}
+
+/// 修改 level.dat 的请求，为 `None` 的字段保持不变
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct LevelUpdate {
+    #[serde(default)]
+    pub level_name: Option<String>,
+    #[serde(default)]
+    pub seed: Option<String>,
+    #[serde(default)]
+    pub spawn_x: Option<i32>,
+    #[serde(default)]
+    pub spawn_y: Option<i32>,
+    #[serde(default)]
+    pub spawn_z: Option<i32>,
+    #[serde(default)]
+    pub difficulty: Option<u8>,
+    #[serde(default)]
+    pub difficulty_locked: Option<bool>,
+    #[serde(default)]
+    pub hardcore: Option<bool>,
+    #[serde(default)]
+    pub game_type: Option<i32>,
+    /// 只包含需要修改的游戏规则
+    #[serde(default)]
+    pub game_rules: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LevelUpdateResult {
+    pub info: LevelInfo,
+    /// 修改前的 level.dat 备份路径
+    pub backup_path: String,
+}
+
+/// playerdata 目录中的一个玩家
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PlayerDataSummary {
+    pub uuid: String,
+    /// 来自 usercache.json，缺失时使用 Bukkit 记录的 lastKnownName
+    pub name: Option<String>,
+    /// 最后登录时间（毫秒），仅 Paper / Bukkit 服务端会记录
+    pub last_login: Option<i64>,
+    /// 文件修改时间（秒），即服务端最后一次保存该玩家的时间
+    pub modified_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ItemStack {
+    /// 背包槽位：0-8 快捷栏，9-35 背包，100-103 护甲（鞋→头），-106 副手
+    pub slot: Option<i8>,
+    pub id: String,
+    pub count: i32,
+    /// 1.20.5 起的 `components`，或更早版本的 `tag`
+    pub data: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PlayerData {
+    pub uuid: String,
+    pub name: Option<String>,
+    pub last_login: Option<i64>,
+    pub modified_at: u64,
+    pub dimension: Option<String>,
+    pub position: Option<[f64; 3]>,
+    pub health: Option<f32>,
+    pub food_level: Option<i32>,
+    pub xp_level: Option<i32>,
+    pub xp_total: Option<i32>,
+    /// 当前等级的经验进度（0-1）
+    pub xp_progress: Option<f32>,
+    pub game_type: Option<i32>,
+    pub inventory: Vec<ItemStack>,
+    pub ender_chest: Vec<ItemStack>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemContainer {
+    Inventory,
+    EnderChest,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ItemRemoval {
+    pub container: ItemContainer,
+    pub slot: i8,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ItemRemovalResult {
+    pub data: PlayerData,
+    pub removed: usize,
+    /// 修改前的玩家文件备份路径
+    pub backup_path: String,
+}
+
+/// 服务器目录中的一个世界
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WorldEntry {
+    /// 世界目录名
+    pub name: String,
+    /// 是否为 server.properties 中 `level-name` 指向的世界
+    pub active: bool,
+    /// Bukkit 布局下拆分出的维度目录（`<name>_nether` / `<name>_the_end`）
+    pub dimension_dirs: Vec<String>,
+    /// 含维度目录在内的总大小
+    pub size_bytes: u64,
+    /// 目录内最后修改时间（秒）
+    pub last_modified: u64,
+    pub seed: Option<String>,
+    pub version_name: Option<String>,
+}
+
+/// 世界维度的目录布局
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WorldLayout {
+    /// 原版 / Fabric / Forge：下界与末地位于 `<world>/DIM-1`、`<world>/DIM1`
+    Vanilla,
+    /// Bukkit 系：下界与末地位于 `<world>_nether/DIM-1`、`<world>_the_end/DIM1`
+    Bukkit,
+}
+
+/// 一次布局转换的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayoutConversion {
+    pub world: String,
+    pub layout: WorldLayout,
+    /// 实际移动的目录，形如 `world/DIM-1 -> world_nether/DIM-1`
+    pub moved: Vec<String>,
+}
+
+/// 区块删除条件，所有已设置的条件需同时满足，且至少设置一项
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PruneCriteria {
+    /// 只删除 InhabitedTime（玩家停留的 tick 数）低于该值的区块
+    #[serde(default)]
+    pub max_inhabited_ticks: Option<i64>,
+    /// 只删除区块中心距出生点超过该半径（方块）的区块
+    #[serde(default)]
+    pub outside_radius: Option<u32>,
+    /// 只处理这些区域文件（区域坐标 `[x, z]`）
+    #[serde(default)]
+    pub regions: Vec<[i32; 2]>,
+    /// 只处理这些维度（overworld / the_nether / the_end），为空时处理全部
+    #[serde(default)]
+    pub dimensions: Vec<String>,
+}
+
+/// 单个区域文件的删除结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegionPruneEntry {
+    pub dimension: String,
+    pub region: [i32; 2],
+    pub chunks_total: usize,
+    pub chunks_deleted: usize,
+    /// 含同坐标 entities / poi 文件在内释放的字节数
+    pub bytes_reclaimed: u64,
+}
+
+/// 区块删除报告；`dry_run` 为 true 时没有修改任何文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PruneReport {
+    pub world: String,
+    pub dry_run: bool,
+    pub regions_scanned: usize,
+    pub chunks_scanned: usize,
+    pub chunks_deleted: usize,
+    /// 无法解析而被保留的区块数
+    pub chunks_unreadable: usize,
+    pub bytes_reclaimed: u64,
+    /// 只包含有区块被删除的区域文件
+    pub regions: Vec<RegionPruneEntry>,
+}
+
+/// 地图渲染结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MapRenderReport {
+    pub world: String,
+    pub dimension: String,
+    /// 本次重新渲染的瓦片数
+    pub rendered: usize,
+    /// 区域文件未变化而跳过的瓦片数
+    pub unchanged: usize,
+    /// 区域文件已不存在而删除的瓦片数
+    pub removed: usize,
+    /// 渲染失败的区域文件名，下次渲染时会重试
+    pub failed: Vec<String>,
+    /// 当前所有瓦片的区域坐标 `[x, z]`
+    pub tiles: Vec<[i32; 2]>,
+}
+
+/// `<world>/datapacks` 中的一个数据包
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatapackInfo {
+    /// 文件或目录名
+    pub name: String,
+    /// level.dat 与 `/datapack` 命令中使用的 ID，形如 `file/<name>`
+    pub id: String,
+    pub is_archive: bool,
+    pub description: Option<String>,
+    pub pack_format: Option<u32>,
+    /// pack.mcmeta 声明的兼容格式范围 `[min, max]`
+    pub supported_formats: Option<[u32; 2]>,
+    /// level.dat 中的状态；`None` 表示服务端尚未加载过该数据包（下次启动时默认启用）
+    pub enabled: Option<bool>,
+    pub size_bytes: u64,
+    /// 数据包格式与服务器版本不匹配等提示
+    pub warning: Option<String>,
+}
@@ -10,19 +10,20 @@ pub(super) fn get_logs(lua: &Lua, _ctx: &ConsoleContext) -> Result<mlua::Functio
             let _ = get_server_status_checked(&server_id)?;
             let offset = offset.unwrap_or(0);
             let count = count.unwrap_or(DEFAULT_LOG_COUNT).min(MAX_LOG_COUNT);
-            let logs =
-                crate::services::server_log_pipeline::get_logs(&server_id, offset, Some(count));
+            let logs = crate::services::server_log_pipeline::get_log_entries_after(
+                &server_id, offset, count,
+            );
 
             let result = lua.create_table()?;
             result.set("server_id", server_id.clone())?;
             result.set("offset", offset)?;
             result.set("count", count)?;
-            result.set("next_offset", offset + logs.len())?;
+            result.set("next_offset", logs.last().map_or(offset, |(id, _)| *id))?;
 
             let entries = lua.create_table()?;
-            for (i, log) in logs.iter().enumerate() {
+            for (i, (id, log)) in logs.iter().enumerate() {
                 let entry = lua.create_table()?;
-                entry.set("index", offset + i)?;
+                entry.set("index", *id)?;
                 entry.set("content", log.clone())?;
                 entries.set(i + 1, entry)?;
             }
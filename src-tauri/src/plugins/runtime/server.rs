@@ -10,6 +10,7 @@ use mlua::Table;
 mod common;
 mod files;
 mod logs;
+mod players;
 
 use common::{
     create_server_table, map_lua_err, set_server_function, set_server_table, ServerContext,
@@ -18,7 +19,7 @@ use common::{
 impl PluginRuntime {
     pub(super) fn setup_server_namespace(&self, sl: &Table) -> Result<(), String> {
         let server_table = create_server_table(&self.lua)?;
-        let ctx = ServerContext::new(self.permissions.clone());
+        let ctx = ServerContext::new(self.plugin_id.clone(), self.permissions.clone());
 
         set_server_function(
             &server_table,
@@ -57,6 +58,19 @@ impl PluginRuntime {
             "server.set_exists_failed",
         )?;
 
+        set_server_function(
+            &server_table,
+            "get_online_players",
+            players::get_online_players(&self.lua, &ctx)?,
+            "server.set_get_online_players_failed",
+        )?;
+        set_server_function(
+            &server_table,
+            "get_player_sessions",
+            players::get_player_sessions(&self.lua, &ctx)?,
+            "server.set_get_player_sessions_failed",
+        )?;
+
         logs::register(&self.lua, &server_table, &ctx)?;
         set_server_table(sl, server_table)
             .map_err(|e| map_lua_err("server.set_server_failed", mlua::Error::runtime(e)))
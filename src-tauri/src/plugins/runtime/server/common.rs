@@ -11,12 +11,13 @@ pub(super) const MAX_FILE_SIZE: u64 = 128 * 1024 * 1024;
 
 #[derive(Clone)]
 pub(super) struct ServerContext {
+    pub(super) plugin_id: String,
     pub(super) permissions: Vec<String>,
 }
 
 impl ServerContext {
-    pub(super) fn new(permissions: Vec<String>) -> Self {
-        Self { permissions }
+    pub(super) fn new(plugin_id: String, permissions: Vec<String>) -> Self {
+        Self { plugin_id, permissions }
     }
 }
 
@@ -2,8 +2,20 @@ use super::common::{
     check_server_permission, checked_file_metadata, create_server_entry, map_lua_err, with_server,
     with_server_path, ServerContext,
 };
+use crate::services::server::config_history::{self, ConfigOrigin};
 use mlua::{Function, Lua};
 use std::fs;
+use std::path::Path;
+
+/// 写入时记录配置历史的文件扩展名
+const CONFIG_EXTENSIONS: [&str; 8] =
+    ["properties", "yml", "yaml", "toml", "json", "json5", "conf", "cfg"];
+
+fn is_config_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| CONFIG_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
 
 pub(super) fn list(lua: &Lua, ctx: &ServerContext) -> Result<Function, String> {
     let ctx = ctx.clone();
@@ -60,6 +72,16 @@ pub(super) fn write_file(lua: &Lua, ctx: &ServerContext) -> Result<Function, Str
                 })?;
             }
 
+            // 配置文件改动记入配置历史，便于回退插件造成的错误修改
+            if is_config_file(&full_path) {
+                config_history::record_previous(
+                    &full_path,
+                    content.as_bytes(),
+                    &ConfigOrigin::Plugin(ctx.plugin_id.clone()),
+                )
+                .map_err(mlua::Error::runtime)?;
+            }
+
             fs::write(&full_path, &content).map_err(|e| {
                 mlua::Error::runtime(crate::services::global::i18n_service().t_with_options(
                     "server.failed_to_write_file",
@@ -2,7 +2,9 @@ use super::common::{
     check_server_permission, create_logs_table, find_server, map_lua_err, running_log_pairs,
     set_logs_function, set_logs_table, ServerContext,
 };
-use mlua::{Lua, Table};
+use crate::services::global::i18n_service;
+use crate::services::server::log_search::{self, LogSearchQuery};
+use mlua::{Lua, LuaSerdeExt, Table};
 
 pub(super) fn register(lua: &Lua, server_table: &Table, ctx: &ServerContext) -> Result<(), String> {
     let logs_table = create_logs_table(lua)?;
@@ -13,6 +15,9 @@ pub(super) fn register(lua: &Lua, server_table: &Table, ctx: &ServerContext) ->
     let get_all_logs_fn = get_all(lua, ctx)?;
     set_logs_function(&logs_table, "getAll", get_all_logs_fn, "server.set_logs_getall_failed")?;
 
+    let search_logs_fn = search(lua, ctx)?;
+    set_logs_function(&logs_table, "search", search_logs_fn, "server.set_logs_search_failed")?;
+
     set_logs_table(server_table, logs_table)
 }
 
@@ -60,3 +65,24 @@ fn get_all(lua: &Lua, ctx: &ServerContext) -> Result<mlua::Function, String> {
     })
     .map_err(|e| map_lua_err("server.create_logs_getall_failed", e))
 }
+
+fn search(lua: &Lua, ctx: &ServerContext) -> Result<mlua::Function, String> {
+    let ctx = ctx.clone();
+    lua.create_function(move |lua, (server_id, options): (String, Option<Table>)| {
+        check_server_permission(&ctx.permissions)?;
+        find_server(&server_id)?;
+
+        let query = match options {
+            Some(options) => lua.from_value::<LogSearchQuery>(mlua::Value::Table(options))?,
+            None => LogSearchQuery::default(),
+        };
+        let page = log_search::search_logs(&server_id, &query).map_err(|e| {
+            mlua::Error::runtime(i18n_service().t_with_options(
+                "server.failed_to_search_logs",
+                &crate::plugins::runtime::console::i18n_arg("0", &e),
+            ))
+        })?;
+        lua.to_value(&page)
+    })
+    .map_err(|e| map_lua_err("server.create_logs_search_failed", e))
+}
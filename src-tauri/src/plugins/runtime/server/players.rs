@@ -0,0 +1,62 @@
+use super::common::{check_server_permission, find_server, map_lua_err, ServerContext};
+use crate::services::global::i18n_service;
+use crate::services::server::player_sessions;
+use mlua::{Function, Lua};
+
+pub(super) fn get_online_players(lua: &Lua, ctx: &ServerContext) -> Result<Function, String> {
+    let ctx = ctx.clone();
+    lua.create_function(move |lua, server_id: String| {
+        check_server_permission(&ctx.permissions)?;
+        find_server(&server_id)?;
+
+        let result = lua.create_table()?;
+        for (i, player) in player_sessions::get_online_players(&server_id)
+            .into_iter()
+            .enumerate()
+        {
+            let entry = lua.create_table()?;
+            entry.set("name", player.name)?;
+            entry.set("uuid", player.uuid)?;
+            entry.set("ip", player.ip)?;
+            entry.set("joined_at", player.joined_at)?;
+            result.set(i + 1, entry)?;
+        }
+        Ok(result)
+    })
+    .map_err(|e| map_lua_err("server.create_get_online_players_failed", e))
+}
+
+pub(super) fn get_player_sessions(lua: &Lua, ctx: &ServerContext) -> Result<Function, String> {
+    let ctx = ctx.clone();
+    lua.create_function(
+        move |lua, (server_id, player, limit): (String, Option<String>, Option<u32>)| {
+            check_server_permission(&ctx.permissions)?;
+            find_server(&server_id)?;
+
+            let limit = limit.unwrap_or(100).min(1000);
+            let sessions =
+                player_sessions::get_player_sessions(&server_id, player.as_deref(), limit)
+                    .map_err(|e| {
+                        mlua::Error::runtime(i18n_service().t_with_options(
+                            "server.failed_to_read_sessions",
+                            &crate::plugins::runtime::console::i18n_arg("0", &e),
+                        ))
+                    })?;
+
+            let result = lua.create_table()?;
+            for (i, session) in sessions.into_iter().enumerate() {
+                let entry = lua.create_table()?;
+                entry.set("id", session.id)?;
+                entry.set("player", session.player)?;
+                entry.set("uuid", session.uuid)?;
+                entry.set("ip", session.ip)?;
+                entry.set("joined_at", session.joined_at)?;
+                entry.set("left_at", session.left_at)?;
+                entry.set("reason", session.reason)?;
+                result.set(i + 1, entry)?;
+            }
+            Ok(result)
+        },
+    )
+    .map_err(|e| map_lua_err("server.create_get_player_sessions_failed", e))
+}
@@ -1,4 +1,4 @@
-//! 全局单例访问入口：提供 server_manager / settings_manager / i18n_service 等静态句柄。
+//! 全局单例访问入口：提供 server_manager / settings_manager / alert_manager / i18n_service 等静态句柄。
 //!
 //! 所有函数都基于 OnceLock 懒初始化，在进程生命周期内保持 `&'static` 引用。
 //! 注意：`mod_manager()` 目前仍然使用 `expect("Failed to initialize ModManager")`
@@ -7,6 +7,9 @@ use super::i18n::I18nService;
 use super::join_manager::JoinManager;
 use super::mcs_plugin_manager::m_PluginManager;
 use super::mod_manager::ModManager;
+use super::server::backup::BackupManager;
+use super::server::log_alerts::AlertManager;
+use super::server::scheduler::Scheduler;
 use super::server_id_manager::ServerIdManager;
 use super::server_manager::ServerManager;
 use super::settings_manager::SettingsManager;
@@ -24,6 +27,21 @@ pub fn settings_manager() -> &'static SettingsManager {
     INSTANCE.get_or_init(SettingsManager::new)
 }
 
+pub fn backup_manager() -> &'static BackupManager {
+    static INSTANCE: OnceLock<BackupManager> = OnceLock::new();
+    INSTANCE.get_or_init(BackupManager::new)
+}
+
+pub fn scheduler() -> &'static Scheduler {
+    static INSTANCE: OnceLock<Scheduler> = OnceLock::new();
+    INSTANCE.get_or_init(Scheduler::new)
+}
+
+pub fn alert_manager() -> &'static AlertManager {
+    static INSTANCE: OnceLock<AlertManager> = OnceLock::new();
+    INSTANCE.get_or_init(AlertManager::new)
+}
+
 pub fn i18n_service() -> &'static I18nService {
     static INSTANCE: OnceLock<I18nService> = OnceLock::new();
     INSTANCE.get_or_init(I18nService::new)
@@ -1,12 +1,24 @@
+use crate::commands::alert as alert_commands;
+use crate::commands::backup as backup_commands;
 use crate::commands::config as config_commands;
 use crate::commands::java as java_commands;
 use crate::commands::player as player_commands;
+use crate::commands::scheduler as scheduler_commands;
 use crate::commands::server as server_commands;
 use crate::commands::settings as settings_commands;
 use crate::commands::system as system_commands;
 use crate::commands::tunnel as tunnel_commands;
 use crate::commands::update as update_commands;
+use crate::commands::world as world_commands;
+use crate::models::alert::AlertRuleRequest;
+use crate::models::backup::{BackupOptions, BackupRetention, SnapshotOptions};
+use crate::models::config::ConfigPreset;
+use crate::models::scheduler::ScheduledJobRequest;
+use crate::models::server::{RestartPolicy, StopCountdown};
 use crate::models::settings::{AppSettings, PartialSettings};
+use crate::models::world::{ItemRemoval, LevelUpdate, PruneCriteria, WorldLayout};
+use crate::services::server::config_history::ConfigOrigin;
+use crate::services::server::log_search::LogSearchQuery;
 use serde::Deserialize;
 use serde_json::Value;
 use std::collections::HashMap;
@@ -29,14 +41,81 @@ impl CommandRegistry {
         handlers.insert("import_modpack".to_string(), handle_import_modpack as CommandHandler);
         handlers.insert("start_server".to_string(), handle_start_server as CommandHandler);
         handlers.insert("stop_server".to_string(), handle_stop_server as CommandHandler);
+        handlers.insert("stop_all_servers".to_string(), handle_stop_all_servers as CommandHandler);
         handlers.insert("send_command".to_string(), handle_send_command as CommandHandler);
         handlers.insert("get_server_list".to_string(), handle_get_server_list as CommandHandler);
         handlers
             .insert("get_server_status".to_string(), handle_get_server_status as CommandHandler);
         handlers.insert("delete_server".to_string(), handle_delete_server as CommandHandler);
         handlers.insert("get_server_logs".to_string(), handle_get_server_logs as CommandHandler);
+        handlers
+            .insert("search_server_logs".to_string(), handle_search_server_logs as CommandHandler);
+        handlers.insert(
+            "get_log_storage_status".to_string(),
+            handle_get_log_storage_status as CommandHandler,
+        );
+        handlers
+            .insert("get_crash_reports".to_string(), handle_get_crash_reports as CommandHandler);
         handlers
             .insert("update_server_name".to_string(), handle_update_server_name as CommandHandler);
+        handlers.insert(
+            "update_restart_policy".to_string(),
+            handle_update_restart_policy as CommandHandler,
+        );
+        handlers.insert(
+            "list_scheduled_jobs".to_string(),
+            handle_list_scheduled_jobs as CommandHandler,
+        );
+        handlers.insert(
+            "create_scheduled_job".to_string(),
+            handle_create_scheduled_job as CommandHandler,
+        );
+        handlers.insert(
+            "update_scheduled_job".to_string(),
+            handle_update_scheduled_job as CommandHandler,
+        );
+        handlers.insert(
+            "delete_scheduled_job".to_string(),
+            handle_delete_scheduled_job as CommandHandler,
+        );
+        handlers
+            .insert("run_scheduled_job".to_string(), handle_run_scheduled_job as CommandHandler);
+        handlers.insert("list_alert_rules".to_string(), handle_list_alert_rules as CommandHandler);
+        handlers
+            .insert("create_alert_rule".to_string(), handle_create_alert_rule as CommandHandler);
+        handlers
+            .insert("update_alert_rule".to_string(), handle_update_alert_rule as CommandHandler);
+        handlers.insert(
+            "set_alert_rule_enabled".to_string(),
+            handle_set_alert_rule_enabled as CommandHandler,
+        );
+        handlers
+            .insert("delete_alert_rule".to_string(), handle_delete_alert_rule as CommandHandler);
+        handlers.insert("test_alert_rule".to_string(), handle_test_alert_rule as CommandHandler);
+        handlers
+            .insert("list_alert_firings".to_string(), handle_list_alert_firings as CommandHandler);
+        handlers.insert(
+            "clear_server_unhealthy".to_string(),
+            handle_clear_server_unhealthy as CommandHandler,
+        );
+        handlers.insert("list_backups".to_string(), handle_list_backups as CommandHandler);
+        handlers.insert("create_backup".to_string(), handle_create_backup as CommandHandler);
+        handlers.insert("restore_backup".to_string(), handle_restore_backup as CommandHandler);
+        handlers.insert("delete_backup".to_string(), handle_delete_backup as CommandHandler);
+        handlers.insert(
+            "get_backup_retention".to_string(),
+            handle_get_backup_retention as CommandHandler,
+        );
+        handlers.insert(
+            "update_backup_retention".to_string(),
+            handle_update_backup_retention as CommandHandler,
+        );
+        handlers.insert("list_snapshots".to_string(), handle_list_snapshots as CommandHandler);
+        handlers.insert("create_snapshot".to_string(), handle_create_snapshot as CommandHandler);
+        handlers.insert("delete_snapshot".to_string(), handle_delete_snapshot as CommandHandler);
+        handlers.insert("verify_backup".to_string(), handle_verify_backup as CommandHandler);
+        handlers.insert("gc_backup_store".to_string(), handle_gc_backup_store as CommandHandler);
+        handlers.insert("restore_snapshot".to_string(), handle_restore_snapshot as CommandHandler);
         handlers.insert(
             "scan_startup_candidates".to_string(),
             handle_scan_startup_candidates as CommandHandler,
@@ -71,6 +150,10 @@ impl CommandRegistry {
         // 注册 Config 命令
         handlers.insert("read_config".to_string(), handle_read_config as CommandHandler);
         handlers.insert("write_config".to_string(), handle_write_config as CommandHandler);
+        handlers.insert(
+            "preview_config_write".to_string(),
+            handle_preview_config_write as CommandHandler,
+        );
         handlers.insert(
             "read_server_properties".to_string(),
             handle_read_server_properties as CommandHandler,
@@ -99,6 +182,38 @@ impl CommandRegistry {
             "preview_server_properties_write_from_source".to_string(),
             handle_preview_server_properties_write_from_source as CommandHandler,
         );
+        handlers.insert(
+            "list_config_history".to_string(),
+            handle_list_config_history as CommandHandler,
+        );
+        handlers.insert(
+            "diff_config_revision".to_string(),
+            handle_diff_config_revision as CommandHandler,
+        );
+        handlers.insert(
+            "revert_config_revision".to_string(),
+            handle_revert_config_revision as CommandHandler,
+        );
+        handlers.insert(
+            "compare_server_configs".to_string(),
+            handle_compare_server_configs as CommandHandler,
+        );
+        handlers
+            .insert("sync_server_config".to_string(), handle_sync_server_config as CommandHandler);
+        handlers.insert(
+            "list_config_presets".to_string(),
+            handle_list_config_presets as CommandHandler,
+        );
+        handlers
+            .insert("save_config_preset".to_string(), handle_save_config_preset as CommandHandler);
+        handlers.insert(
+            "delete_config_preset".to_string(),
+            handle_delete_config_preset as CommandHandler,
+        );
+        handlers.insert(
+            "apply_config_preset".to_string(),
+            handle_apply_config_preset as CommandHandler,
+        );
 
         // 注册 System 命令
         handlers.insert("get_system_info".to_string(), handle_get_system_info as CommandHandler);
@@ -114,6 +229,18 @@ impl CommandRegistry {
         handlers
             .insert("get_banned_players".to_string(), handle_get_banned_players as CommandHandler);
         handlers.insert("get_ops".to_string(), handle_get_ops as CommandHandler);
+        handlers
+            .insert("get_online_players".to_string(), handle_get_online_players as CommandHandler);
+        handlers.insert(
+            "get_player_sessions".to_string(),
+            handle_get_player_sessions as CommandHandler,
+        );
+        handlers.insert("list_player_data".to_string(), handle_list_player_data as CommandHandler);
+        handlers.insert("get_player_data".to_string(), handle_get_player_data as CommandHandler);
+        handlers.insert(
+            "remove_player_items".to_string(),
+            handle_remove_player_items as CommandHandler,
+        );
         handlers.insert("add_to_whitelist".to_string(), handle_add_to_whitelist as CommandHandler);
         handlers.insert(
             "remove_from_whitelist".to_string(),
@@ -121,11 +248,43 @@ impl CommandRegistry {
         );
         handlers.insert("ban_player".to_string(), handle_ban_player as CommandHandler);
         handlers.insert("unban_player".to_string(), handle_unban_player as CommandHandler);
+        handlers.insert("get_banned_ips".to_string(), handle_get_banned_ips as CommandHandler);
+        handlers.insert("ban_ip".to_string(), handle_ban_ip as CommandHandler);
+        handlers.insert("unban_ip".to_string(), handle_unban_ip as CommandHandler);
         handlers.insert("add_op".to_string(), handle_add_op as CommandHandler);
         handlers.insert("remove_op".to_string(), handle_remove_op as CommandHandler);
         handlers.insert("kick_player".to_string(), handle_kick_player as CommandHandler);
         handlers.insert("export_logs".to_string(), handle_export_logs as CommandHandler);
 
+        // 注册 World 命令
+        handlers.insert("get_level_info".to_string(), handle_get_level_info as CommandHandler);
+        handlers
+            .insert("update_level_info".to_string(), handle_update_level_info as CommandHandler);
+        handlers.insert("list_worlds".to_string(), handle_list_worlds as CommandHandler);
+        handlers.insert("upload_world".to_string(), handle_upload_world as CommandHandler);
+        handlers.insert(
+            "import_singleplayer_world".to_string(),
+            handle_import_singleplayer_world as CommandHandler,
+        );
+        handlers.insert(
+            "convert_world_layout".to_string(),
+            handle_convert_world_layout as CommandHandler,
+        );
+        handlers.insert("reset_world".to_string(), handle_reset_world as CommandHandler);
+        handlers.insert("switch_world".to_string(), handle_switch_world as CommandHandler);
+        handlers
+            .insert("prune_world_chunks".to_string(), handle_prune_world_chunks as CommandHandler);
+        handlers.insert("render_world_map".to_string(), handle_render_world_map as CommandHandler);
+        handlers.insert("list_map_tiles".to_string(), handle_list_map_tiles as CommandHandler);
+        handlers.insert("get_map_tile".to_string(), handle_get_map_tile as CommandHandler);
+        handlers.insert("list_datapacks".to_string(), handle_list_datapacks as CommandHandler);
+        handlers.insert("install_datapack".to_string(), handle_install_datapack as CommandHandler);
+        handlers.insert(
+            "set_datapack_enabled".to_string(),
+            handle_set_datapack_enabled as CommandHandler,
+        );
+        handlers.insert("remove_datapack".to_string(), handle_remove_datapack as CommandHandler);
+
         // 注册 Settings 命令
         handlers.insert("get_settings".to_string(), handle_get_settings as CommandHandler);
         handlers.insert("save_settings".to_string(), handle_save_settings as CommandHandler);
@@ -306,9 +465,23 @@ fn handle_start_server(
 
 fn handle_stop_server(params: Value) -> futures::future::BoxFuture<'static, Result<Value, String>> {
     Box::pin(async move {
-        let req: ServerIdRequest =
+        let req: StopServerRequest =
             serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
-        server_commands::stop_server(req.id)?;
+        server_commands::stop_server(req.id, req.countdown)?;
+        Ok(Value::Null)
+    })
+}
+
+fn handle_stop_all_servers(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: StopAllServersRequest = if params.is_null() {
+            StopAllServersRequest::default()
+        } else {
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?
+        };
+        server_commands::stop_all_servers(req.countdown)?;
         Ok(Value::Null)
     })
 }
@@ -319,8 +492,8 @@ fn handle_send_command(
     Box::pin(async move {
         let req: SendCommandRequest =
             serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
-        server_commands::send_command(req.id, req.command)?;
-        Ok(Value::Null)
+        let result = server_commands::send_command(req.id, req.command)?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
     })
 }
 
@@ -367,361 +540,1096 @@ fn handle_get_server_logs(
     })
 }
 
-fn handle_update_server_name(
+fn handle_search_server_logs(
     params: Value,
 ) -> futures::future::BoxFuture<'static, Result<Value, String>> {
     Box::pin(async move {
-        let req: UpdateNameRequest =
+        let req: SearchLogsRequest =
             serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
-        server_commands::update_server_name(req.id, req.name)?;
-        Ok(Value::Null)
+        let result = server_commands::search_server_logs(req.id, req.query).await?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
     })
 }
 
-fn handle_scan_startup_candidates(
+fn handle_get_log_storage_status(
     params: Value,
 ) -> futures::future::BoxFuture<'static, Result<Value, String>> {
     Box::pin(async move {
-        let req: ScanStartupCandidatesRequest =
+        let req: ServerIdRequest =
             serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
-        let result = server_commands::scan_startup_candidates(req.source_path, req.source_type)
-            .await
-            .map_err(|e| format!("Failed to scan startup candidates: {}", e))?;
+        let result = server_commands::get_log_storage_status(req.id).await?;
         serde_json::to_value(result).map_err(|e| e.to_string())
     })
 }
 
-fn handle_parse_server_core_type(
+fn handle_get_crash_reports(
     params: Value,
 ) -> futures::future::BoxFuture<'static, Result<Value, String>> {
     Box::pin(async move {
-        let req: ParseServerCoreTypeRequest =
+        let req: ServerIdRequest =
             serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
-        let result = server_commands::parse_server_core_type(req.source_path)
-            .await
-            .map_err(|e| format!("Failed to parse server core type: {}", e))?;
+        let result = server_commands::get_crash_reports(req.id)?;
         serde_json::to_value(result).map_err(|e| e.to_string())
     })
 }
 
-fn handle_collect_copy_conflicts(
+fn handle_update_server_name(
     params: Value,
 ) -> futures::future::BoxFuture<'static, Result<Value, String>> {
     Box::pin(async move {
-        let req: CollectCopyConflictsRequest =
+        let req: UpdateNameRequest =
             serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
-        let result = server_commands::collect_copy_conflicts(req.source_dir, req.target_dir)?;
-        serde_json::to_value(result).map_err(|e| e.to_string())
+        server_commands::update_server_name(req.id, req.name)?;
+        Ok(Value::Null)
     })
 }
 
-fn handle_copy_directory_contents(
+fn handle_update_restart_policy(
     params: Value,
 ) -> futures::future::BoxFuture<'static, Result<Value, String>> {
     Box::pin(async move {
-        let req: CopyDirectoryContentsRequest =
+        let req: UpdateRestartPolicyRequest =
             serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
-        server_commands::copy_directory_contents(req.source_dir, req.target_dir)?;
-        Ok(Value::Null)
+        let result = server_commands::update_restart_policy(req.id, req.policy)?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
     })
 }
 
-fn handle_add_existing_server(
+fn handle_list_scheduled_jobs(
     params: Value,
 ) -> futures::future::BoxFuture<'static, Result<Value, String>> {
     Box::pin(async move {
-        let req: AddExistingServerRequest =
-            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
-        let result = server_commands::add_existing_server(
-            req.name,
-            req.server_path,
-            req.java_path,
-            req.max_memory,
-            req.min_memory,
-            req.port,
-            req.startup_mode,
-            req.executable_path,
-            req.custom_command,
-        )?;
+        let req: ListScheduledJobsRequest = if params.is_null() {
+            ListScheduledJobsRequest::default()
+        } else {
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?
+        };
+        let result = scheduler_commands::list_scheduled_jobs(req.server_id)?;
         serde_json::to_value(result).map_err(|e| e.to_string())
     })
 }
 
-// ============ Java 命令处理器 ============
-
-fn handle_detect_java(
-    _params: Value,
+fn handle_create_scheduled_job(
+    params: Value,
 ) -> futures::future::BoxFuture<'static, Result<Value, String>> {
     Box::pin(async move {
-        let result = java_commands::detect_java().await?;
+        let req: CreateScheduledJobRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = scheduler_commands::create_scheduled_job(req.job)?;
         serde_json::to_value(result).map_err(|e| e.to_string())
     })
 }
 
-fn handle_validate_java_path(
+fn handle_update_scheduled_job(
     params: Value,
 ) -> futures::future::BoxFuture<'static, Result<Value, String>> {
     Box::pin(async move {
-        let req: ValidateJavaPathRequest =
+        let req: UpdateScheduledJobRequest =
             serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
-        let result = java_commands::validate_java_path(req.path).await?;
+        let result = scheduler_commands::update_scheduled_job(req.id, req.job)?;
         serde_json::to_value(result).map_err(|e| e.to_string())
     })
 }
 
-fn handle_cancel_java_install(
-    _params: Value,
+fn handle_delete_scheduled_job(
+    params: Value,
 ) -> futures::future::BoxFuture<'static, Result<Value, String>> {
     Box::pin(async move {
-        java_commands::cancel_java_install().await?;
+        let req: ScheduledJobIdRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        scheduler_commands::delete_scheduled_job(req.id)?;
         Ok(Value::Null)
     })
 }
 
-// ============ Config 命令处理器 ============
-
-fn handle_read_config(params: Value) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+fn handle_run_scheduled_job(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
     Box::pin(async move {
-        let req: ReadConfigRequest =
+        let req: ScheduledJobIdRequest =
             serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
-        let result = config_commands::read_config(req.server_path, req.path)?;
+        let result = scheduler_commands::run_scheduled_job(req.id).await?;
         serde_json::to_value(result).map_err(|e| e.to_string())
     })
 }
 
-fn handle_write_config(
+fn handle_list_alert_rules(
     params: Value,
 ) -> futures::future::BoxFuture<'static, Result<Value, String>> {
     Box::pin(async move {
-        let req: WriteConfigRequest =
-            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
-        config_commands::write_config(req.server_path, req.path, req.values)?;
-        Ok(Value::Null)
+        let req: ListAlertRulesRequest = if params.is_null() {
+            ListAlertRulesRequest::default()
+        } else {
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?
+        };
+        let result = alert_commands::list_alert_rules(req.server_id)?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
     })
 }
 
-fn handle_read_server_properties(
+fn handle_create_alert_rule(
     params: Value,
 ) -> futures::future::BoxFuture<'static, Result<Value, String>> {
     Box::pin(async move {
-        let req: ReadServerPropertiesRequest =
+        let req: CreateAlertRuleRequest =
             serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
-        let result = config_commands::read_server_properties(req.server_path)?;
+        let result = alert_commands::create_alert_rule(req.rule)?;
         serde_json::to_value(result).map_err(|e| e.to_string())
     })
 }
 
-fn handle_write_server_properties(
+fn handle_update_alert_rule(
     params: Value,
 ) -> futures::future::BoxFuture<'static, Result<Value, String>> {
     Box::pin(async move {
-        let req: WriteServerPropertiesRequest =
+        let req: UpdateAlertRuleRequest =
             serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
-        config_commands::write_server_properties(req.server_path, req.values)?;
-        Ok(Value::Null)
+        let result = alert_commands::update_alert_rule(req.id, req.rule)?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
     })
 }
 
-fn handle_read_server_properties_source(
+fn handle_set_alert_rule_enabled(
     params: Value,
 ) -> futures::future::BoxFuture<'static, Result<Value, String>> {
     Box::pin(async move {
-        let req: ReadServerPropertiesRequest =
+        let req: SetAlertRuleEnabledRequest =
             serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
-        let result = config_commands::read_server_properties_source(req.server_path)?;
+        let result = alert_commands::set_alert_rule_enabled(req.id, req.enabled)?;
         serde_json::to_value(result).map_err(|e| e.to_string())
     })
 }
 
-fn handle_write_server_properties_source(
+fn handle_delete_alert_rule(
     params: Value,
 ) -> futures::future::BoxFuture<'static, Result<Value, String>> {
     Box::pin(async move {
-        let req: WriteServerPropertiesSourceRequest =
+        let req: AlertRuleIdRequest =
             serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
-        config_commands::write_server_properties_source(req.server_path, req.source)?;
+        alert_commands::delete_alert_rule(req.id)?;
         Ok(Value::Null)
     })
 }
 
-fn handle_parse_server_properties_source(
+fn handle_test_alert_rule(
     params: Value,
 ) -> futures::future::BoxFuture<'static, Result<Value, String>> {
     Box::pin(async move {
-        let req: ParseServerPropertiesSourceRequest =
+        let req: TestAlertRuleRequest =
             serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
-        let result = config_commands::parse_server_properties_source(req.source)?;
+        let result = alert_commands::test_alert_rule(req.rule, req.since).await?;
         serde_json::to_value(result).map_err(|e| e.to_string())
     })
 }
 
-fn handle_preview_server_properties_write(
+fn handle_list_alert_firings(
     params: Value,
 ) -> futures::future::BoxFuture<'static, Result<Value, String>> {
     Box::pin(async move {
-        let req: PreviewServerPropertiesWriteRequest =
-            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
-        let result = config_commands::preview_server_properties_write(req.server_path, req.values)?;
+        let req: ListAlertFiringsRequest = if params.is_null() {
+            ListAlertFiringsRequest::default()
+        } else {
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?
+        };
+        let result = alert_commands::list_alert_firings(req.server_id, req.rule_id, req.limit)?;
         serde_json::to_value(result).map_err(|e| e.to_string())
     })
 }
 
-fn handle_preview_server_properties_write_from_source(
+fn handle_clear_server_unhealthy(
     params: Value,
 ) -> futures::future::BoxFuture<'static, Result<Value, String>> {
     Box::pin(async move {
-        let req: PreviewServerPropertiesWriteFromSourceRequest =
+        let req: ClearServerUnhealthyRequest =
             serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
-        let result =
-            config_commands::preview_server_properties_write_from_source(req.source, req.values)?;
-        serde_json::to_value(result).map_err(|e| e.to_string())
+        alert_commands::clear_server_unhealthy(req.server_id);
+        Ok(Value::Null)
     })
 }
 
-// ============ System 命令处理器 ============
-
-fn handle_get_system_info(
-    _params: Value,
+fn handle_list_backups(
+    params: Value,
 ) -> futures::future::BoxFuture<'static, Result<Value, String>> {
     Box::pin(async move {
-        let result = system_commands::get_system_info()?;
-        Ok(result)
+        let req: BackupServerRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = backup_commands::list_backups(req.server_id)?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
     })
 }
 
-fn handle_unsupported(
-    _params: Value,
-) -> futures::future::BoxFuture<'static, Result<Value, String>> {
-    Box::pin(async move { Err("This command is not supported in HTTP/Docker mode".to_string()) })
-}
-
-// ============ Player 命令处理器 ============
-
-fn handle_get_whitelist(
+fn handle_create_backup(
     params: Value,
 ) -> futures::future::BoxFuture<'static, Result<Value, String>> {
     Box::pin(async move {
-        let req: ServerPathRequest =
+        let req: CreateBackupRequest =
             serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
-        let result = player_commands::get_whitelist(req.server_path)?;
+        let result = backup_commands::create_backup(req.server_id, req.options).await?;
         serde_json::to_value(result).map_err(|e| e.to_string())
     })
 }
 
-fn handle_get_banned_players(
+fn handle_restore_backup(
     params: Value,
 ) -> futures::future::BoxFuture<'static, Result<Value, String>> {
     Box::pin(async move {
-        let req: ServerPathRequest =
+        let req: BackupIdRequest =
             serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
-        let result = player_commands::get_banned_players(req.server_path)?;
+        let result = backup_commands::restore_backup(req.server_id, req.backup_id).await?;
         serde_json::to_value(result).map_err(|e| e.to_string())
     })
 }
 
-fn handle_get_ops(params: Value) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+fn handle_delete_backup(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
     Box::pin(async move {
-        let req: ServerPathRequest =
+        let req: BackupIdRequest =
             serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
-        let result = player_commands::get_ops(req.server_path)?;
-        serde_json::to_value(result).map_err(|e| e.to_string())
+        backup_commands::delete_backup(req.server_id, req.backup_id)?;
+        Ok(Value::Null)
     })
 }
 
-fn handle_add_to_whitelist(
+fn handle_get_backup_retention(
     params: Value,
 ) -> futures::future::BoxFuture<'static, Result<Value, String>> {
     Box::pin(async move {
-        let req: PlayerActionRequest =
+        let req: BackupServerRequest =
             serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
-        let result = player_commands::add_to_whitelist(req.server_id, req.name)?;
-        Ok(Value::String(result))
+        let result = backup_commands::get_backup_retention(req.server_id)?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
     })
 }
 
-fn handle_remove_from_whitelist(
+fn handle_update_backup_retention(
     params: Value,
 ) -> futures::future::BoxFuture<'static, Result<Value, String>> {
     Box::pin(async move {
-        let req: PlayerActionRequest =
+        let req: UpdateBackupRetentionRequest =
             serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
-        let result = player_commands::remove_from_whitelist(req.server_id, req.name)?;
-        Ok(Value::String(result))
+        let result = backup_commands::update_backup_retention(req.server_id, req.retention)?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
     })
 }
 
-fn handle_ban_player(params: Value) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+fn handle_list_snapshots(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
     Box::pin(async move {
-        let req: BanPlayerRequest =
+        let req: BackupServerRequest =
             serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
-        let result = player_commands::ban_player(req.server_id, req.name, req.reason)?;
-        Ok(Value::String(result))
+        let result = backup_commands::list_snapshots(req.server_id)?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
     })
 }
 
-fn handle_unban_player(
+fn handle_create_snapshot(
     params: Value,
 ) -> futures::future::BoxFuture<'static, Result<Value, String>> {
     Box::pin(async move {
-        let req: PlayerActionRequest =
+        let req: CreateSnapshotRequest =
             serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
-        let result = player_commands::unban_player(req.server_id, req.name)?;
-        Ok(Value::String(result))
+        let result = backup_commands::create_snapshot(req.server_id, req.options).await?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
     })
 }
 
-fn handle_add_op(params: Value) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+fn handle_delete_snapshot(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
     Box::pin(async move {
-        let req: PlayerActionRequest =
+        let req: SnapshotIdRequest =
             serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
-        let result = player_commands::add_op(req.server_id, req.name)?;
-        Ok(Value::String(result))
+        backup_commands::delete_snapshot(req.server_id, req.snapshot_id)?;
+        Ok(Value::Null)
     })
 }
 
-fn handle_remove_op(params: Value) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+fn handle_verify_backup(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
     Box::pin(async move {
-        let req: PlayerActionRequest =
+        let req: SnapshotIdRequest =
             serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
-        let result = player_commands::remove_op(req.server_id, req.name)?;
-        Ok(Value::String(result))
+        let result = backup_commands::verify_backup(req.server_id, req.snapshot_id).await?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
     })
 }
 
-fn handle_kick_player(params: Value) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+fn handle_gc_backup_store(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
     Box::pin(async move {
-        let req: KickPlayerRequest =
+        let req: BackupServerRequest =
             serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
-        let result = player_commands::kick_player(req.server_id, req.name, req.reason)?;
-        Ok(Value::String(result))
+        let result = backup_commands::gc_backup_store(req.server_id).await?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
     })
 }
 
-fn handle_export_logs(params: Value) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+fn handle_restore_snapshot(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
     Box::pin(async move {
-        let req: ExportLogsRequest =
+        let req: RestoreSnapshotRequest =
             serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
-        player_commands::export_logs(req.logs, req.save_path)?;
-        Ok(Value::Null)
+        let result =
+            backup_commands::restore_snapshot(req.server_id, req.snapshot_id, req.target_dir)
+                .await?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
     })
 }
 
-// ============ Settings 命令处理器 ============
-
-fn handle_get_settings(
-    _params: Value,
+fn handle_scan_startup_candidates(
+    params: Value,
 ) -> futures::future::BoxFuture<'static, Result<Value, String>> {
     Box::pin(async move {
-        let result = settings_commands::get_settings();
+        let req: ScanStartupCandidatesRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = server_commands::scan_startup_candidates(req.source_path, req.source_type)
+            .await
+            .map_err(|e| format!("Failed to scan startup candidates: {}", e))?;
         serde_json::to_value(result).map_err(|e| e.to_string())
     })
 }
 
-fn handle_save_settings(
+fn handle_parse_server_core_type(
     params: Value,
 ) -> futures::future::BoxFuture<'static, Result<Value, String>> {
     Box::pin(async move {
-        let settings: AppSettings =
+        let req: ParseServerCoreTypeRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = server_commands::parse_server_core_type(req.source_path)
+            .await
+            .map_err(|e| format!("Failed to parse server core type: {}", e))?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_collect_copy_conflicts(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: CollectCopyConflictsRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = server_commands::collect_copy_conflicts(req.source_dir, req.target_dir)?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_copy_directory_contents(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: CopyDirectoryContentsRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        server_commands::copy_directory_contents(req.source_dir, req.target_dir)?;
+        Ok(Value::Null)
+    })
+}
+
+fn handle_add_existing_server(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: AddExistingServerRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = server_commands::add_existing_server(
+            req.name,
+            req.server_path,
+            req.java_path,
+            req.max_memory,
+            req.min_memory,
+            req.port,
+            req.startup_mode,
+            req.executable_path,
+            req.custom_command,
+        )?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+// ============ Java 命令处理器 ============
+
+fn handle_detect_java(
+    _params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let result = java_commands::detect_java().await?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_validate_java_path(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: ValidateJavaPathRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = java_commands::validate_java_path(req.path).await?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_cancel_java_install(
+    _params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        java_commands::cancel_java_install().await?;
+        Ok(Value::Null)
+    })
+}
+
+// ============ Config 命令处理器 ============
+
+fn handle_read_config(params: Value) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: ReadConfigRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = config_commands::read_config(req.server_path, req.path)?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_write_config(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: WriteConfigRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = config_commands::write_config_with_origin(
+            req.server_path,
+            req.path,
+            req.values,
+            ConfigOrigin::Http,
+        )?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_preview_config_write(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: WriteConfigRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = config_commands::preview_config_write(req.server_path, req.path, req.values)?;
+        Ok(Value::String(result))
+    })
+}
+
+fn handle_list_config_history(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: ListConfigHistoryRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = config_commands::list_config_history(req.server_id, req.file)?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_diff_config_revision(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: ConfigRevisionRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = config_commands::diff_config_revision(req.server_id, req.revision_id)?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_revert_config_revision(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: ConfigRevisionRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = config_commands::revert_config_revision_with_origin(
+            req.server_id,
+            req.revision_id,
+            ConfigOrigin::Http,
+        )?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_compare_server_configs(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: CompareServerConfigsRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = config_commands::compare_server_configs(req.server_ids, req.sections)?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_sync_server_config(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: SyncServerConfigRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = config_commands::sync_server_config_with_origin(
+            req.source_id,
+            req.target_ids,
+            req.section,
+            req.keys,
+            ConfigOrigin::Http,
+        )?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_list_config_presets(
+    _params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let result = config_commands::list_config_presets()?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_save_config_preset(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: SaveConfigPresetRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = config_commands::save_config_preset(req.preset)?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_delete_config_preset(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: ConfigPresetNameRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        config_commands::delete_config_preset(req.name)?;
+        Ok(Value::Null)
+    })
+}
+
+fn handle_apply_config_preset(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: ApplyConfigPresetRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = config_commands::apply_config_preset_with_origin(
+            req.name,
+            req.server_ids,
+            ConfigOrigin::Http,
+        )?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_read_server_properties(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: ReadServerPropertiesRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = config_commands::read_server_properties(req.server_path)?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_write_server_properties(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: WriteServerPropertiesRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = config_commands::write_server_properties_with_origin(
+            req.server_path,
+            req.values,
+            ConfigOrigin::Http,
+        )?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_read_server_properties_source(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: ReadServerPropertiesRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = config_commands::read_server_properties_source(req.server_path)?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_write_server_properties_source(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: WriteServerPropertiesSourceRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = config_commands::write_server_properties_source_with_origin(
+            req.server_path,
+            req.source,
+            ConfigOrigin::Http,
+        )?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_parse_server_properties_source(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: ParseServerPropertiesSourceRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = config_commands::parse_server_properties_source(req.source, req.server_path)?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_preview_server_properties_write(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: PreviewServerPropertiesWriteRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = config_commands::preview_server_properties_write(req.server_path, req.values)?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_preview_server_properties_write_from_source(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: PreviewServerPropertiesWriteFromSourceRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result =
+            config_commands::preview_server_properties_write_from_source(req.source, req.values)?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+// ============ System 命令处理器 ============
+
+fn handle_get_system_info(
+    _params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let result = system_commands::get_system_info()?;
+        Ok(result)
+    })
+}
+
+fn handle_unsupported(
+    _params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move { Err("This command is not supported in HTTP/Docker mode".to_string()) })
+}
+
+// ============ Player 命令处理器 ============
+
+fn handle_get_whitelist(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: ServerPathRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = player_commands::get_whitelist(req.server_path)?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_get_banned_players(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: ServerPathRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = player_commands::get_banned_players(req.server_path)?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_get_ops(params: Value) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: ServerPathRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = player_commands::get_ops(req.server_path)?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_add_to_whitelist(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: PlayerActionRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = player_commands::add_to_whitelist(req.server_id, req.name).await?;
+        Ok(Value::String(result))
+    })
+}
+
+fn handle_remove_from_whitelist(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: PlayerActionRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = player_commands::remove_from_whitelist(req.server_id, req.name).await?;
+        Ok(Value::String(result))
+    })
+}
+
+fn handle_ban_player(params: Value) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: BanPlayerRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = player_commands::ban_player(req.server_id, req.name, req.reason).await?;
+        Ok(Value::String(result))
+    })
+}
+
+fn handle_unban_player(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: PlayerActionRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = player_commands::unban_player(req.server_id, req.name).await?;
+        Ok(Value::String(result))
+    })
+}
+
+fn handle_get_banned_ips(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: ServerPathRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = player_commands::get_banned_ips(req.server_path)?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_ban_ip(params: Value) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: BanIpRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = player_commands::ban_ip(req.server_id, req.ip, req.reason).await?;
+        Ok(Value::String(result))
+    })
+}
+
+fn handle_unban_ip(params: Value) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: UnbanIpRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = player_commands::unban_ip(req.server_id, req.ip).await?;
+        Ok(Value::String(result))
+    })
+}
+
+fn handle_add_op(params: Value) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: PlayerActionRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = player_commands::add_op(req.server_id, req.name).await?;
+        Ok(Value::String(result))
+    })
+}
+
+fn handle_remove_op(params: Value) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: PlayerActionRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = player_commands::remove_op(req.server_id, req.name).await?;
+        Ok(Value::String(result))
+    })
+}
+
+fn handle_get_online_players(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: PlayerServerRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = player_commands::get_online_players(req.server_id);
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_get_player_sessions(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: PlayerSessionsRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = player_commands::get_player_sessions(req.server_id, req.player, req.limit)?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_list_player_data(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: PlayerServerRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = player_commands::list_player_data(req.server_id)?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_get_player_data(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: PlayerDataRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = player_commands::get_player_data(req.server_id, req.uuid)?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_remove_player_items(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: RemovePlayerItemsRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result =
+            player_commands::remove_player_items(req.server_id, req.uuid, req.removals).await?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_kick_player(params: Value) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: KickPlayerRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = player_commands::kick_player(req.server_id, req.name, req.reason)?;
+        Ok(Value::String(result))
+    })
+}
+
+fn handle_export_logs(params: Value) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: ExportLogsRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        player_commands::export_logs(req.logs, req.save_path)?;
+        Ok(Value::Null)
+    })
+}
+
+// ============ World 命令处理器 ============
+
+fn handle_get_level_info(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: WorldServerRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = world_commands::get_level_info(req.server_id)?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_update_level_info(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: UpdateLevelInfoRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = world_commands::update_level_info(req.server_id, req.update).await?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_list_worlds(params: Value) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: WorldServerRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = world_commands::list_worlds(req.server_id)?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_upload_world(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: UploadWorldRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result =
+            world_commands::upload_world(req.server_id, req.archive_path, req.name, req.activate)
+                .await?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_import_singleplayer_world(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: ImportSingleplayerWorldRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = world_commands::import_singleplayer_world(
+            req.server_id,
+            req.save_path,
+            req.name,
+            req.activate,
+        )
+        .await?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_convert_world_layout(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: ConvertWorldLayoutRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result =
+            world_commands::convert_world_layout(req.server_id, req.name, req.layout).await?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_reset_world(params: Value) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: ResetWorldRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        world_commands::reset_world(req.server_id, req.name, req.seed).await?;
+        Ok(Value::Null)
+    })
+}
+
+fn handle_switch_world(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: SwitchWorldRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        world_commands::switch_world(req.server_id, req.name).await?;
+        Ok(Value::Null)
+    })
+}
+
+fn handle_prune_world_chunks(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: PruneWorldChunksRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result =
+            world_commands::prune_world_chunks(req.server_id, req.criteria, req.dry_run).await?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_render_world_map(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: RenderWorldMapRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result =
+            world_commands::render_world_map(req.server_id, req.dimension, req.force).await?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_list_map_tiles(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: MapTilesRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = world_commands::list_map_tiles(req.server_id, req.dimension)?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_get_map_tile(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: MapTileRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = world_commands::get_map_tile(req.server_id, req.dimension, req.x, req.z)?;
+        Ok(Value::String(result))
+    })
+}
+
+fn handle_list_datapacks(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: WorldServerRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = world_commands::list_datapacks(req.server_id)?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_install_datapack(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: InstallDatapackRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        let result = world_commands::install_datapack(req.server_id, req.source_path).await?;
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_set_datapack_enabled(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: SetDatapackEnabledRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        world_commands::set_datapack_enabled(req.server_id, req.name, req.enabled).await?;
+        Ok(Value::Null)
+    })
+}
+
+fn handle_remove_datapack(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let req: DatapackRequest =
+            serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
+        world_commands::remove_datapack(req.server_id, req.name).await?;
+        Ok(Value::Null)
+    })
+}
+
+// ============ Settings 命令处理器 ============
+
+fn handle_get_settings(
+    _params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let result = settings_commands::get_settings();
+        serde_json::to_value(result).map_err(|e| e.to_string())
+    })
+}
+
+fn handle_save_settings(
+    params: Value,
+) -> futures::future::BoxFuture<'static, Result<Value, String>> {
+    Box::pin(async move {
+        let settings: AppSettings =
             serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))?;
         settings_commands::save_settings(settings)?;
         Ok(Value::Null)
@@ -931,53 +1839,218 @@ struct ImportServerRequest {
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct ImportModpackRequest {
-    name: String,
-    modpack_path: String,
-    java_path: String,
-    max_memory: u32,
-    min_memory: u32,
-    port: u16,
-    startup_mode: String,
-    online_mode: bool,
-    custom_command: Option<String>,
-    run_path: String,
-    startup_file_path: Option<String>,
-    core_type: Option<String>,
-    mc_version: Option<String>,
+struct ImportModpackRequest {
+    name: String,
+    modpack_path: String,
+    java_path: String,
+    max_memory: u32,
+    min_memory: u32,
+    port: u16,
+    startup_mode: String,
+    online_mode: bool,
+    custom_command: Option<String>,
+    run_path: String,
+    startup_file_path: Option<String>,
+    core_type: Option<String>,
+    mc_version: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SendCommandRequest {
+    id: String,
+    command: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GetServerStatusRequest {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ServerIdRequest {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StopServerRequest {
+    id: String,
+    #[serde(default)]
+    countdown: Option<StopCountdown>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StopAllServersRequest {
+    #[serde(default)]
+    countdown: Option<StopCountdown>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GetLogsRequest {
+    id: String,
+    since: usize,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchLogsRequest {
+    id: String,
+    #[serde(default)]
+    query: LogSearchQuery,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UpdateNameRequest {
+    id: String,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UpdateRestartPolicyRequest {
+    id: String,
+    policy: RestartPolicy,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ListScheduledJobsRequest {
+    #[serde(default)]
+    server_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateScheduledJobRequest {
+    job: ScheduledJobRequest,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UpdateScheduledJobRequest {
+    id: String,
+    job: ScheduledJobRequest,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ScheduledJobIdRequest {
+    id: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ListAlertRulesRequest {
+    #[serde(default)]
+    server_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateAlertRuleRequest {
+    rule: AlertRuleRequest,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UpdateAlertRuleRequest {
+    id: String,
+    rule: AlertRuleRequest,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SetAlertRuleEnabledRequest {
+    id: String,
+    enabled: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AlertRuleIdRequest {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TestAlertRuleRequest {
+    rule: AlertRuleRequest,
+    #[serde(default)]
+    since: Option<i64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ListAlertFiringsRequest {
+    #[serde(default)]
+    server_id: Option<String>,
+    #[serde(default)]
+    rule_id: Option<String>,
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ClearServerUnhealthyRequest {
+    server_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BackupServerRequest {
+    server_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateBackupRequest {
+    server_id: String,
+    #[serde(default)]
+    options: Option<BackupOptions>,
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct SendCommandRequest {
-    id: String,
-    command: String,
+struct BackupIdRequest {
+    server_id: String,
+    backup_id: String,
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct GetServerStatusRequest {
-    id: String,
+struct UpdateBackupRetentionRequest {
+    server_id: String,
+    retention: BackupRetention,
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct ServerIdRequest {
-    id: String,
+struct CreateSnapshotRequest {
+    server_id: String,
+    #[serde(default)]
+    options: Option<SnapshotOptions>,
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct GetLogsRequest {
-    id: String,
-    since: usize,
+struct SnapshotIdRequest {
+    server_id: String,
+    snapshot_id: String,
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct UpdateNameRequest {
-    id: String,
-    name: String,
+struct RestoreSnapshotRequest {
+    server_id: String,
+    snapshot_id: String,
+    target_dir: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -1053,6 +2126,57 @@ struct WriteConfigRequest {
     values: HashMap<String, String>,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ListConfigHistoryRequest {
+    server_id: String,
+    #[serde(default)]
+    file: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConfigRevisionRequest {
+    server_id: String,
+    revision_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CompareServerConfigsRequest {
+    server_ids: Vec<String>,
+    #[serde(default)]
+    sections: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncServerConfigRequest {
+    source_id: String,
+    target_ids: Vec<String>,
+    section: String,
+    keys: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SaveConfigPresetRequest {
+    preset: ConfigPreset,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConfigPresetNameRequest {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApplyConfigPresetRequest {
+    name: String,
+    server_ids: Vec<String>,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct WriteServerPropertiesRequest {
@@ -1071,6 +2195,8 @@ struct WriteServerPropertiesSourceRequest {
 #[serde(rename_all = "camelCase")]
 struct ParseServerPropertiesSourceRequest {
     source: String,
+    #[serde(default)]
+    server_path: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -1120,6 +2246,53 @@ struct BanPlayerRequest {
     reason: String,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PlayerDataRequest {
+    server_id: String,
+    uuid: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RemovePlayerItemsRequest {
+    server_id: String,
+    uuid: String,
+    removals: Vec<ItemRemoval>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BanIpRequest {
+    server_id: String,
+    ip: String,
+    #[serde(default)]
+    reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UnbanIpRequest {
+    server_id: String,
+    ip: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PlayerServerRequest {
+    server_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PlayerSessionsRequest {
+    server_id: String,
+    #[serde(default)]
+    player: Option<String>,
+    #[serde(default)]
+    limit: Option<u32>,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct KickPlayerRequest {
@@ -1128,6 +2301,117 @@ struct KickPlayerRequest {
     reason: String,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WorldServerRequest {
+    server_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UpdateLevelInfoRequest {
+    server_id: String,
+    update: LevelUpdate,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UploadWorldRequest {
+    server_id: String,
+    archive_path: String,
+    name: Option<String>,
+    #[serde(default)]
+    activate: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ImportSingleplayerWorldRequest {
+    server_id: String,
+    save_path: String,
+    name: Option<String>,
+    #[serde(default)]
+    activate: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConvertWorldLayoutRequest {
+    server_id: String,
+    name: String,
+    layout: WorldLayout,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ResetWorldRequest {
+    server_id: String,
+    name: String,
+    seed: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SwitchWorldRequest {
+    server_id: String,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PruneWorldChunksRequest {
+    server_id: String,
+    criteria: PruneCriteria,
+    dry_run: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RenderWorldMapRequest {
+    server_id: String,
+    dimension: String,
+    #[serde(default)]
+    force: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MapTilesRequest {
+    server_id: String,
+    dimension: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MapTileRequest {
+    server_id: String,
+    dimension: String,
+    x: i32,
+    z: i32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InstallDatapackRequest {
+    server_id: String,
+    source_path: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SetDatapackEnabledRequest {
+    server_id: String,
+    name: String,
+    enabled: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DatapackRequest {
+    server_id: String,
+    name: String,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct ExportLogsRequest {
@@ -1160,4 +2444,156 @@ mod tests {
 
         assert!(commands.contains(&"preview_server_properties_write_from_source".to_string()));
     }
+
+    #[test]
+    fn command_registry_includes_config_history_commands() {
+        let registry = CommandRegistry::new();
+        let commands = registry.list_commands();
+
+        assert!(commands.contains(&"list_config_history".to_string()));
+        assert!(commands.contains(&"diff_config_revision".to_string()));
+        assert!(commands.contains(&"revert_config_revision".to_string()));
+    }
+
+    #[test]
+    fn command_registry_includes_config_sync_commands() {
+        let registry = CommandRegistry::new();
+        let commands = registry.list_commands();
+
+        assert!(commands.contains(&"compare_server_configs".to_string()));
+        assert!(commands.contains(&"sync_server_config".to_string()));
+        assert!(commands.contains(&"list_config_presets".to_string()));
+        assert!(commands.contains(&"save_config_preset".to_string()));
+        assert!(commands.contains(&"delete_config_preset".to_string()));
+        assert!(commands.contains(&"apply_config_preset".to_string()));
+    }
+
+    #[test]
+    fn command_registry_includes_log_search_command() {
+        let registry = CommandRegistry::new();
+        let commands = registry.list_commands();
+
+        assert!(commands.contains(&"search_server_logs".to_string()));
+    }
+
+    #[test]
+    fn command_registry_includes_log_storage_status_command() {
+        let registry = CommandRegistry::new();
+        let commands = registry.list_commands();
+
+        assert!(commands.contains(&"get_log_storage_status".to_string()));
+    }
+
+    #[test]
+    fn command_registry_includes_crash_reports_command() {
+        let registry = CommandRegistry::new();
+        let commands = registry.list_commands();
+
+        assert!(commands.contains(&"get_crash_reports".to_string()));
+    }
+
+    #[test]
+    fn command_registry_includes_player_session_commands() {
+        let registry = CommandRegistry::new();
+        let commands = registry.list_commands();
+
+        assert!(commands.contains(&"get_online_players".to_string()));
+        assert!(commands.contains(&"get_player_sessions".to_string()));
+    }
+
+    #[test]
+    fn command_registry_includes_player_data_commands() {
+        let registry = CommandRegistry::new();
+        let commands = registry.list_commands();
+
+        assert!(commands.contains(&"list_player_data".to_string()));
+        assert!(commands.contains(&"get_player_data".to_string()));
+        assert!(commands.contains(&"remove_player_items".to_string()));
+    }
+
+    #[test]
+    fn command_registry_includes_world_commands() {
+        let registry = CommandRegistry::new();
+        let commands = registry.list_commands();
+
+        assert!(commands.contains(&"get_level_info".to_string()));
+        assert!(commands.contains(&"update_level_info".to_string()));
+        assert!(commands.contains(&"list_worlds".to_string()));
+        assert!(commands.contains(&"upload_world".to_string()));
+        assert!(commands.contains(&"import_singleplayer_world".to_string()));
+        assert!(commands.contains(&"convert_world_layout".to_string()));
+        assert!(commands.contains(&"reset_world".to_string()));
+        assert!(commands.contains(&"switch_world".to_string()));
+        assert!(commands.contains(&"prune_world_chunks".to_string()));
+        assert!(commands.contains(&"render_world_map".to_string()));
+        assert!(commands.contains(&"list_map_tiles".to_string()));
+        assert!(commands.contains(&"get_map_tile".to_string()));
+        assert!(commands.contains(&"list_datapacks".to_string()));
+        assert!(commands.contains(&"install_datapack".to_string()));
+        assert!(commands.contains(&"set_datapack_enabled".to_string()));
+        assert!(commands.contains(&"remove_datapack".to_string()));
+    }
+
+    #[test]
+    fn command_registry_includes_ip_ban_commands() {
+        let registry = CommandRegistry::new();
+        let commands = registry.list_commands();
+
+        assert!(commands.contains(&"get_banned_ips".to_string()));
+        assert!(commands.contains(&"ban_ip".to_string()));
+        assert!(commands.contains(&"unban_ip".to_string()));
+    }
+
+    #[test]
+    fn command_registry_includes_stop_commands() {
+        let registry = CommandRegistry::new();
+        let commands = registry.list_commands();
+
+        assert!(commands.contains(&"stop_server".to_string()));
+        assert!(commands.contains(&"stop_all_servers".to_string()));
+    }
+
+    #[test]
+    fn command_registry_includes_scheduler_commands() {
+        let registry = CommandRegistry::new();
+        let commands = registry.list_commands();
+
+        assert!(commands.contains(&"list_scheduled_jobs".to_string()));
+        assert!(commands.contains(&"create_scheduled_job".to_string()));
+        assert!(commands.contains(&"update_scheduled_job".to_string()));
+        assert!(commands.contains(&"delete_scheduled_job".to_string()));
+        assert!(commands.contains(&"run_scheduled_job".to_string()));
+    }
+
+    #[test]
+    fn command_registry_includes_alert_commands() {
+        let registry = CommandRegistry::new();
+        let commands = registry.list_commands();
+
+        assert!(commands.contains(&"list_alert_rules".to_string()));
+        assert!(commands.contains(&"create_alert_rule".to_string()));
+        assert!(commands.contains(&"update_alert_rule".to_string()));
+        assert!(commands.contains(&"set_alert_rule_enabled".to_string()));
+        assert!(commands.contains(&"delete_alert_rule".to_string()));
+        assert!(commands.contains(&"test_alert_rule".to_string()));
+        assert!(commands.contains(&"list_alert_firings".to_string()));
+        assert!(commands.contains(&"clear_server_unhealthy".to_string()));
+    }
+
+    #[test]
+    fn command_registry_includes_backup_commands() {
+        let registry = CommandRegistry::new();
+        let commands = registry.list_commands();
+
+        assert!(commands.contains(&"list_backups".to_string()));
+        assert!(commands.contains(&"create_backup".to_string()));
+        assert!(commands.contains(&"restore_backup".to_string()));
+        assert!(commands.contains(&"delete_backup".to_string()));
+        assert!(commands.contains(&"get_backup_retention".to_string()));
+        assert!(commands.contains(&"update_backup_retention".to_string()));
+        assert!(commands.contains(&"create_snapshot".to_string()));
+        assert!(commands.contains(&"verify_backup".to_string()));
+        assert!(commands.contains(&"gc_backup_store".to_string()));
+        assert!(commands.contains(&"restore_snapshot".to_string()));
+    }
 }
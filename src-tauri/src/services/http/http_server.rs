@@ -1,7 +1,7 @@
 use super::http_command_handlers::CommandRegistry;
 use axum::{
     extract::{DefaultBodyLimit, Multipart, Path, State},
-    http::StatusCode,
+    http::{header, StatusCode},
     response::{sse::Event, IntoResponse, Sse},
     routing::{get, post},
     Json, Router,
@@ -90,6 +90,8 @@ pub async fn run_http_server(addr: &str, static_dir: Option<String>) {
         .route("/upload", post(handle_file_upload))
         // SSE 实时日志推送端点
         .route("/api/logs/stream", get(handle_log_stream))
+        // 已渲染的地图瓦片（先调用 render_world_map 生成）
+        .route("/map/{server_id}/{dimension}/{tile}", get(handle_map_tile))
         // 上传路由添加请求体大小限制（500MB）
         .layer(DefaultBodyLimit::max(500 * 1024 * 1024))
         .layer(cors)
@@ -123,6 +125,10 @@ pub async fn run_http_server(addr: &str, static_dir: Option<String>) {
     println!("API endpoints available at http://{}/api/<command>", addr);
     println!("Health check at http://{}/health", addr);
     println!("File upload available at http://{}/upload", addr);
+    println!(
+        "Map tiles available at http://{}/map/<server_id>/<dimension>/r.<x>.<z>.png",
+        addr
+    );
 
     if let Err(e) = axum::serve(listener, app).await {
         eprintln!("SeaLantern HTTP server error on {}: {}", addr, e);
@@ -258,6 +264,26 @@ async fn handle_api_command(
     }
 }
 
+/// 提供地图瓦片 PNG，路径形如 `/map/<server_id>/overworld/r.0.-1.png`
+async fn handle_map_tile(
+    Path((server_id, dimension, tile)): Path<(String, String, String)>,
+) -> impl IntoResponse {
+    use crate::services::world::map;
+
+    let path = match (map::tiles_dir(&server_id, &dimension), map::parse_tile_file_name(&tile)) {
+        (Ok(dir), Some((x, z))) => dir.join(map::tile_file_name(x, z)),
+        _ => return (StatusCode::BAD_REQUEST, "Invalid tile path").into_response(),
+    };
+    match fs::read(&path).await {
+        Ok(bytes) => (
+            [(header::CONTENT_TYPE, "image/png"), (header::CACHE_CONTROL, "no-cache")],
+            bytes,
+        )
+            .into_response(),
+        Err(_) => (StatusCode::NOT_FOUND, "Tile not found").into_response(),
+    }
+}
+
 /// SSE 实时日志流处理
 async fn handle_log_stream() -> impl IntoResponse {
     let receiver = LOG_BROADCAST.subscribe();
@@ -1,6 +1,6 @@
 //! SeaLantern services 层入口模块。
 //!
-//! - 按领域导出子模块：`server` / `http` / `download`；
+//! - 按领域导出子模块：`server` / `http` / `download` / `world`；
 //! - 顶层仅保留少量横切模块：`global`、`i18n`、`panic_report`、`async_loader` 等；
 //! - 通过 `pub use` 为历史模块名提供别名（如 `server_manager`、`download_manager`），
 //!   以便在未来大版本中按计划移除这些别名而不影响当前调用方。
@@ -17,6 +17,7 @@ pub mod online;
 pub mod panic_report;
 pub mod server;
 pub mod settings_manager;
+pub mod world;
 
 pub use download::download_manager;
 pub use download::java_installer;
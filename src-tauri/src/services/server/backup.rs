@@ -0,0 +1,887 @@
+//! 世界备份与还原。
+//!
+//! 备份文件统一存放在 `<应用数据目录>/backups/<server_id>/` 下，同目录的 `index.json`
+//! 记录备份列表与保留策略。
+//!
+//! - 创建：默认只打包世界目录，也可以按 include/exclude glob 打包整个服务器目录；
+//!   服务器运行中时依次发送 `save-off` / `save-all flush`，等待存档落盘后再打包，结束后 `save-on`。
+//! - 保留：每次创建后按 [`BackupRetention`] 清理多余备份，安全快照单独保留最近几个。
+//! - 还原：服务器运行中时拒绝执行；先为将被覆盖的路径生成安全快照，
+//!   再解压到临时目录，全部成功后才替换原有文件。
+
+use std::collections::{BTreeSet, HashSet};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{Datelike, Local, TimeZone};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+
+use crate::models::backup::{
+    BackupEntry, BackupFormat, BackupKind, BackupOptions, BackupRetention,
+};
+use crate::models::server::ServerInstance;
+use crate::services::global;
+
+use super::log_pipeline as server_log_pipeline;
+use super::manager::current_timestamp_secs;
+
+///此处常量见 utils/constants.rs
+use crate::utils::constants::{
+    BACKUPS_DIR_NAME, BACKUP_INDEX_FILE, BACKUP_RESTORE_STAGING_DIR, BACKUP_SAFETY_KEEP,
+    BACKUP_SAVE_TIMEOUT_SECS,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct BackupIndex {
+    #[serde(default)]
+    retention: BackupRetention,
+    #[serde(default)]
+    backups: Vec<BackupEntry>,
+}
+
+/// 待归档的单个文件
+pub(super) struct ArchiveItem {
+    pub(super) source: PathBuf,
+    /// 归档内路径，始终使用 `/` 分隔
+    pub(super) name: String,
+    pub(super) size: u64,
+}
+
+pub struct BackupManager {
+    /// 正在备份 / 还原的服务器，同一服务器的操作互斥
+    busy: Mutex<HashSet<String>>,
+}
+
+/// 持有期间占用服务器的备份操作锁，离开作用域自动释放
+pub(super) struct BusyGuard<'a> {
+    manager: &'a BackupManager,
+    server_id: String,
+}
+
+impl Drop for BusyGuard<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut busy) = self.manager.busy.lock() {
+            busy.remove(&self.server_id);
+        }
+    }
+}
+
+impl BackupManager {
+    pub fn new() -> Self {
+        BackupManager { busy: Mutex::new(HashSet::new()) }
+    }
+
+    pub fn list_backups(&self, server_id: &str) -> Result<Vec<BackupEntry>, String> {
+        find_server(server_id)?;
+        let mut backups = load_index(server_id).backups;
+        backups.sort_by_key(|b| std::cmp::Reverse(b.created_at));
+        Ok(backups)
+    }
+
+    pub fn get_retention(&self, server_id: &str) -> Result<BackupRetention, String> {
+        find_server(server_id)?;
+        Ok(load_index(server_id).retention)
+    }
+
+    /// 更新保留策略并立即按新策略清理，返回清理后的备份列表。
+    pub fn update_retention(
+        &self,
+        server_id: &str,
+        retention: BackupRetention,
+    ) -> Result<Vec<BackupEntry>, String> {
+        find_server(server_id)?;
+        let _guard = self.acquire(server_id)?;
+        let mut index = load_index(server_id);
+        index.retention = retention;
+        apply_retention(server_id, &mut index);
+        save_index(server_id, &index)?;
+        self.list_backups(server_id)
+    }
+
+    pub fn create_backup(
+        &self,
+        server_id: &str,
+        options: BackupOptions,
+        kind: BackupKind,
+    ) -> Result<BackupEntry, String> {
+        let server = find_server(server_id)?;
+        let _guard = self.acquire(server_id)?;
+        let server_dir = PathBuf::from(&server.path);
+        if !server_dir.is_dir() {
+            return Err("服务器目录不存在".to_string());
+        }
+
+        let entry = with_saves_paused(server_id, || {
+            let (roots, items) =
+                collect_items(&server_dir, options.full, &options.include, &options.exclude)?;
+            write_backup(server_id, &roots, &items, &options, kind)
+        })?;
+        let mut index = load_index(server_id);
+        index.backups.push(entry.clone());
+        apply_retention(server_id, &mut index);
+        save_index(server_id, &index)?;
+
+        let _ = server_log_pipeline::append_sealantern_log(
+            server_id,
+            &format!(
+                "[Sea Lantern] 已创建备份 {}（{} 个文件，{} 字节）",
+                entry.file_name, entry.file_count, entry.size_bytes
+            ),
+        );
+        Ok(entry)
+    }
+
+    /// 还原备份，返回还原前自动生成的安全快照（没有需要覆盖的文件时为 None）。
+    pub fn restore_backup(
+        &self,
+        server_id: &str,
+        backup_id: &str,
+    ) -> Result<Option<BackupEntry>, String> {
+        let server = find_server(server_id)?;
+        if global::server_manager().is_server_online(server_id) {
+            return Err("服务器正在运行，请先停止服务器再还原备份".to_string());
+        }
+        let _guard = self.acquire(server_id)?;
+
+        let mut index = load_index(server_id);
+        let entry = index
+            .backups
+            .iter()
+            .find(|b| b.id == backup_id)
+            .cloned()
+            .ok_or_else(|| "未找到备份".to_string())?;
+        let archive_path = backup_dir(server_id).join(&entry.file_name);
+        if !archive_path.is_file() {
+            return Err(format!("备份文件不存在: {}", entry.file_name));
+        }
+
+        let server_dir = PathBuf::from(&server.path);
+        for root in &entry.roots {
+            validate_relative_path(root)?;
+        }
+
+        // 1. 为即将被覆盖的路径生成安全快照
+        let existing_roots: Vec<String> = entry
+            .roots
+            .iter()
+            .filter(|root| server_dir.join(root).exists())
+            .cloned()
+            .collect();
+        let safety = if existing_roots.is_empty() {
+            None
+        } else {
+            let options = BackupOptions {
+                note: Some(format!("还原 {} 前自动创建", entry.file_name)),
+                ..BackupOptions::default()
+            };
+            let items = collect_roots(&server_dir, &existing_roots, &[])?;
+            let snapshot =
+                write_backup(server_id, &existing_roots, &items, &options, BackupKind::Safety)?;
+            index.backups.push(snapshot.clone());
+            apply_retention(server_id, &mut index);
+            save_index(server_id, &index)?;
+            Some(snapshot)
+        };
+
+        // 2. 解压到临时目录
+        let staging = server_dir.join(BACKUP_RESTORE_STAGING_DIR);
+        if staging.exists() {
+            std::fs::remove_dir_all(&staging).map_err(|e| format!("清理临时目录失败: {}", e))?;
+        }
+        std::fs::create_dir_all(&staging).map_err(|e| format!("创建临时目录失败: {}", e))?;
+        if let Err(err) = extract_archive(&archive_path, entry.format, &staging) {
+            let _ = std::fs::remove_dir_all(&staging);
+            return Err(err);
+        }
+
+        // 3. 替换原有路径
+        let replace_result = replace_roots(&server_dir, &staging, &entry.roots);
+        let _ = std::fs::remove_dir_all(&staging);
+        replace_result.map_err(|err| match &safety {
+            Some(snapshot) => format!("{}（可使用安全快照 {} 恢复）", err, snapshot.file_name),
+            None => err,
+        })?;
+
+        let _ = server_log_pipeline::append_sealantern_log(
+            server_id,
+            &format!("[Sea Lantern] 已从备份 {} 还原", entry.file_name),
+        );
+        Ok(safety)
+    }
+
+    pub fn delete_backup(&self, server_id: &str, backup_id: &str) -> Result<(), String> {
+        find_server(server_id)?;
+        let _guard = self.acquire(server_id)?;
+        let mut index = load_index(server_id);
+        let position = index
+            .backups
+            .iter()
+            .position(|b| b.id == backup_id)
+            .ok_or_else(|| "未找到备份".to_string())?;
+        let entry = index.backups.remove(position);
+        let path = backup_dir(server_id).join(&entry.file_name);
+        if path.exists() {
+            std::fs::remove_file(&path).map_err(|e| format!("删除备份文件失败: {}", e))?;
+        }
+        save_index(server_id, &index)
+    }
+
+    pub(super) fn acquire(&self, server_id: &str) -> Result<BusyGuard<'_>, String> {
+        let mut busy = self
+            .busy
+            .lock()
+            .map_err(|_| "backup lock poisoned".to_string())?;
+        if !busy.insert(server_id.to_string()) {
+            return Err("该服务器正在进行备份或还原操作，请稍后再试".to_string());
+        }
+        Ok(BusyGuard {
+            manager: self,
+            server_id: server_id.to_string(),
+        })
+    }
+}
+
+impl Default for BackupManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 用临时目录中解压出的内容替换服务器目录下对应的顶层路径
+fn replace_roots(server_dir: &Path, staging: &Path, roots: &[String]) -> Result<(), String> {
+    for root in roots {
+        let staged = staging.join(root);
+        if !staged.exists() {
+            continue;
+        }
+        let target = server_dir.join(root);
+        remove_path(&target)?;
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+        }
+        std::fs::rename(&staged, &target).map_err(|e| format!("还原 {} 失败: {}", root, e))?;
+    }
+    Ok(())
+}
+
+pub(super) fn find_server(server_id: &str) -> Result<ServerInstance, String> {
+    global::server_manager()
+        .get_server_list()
+        .into_iter()
+        .find(|server| server.id == server_id)
+        .ok_or_else(|| "未找到服务器".to_string())
+}
+
+/// 服务器运行中时先暂停自动保存并落盘，执行完 `f` 后无论成功与否都恢复自动保存。
+pub(super) fn with_saves_paused<T>(
+    server_id: &str,
+    f: impl FnOnce() -> Result<T, String>,
+) -> Result<T, String> {
+    let running = global::server_manager().is_server_online(server_id);
+    if running {
+        prepare_live_backup(server_id)?;
+    }
+    let result = f();
+    if running {
+        if let Err(err) = global::server_manager().send_command(server_id, "save-on") {
+            let _ = server_log_pipeline::append_sealantern_log(
+                server_id,
+                &format!("[Sea Lantern] 恢复自动保存失败，请手动执行 save-on: {}", err),
+            );
+        }
+    }
+    result
+}
+
+/// 关闭自动保存并强制落盘，等待服务端输出 "Saved the game"。
+fn prepare_live_backup(server_id: &str) -> Result<(), String> {
+    let manager = global::server_manager();
+    manager.send_command(server_id, "save-off")?;
+    // 先让 Writer 落盘队列中的旧日志，避免之前的 "Saved the game" 排在基线之后被误判为本次保存
+    server_log_pipeline::flush_writer(server_id);
+    let baseline = server_log_pipeline::get_latest_log_id(server_id);
+    if let Err(err) = manager.send_command(server_id, "save-all flush") {
+        let _ = manager.send_command(server_id, "save-on");
+        return Err(err);
+    }
+    // 非 SeaLantern 启动的服务器经 RCON 执行命令，`save-all flush` 在落盘完成后才返回，
+    // 其输出也不会进入日志数据库
+    if !manager
+        .get_running_server_ids()
+        .iter()
+        .any(|id| id == server_id)
+    {
+        return Ok(());
+    }
+
+    let timeout = Duration::from_secs(BACKUP_SAVE_TIMEOUT_SECS);
+    if server_log_pipeline::wait_for_log_line(server_id, baseline, "Saved the game", timeout) {
+        return Ok(());
+    }
+
+    let _ = server_log_pipeline::append_sealantern_log(
+        server_id,
+        &format!(
+            "[Sea Lantern] 等待存档保存超时（{} 秒），将直接打包当前文件",
+            BACKUP_SAVE_TIMEOUT_SECS
+        ),
+    );
+    Ok(())
+}
+
+/// 根据选项收集需要归档的文件，返回（顶层路径列表，文件列表）。
+pub(super) fn collect_items(
+    server_dir: &Path,
+    full: bool,
+    include: &[String],
+    exclude: &[String],
+) -> Result<(Vec<String>, Vec<ArchiveItem>), String> {
+    let roots: Vec<String> = if full {
+        let mut roots = Vec::new();
+        let entries =
+            std::fs::read_dir(server_dir).map_err(|e| format!("读取服务器目录失败: {}", e))?;
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name != BACKUP_RESTORE_STAGING_DIR {
+                roots.push(name);
+            }
+        }
+        roots.sort();
+        roots
+    } else {
+        world_roots(server_dir)?
+    };
+
+    let mut items = collect_roots(server_dir, &roots, exclude)?;
+    if full && !include.is_empty() {
+        items.retain(|item| {
+            include
+                .iter()
+                .any(|pattern| matches_path_or_ancestor(pattern, &item.name))
+        });
+    }
+    if items.is_empty() {
+        return Err("没有找到需要备份的文件".to_string());
+    }
+
+    let used_roots: BTreeSet<String> = items
+        .iter()
+        .filter_map(|item| item.name.split('/').next().map(|s| s.to_string()))
+        .collect();
+    Ok((used_roots.into_iter().collect(), items))
+}
+
+/// 世界目录：`level-name` 以及 Bukkit 布局下的 `_nether` / `_the_end`
+fn world_roots(server_dir: &Path) -> Result<Vec<String>, String> {
+    // world_dir 负责拒绝越出服务器目录的 level-name
+    crate::services::world::world_dir(server_dir)?;
+    let level_name = crate::services::world::level_name(server_dir);
+
+    let roots: Vec<String> = [
+        level_name.clone(),
+        format!("{}_nether", level_name),
+        format!("{}_the_end", level_name),
+    ]
+    .into_iter()
+    .filter(|root| server_dir.join(root).is_dir())
+    .collect();
+    if roots.is_empty() {
+        return Err(format!("未找到世界目录: {}", level_name));
+    }
+    Ok(roots)
+}
+
+fn collect_roots(
+    server_dir: &Path,
+    roots: &[String],
+    exclude: &[String],
+) -> Result<Vec<ArchiveItem>, String> {
+    let mut items = Vec::new();
+    for root in roots {
+        walk(server_dir, &server_dir.join(root), exclude, &mut items)?;
+    }
+    Ok(items)
+}
+
+fn walk(
+    server_dir: &Path,
+    path: &Path,
+    exclude: &[String],
+    items: &mut Vec<ArchiveItem>,
+) -> Result<(), String> {
+    let relative = path
+        .strip_prefix(server_dir)
+        .map_err(|_| "备份路径超出服务器目录".to_string())?;
+    let name = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<_>>()
+        .join("/");
+    if exclude
+        .iter()
+        .any(|pattern| matches_path_or_ancestor(pattern, &name))
+    {
+        return Ok(());
+    }
+
+    let metadata =
+        std::fs::symlink_metadata(path).map_err(|e| format!("读取文件信息失败: {}", e))?;
+    if metadata.file_type().is_symlink() {
+        return Ok(());
+    }
+    if metadata.is_dir() {
+        let mut entries: Vec<PathBuf> = std::fs::read_dir(path)
+            .map_err(|e| format!("读取目录失败: {}", e))?
+            .flatten()
+            .map(|entry| entry.path())
+            .collect();
+        entries.sort();
+        for entry in entries {
+            walk(server_dir, &entry, exclude, items)?;
+        }
+    } else if metadata.is_file() {
+        // 服务端独占持有 session.lock，且还原后会自动重建
+        if path
+            .file_name()
+            .map(|n| n == "session.lock")
+            .unwrap_or(false)
+        {
+            return Ok(());
+        }
+        items.push(ArchiveItem {
+            source: path.to_path_buf(),
+            name,
+            size: metadata.len(),
+        });
+    }
+    Ok(())
+}
+
+fn write_backup(
+    server_id: &str,
+    roots: &[String],
+    items: &[ArchiveItem],
+    options: &BackupOptions,
+    kind: BackupKind,
+) -> Result<BackupEntry, String> {
+    let dir = backup_dir(server_id);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("创建备份目录失败: {}", e))?;
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let created_at = current_timestamp_secs();
+    let stamp = Local
+        .timestamp_opt(created_at as i64, 0)
+        .single()
+        .map(|t| t.format("%Y%m%d-%H%M%S").to_string())
+        .unwrap_or_else(|| created_at.to_string());
+    let prefix = if kind == BackupKind::Safety {
+        "safety"
+    } else {
+        "backup"
+    };
+    let file_name = format!("{}-{}-{}.{}", prefix, stamp, &id[..8], options.format.extension());
+    let final_path = dir.join(&file_name);
+    let partial_path = dir.join(format!("{}.partial", file_name));
+
+    let written = match options.format {
+        BackupFormat::Zip => write_zip(&partial_path, items),
+        BackupFormat::TarGz => write_tar_gz(&partial_path, items),
+    };
+    if let Err(err) = written {
+        let _ = std::fs::remove_file(&partial_path);
+        return Err(err);
+    }
+    std::fs::rename(&partial_path, &final_path).map_err(|e| format!("保存备份文件失败: {}", e))?;
+
+    let size_bytes = std::fs::metadata(&final_path).map(|m| m.len()).unwrap_or(0);
+    Ok(BackupEntry {
+        id,
+        server_id: server_id.to_string(),
+        file_name,
+        format: options.format,
+        kind,
+        full: options.full,
+        roots: roots.to_vec(),
+        size_bytes,
+        file_count: items.len() as u64,
+        created_at,
+        note: options.note.clone().filter(|note| !note.trim().is_empty()),
+    })
+}
+
+fn write_zip(path: &Path, items: &[ArchiveItem]) -> Result<(), String> {
+    let file = File::create(path).map_err(|e| format!("创建备份文件失败: {}", e))?;
+    let mut writer = zip::ZipWriter::new(BufWriter::new(file));
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    for item in items {
+        writer
+            .start_file(item.name.as_str(), options.large_file(item.size >= u32::MAX as u64))
+            .map_err(|e| format!("写入 ZIP 条目失败: {}", e))?;
+        let mut source =
+            File::open(&item.source).map_err(|e| format!("读取 {} 失败: {}", item.name, e))?;
+        std::io::copy(&mut source, &mut writer)
+            .map_err(|e| format!("写入 {} 失败: {}", item.name, e))?;
+    }
+    writer
+        .finish()
+        .map_err(|e| format!("完成 ZIP 写入失败: {}", e))?;
+    Ok(())
+}
+
+fn write_tar_gz(path: &Path, items: &[ArchiveItem]) -> Result<(), String> {
+    let file = File::create(path).map_err(|e| format!("创建备份文件失败: {}", e))?;
+    let encoder = GzEncoder::new(BufWriter::new(file), Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+
+    for item in items {
+        builder
+            .append_path_with_name(&item.source, &item.name)
+            .map_err(|e| format!("写入 {} 失败: {}", item.name, e))?;
+    }
+    builder
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .map_err(|e| format!("完成 TAR.GZ 写入失败: {}", e))?;
+    Ok(())
+}
+
+fn extract_archive(archive_path: &Path, format: BackupFormat, target: &Path) -> Result<(), String> {
+    let file = File::open(archive_path).map_err(|e| format!("打开备份文件失败: {}", e))?;
+    match format {
+        BackupFormat::Zip => {
+            let mut archive =
+                zip::ZipArchive::new(file).map_err(|e| format!("读取 ZIP 文件失败: {}", e))?;
+            for index in 0..archive.len() {
+                let mut entry = archive
+                    .by_index(index)
+                    .map_err(|e| format!("读取 ZIP 条目失败: {}", e))?;
+                let enclosed = entry
+                    .enclosed_name()
+                    .ok_or_else(|| "ZIP 条目包含非法路径".to_string())?;
+                let out_path = target.join(enclosed);
+                if entry.is_dir() {
+                    std::fs::create_dir_all(&out_path)
+                        .map_err(|e| format!("创建目录失败: {}", e))?;
+                    continue;
+                }
+                if let Some(parent) = out_path.parent() {
+                    std::fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+                }
+                let mut out_file =
+                    File::create(&out_path).map_err(|e| format!("创建文件失败: {}", e))?;
+                std::io::copy(&mut entry, &mut out_file)
+                    .map_err(|e| format!("写入文件失败: {}", e))?;
+            }
+            Ok(())
+        }
+        BackupFormat::TarGz => {
+            let mut archive = tar::Archive::new(GzDecoder::new(file));
+            let entries = archive
+                .entries()
+                .map_err(|e| format!("读取 TAR 条目失败: {}", e))?;
+            for entry in entries {
+                let mut entry = entry.map_err(|e| format!("解析 TAR 条目失败: {}", e))?;
+                entry
+                    .unpack_in(target)
+                    .map_err(|e| format!("解压 TAR 条目失败: {}", e))?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// 按保留策略删除多余的备份文件，并从索引中移除。
+fn apply_retention(server_id: &str, index: &mut BackupIndex) {
+    let expired: HashSet<String> = select_expired_backups(&index.backups, &index.retention)
+        .into_iter()
+        .collect();
+    if expired.is_empty() {
+        return;
+    }
+    let dir = backup_dir(server_id);
+    index.backups.retain(|entry| {
+        if !expired.contains(&entry.id) {
+            return true;
+        }
+        let path = dir.join(&entry.file_name);
+        // 删除失败时保留索引记录，避免文件变成无人管理的孤儿
+        let removed = std::fs::remove_file(&path).is_ok() || !path.exists();
+        !removed
+    });
+}
+
+/// 计算需要清理的备份 ID。
+///
+/// 常规备份按 `keep_last` / `keep_daily` / `keep_weekly` 取并集保留（全为 0 时全部保留）；
+/// 安全快照与之独立，只保留最近 `BACKUP_SAFETY_KEEP` 个。
+fn select_expired_backups(backups: &[BackupEntry], retention: &BackupRetention) -> Vec<String> {
+    let mut sorted: Vec<&BackupEntry> = backups.iter().collect();
+    sorted.sort_by_key(|b| std::cmp::Reverse(b.created_at));
+
+    let mut expired: Vec<String> = sorted
+        .iter()
+        .filter(|entry| entry.kind == BackupKind::Safety)
+        .skip(BACKUP_SAFETY_KEEP)
+        .map(|entry| entry.id.clone())
+        .collect();
+
+    if retention.keep_last == 0 && retention.keep_daily == 0 && retention.keep_weekly == 0 {
+        return expired;
+    }
+
+    let regular: Vec<&BackupEntry> = sorted
+        .into_iter()
+        .filter(|entry| entry.kind != BackupKind::Safety)
+        .collect();
+    let mut keep: HashSet<&str> = regular
+        .iter()
+        .take(retention.keep_last as usize)
+        .map(|entry| entry.id.as_str())
+        .collect();
+
+    let mut days = HashSet::new();
+    let mut weeks = HashSet::new();
+    for entry in &regular {
+        let Some(local) = Local.timestamp_opt(entry.created_at as i64, 0).single() else {
+            continue;
+        };
+        let day = local.date_naive();
+        if days.len() < retention.keep_daily as usize && days.insert(day) {
+            keep.insert(entry.id.as_str());
+        }
+        let week = (day.iso_week().year(), day.iso_week().week());
+        if weeks.len() < retention.keep_weekly as usize && weeks.insert(week) {
+            keep.insert(entry.id.as_str());
+        }
+    }
+
+    expired.extend(
+        regular
+            .iter()
+            .filter(|entry| !keep.contains(entry.id.as_str()))
+            .map(|entry| entry.id.clone()),
+    );
+    expired
+}
+
+/// 简易 glob 匹配：`*` 匹配段内任意字符，`?` 匹配单个字符，`**` 匹配任意层级目录。
+/// 不含 `/` 的模式匹配任意层级的文件/目录名（类似 .gitignore）。
+fn glob_matches(pattern: &str, path: &str) -> bool {
+    let pattern = pattern.trim().trim_start_matches("./").trim_matches('/');
+    if pattern.is_empty() {
+        return false;
+    }
+    let path_segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    if !pattern.contains('/') {
+        return path_segments
+            .last()
+            .map(|name| segment_matches(pattern.as_bytes(), name.as_bytes()))
+            .unwrap_or(false);
+    }
+    let pattern_segments: Vec<&str> = pattern.split('/').collect();
+    segments_match(&pattern_segments, &path_segments)
+}
+
+/// 路径本身或其任一上级目录匹配即视为命中，用于按目录包含/排除。
+fn matches_path_or_ancestor(pattern: &str, path: &str) -> bool {
+    let mut prefix = String::new();
+    for segment in path.split('/') {
+        if !prefix.is_empty() {
+            prefix.push('/');
+        }
+        prefix.push_str(segment);
+        if glob_matches(pattern, &prefix) {
+            return true;
+        }
+    }
+    false
+}
+
+fn segments_match(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| segments_match(rest, &path[skip..])),
+        Some((first, rest)) => match path.split_first() {
+            Some((segment, path_rest)) => {
+                segment_matches(first.as_bytes(), segment.as_bytes())
+                    && segments_match(rest, path_rest)
+            }
+            None => false,
+        },
+    }
+}
+
+fn segment_matches(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) => (0..=text.len()).any(|skip| segment_matches(rest, &text[skip..])),
+        Some((b'?', rest)) => !text.is_empty() && segment_matches(rest, &text[1..]),
+        Some((c, rest)) => text.first() == Some(c) && segment_matches(rest, &text[1..]),
+    }
+}
+
+/// 只允许服务器目录内的相对路径
+pub(super) fn validate_relative_path(path: &str) -> Result<(), String> {
+    let candidate = Path::new(path);
+    let valid = !path.trim().is_empty()
+        && candidate
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("非法路径: {}", path))
+    }
+}
+
+fn remove_path(path: &Path) -> Result<(), String> {
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(_) => return Ok(()),
+    };
+    if metadata.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    }
+    .map_err(|e| format!("删除 {} 失败: {}", path.display(), e))
+}
+
+pub(super) fn backup_dir(server_id: &str) -> PathBuf {
+    PathBuf::from(crate::utils::path::get_or_create_app_data_dir())
+        .join(BACKUPS_DIR_NAME)
+        .join(server_id)
+}
+
+fn load_index(server_id: &str) -> BackupIndex {
+    let p = backup_dir(server_id).join(BACKUP_INDEX_FILE);
+    std::fs::read_to_string(&p)
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_index(server_id: &str, index: &BackupIndex) -> Result<(), String> {
+    let dir = backup_dir(server_id);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("创建备份目录失败: {}", e))?;
+    let json = serde_json::to_string_pretty(index).map_err(|e| e.to_string())?;
+    std::fs::write(dir.join(BACKUP_INDEX_FILE), json)
+        .map_err(|e| format!("保存备份索引失败: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_utils::temp_dir;
+
+    fn entry(id: &str, created_at: u64, kind: BackupKind) -> BackupEntry {
+        BackupEntry {
+            id: id.to_string(),
+            server_id: "s".to_string(),
+            file_name: format!("{}.zip", id),
+            format: BackupFormat::Zip,
+            kind,
+            full: false,
+            roots: vec!["world".to_string()],
+            size_bytes: 0,
+            file_count: 0,
+            created_at,
+            note: None,
+        }
+    }
+
+    #[test]
+    fn glob_matching() {
+        assert!(glob_matches("*.log", "logs/latest.log"));
+        assert!(glob_matches("logs", "logs"));
+        assert!(glob_matches("world/region/*.mca", "world/region/r.0.0.mca"));
+        assert!(glob_matches("**/session.lock", "world/session.lock"));
+        assert!(glob_matches("world/**", "world/data/raids.dat"));
+        assert!(!glob_matches("world/*.mca", "world/region/r.0.0.mca"));
+        assert!(!glob_matches("r.?.mca", "r.10.mca"));
+        assert!(matches_path_or_ancestor("plugins/dynmap", "plugins/dynmap/web/index.html"));
+    }
+
+    #[test]
+    fn retention_disabled_keeps_everything_but_old_safety_snapshots() {
+        let mut backups: Vec<BackupEntry> = (0..5)
+            .map(|i| entry(&format!("b{}", i), 1000 + i, BackupKind::Manual))
+            .collect();
+        backups.extend((0..5).map(|i| entry(&format!("s{}", i), 1000 + i, BackupKind::Safety)));
+
+        let mut expired = select_expired_backups(&backups, &BackupRetention::default());
+        expired.sort();
+        assert_eq!(expired, vec!["s0".to_string(), "s1".to_string()]);
+    }
+
+    #[test]
+    fn retention_keeps_last_and_thins_daily() {
+        const DAY: u64 = 86_400;
+        // 连续 4 天，每天中午和下午各一个备份
+        let base = 1_700_000_000 / DAY * DAY + 12 * 3600;
+        let backups: Vec<BackupEntry> = (0..4)
+            .flat_map(|d| {
+                vec![
+                    entry(&format!("d{}a", d), base + d * DAY, BackupKind::Scheduled),
+                    entry(&format!("d{}b", d), base + d * DAY + 3600, BackupKind::Scheduled),
+                ]
+            })
+            .collect();
+
+        let retention = BackupRetention {
+            keep_last: 1,
+            keep_daily: 3,
+            keep_weekly: 0,
+        };
+        let mut expired = select_expired_backups(&backups, &retention);
+        expired.sort();
+        // 保留 d3b（最新）以及 d3、d2、d1 每天最新的一个
+        assert_eq!(expired, vec!["d0a", "d0b", "d1a", "d2a", "d3a"]);
+    }
+
+    #[test]
+    fn archives_round_trip() {
+        let source = temp_dir("source");
+        std::fs::create_dir_all(source.join("world/region")).unwrap();
+        std::fs::write(source.join("world/level.dat"), b"level").unwrap();
+        std::fs::write(source.join("world/region/r.0.0.mca"), b"region").unwrap();
+        std::fs::write(source.join("world/session.lock"), b"lock").unwrap();
+
+        let items = collect_roots(&source, &["world".to_string()], &["*.mca".to_string()]).unwrap();
+        let names: Vec<&str> = items.iter().map(|item| item.name.as_str()).collect();
+        assert_eq!(names, vec!["world/level.dat"]);
+
+        let items = collect_roots(&source, &["world".to_string()], &[]).unwrap();
+        for format in [BackupFormat::Zip, BackupFormat::TarGz] {
+            let archive = source.join(format!("backup.{}", format.extension()));
+            match format {
+                BackupFormat::Zip => write_zip(&archive, &items).unwrap(),
+                BackupFormat::TarGz => write_tar_gz(&archive, &items).unwrap(),
+            }
+            let target = temp_dir("target");
+            extract_archive(&archive, format, &target).unwrap();
+            assert_eq!(std::fs::read(target.join("world/level.dat")).unwrap(), b"level");
+            assert_eq!(std::fs::read(target.join("world/region/r.0.0.mca")).unwrap(), b"region");
+            assert!(!target.join("world/session.lock").exists());
+            let _ = std::fs::remove_dir_all(&target);
+        }
+        let _ = std::fs::remove_dir_all(&source);
+    }
+
+    #[test]
+    fn rejects_paths_outside_server_dir() {
+        assert!(validate_relative_path("world").is_ok());
+        assert!(validate_relative_path("worlds/survival").is_ok());
+        assert!(validate_relative_path("../world").is_err());
+        assert!(validate_relative_path("/etc").is_err());
+        assert!(validate_relative_path("").is_err());
+    }
+}
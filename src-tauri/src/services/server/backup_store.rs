@@ -0,0 +1,545 @@
+//! 去重增量备份（内容寻址对象库）。
+//!
+//! 目录结构位于 `<应用数据目录>/backups/<server_id>/store/`：
+//! - `objects/<前两位>/<sha256>`：按文件内容 SHA-256 存放的对象，相同内容只存一份；
+//! - `snapshots/<id>.json`：快照清单，记录每个文件的相对路径、哈希、大小与修改时间；
+//! - `tmp/`：写入中的临时对象，完成后原子重命名到 `objects/`。
+//!
+//! 创建快照时，若文件大小与修改时间和上一个快照一致，直接复用上次的哈希而不重新读取，
+//! 因此大型世界的定期快照只会读取并存储发生变化的区域文件。
+//! 区域文件本身已压缩，对象按原始内容存放，不再二次压缩。
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::models::backup::{
+    SnapshotOptions, SnapshotSummary, SnapshotVerifyReport, StoreGcReport,
+};
+
+use super::backup::{
+    backup_dir, collect_items, find_server, validate_relative_path, with_saves_paused, ArchiveItem,
+    BackupManager,
+};
+use super::log_pipeline as server_log_pipeline;
+use super::manager::current_timestamp_secs;
+
+///此处常量见 utils/constants.rs
+use crate::utils::constants::BACKUP_STORE_DIR_NAME;
+
+const HASH_BUFFER_SIZE: usize = 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct ManifestFile {
+    path: String,
+    hash: String,
+    size: u64,
+    modified_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SnapshotManifest {
+    #[serde(flatten)]
+    summary: SnapshotSummary,
+    files: Vec<ManifestFile>,
+}
+
+struct ObjectStore {
+    root: PathBuf,
+}
+
+impl BackupManager {
+    pub fn create_snapshot(
+        &self,
+        server_id: &str,
+        options: SnapshotOptions,
+    ) -> Result<SnapshotSummary, String> {
+        let server = find_server(server_id)?;
+        let _guard = self.acquire(server_id)?;
+        let server_dir = PathBuf::from(&server.path);
+        if !server_dir.is_dir() {
+            return Err("服务器目录不存在".to_string());
+        }
+
+        let store = ObjectStore::open(server_id)?;
+        let previous = store.latest_manifest();
+        let manifest = with_saves_paused(server_id, || {
+            let (roots, items) =
+                collect_items(&server_dir, options.full, &options.include, &options.exclude)?;
+            store.build_snapshot(server_id, &roots, &items, &options, previous.as_ref())
+        })?;
+        store.save_manifest(&manifest)?;
+
+        let summary = manifest.summary;
+        let _ = server_log_pipeline::append_sealantern_log(
+            server_id,
+            &format!(
+                "[Sea Lantern] 已创建增量快照 {}（{} 个文件，新增 {} 个对象 / {} 字节）",
+                summary.id, summary.file_count, summary.new_objects, summary.new_bytes
+            ),
+        );
+        Ok(summary)
+    }
+
+    pub fn list_snapshots(&self, server_id: &str) -> Result<Vec<SnapshotSummary>, String> {
+        find_server(server_id)?;
+        let store = ObjectStore::open(server_id)?;
+        Ok(store
+            .load_manifests()
+            .into_iter()
+            .map(|manifest| manifest.summary)
+            .collect())
+    }
+
+    /// 删除快照清单；对象文件需通过 `gc_store` 回收。
+    pub fn delete_snapshot(&self, server_id: &str, snapshot_id: &str) -> Result<(), String> {
+        find_server(server_id)?;
+        let _guard = self.acquire(server_id)?;
+        ObjectStore::open(server_id)?.delete_manifest(snapshot_id)
+    }
+
+    pub fn verify_snapshot(
+        &self,
+        server_id: &str,
+        snapshot_id: &str,
+    ) -> Result<SnapshotVerifyReport, String> {
+        find_server(server_id)?;
+        let _guard = self.acquire(server_id)?;
+        let store = ObjectStore::open(server_id)?;
+        let manifest = store.load_manifest(snapshot_id)?;
+        Ok(store.verify(&manifest))
+    }
+
+    pub fn gc_store(&self, server_id: &str) -> Result<StoreGcReport, String> {
+        find_server(server_id)?;
+        let _guard = self.acquire(server_id)?;
+        ObjectStore::open(server_id)?.gc()
+    }
+
+    /// 将快照还原到指定目录，目标目录必须不存在或为空。
+    pub fn restore_snapshot(
+        &self,
+        server_id: &str,
+        snapshot_id: &str,
+        target_dir: &str,
+    ) -> Result<SnapshotSummary, String> {
+        find_server(server_id)?;
+        let _guard = self.acquire(server_id)?;
+        let store = ObjectStore::open(server_id)?;
+        let manifest = store.load_manifest(snapshot_id)?;
+        store.restore(&manifest, Path::new(target_dir))?;
+        Ok(manifest.summary)
+    }
+}
+
+impl ObjectStore {
+    fn open(server_id: &str) -> Result<Self, String> {
+        Self::at(backup_dir(server_id).join(BACKUP_STORE_DIR_NAME))
+    }
+
+    fn at(root: PathBuf) -> Result<Self, String> {
+        for dir in ["objects", "snapshots", "tmp"] {
+            std::fs::create_dir_all(root.join(dir))
+                .map_err(|e| format!("创建备份库目录失败: {}", e))?;
+        }
+        Ok(ObjectStore { root })
+    }
+
+    fn object_path(&self, hash: &str) -> PathBuf {
+        let prefix = hash.get(..2).unwrap_or("00");
+        self.root.join("objects").join(prefix).join(hash)
+    }
+
+    fn manifest_path(&self, snapshot_id: &str) -> Result<PathBuf, String> {
+        let valid = !snapshot_id.is_empty()
+            && snapshot_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-');
+        if !valid {
+            return Err(format!("非法快照 ID: {}", snapshot_id));
+        }
+        Ok(self
+            .root
+            .join("snapshots")
+            .join(format!("{}.json", snapshot_id)))
+    }
+
+    fn build_snapshot(
+        &self,
+        server_id: &str,
+        roots: &[String],
+        items: &[ArchiveItem],
+        options: &SnapshotOptions,
+        previous: Option<&SnapshotManifest>,
+    ) -> Result<SnapshotManifest, String> {
+        let known: HashMap<&str, &ManifestFile> = previous
+            .map(|manifest| {
+                manifest
+                    .files
+                    .iter()
+                    .map(|file| (file.path.as_str(), file))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut files = Vec::with_capacity(items.len());
+        let mut total_bytes = 0u64;
+        let mut new_bytes = 0u64;
+        let mut new_objects = 0u64;
+
+        for item in items {
+            let metadata = std::fs::metadata(&item.source)
+                .map_err(|e| format!("读取 {} 信息失败: {}", item.name, e))?;
+            let size = metadata.len();
+            let modified_ms = metadata
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|duration| duration.as_millis() as u64)
+                .unwrap_or(0);
+
+            let reusable = known.get(item.name.as_str()).filter(|file| {
+                file.size == size
+                    && file.modified_ms == modified_ms
+                    && modified_ms != 0
+                    && self.object_path(&file.hash).is_file()
+            });
+            let hash = match reusable {
+                Some(file) => file.hash.clone(),
+                None => {
+                    let (hash, written) = self.store_file(&item.source)?;
+                    if written {
+                        new_objects += 1;
+                        new_bytes += size;
+                    }
+                    hash
+                }
+            };
+
+            total_bytes += size;
+            files.push(ManifestFile {
+                path: item.name.clone(),
+                hash,
+                size,
+                modified_ms,
+            });
+        }
+
+        let summary = SnapshotSummary {
+            id: uuid::Uuid::new_v4().to_string(),
+            server_id: server_id.to_string(),
+            created_at: current_timestamp_secs(),
+            full: options.full,
+            roots: roots.to_vec(),
+            file_count: files.len() as u64,
+            total_bytes,
+            new_bytes,
+            new_objects,
+            note: options.note.clone().filter(|note| !note.trim().is_empty()),
+        };
+        Ok(SnapshotManifest { summary, files })
+    }
+
+    /// 边读取边计算哈希并写入临时文件；对象已存在时丢弃临时文件。
+    /// 返回（哈希，是否新写入了对象）。
+    fn store_file(&self, source: &Path) -> Result<(String, bool), String> {
+        let temp_path = self.root.join("tmp").join(uuid::Uuid::new_v4().to_string());
+        let result = self.write_object(source, &temp_path);
+        let _ = std::fs::remove_file(&temp_path);
+        result
+    }
+
+    fn write_object(&self, source: &Path, temp_path: &Path) -> Result<(String, bool), String> {
+        let mut input =
+            File::open(source).map_err(|e| format!("读取 {} 失败: {}", source.display(), e))?;
+        let mut output = BufWriter::new(
+            File::create(temp_path).map_err(|e| format!("创建临时对象失败: {}", e))?,
+        );
+        let mut hasher = Sha256::new();
+        let mut buffer = vec![0u8; HASH_BUFFER_SIZE];
+        loop {
+            let read = input
+                .read(&mut buffer)
+                .map_err(|e| format!("读取 {} 失败: {}", source.display(), e))?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+            output
+                .write_all(&buffer[..read])
+                .map_err(|e| format!("写入临时对象失败: {}", e))?;
+        }
+        output
+            .flush()
+            .map_err(|e| format!("写入临时对象失败: {}", e))?;
+        drop(output);
+
+        let hash = format!("{:x}", hasher.finalize());
+        let object_path = self.object_path(&hash);
+        if object_path.is_file() {
+            return Ok((hash, false));
+        }
+        if let Some(parent) = object_path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("创建对象目录失败: {}", e))?;
+        }
+        std::fs::rename(temp_path, &object_path).map_err(|e| format!("保存对象失败: {}", e))?;
+        Ok((hash, true))
+    }
+
+    fn load_manifests(&self) -> Vec<SnapshotManifest> {
+        let mut manifests: Vec<SnapshotManifest> = std::fs::read_dir(self.root.join("snapshots"))
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter(|entry| {
+                        entry
+                            .path()
+                            .extension()
+                            .map(|ext| ext == "json")
+                            .unwrap_or(false)
+                    })
+                    .filter_map(|entry| std::fs::read_to_string(entry.path()).ok())
+                    .filter_map(|content| serde_json::from_str(&content).ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+        manifests.sort_by_key(|manifest| std::cmp::Reverse(manifest.summary.created_at));
+        manifests
+    }
+
+    fn latest_manifest(&self) -> Option<SnapshotManifest> {
+        self.load_manifests().into_iter().next()
+    }
+
+    fn load_manifest(&self, snapshot_id: &str) -> Result<SnapshotManifest, String> {
+        let path = self.manifest_path(snapshot_id)?;
+        let content = std::fs::read_to_string(&path).map_err(|_| "未找到快照".to_string())?;
+        serde_json::from_str(&content).map_err(|e| format!("解析快照清单失败: {}", e))
+    }
+
+    fn save_manifest(&self, manifest: &SnapshotManifest) -> Result<(), String> {
+        let path = self.manifest_path(&manifest.summary.id)?;
+        let json = serde_json::to_string(manifest).map_err(|e| e.to_string())?;
+        std::fs::write(path, json).map_err(|e| format!("保存快照清单失败: {}", e))
+    }
+
+    fn delete_manifest(&self, snapshot_id: &str) -> Result<(), String> {
+        let path = self.manifest_path(snapshot_id)?;
+        if !path.is_file() {
+            return Err("未找到快照".to_string());
+        }
+        std::fs::remove_file(path).map_err(|e| format!("删除快照失败: {}", e))
+    }
+
+    fn verify(&self, manifest: &SnapshotManifest) -> SnapshotVerifyReport {
+        let mut missing = Vec::new();
+        let mut corrupted = Vec::new();
+        let mut checked: HashMap<&str, Option<bool>> = HashMap::new();
+
+        for file in &manifest.files {
+            let state = *checked.entry(file.hash.as_str()).or_insert_with(|| {
+                let path = self.object_path(&file.hash);
+                if !path.is_file() {
+                    return None;
+                }
+                Some(
+                    hash_file(&path)
+                        .map(|hash| hash == file.hash)
+                        .unwrap_or(false),
+                )
+            });
+            match state {
+                None => missing.push(file.path.clone()),
+                Some(false) => corrupted.push(file.path.clone()),
+                Some(true) => {}
+            }
+        }
+
+        SnapshotVerifyReport {
+            snapshot_id: manifest.summary.id.clone(),
+            ok: missing.is_empty() && corrupted.is_empty(),
+            checked_files: manifest.files.len() as u64,
+            missing,
+            corrupted,
+        }
+    }
+
+    /// 删除所有快照都不再引用的对象，并清理残留的临时文件。
+    fn gc(&self) -> Result<StoreGcReport, String> {
+        let referenced: HashSet<String> = self
+            .load_manifests()
+            .into_iter()
+            .flat_map(|manifest| manifest.files.into_iter().map(|file| file.hash))
+            .collect();
+
+        let mut removed_objects = 0u64;
+        let mut freed_bytes = 0u64;
+        let mut remaining_objects = 0u64;
+        let objects_dir = self.root.join("objects");
+        let prefixes =
+            std::fs::read_dir(&objects_dir).map_err(|e| format!("读取对象目录失败: {}", e))?;
+        for prefix in prefixes.flatten() {
+            let Ok(objects) = std::fs::read_dir(prefix.path()) else {
+                continue;
+            };
+            for object in objects.flatten() {
+                let name = object.file_name().to_string_lossy().to_string();
+                if referenced.contains(&name) {
+                    remaining_objects += 1;
+                    continue;
+                }
+                let size = object.metadata().map(|m| m.len()).unwrap_or(0);
+                if std::fs::remove_file(object.path()).is_ok() {
+                    removed_objects += 1;
+                    freed_bytes += size;
+                }
+            }
+            let _ = std::fs::remove_dir(prefix.path());
+        }
+
+        if let Ok(temps) = std::fs::read_dir(self.root.join("tmp")) {
+            for temp in temps.flatten() {
+                let _ = std::fs::remove_file(temp.path());
+            }
+        }
+
+        Ok(StoreGcReport {
+            removed_objects,
+            freed_bytes,
+            remaining_objects,
+        })
+    }
+
+    fn restore(&self, manifest: &SnapshotManifest, target: &Path) -> Result<(), String> {
+        if target.exists() {
+            let is_empty = std::fs::read_dir(target)
+                .map_err(|e| format!("读取目标目录失败: {}", e))?
+                .next()
+                .is_none();
+            if !is_empty {
+                return Err("目标目录必须为空".to_string());
+            }
+        }
+        std::fs::create_dir_all(target).map_err(|e| format!("创建目标目录失败: {}", e))?;
+
+        for file in &manifest.files {
+            validate_relative_path(&file.path)?;
+            let object_path = self.object_path(&file.hash);
+            if !object_path.is_file() {
+                return Err(format!("对象缺失，无法还原 {}", file.path));
+            }
+            let out_path = target.join(&file.path);
+            if let Some(parent) = out_path.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+            }
+            std::fs::copy(&object_path, &out_path)
+                .map_err(|e| format!("还原 {} 失败: {}", file.path, e))?;
+            if file.modified_ms > 0 {
+                if let Ok(out_file) = File::options().write(true).open(&out_path) {
+                    let _ =
+                        out_file.set_modified(UNIX_EPOCH + Duration::from_millis(file.modified_ms));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn hash_file(path: &Path) -> Result<String, String> {
+    let mut file = File::open(path).map_err(|e| format!("读取对象失败: {}", e))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; HASH_BUFFER_SIZE];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .map_err(|e| format!("读取对象失败: {}", e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_utils::temp_dir;
+
+    fn item(dir: &Path, name: &str) -> ArchiveItem {
+        let source = dir.join(name);
+        let size = std::fs::metadata(&source).unwrap().len();
+        ArchiveItem { source, name: name.to_string(), size }
+    }
+
+    #[test]
+    fn unchanged_files_are_deduplicated() {
+        let world = temp_dir("world");
+        std::fs::write(world.join("a.mca"), b"region a").unwrap();
+        std::fs::write(world.join("b.mca"), b"region b").unwrap();
+        std::fs::write(world.join("copy.mca"), b"region a").unwrap();
+        let store = ObjectStore::at(temp_dir("store_dedup")).unwrap();
+        let items = vec![item(&world, "a.mca"), item(&world, "b.mca"), item(&world, "copy.mca")];
+        let options = SnapshotOptions::default();
+
+        let first = store
+            .build_snapshot("s", &[], &items, &options, None)
+            .unwrap();
+        assert_eq!(first.summary.new_objects, 2);
+
+        std::fs::write(world.join("b.mca"), b"region b changed").unwrap();
+        let items = vec![item(&world, "a.mca"), item(&world, "b.mca"), item(&world, "copy.mca")];
+        let second = store
+            .build_snapshot("s", &[], &items, &options, Some(&first))
+            .unwrap();
+        assert_eq!(second.summary.new_objects, 1);
+        assert_eq!(second.summary.new_bytes, "region b changed".len() as u64);
+        assert_eq!(second.files[0].hash, first.files[0].hash);
+        assert_ne!(second.files[1].hash, first.files[1].hash);
+    }
+
+    #[test]
+    fn verify_gc_and_restore() {
+        let world = temp_dir("world_restore");
+        std::fs::create_dir_all(world.join("world/region")).unwrap();
+        std::fs::write(world.join("world/level.dat"), b"level").unwrap();
+        std::fs::write(world.join("world/region/r.0.0.mca"), b"region").unwrap();
+        let store = ObjectStore::at(temp_dir("store_restore")).unwrap();
+        let options = SnapshotOptions::default();
+
+        let old = store
+            .build_snapshot("s", &[], &[item(&world, "world/level.dat")], &options, None)
+            .unwrap();
+        std::fs::write(world.join("world/level.dat"), b"level v2").unwrap();
+        let items = vec![item(&world, "world/level.dat"), item(&world, "world/region/r.0.0.mca")];
+        let current = store
+            .build_snapshot("s", &[], &items, &options, None)
+            .unwrap();
+        store.save_manifest(&old).unwrap();
+        store.save_manifest(&current).unwrap();
+        assert!(store.verify(&current).ok);
+
+        // 删除旧快照后，旧版 level.dat 的对象应被回收
+        store.delete_manifest(&old.summary.id).unwrap();
+        let report = store.gc().unwrap();
+        assert_eq!(report.removed_objects, 1);
+        assert_eq!(report.remaining_objects, 2);
+
+        let target = temp_dir("restore_target").join("out");
+        store.restore(&current, &target).unwrap();
+        assert_eq!(std::fs::read(target.join("world/level.dat")).unwrap(), b"level v2");
+        assert_eq!(std::fs::read(target.join("world/region/r.0.0.mca")).unwrap(), b"region");
+        assert!(store.restore(&current, &target).is_err());
+
+        // 篡改对象后校验应报告损坏
+        std::fs::write(store.object_path(&current.files[1].hash), b"broken").unwrap();
+        let report = store.verify(&current);
+        assert!(!report.ok);
+        assert_eq!(report.corrupted, vec!["world/region/r.0.0.mca".to_string()]);
+    }
+}
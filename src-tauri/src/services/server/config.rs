@@ -1,6 +1,9 @@
 use std::collections::HashMap;
 use std::fs;
+use std::path::Path;
 
+use super::config_history::ConfigOrigin;
+use super::properties_schema::{property_schema, validate_properties, PropertyKind};
 use crate::models::config::*;
 
 fn parse_properties_content(content: &str) -> HashMap<String, String> {
@@ -61,15 +64,35 @@ pub fn read_raw_text(file_path: &str) -> Result<String, String> {
     fs::read_to_string(file_path).map_err(|e| format!("Failed to read file: {}", e))
 }
 
-pub fn write_raw_text(file_path: &str, content: &str) -> Result<(), String> {
+/// 写入配置文本；原内容先记入所属服务器的配置历史，记录失败时不写入
+pub fn write_raw_text(file_path: &str, content: &str, origin: &ConfigOrigin) -> Result<(), String> {
+    write_raw_bytes(file_path, content.as_bytes(), origin)
+}
+
+/// 按原始字节写入，用于恢复非 UTF-8 编码的配置
+pub fn write_raw_bytes(
+    file_path: &str,
+    content: &[u8],
+    origin: &ConfigOrigin,
+) -> Result<(), String> {
+    super::config_history::record_previous(Path::new(file_path), content, origin)?;
     fs::write(file_path, content).map_err(|e| format!("Failed to write file: {}", e))
 }
 
+/// 读取待写入文件的原内容；文件不存在时视为空文件，其余读取错误直接返回，避免覆盖无法读取的文件
+fn read_existing_text(file_path: &str) -> Result<String, String> {
+    match fs::read_to_string(file_path) {
+        Ok(content) => Ok(content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(format!("Failed to read file: {}", e)),
+    }
+}
+
 pub fn preview_properties_write(
     file_path: &str,
     values: &HashMap<String, String>,
 ) -> Result<String, String> {
-    let original = fs::read_to_string(file_path).unwrap_or_default();
+    let original = read_existing_text(file_path)?;
     Ok(render_properties_content(&original, values))
 }
 
@@ -87,92 +110,281 @@ pub fn read_properties(file_path: &str) -> Result<HashMap<String, String>, Strin
 }
 
 /// Write a HashMap to a .properties file, preserving comments
-pub fn write_properties(file_path: &str, values: &HashMap<String, String>) -> Result<(), String> {
+pub fn write_properties(
+    file_path: &str,
+    values: &HashMap<String, String>,
+    origin: &ConfigOrigin,
+) -> Result<(), String> {
     let output = preview_properties_write(file_path, values)?;
-    write_raw_text(file_path, &output)
+    write_raw_text(file_path, &output, origin)
+}
+
+/// 按扩展名识别的配置文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Properties,
+    Yaml,
+    Toml,
+}
+
+impl ConfigFormat {
+    pub fn from_path(file_path: &str) -> Self {
+        let extension = Path::new(file_path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+        match extension.as_deref() {
+            Some("yml" | "yaml") => ConfigFormat::Yaml,
+            Some("toml") => ConfigFormat::Toml,
+            _ => ConfigFormat::Properties,
+        }
+    }
+}
+
+/// 读取配置文件为扁平键值表。YAML / TOML 的嵌套键以点号连接（如 `settings.debug`），
+/// 数组等非字符串值以 JSON 文本表示。
+pub fn read_config_file(file_path: &str) -> Result<HashMap<String, String>, String> {
+    let content = read_raw_text(file_path)?;
+    match ConfigFormat::from_path(file_path) {
+        ConfigFormat::Properties => Ok(parse_properties_content(&content)),
+        ConfigFormat::Yaml => super::config_yaml::flatten(&content),
+        ConfigFormat::Toml => super::config_toml::flatten(&content),
+    }
+}
+
+/// 预览按点号路径写入后的完整文本；注释、键顺序与未修改的内容保持不变。
+pub fn preview_config_write(
+    file_path: &str,
+    values: &HashMap<String, String>,
+) -> Result<String, String> {
+    let original = read_existing_text(file_path)?;
+    preview_config_write_from_source(ConfigFormat::from_path(file_path), &original, values)
+}
+
+pub fn preview_config_write_from_source(
+    format: ConfigFormat,
+    source: &str,
+    values: &HashMap<String, String>,
+) -> Result<String, String> {
+    match format {
+        ConfigFormat::Properties => Ok(render_properties_content(source, values)),
+        ConfigFormat::Yaml => super::config_yaml::set_values(source, values),
+        ConfigFormat::Toml => super::config_toml::set_values(source, values),
+    }
+}
+
+pub fn write_config_file(
+    file_path: &str,
+    values: &HashMap<String, String>,
+    origin: &ConfigOrigin,
+) -> Result<(), String> {
+    let output = preview_config_write(file_path, values)?;
+    write_raw_text(file_path, &output, origin)
+}
+
+/// 拆分点号路径；含点号的键用双引号包裹，如 `permissions."essentials.home"`
+pub fn split_config_path(path: &str) -> Result<Vec<String>, String> {
+    let invalid = || format!("配置键路径无效: {}", path);
+    let mut segments = Vec::new();
+    let mut rest = path;
+    loop {
+        let (segment, remaining) = if rest.starts_with('"') {
+            let end = double_quoted_end(rest).ok_or_else(invalid)?;
+            let segment: String = serde_json::from_str(&rest[..end]).map_err(|_| invalid())?;
+            (segment, &rest[end..])
+        } else {
+            let end = rest.find('.').unwrap_or(rest.len());
+            if end == 0 {
+                return Err(invalid());
+            }
+            (rest[..end].to_string(), &rest[end..])
+        };
+        segments.push(segment);
+        match remaining.strip_prefix('.') {
+            Some(next) => rest = next,
+            None if remaining.is_empty() => return Ok(segments),
+            None => return Err(invalid()),
+        }
+    }
+}
+
+/// 双引号字符串（含引号）的结束位置
+pub(crate) fn double_quoted_end(text: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (i, c) in text.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return Some(i + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+pub fn join_config_path(segments: &[String]) -> String {
+    segments
+        .iter()
+        .map(|segment| {
+            if segment.is_empty() || segment.contains(['.', '"']) {
+                serde_json::to_string(segment).unwrap_or_default()
+            } else {
+                segment.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// 把解析后的文档展开为点号路径到值的映射；空表不产生条目
+pub(crate) fn flatten_config_value(value: &serde_json::Value) -> HashMap<String, String> {
+    fn walk(value: &serde_json::Value, path: &mut Vec<String>, out: &mut HashMap<String, String>) {
+        match value {
+            serde_json::Value::Object(map) if !path.is_empty() || !map.is_empty() => {
+                for (key, child) in map {
+                    path.push(key.clone());
+                    walk(child, path, out);
+                    path.pop();
+                }
+            }
+            serde_json::Value::Object(_) => {}
+            serde_json::Value::String(text) => {
+                out.insert(join_config_path(path), text.clone());
+            }
+            other => {
+                out.insert(join_config_path(path), other.to_string());
+            }
+        }
+    }
+
+    let mut out = HashMap::new();
+    walk(value, &mut Vec::new(), &mut out);
+    out
+}
+
+pub(crate) fn lookup_config_value<'a>(
+    root: &'a serde_json::Value,
+    segments: &[String],
+) -> Option<&'a serde_json::Value> {
+    segments
+        .iter()
+        .try_fold(root, |value, segment| value.as_object()?.get(segment))
+}
+
+/// 把界面提交的文本转换为带类型的值：已有键沿用原类型，新键按内容推断。
+pub(crate) fn typed_config_value(
+    path: &str,
+    input: &str,
+    existing: Option<&serde_json::Value>,
+) -> Result<serde_json::Value, String> {
+    use serde_json::Value;
+
+    let parse_bool = || match input.trim() {
+        "true" => Some(Value::Bool(true)),
+        "false" => Some(Value::Bool(false)),
+        _ => None,
+    };
+    let parse_number = || {
+        let trimmed = input.trim();
+        trimmed.parse::<i64>().map(Value::from).ok().or_else(|| {
+            trimmed
+                .parse::<f64>()
+                .ok()
+                .and_then(serde_json::Number::from_f64)
+                .map(Value::Number)
+        })
+    };
+    let parse_array = || match serde_json::from_str::<Value>(input) {
+        Ok(value @ Value::Array(_)) => Some(value),
+        _ => None,
+    };
+
+    match existing {
+        Some(Value::String(_)) => Ok(Value::String(input.to_string())),
+        Some(Value::Bool(_)) => parse_bool().ok_or_else(|| format!("{} 需要布尔值", path)),
+        Some(Value::Number(_)) => parse_number().ok_or_else(|| format!("{} 需要数字", path)),
+        Some(Value::Array(_)) => {
+            parse_array().ok_or_else(|| format!("{} 需要 JSON 数组，如 [\"a\", \"b\"]", path))
+        }
+        Some(Value::Object(_)) => Err(format!("{} 是一个表，无法设置为单个值", path)),
+        Some(Value::Null) | None => Ok(parse_bool()
+            .or_else(|| input.trim().starts_with('[').then(parse_array).flatten())
+            .or_else(|| {
+                // 只把看起来像数字的文本当作数字，避免 "1e5"、"inf" 之类被误判
+                input
+                    .trim()
+                    .chars()
+                    .all(|c| c.is_ascii_digit() || matches!(c, '-' | '.'))
+                    .then(parse_number)
+                    .flatten()
+            })
+            .unwrap_or_else(|| Value::String(input.to_string()))),
+    }
 }
 
 /// Parse server.properties with descriptions
-pub fn parse_server_properties(file_path: &str) -> Result<ServerProperties, String> {
+pub fn parse_server_properties(
+    file_path: &str,
+    mc_version: Option<&str>,
+) -> Result<ServerProperties, String> {
     let content = read_raw_text(file_path)?;
-    parse_server_properties_from_source(&content)
+    parse_server_properties_from_source(&content, mc_version)
 }
 
-pub fn parse_server_properties_from_source(source: &str) -> Result<ServerProperties, String> {
+/// 解析 server.properties 文本，并按 `mc_version` 校验各配置项
+pub fn parse_server_properties_from_source(
+    source: &str,
+    mc_version: Option<&str>,
+) -> Result<ServerProperties, String> {
     let raw = parse_properties_content(source);
     let mut entries = Vec::new();
 
-    let descriptions = get_property_descriptions();
-
     for (key, value) in &raw {
-        let desc_info = descriptions.get(key.as_str());
+        let schema = property_schema(key);
+        let (min, max, options) = match schema.map(|s| s.kind) {
+            Some(PropertyKind::Integer { min, max }) => (Some(min), Some(max), Vec::new()),
+            Some(PropertyKind::Select { options, .. }) => {
+                (None, None, options.iter().map(|o| o.to_string()).collect())
+            }
+            _ => (None, None, Vec::new()),
+        };
         entries.push(ConfigEntry {
             key: key.clone(),
             value: value.clone(),
-            description: desc_info.map(|d| d.0.to_string()).unwrap_or_default(),
-            value_type: desc_info
-                .map(|d| d.1.to_string())
+            description: schema
+                .map(|s| s.description.to_string())
+                .unwrap_or_default(),
+            value_type: schema
+                .map(|s| s.kind.type_name().to_string())
                 .unwrap_or_else(|| "string".to_string()),
-            default_value: desc_info.map(|d| d.2.to_string()).unwrap_or_default(),
-            category: desc_info
-                .map(|d| d.3.to_string())
+            default_value: schema
+                .map(|s| s.default_value.to_string())
+                .unwrap_or_default(),
+            category: schema
+                .map(|s| s.category.to_string())
                 .unwrap_or_else(|| "other".to_string()),
+            min,
+            max,
+            options,
+            since: schema.and_then(|s| s.since).map(str::to_string),
+            removed_in: schema.and_then(|s| s.removed_in).map(str::to_string),
         });
     }
 
     // Sort by category
     entries.sort_by(|a, b| a.category.cmp(&b.category).then(a.key.cmp(&b.key)));
 
-    Ok(ServerProperties { entries, raw })
-}
-
-/// Property descriptions: (description, type, default, category)
-fn get_property_descriptions(
-) -> HashMap<&'static str, (&'static str, &'static str, &'static str, &'static str)> {
-    let mut m = HashMap::new();
-    m.insert("server-port", ("服务器端口", "number", "25565", "network"));
-    m.insert("server-ip", ("服务器绑定IP，留空表示所有", "string", "", "network"));
-    m.insert("max-players", ("最大玩家数", "number", "20", "player"));
-    m.insert("online-mode", ("正版验证", "boolean", "true", "player"));
-    m.insert("white-list", ("启用白名单", "boolean", "false", "player"));
-    m.insert("enforce-whitelist", ("强制白名单", "boolean", "false", "player"));
-    m.insert("gamemode", ("默认游戏模式", "select", "survival", "game"));
-    m.insert("difficulty", ("游戏难度", "select", "easy", "game"));
-    m.insert("hardcore", ("极限模式", "boolean", "false", "game"));
-    m.insert("pvp", ("允许PVP", "boolean", "true", "game"));
-    m.insert("allow-flight", ("允许飞行", "boolean", "false", "game"));
-    m.insert("allow-nether", ("允许下界", "boolean", "true", "world"));
-    m.insert("spawn-monsters", ("生成怪物", "boolean", "true", "world"));
-    m.insert("spawn-animals", ("生成动物", "boolean", "true", "world"));
-    m.insert("spawn-npcs", ("生成NPC", "boolean", "true", "world"));
-    m.insert("generate-structures", ("生成结构", "boolean", "true", "world"));
-    m.insert("level-name", ("世界名称", "string", "world", "world"));
-    m.insert("level-seed", ("世界种子", "string", "", "world"));
-    m.insert("level-type", ("世界类型", "string", "minecraft:normal", "world"));
-    m.insert("view-distance", ("视距", "number", "10", "performance"));
-    m.insert("simulation-distance", ("模拟距离", "number", "10", "performance"));
-    m.insert(
-        "max-tick-time",
-        ("最大tick时间(ms)，-1为禁用", "number", "60000", "performance"),
-    );
-    m.insert(
-        "network-compression-threshold",
-        ("网络压缩阈值", "number", "256", "performance"),
-    );
-    m.insert("motd", ("服务器描述(MOTD)", "string", "A Minecraft Server", "display"));
-    m.insert("enable-command-block", ("启用命令方块", "boolean", "false", "game"));
-    m.insert("enable-query", ("启用Query协议", "boolean", "false", "network"));
-    m.insert("enable-rcon", ("启用RCON远程控制", "boolean", "false", "network"));
-    m.insert("enable-status", ("启用服务器列表状态", "boolean", "true", "network"));
-    m.insert("force-gamemode", ("强制游戏模式", "boolean", "false", "game"));
-    m.insert("spawn-protection", ("出生点保护半径", "number", "16", "world"));
-    m.insert("sync-chunk-writes", ("同步区块写入", "boolean", "true", "performance"));
-    m
+    let issues = validate_properties(&raw, mc_version);
+    Ok(ServerProperties { entries, raw, issues })
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{parse_properties_content, render_properties_content};
+    use super::{
+        join_config_path, parse_properties_content, preview_config_write,
+        render_properties_content, split_config_path, ConfigFormat,
+    };
+    use crate::utils::test_utils::temp_dir;
     use std::collections::HashMap;
 
     #[test]
@@ -193,4 +405,31 @@ mod tests {
 
         assert_eq!(parsed.get("motd"), Some(&"hello=world".to_string()));
     }
+
+    #[test]
+    fn config_paths_round_trip_quoted_segments() {
+        let segments = split_config_path(r#"permissions."essentials.home".default"#).unwrap();
+        assert_eq!(segments, vec!["permissions", "essentials.home", "default"]);
+        assert_eq!(join_config_path(&segments), r#"permissions."essentials.home".default"#);
+        assert!(split_config_path("settings..debug").is_err());
+        assert!(split_config_path(r#""open.quote"#).is_err());
+        assert_eq!(ConfigFormat::from_path("plugins/Essentials/config.YML"), ConfigFormat::Yaml);
+        assert_eq!(ConfigFormat::from_path("config/forge-server.toml"), ConfigFormat::Toml);
+    }
+
+    #[test]
+    fn preview_treats_only_missing_files_as_empty() {
+        let dir = temp_dir("config_preview_read");
+        let values = HashMap::from([("motd".to_string(), "Lobby".to_string())]);
+
+        let missing = dir.join("server.properties");
+        let preview = preview_config_write(missing.to_str().unwrap(), &values).unwrap();
+        assert_eq!(preview, "motd=Lobby\n");
+
+        let unreadable = dir.join("bukkit.yml");
+        std::fs::write(&unreadable, [0xff, 0xfe, 0x00]).unwrap();
+        assert!(preview_config_write(unreadable.to_str().unwrap(), &values).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
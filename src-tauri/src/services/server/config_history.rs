@@ -0,0 +1,398 @@
+//! 配置文件修改历史。
+//!
+//! 每次通过 config 服务写入配置前，把文件原有内容保存为一个修订版本，位于
+//! `<应用数据目录>/config_history/<server_id>/<修订 ID>.json`，记录相对路径、时间与来源。
+//! 内容未变化、文件原本不存在或文件不属于任何已登记服务器时不记录。
+//! 每个文件保留最近若干个修订版本。
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::{Deserialize, Serialize};
+use similar::{Algorithm, ChangeTag, TextDiff};
+
+use crate::models::config::{ConfigRevision, ConfigRevisionDiff};
+
+use super::manager::{current_timestamp_millis, current_timestamp_secs};
+
+///此处常量见 utils/constants.rs
+use crate::utils::constants::{CONFIG_HISTORY_DIR_NAME, CONFIG_HISTORY_KEEP};
+
+/// diff 中变更前后保留的上下文行数
+const DIFF_CONTEXT_LINES: usize = 3;
+/// 单次 diff 的计算时限
+const DIFF_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// 写入配置的来源
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigOrigin {
+    /// 桌面界面（Tauri 命令）
+    Ui,
+    /// HTTP API（Docker 模式下的网页端）
+    Http,
+    /// 插件，附带插件 ID
+    Plugin(String),
+    /// 应用自身的自动修改，如切换世界、创建服务器
+    System,
+}
+
+impl fmt::Display for ConfigOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigOrigin::Ui => write!(f, "ui"),
+            ConfigOrigin::Http => write!(f, "http"),
+            ConfigOrigin::Plugin(id) => write!(f, "plugin:{}", id),
+            ConfigOrigin::System => write!(f, "system"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredRevision {
+    #[serde(flatten)]
+    revision: ConfigRevision,
+    /// 文件原始字节的 base64，非 UTF-8 编码（如 GBK）的配置也能原样恢复
+    content_base64: String,
+}
+
+impl StoredRevision {
+    fn content(&self) -> Result<Vec<u8>, String> {
+        BASE64
+            .decode(&self.content_base64)
+            .map_err(|e| format!("修订版本已损坏: {}", e))
+    }
+}
+
+/// 写入 `file` 之前调用：原内容与 `new_content` 不同时保存为修订版本。
+pub fn record_previous(
+    file: &Path,
+    new_content: &[u8],
+    origin: &ConfigOrigin,
+) -> Result<Option<ConfigRevision>, String> {
+    let Some((server_id, server_dir)) = owning_server(file) else {
+        return Ok(None);
+    };
+    record_in(&history_dir(&server_id)?, &server_dir, file, new_content, origin)
+}
+
+/// 按时间倒序列出修订版本；`file` 为相对服务器目录的路径，用于只看单个文件
+pub fn list_history(server_id: &str, file: Option<&str>) -> Result<Vec<ConfigRevision>, String> {
+    list_in(&history_dir(server_id)?, file)
+}
+
+/// 修订版本相对当前文件内容的差异（`-` 为修订版本中的行，`+` 为当前文件中的行）
+pub fn diff_revision(
+    server_id: &str,
+    server_dir: &Path,
+    revision_id: &str,
+) -> Result<ConfigRevisionDiff, String> {
+    diff_in(&history_dir(server_id)?, server_dir, revision_id)
+}
+
+/// 把文件恢复为修订版本的内容；恢复前的内容同样会记入历史，因此恢复本身也可撤销。
+pub fn revert_revision(
+    server_id: &str,
+    server_dir: &Path,
+    revision_id: &str,
+    origin: &ConfigOrigin,
+) -> Result<ConfigRevision, String> {
+    let stored = load(&history_dir(server_id)?, revision_id)?;
+    let path = revision_path(server_dir, &stored.revision)?;
+    let path_str = path
+        .to_str()
+        .ok_or_else(|| format!("配置路径无效: {}", path.display()))?;
+    super::config::write_raw_bytes(path_str, &stored.content()?, origin)?;
+    Ok(stored.revision)
+}
+
+fn history_dir(server_id: &str) -> Result<PathBuf, String> {
+    if server_id.is_empty() || server_id.contains(['/', '\\']) || server_id.contains("..") {
+        return Err(format!("非法的服务器 ID: {}", server_id));
+    }
+    Ok(PathBuf::from(crate::utils::path::get_or_create_app_data_dir())
+        .join(CONFIG_HISTORY_DIR_NAME)
+        .join(server_id))
+}
+
+/// 包含该文件的已登记服务器；服务器目录相互嵌套时取最深的一个
+fn owning_server(file: &Path) -> Option<(String, PathBuf)> {
+    let parent = std::fs::canonicalize(file.parent()?).ok()?;
+    crate::services::global::server_manager()
+        .get_server_list()
+        .into_iter()
+        .filter_map(|server| {
+            let dir = std::fs::canonicalize(&server.path).ok()?;
+            parent.starts_with(&dir).then_some((server.id, dir))
+        })
+        .max_by_key(|(_, dir)| dir.components().count())
+}
+
+fn record_in(
+    history_dir: &Path,
+    server_dir: &Path,
+    file: &Path,
+    new_content: &[u8],
+    origin: &ConfigOrigin,
+) -> Result<Option<ConfigRevision>, String> {
+    let Ok(previous) = std::fs::read(file) else {
+        return Ok(None);
+    };
+    if previous == new_content {
+        return Ok(None);
+    }
+    let relative = relative_path(server_dir, file)?;
+
+    let stored = StoredRevision {
+        revision: ConfigRevision {
+            id: format!(
+                "{:016}-{}",
+                next_revision_stamp(),
+                &uuid::Uuid::new_v4().simple().to_string()[..8]
+            ),
+            file: relative.clone(),
+            created_at: current_timestamp_secs(),
+            origin: origin.to_string(),
+            size_bytes: previous.len() as u64,
+        },
+        content_base64: BASE64.encode(&previous),
+    };
+
+    std::fs::create_dir_all(history_dir).map_err(|e| format!("创建配置历史目录失败: {}", e))?;
+    let json = serde_json::to_vec(&stored).map_err(|e| format!("序列化配置历史失败: {}", e))?;
+    let target = history_dir.join(format!("{}.json", stored.revision.id));
+    let tmp = target.with_extension("json.tmp");
+    std::fs::write(&tmp, json).map_err(|e| format!("写入配置历史失败: {}", e))?;
+    std::fs::rename(&tmp, &target).map_err(|e| {
+        let _ = std::fs::remove_file(&tmp);
+        format!("写入配置历史失败: {}", e)
+    })?;
+
+    // 只清理同一文件的旧修订版本
+    for old in list_in(history_dir, Some(&relative))?
+        .iter()
+        .skip(CONFIG_HISTORY_KEEP)
+    {
+        let _ = std::fs::remove_file(history_dir.join(format!("{}.json", old.id)));
+    }
+    Ok(Some(stored.revision))
+}
+
+fn list_in(history_dir: &Path, file: Option<&str>) -> Result<Vec<ConfigRevision>, String> {
+    let Ok(entries) = std::fs::read_dir(history_dir) else {
+        return Ok(Vec::new());
+    };
+    let file = file.map(normalize_relative);
+    let mut revisions: Vec<ConfigRevision> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|e| std::fs::read(e.path()).ok())
+        .filter_map(|data| serde_json::from_slice::<StoredRevision>(&data).ok())
+        .map(|stored| stored.revision)
+        .filter(|revision| file.as_ref().is_none_or(|file| &revision.file == file))
+        .collect();
+    // ID 以定长的毫秒时间戳开头，按 ID 倒序即按时间倒序
+    revisions.sort_by(|a, b| b.id.cmp(&a.id));
+    Ok(revisions)
+}
+
+/// 修订 ID 的时间部分（毫秒）；同一毫秒内的多次写入依次加一，保证 ID 顺序即写入顺序
+fn next_revision_stamp() -> i64 {
+    static LAST: Mutex<i64> = Mutex::new(0);
+    let mut last = LAST.lock().unwrap_or_else(|e| e.into_inner());
+    *last = current_timestamp_millis().max(*last + 1);
+    *last
+}
+
+fn load(history_dir: &Path, revision_id: &str) -> Result<StoredRevision, String> {
+    if revision_id.is_empty()
+        || !revision_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+    {
+        return Err(format!("非法的修订版本 ID: {}", revision_id));
+    }
+    let data = std::fs::read(history_dir.join(format!("{}.json", revision_id)))
+        .map_err(|_| format!("修订版本不存在: {}", revision_id))?;
+    serde_json::from_slice(&data).map_err(|e| format!("修订版本已损坏: {}", e))
+}
+
+fn diff_in(
+    history_dir: &Path,
+    server_dir: &Path,
+    revision_id: &str,
+) -> Result<ConfigRevisionDiff, String> {
+    let stored = load(history_dir, revision_id)?;
+    let path = revision_path(server_dir, &stored.revision)?;
+    let current = std::fs::read(&path).unwrap_or_default();
+
+    // diff 只用于展示，非 UTF-8 内容按有损解码比较
+    let previous = stored.content()?;
+    let (added, removed, unified) = unified_diff(
+        &String::from_utf8_lossy(&previous),
+        &String::from_utf8_lossy(&current),
+        &format!("{}@{}", stored.revision.file, stored.revision.id),
+        &stored.revision.file,
+    );
+    Ok(ConfigRevisionDiff {
+        revision: stored.revision,
+        added,
+        removed,
+        unified,
+    })
+}
+
+/// 修订版本对应的文件路径，拒绝越出服务器目录的记录
+fn revision_path(server_dir: &Path, revision: &ConfigRevision) -> Result<PathBuf, String> {
+    let relative = Path::new(&revision.file);
+    if relative.is_absolute()
+        || relative
+            .components()
+            .any(|c| !matches!(c, std::path::Component::Normal(_)))
+    {
+        return Err(format!("修订版本中的路径无效: {}", revision.file));
+    }
+    Ok(server_dir.join(relative))
+}
+
+fn relative_path(server_dir: &Path, file: &Path) -> Result<String, String> {
+    let server_dir = std::fs::canonicalize(server_dir).unwrap_or_else(|_| server_dir.to_path_buf());
+    let file = file
+        .parent()
+        .and_then(|parent| std::fs::canonicalize(parent).ok())
+        .zip(file.file_name())
+        .map(|(parent, name)| parent.join(name))
+        .unwrap_or_else(|| file.to_path_buf());
+    let relative = file
+        .strip_prefix(&server_dir)
+        .map_err(|_| format!("配置文件不在服务器目录内: {}", file.display()))?;
+    Ok(normalize_relative(&relative.to_string_lossy()))
+}
+
+fn normalize_relative(path: &str) -> String {
+    path.replace('\\', "/").trim_start_matches("./").to_string()
+}
+
+/// 逐行 diff（Myers 算法），返回新增行数、删除行数与 unified diff 文本；
+/// 超过时限时退化为较粗的结果
+fn unified_diff(old: &str, new: &str, old_name: &str, new_name: &str) -> (usize, usize, String) {
+    let diff = TextDiff::configure()
+        .algorithm(Algorithm::Myers)
+        .deadline(Instant::now() + DIFF_TIMEOUT)
+        .diff_lines(old, new);
+    let (added, removed) = diff
+        .iter_all_changes()
+        .fold((0, 0), |(added, removed), change| match change.tag() {
+            ChangeTag::Insert => (added + 1, removed),
+            ChangeTag::Delete => (added, removed + 1),
+            ChangeTag::Equal => (added, removed),
+        });
+    let unified = diff
+        .unified_diff()
+        .context_radius(DIFF_CONTEXT_LINES)
+        .header(old_name, new_name)
+        .to_string();
+    (added, removed, unified)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_utils::temp_dir;
+
+    fn temp_dirs(name: &str) -> (PathBuf, PathBuf) {
+        let root = temp_dir(name);
+        let server = root.join("server");
+        std::fs::create_dir_all(server.join("config")).unwrap();
+        (root.join("history"), server)
+    }
+
+    #[test]
+    fn records_lists_diffs_and_prunes_revisions() {
+        let (history, server) = temp_dirs("record");
+        let file = server.join("config/paper-global.yml");
+        assert!(record_in(&history, &server, &file, b"a", &ConfigOrigin::Ui)
+            .unwrap()
+            .is_none());
+
+        std::fs::write(&file, "one\ntwo\nthree\n").unwrap();
+        assert!(record_in(&history, &server, &file, b"one\ntwo\nthree\n", &ConfigOrigin::Ui)
+            .unwrap()
+            .is_none());
+        let first = record_in(
+            &history,
+            &server,
+            &file,
+            b"one\n2\nthree\n",
+            &ConfigOrigin::Plugin("backup-helper".to_string()),
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(first.file, "config/paper-global.yml");
+        assert_eq!(first.origin, "plugin:backup-helper");
+        std::fs::write(&file, "one\n2\nthree\nfour\n").unwrap();
+
+        let diff = diff_in(&history, &server, &first.id).unwrap();
+        assert_eq!((diff.added, diff.removed), (2, 1));
+        assert_eq!(
+            diff.unified,
+            format!(
+                "--- config/paper-global.yml@{}\n+++ config/paper-global.yml\n\
+                 @@ -1,3 +1,4 @@\n one\n-two\n+2\n three\n+four\n",
+                first.id
+            )
+        );
+
+        for i in 0..CONFIG_HISTORY_KEEP + 2 {
+            record_in(&history, &server, &file, format!("v{}", i).as_bytes(), &ConfigOrigin::Http)
+                .unwrap();
+            std::fs::write(&file, format!("v{}", i)).unwrap();
+        }
+        let revisions = list_in(&history, Some("config/paper-global.yml")).unwrap();
+        assert_eq!(revisions.len(), CONFIG_HISTORY_KEEP);
+        assert!(revisions.windows(2).all(|w| w[0].id > w[1].id));
+        assert!(list_in(&history, Some("server.properties"))
+            .unwrap()
+            .is_empty());
+        assert!(load(&history, "../escape").is_err());
+
+        let _ = std::fs::remove_dir_all(history.parent().unwrap());
+    }
+
+    #[test]
+    fn keeps_non_utf8_content_byte_for_byte() {
+        let (history, server) = temp_dirs("non_utf8");
+        let file = server.join("server.properties");
+        // GBK 编码的 "motd=海灯"
+        let gbk: &[u8] = b"motd=\xba\xa3\xb5\xc6\n";
+        std::fs::write(&file, gbk).unwrap();
+
+        let revision = record_in(&history, &server, &file, b"motd=A\n", &ConfigOrigin::Ui)
+            .unwrap()
+            .unwrap();
+        assert_eq!(revision.size_bytes, gbk.len() as u64);
+        assert_eq!(load(&history, &revision.id).unwrap().content().unwrap(), gbk);
+
+        std::fs::write(&file, "motd=A\n").unwrap();
+        let diff = diff_in(&history, &server, &revision.id).unwrap();
+        assert_eq!((diff.added, diff.removed), (1, 1));
+
+        let _ = std::fs::remove_dir_all(history.parent().unwrap());
+    }
+
+    #[test]
+    fn unified_diff_separates_distant_hunks() {
+        let old: Vec<String> = (1..=20).map(|i| format!("line{}", i)).collect();
+        let mut new = old.clone();
+        new[1] = "changed2".to_string();
+        new.remove(17);
+        let (added, removed, hunks) =
+            unified_diff(&(old.join("\n") + "\n"), &(new.join("\n") + "\n"), "a", "b");
+        assert_eq!((added, removed), (1, 2));
+        assert_eq!(hunks.matches("@@ -").count(), 2);
+        assert!(hunks.starts_with("--- a\n+++ b\n@@ -1,5 +1,5 @@\n line1\n-line2\n+changed2\n"));
+        assert!(hunks.contains("@@ -15,6 +15,5 @@\n line15\n line16\n line17\n-line18\n"));
+    }
+}
@@ -0,0 +1,510 @@
+//! 多台服务器之间的配置对比、同步与预设。
+//!
+//! 比较与写入的单位称为 section：服务器目录下配置文件的相对路径（`server.properties`、
+//! `spigot.yml`、`config/paper-global.yml` 等），或表示服务器实例设置的 `@instance`。
+//! 写入配置文件沿用 config 服务，因此同步与应用预设同样会记入配置历史。
+//! 对单台服务器先完成全部校验与预览，再统一写入；任何一步失败时该服务器保持不变。
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Component, Path, PathBuf};
+
+use super::config;
+use super::config_history::ConfigOrigin;
+use super::manager::current_timestamp_secs;
+use super::properties_schema::validate_properties;
+use crate::models::config::{
+    ConfigComparison, ConfigComparisonEntry, ConfigPreset, ConfigSyncResult, PropertyIssue,
+    PropertyIssueSeverity,
+};
+use crate::models::server::ServerInstance;
+
+///此处常量见 utils/constants.rs
+use crate::utils::constants::CONFIG_PRESETS_FILE;
+
+/// 服务器实例设置（Java 路径、内存、JVM 参数等）对应的 section
+pub const INSTANCE_SECTION: &str = "@instance";
+
+/// 未指定 section 时比较的内容
+const DEFAULT_SECTIONS: [&str; 2] = ["server.properties", INSTANCE_SECTION];
+
+/// `@instance` 中可以同步的键；其余键（核心类型、版本、端口等）只参与比较
+const INSTANCE_SYNC_KEYS: [&str; 4] = ["java_path", "max_memory", "min_memory", "jvm_args"];
+
+/// 对比多台服务器的配置；`sections` 为空时比较 server.properties 与实例设置
+pub fn compare_configs(
+    server_ids: &[String],
+    sections: &[String],
+) -> Result<ConfigComparison, String> {
+    if server_ids.len() < 2 {
+        return Err("至少需要选择两台服务器进行对比".to_string());
+    }
+    let servers = resolve_servers(server_ids)?;
+    let sections: Vec<String> = if sections.is_empty() {
+        DEFAULT_SECTIONS.iter().map(|s| s.to_string()).collect()
+    } else {
+        sections.to_vec()
+    };
+
+    let mut entries = Vec::new();
+    for section in &sections {
+        let maps = servers
+            .iter()
+            .map(|server| read_section(server, section))
+            .collect::<Result<Vec<_>, _>>()?;
+        entries.extend(compare_section(section, &maps));
+    }
+    Ok(ConfigComparison {
+        server_ids: server_ids.to_vec(),
+        sections,
+        entries,
+    })
+}
+
+/// 把源服务器某个 section 中选定的键推送到目标服务器
+pub fn sync_config_keys(
+    source_id: &str,
+    target_ids: &[String],
+    section: &str,
+    keys: &[String],
+    origin: &ConfigOrigin,
+) -> Result<Vec<ConfigSyncResult>, String> {
+    if keys.is_empty() {
+        return Err("未选择要同步的配置项".to_string());
+    }
+    let source = resolve_servers(&[source_id.to_string()])?.remove(0);
+    let source_values = read_section(&source, section)?;
+    let values = keys
+        .iter()
+        .map(|key| {
+            source_values
+                .get(key)
+                .map(|value| (key.clone(), value.clone()))
+                .ok_or_else(|| format!("源服务器的 {} 中没有 {}", section, key))
+        })
+        .collect::<Result<HashMap<_, _>, _>>()?;
+    validate_section(section, &values)?;
+
+    let sections = HashMap::from([(section.to_string(), values)]);
+    let targets = resolve_servers(target_ids)?;
+    Ok(targets
+        .iter()
+        .filter(|target| target.id != source.id)
+        .map(|target| apply_to_server(target, &sections, origin))
+        .collect())
+}
+
+pub fn list_presets() -> Vec<ConfigPreset> {
+    load_presets(&presets_path())
+}
+
+/// 新建或覆盖同名预设
+pub fn save_preset(preset: ConfigPreset) -> Result<ConfigPreset, String> {
+    save_preset_in(&presets_path(), preset)
+}
+
+pub fn delete_preset(name: &str) -> Result<(), String> {
+    delete_preset_in(&presets_path(), name)
+}
+
+/// 把预设应用到多台服务器
+pub fn apply_preset(
+    name: &str,
+    server_ids: &[String],
+    origin: &ConfigOrigin,
+) -> Result<Vec<ConfigSyncResult>, String> {
+    let preset = list_presets()
+        .into_iter()
+        .find(|preset| preset.name == name)
+        .ok_or_else(|| format!("未找到配置预设: {}", name))?;
+    let servers = resolve_servers(server_ids)?;
+    Ok(servers
+        .iter()
+        .map(|server| apply_to_server(server, &preset.sections, origin))
+        .collect())
+}
+
+fn resolve_servers(server_ids: &[String]) -> Result<Vec<ServerInstance>, String> {
+    let servers = crate::services::global::server_manager().get_server_list();
+    server_ids
+        .iter()
+        .map(|id| {
+            servers
+                .iter()
+                .find(|server| &server.id == id)
+                .cloned()
+                .ok_or_else(|| format!("未找到服务器: {}", id))
+        })
+        .collect()
+}
+
+/// 配置文件 section 在服务器目录下的路径；只允许不含 `..` 的相对路径
+fn section_path(server_dir: &Path, section: &str) -> Result<PathBuf, String> {
+    let relative = Path::new(section);
+    if section.is_empty()
+        || relative
+            .components()
+            .any(|component| !matches!(component, Component::Normal(_)))
+    {
+        return Err(format!("配置文件必须是服务器目录内的相对路径: {}", section));
+    }
+    Ok(server_dir.join(relative))
+}
+
+fn read_section(server: &ServerInstance, section: &str) -> Result<HashMap<String, String>, String> {
+    if section == INSTANCE_SECTION {
+        return Ok(instance_values(server));
+    }
+    let path = section_path(Path::new(&server.path), section)?;
+    if !path.is_file() {
+        return Ok(HashMap::new());
+    }
+    config::read_config_file(&path.to_string_lossy())
+        .map_err(|e| format!("读取 {} 的 {} 失败: {}", server.name, section, e))
+}
+
+fn compare_section(section: &str, maps: &[HashMap<String, String>]) -> Vec<ConfigComparisonEntry> {
+    let keys: BTreeSet<&String> = maps.iter().flat_map(|map| map.keys()).collect();
+    keys.into_iter()
+        .map(|key| {
+            let values: Vec<Option<String>> =
+                maps.iter().map(|map| map.get(key).cloned()).collect();
+            let differs = values.iter().any(|value| value != &values[0]);
+            ConfigComparisonEntry {
+                section: section.to_string(),
+                key: key.clone(),
+                values,
+                differs,
+            }
+        })
+        .collect()
+}
+
+fn instance_values(server: &ServerInstance) -> HashMap<String, String> {
+    [
+        ("core_type", server.core_type.clone()),
+        ("core_version", server.core_version.clone()),
+        ("mc_version", server.mc_version.clone()),
+        ("startup_mode", server.startup_mode.clone()),
+        ("port", server.port.to_string()),
+        ("java_path", server.java_path.clone()),
+        ("max_memory", server.max_memory.to_string()),
+        ("min_memory", server.min_memory.to_string()),
+        ("jvm_args", serde_json::to_string(&server.jvm_args).unwrap_or_default()),
+    ]
+    .into_iter()
+    .map(|(key, value)| (key.to_string(), value))
+    .collect()
+}
+
+fn apply_instance_values(
+    server: &mut ServerInstance,
+    values: &HashMap<String, String>,
+) -> Result<(), String> {
+    let memory = |key: &str, value: &str| {
+        value
+            .trim()
+            .parse::<u32>()
+            .ok()
+            .filter(|mb| *mb > 0)
+            .ok_or_else(|| format!("{} 需要正整数（MB），实际为 \"{}\"", key, value))
+    };
+    for (key, value) in values {
+        match key.as_str() {
+            "java_path" => server.java_path = value.trim().to_string(),
+            "max_memory" => server.max_memory = memory(key, value)?,
+            "min_memory" => server.min_memory = memory(key, value)?,
+            "jvm_args" => {
+                server.jvm_args = serde_json::from_str(value).map_err(|_| {
+                    "jvm_args 需要 JSON 字符串数组，如 [\"-XX:+UseG1GC\"]".to_string()
+                })?
+            }
+            other => return Err(format!("实例设置 {} 不支持同步", other)),
+        }
+    }
+    if server.min_memory > server.max_memory {
+        return Err("最小内存不能大于最大内存".to_string());
+    }
+    Ok(())
+}
+
+/// 不依赖具体服务器的检查：路径合法、实例设置只含可同步的键
+fn validate_section(section: &str, values: &HashMap<String, String>) -> Result<(), String> {
+    if section == INSTANCE_SECTION {
+        if let Some(key) = values
+            .keys()
+            .find(|key| !INSTANCE_SYNC_KEYS.contains(&key.as_str()))
+        {
+            return Err(format!("实例设置 {} 不支持同步", key));
+        }
+        return Ok(());
+    }
+    section_path(Path::new(""), section).map(|_| ())
+}
+
+fn apply_to_server(
+    server: &ServerInstance,
+    sections: &HashMap<String, HashMap<String, String>>,
+    origin: &ConfigOrigin,
+) -> ConfigSyncResult {
+    let mut issues = Vec::new();
+    let result = apply_sections(server, sections, origin, &mut issues);
+    ConfigSyncResult {
+        server_id: server.id.clone(),
+        applied: result.as_ref().copied().unwrap_or(0),
+        issues,
+        error: result.err(),
+    }
+}
+
+fn apply_sections(
+    server: &ServerInstance,
+    sections: &HashMap<String, HashMap<String, String>>,
+    origin: &ConfigOrigin,
+    issues: &mut Vec<PropertyIssue>,
+) -> Result<usize, String> {
+    let mut names: Vec<&String> = sections.keys().collect();
+    names.sort();
+
+    let mut instance = None;
+    let mut outputs = Vec::new();
+    for name in names {
+        let values = &sections[name];
+        validate_section(name, values)?;
+        if name == INSTANCE_SECTION {
+            let mut updated = server.clone();
+            apply_instance_values(&mut updated, values)?;
+            instance = Some(values);
+            continue;
+        }
+
+        let path = section_path(Path::new(&server.path), name)?;
+        if name == "server.properties" {
+            let found = validate_properties(values, Some(&server.mc_version));
+            let invalid = found
+                .iter()
+                .any(|issue| issue.severity == PropertyIssueSeverity::Error);
+            issues.extend(found);
+            if invalid {
+                return Err("server.properties 存在不合法的值，未做任何修改".to_string());
+            }
+        }
+        let path = path.to_string_lossy().to_string();
+        let output =
+            config::preview_config_write(&path, values).map_err(|e| format!("{}: {}", name, e))?;
+        outputs.push((path, output));
+    }
+
+    // 记下原内容，之后任一步失败时恢复已写入的文件，保证服务器保持不变
+    let originals: Vec<Option<Vec<u8>>> = outputs
+        .iter()
+        .map(|(path, _)| std::fs::read(path).ok())
+        .collect();
+    let mut written = 0;
+    let mut result = Ok(());
+    for (path, output) in &outputs {
+        // 写入失败的文件可能只写了一部分，同样需要恢复
+        written += 1;
+        result = config::write_raw_text(path, output, origin);
+        if result.is_err() {
+            break;
+        }
+    }
+    if let (Ok(()), Some(values)) = (&result, instance) {
+        result = crate::services::global::server_manager()
+            .update_server_instance(&server.id, |instance| apply_instance_values(instance, values))
+            .map(|_| ());
+    }
+    if let Err(err) = result {
+        return Err(restore_files(&outputs[..written], &originals, err));
+    }
+    Ok(sections.values().map(HashMap::len).sum())
+}
+
+/// 把文件恢复为写入前的内容，原本不存在的文件直接删除；恢复失败的文件附加到错误信息中
+fn restore_files(
+    outputs: &[(String, String)],
+    originals: &[Option<Vec<u8>>],
+    mut error: String,
+) -> String {
+    for ((path, _), original) in outputs.iter().zip(originals) {
+        let restored = match original {
+            Some(content) => std::fs::write(path, content),
+            None => std::fs::remove_file(path),
+        };
+        if let Err(e) = restored {
+            error.push_str(&format!("；恢复 {} 失败: {}", path, e));
+        }
+    }
+    error
+}
+
+fn presets_path() -> PathBuf {
+    PathBuf::from(crate::utils::path::get_or_create_app_data_dir()).join(CONFIG_PRESETS_FILE)
+}
+
+fn load_presets(path: &Path) -> Vec<ConfigPreset> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_presets(path: &Path, presets: &[ConfigPreset]) -> Result<(), String> {
+    let json =
+        serde_json::to_string_pretty(presets).map_err(|e| format!("序列化配置预设失败: {}", e))?;
+    std::fs::write(path, json).map_err(|e| format!("保存配置预设失败: {}", e))
+}
+
+fn save_preset_in(path: &Path, mut preset: ConfigPreset) -> Result<ConfigPreset, String> {
+    preset.name = preset.name.trim().to_string();
+    if preset.name.is_empty() {
+        return Err("预设名称不能为空".to_string());
+    }
+    preset.sections.retain(|_, values| !values.is_empty());
+    if preset.sections.is_empty() {
+        return Err("预设中没有任何配置项".to_string());
+    }
+    for (section, values) in &preset.sections {
+        validate_section(section, values)?;
+    }
+    preset.updated_at = current_timestamp_secs();
+
+    let mut presets = load_presets(path);
+    match presets.iter_mut().find(|p| p.name == preset.name) {
+        Some(existing) => *existing = preset.clone(),
+        None => presets.push(preset.clone()),
+    }
+    presets.sort_by(|a, b| a.name.cmp(&b.name));
+    write_presets(path, &presets)?;
+    Ok(preset)
+}
+
+fn delete_preset_in(path: &Path, name: &str) -> Result<(), String> {
+    let mut presets = load_presets(path);
+    let before = presets.len();
+    presets.retain(|preset| preset.name != name);
+    if presets.len() == before {
+        return Err(format!("未找到配置预设: {}", name));
+    }
+    write_presets(path, &presets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_utils::temp_dir;
+
+    fn map(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn instance() -> ServerInstance {
+        serde_json::from_value(serde_json::json!({
+            "id": "lobby-1",
+            "name": "Lobby 1",
+            "core_type": "paper",
+            "core_version": "1.21.4",
+            "mc_version": "1.21.4",
+            "path": "/srv/lobby-1",
+            "jar_path": "/srv/lobby-1/server.jar",
+            "java_path": "java",
+            "max_memory": 2048,
+            "min_memory": 1024,
+            "jvm_args": ["-XX:+UseG1GC"],
+            "port": 25565,
+            "created_at": 0,
+            "last_started_at": null
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn compares_keys_across_servers() {
+        let entries = compare_section(
+            "server.properties",
+            &[
+                map(&[("motd", "Lobby"), ("max-players", "50")]),
+                map(&[("motd", "Lobby"), ("max-players", "80")]),
+                map(&[("motd", "Lobby")]),
+            ],
+        );
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].key, "max-players");
+        assert!(entries[0].differs);
+        assert_eq!(entries[0].values[2], None);
+        assert_eq!(entries[1].key, "motd");
+        assert!(!entries[1].differs);
+    }
+
+    #[test]
+    fn applies_only_syncable_instance_settings() {
+        let source = instance();
+        let values = instance_values(&source);
+        assert_eq!(values["jvm_args"], "[\"-XX:+UseG1GC\"]");
+
+        let mut target = instance();
+        apply_instance_values(
+            &mut target,
+            &map(&[("max_memory", "4096"), ("jvm_args", "[\"-Xss2m\"]")]),
+        )
+        .unwrap();
+        assert_eq!(target.max_memory, 4096);
+        assert_eq!(target.jvm_args, vec!["-Xss2m"]);
+
+        assert!(apply_instance_values(&mut target, &map(&[("min_memory", "8192")])).is_err());
+        assert!(apply_instance_values(&mut target, &map(&[("jvm_args", "-Xss2m")])).is_err());
+        assert!(validate_section(INSTANCE_SECTION, &map(&[("port", "25566")])).is_err());
+        assert!(validate_section("../other/server.properties", &map(&[("a", "b")])).is_err());
+        assert!(validate_section("config/paper-global.yml", &map(&[("a", "b")])).is_ok());
+    }
+
+    #[test]
+    fn restores_files_after_failed_sync() {
+        let dir = temp_dir("config_sync_restore");
+        let existing = dir.join("server.properties").to_string_lossy().to_string();
+        let created = dir.join("bukkit.yml").to_string_lossy().to_string();
+        std::fs::write(&existing, "motd=Changed\n").unwrap();
+        std::fs::write(&created, "settings: {}\n").unwrap();
+
+        let outputs = vec![(existing.clone(), String::new()), (created.clone(), String::new())];
+        let error = restore_files(&outputs, &[Some(b"motd=Lobby\n".to_vec()), None], "失败".into());
+        assert_eq!(error, "失败");
+        assert_eq!(std::fs::read_to_string(&existing).unwrap(), "motd=Lobby\n");
+        assert!(!std::path::Path::new(&created).exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn saves_and_deletes_presets() {
+        let dir = temp_dir("config_presets");
+        let path = dir.join(CONFIG_PRESETS_FILE);
+
+        let preset = |name: &str, motd: &str| ConfigPreset {
+            name: name.to_string(),
+            description: String::new(),
+            sections: HashMap::from([("server.properties".to_string(), map(&[("motd", motd)]))]),
+            updated_at: 0,
+        };
+        save_preset_in(&path, preset(" lobby ", "Lobby")).unwrap();
+        save_preset_in(&path, preset("lobby", "Welcome")).unwrap();
+        save_preset_in(&path, preset("event", "Event")).unwrap();
+
+        let presets = load_presets(&path);
+        assert_eq!(
+            presets.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(),
+            vec!["event", "lobby"]
+        );
+        assert_eq!(presets[1].sections["server.properties"]["motd"], "Welcome");
+        assert!(save_preset_in(&path, preset("  ", "x")).is_err());
+
+        delete_preset_in(&path, "event").unwrap();
+        assert!(delete_preset_in(&path, "event").is_err());
+        assert_eq!(load_presets(&path).len(), 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,218 @@
+//! TOML 配置（velocity.toml、Forge `*-server.toml` 等）的读取与保留结构的写入。
+//!
+//! 基于 toml_edit 的文档模型，修改值时保留原有的注释、空白与键顺序；
+//! 替换已有值时沿用其前后缀装饰（如行尾注释），日期时间类型的值仍按日期时间写回。
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+use toml_edit::{DocumentMut, Item, Table};
+
+use super::config::{
+    flatten_config_value, join_config_path, lookup_config_value, split_config_path,
+    typed_config_value,
+};
+
+pub fn flatten(source: &str) -> Result<HashMap<String, String>, String> {
+    Ok(flatten_config_value(&table_to_json(parse(source)?.as_table())))
+}
+
+/// 按点号路径写入多个值，返回新的完整文本
+pub fn set_values(source: &str, values: &HashMap<String, String>) -> Result<String, String> {
+    let mut document = parse(source)?;
+    let existing = table_to_json(document.as_table());
+
+    let mut keys: Vec<&String> = values.keys().collect();
+    keys.sort();
+    for key in keys {
+        let segments = split_config_path(key)?;
+        let path = join_config_path(&segments);
+        set_value(&mut document, &segments, &path, &values[key], &existing)?;
+    }
+    Ok(document.to_string())
+}
+
+fn parse(source: &str) -> Result<DocumentMut, String> {
+    source
+        .parse::<DocumentMut>()
+        .map_err(|e| format!("TOML 解析失败: {}", e))
+}
+
+fn set_value(
+    document: &mut DocumentMut,
+    segments: &[String],
+    path: &str,
+    input: &str,
+    existing: &Value,
+) -> Result<(), String> {
+    let (last, parents) = segments
+        .split_last()
+        .ok_or_else(|| format!("配置键路径无效: {}", path))?;
+
+    let mut table: &mut dyn toml_edit::TableLike = document.as_table_mut();
+    for (depth, segment) in parents.iter().enumerate() {
+        let item = table.entry(segment).or_insert_with(|| {
+            let mut child = Table::new();
+            child.set_implicit(true);
+            Item::Table(child)
+        });
+        table = item.as_table_like_mut().ok_or_else(|| {
+            format!("{} 不是表，无法添加子键", join_config_path(&segments[..=depth]))
+        })?;
+    }
+
+    match table.get_mut(last) {
+        Some(item) => {
+            let old = item
+                .as_value_mut()
+                .ok_or_else(|| format!("{} 是一个表，无法设置为单个值", path))?;
+            let mut new = if old.is_datetime() {
+                input
+                    .trim()
+                    .parse::<toml_edit::Datetime>()
+                    .map(toml_edit::Value::from)
+                    .map_err(|_| format!("{} 需要日期时间，如 1979-05-27T07:32:00Z", path))?
+            } else {
+                let value =
+                    typed_config_value(path, input, lookup_config_value(existing, segments))?;
+                json_to_toml(path, &value)?
+            };
+            *new.decor_mut() = old.decor().clone();
+            *old = new;
+        }
+        None => {
+            let value = typed_config_value(path, input, None)?;
+            table.insert(last, Item::Value(json_to_toml(path, &value)?));
+        }
+    }
+    Ok(())
+}
+
+fn json_to_toml(path: &str, value: &Value) -> Result<toml_edit::Value, String> {
+    Ok(match value {
+        Value::String(text) => text.as_str().into(),
+        Value::Bool(b) => (*b).into(),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => i.into(),
+            None => n.as_f64().unwrap_or_default().into(),
+        },
+        Value::Array(items) => {
+            let mut array = toml_edit::Array::new();
+            for item in items {
+                array.push(json_to_toml(path, item)?);
+            }
+            array.into()
+        }
+        Value::Object(map) => {
+            let mut table = toml_edit::InlineTable::new();
+            for (key, item) in map {
+                table.insert(key, json_to_toml(path, item)?);
+            }
+            table.into()
+        }
+        Value::Null => return Err(format!("{} 的值不能为 null，TOML 不支持空值", path)),
+    })
+}
+
+fn table_to_json(table: &dyn toml_edit::TableLike) -> Value {
+    Value::Object(
+        table
+            .iter()
+            .map(|(key, item)| (key.to_string(), item_to_json(item)))
+            .collect(),
+    )
+}
+
+fn item_to_json(item: &Item) -> Value {
+    match item {
+        Item::None => Value::Null,
+        Item::Value(value) => value_to_json(value),
+        Item::Table(table) => table_to_json(table),
+        Item::ArrayOfTables(tables) => {
+            Value::Array(tables.iter().map(|table| table_to_json(table)).collect())
+        }
+    }
+}
+
+fn value_to_json(value: &toml_edit::Value) -> Value {
+    match value {
+        toml_edit::Value::String(s) => Value::String(s.value().clone()),
+        toml_edit::Value::Integer(i) => Value::from(*i.value()),
+        toml_edit::Value::Float(f) => serde_json::Number::from_f64(*f.value())
+            .map(Value::Number)
+            .unwrap_or_else(|| Value::String(f.value().to_string())),
+        toml_edit::Value::Boolean(b) => Value::Bool(*b.value()),
+        toml_edit::Value::Datetime(d) => Value::String(d.value().to_string()),
+        toml_edit::Value::Array(items) => Value::Array(items.iter().map(value_to_json).collect()),
+        toml_edit::Value::InlineTable(table) => table_to_json(table),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VELOCITY: &str = r#"# Config version. Do not change this
+config-version = "2.7"
+
+# What port should the proxy be bound to?
+bind = "0.0.0.0:25577"
+show-max-players = 500 # shown in the server list
+online-mode = true
+
+[servers]
+lobby = "127.0.0.1:30066"
+try = ["lobby"]
+
+[forced-hosts]
+"lobby.example.com" = ["lobby"]
+
+[advanced]
+compression-threshold = 256
+"#;
+
+    fn set(source: &str, pairs: &[(&str, &str)]) -> Result<String, String> {
+        let values = pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        set_values(source, &values)
+    }
+
+    #[test]
+    fn flattens_tables_and_quoted_keys() {
+        let values = flatten(VELOCITY).unwrap();
+        assert_eq!(values["bind"], "0.0.0.0:25577");
+        assert_eq!(values["show-max-players"], "500");
+        assert_eq!(values["servers.try"], "[\"lobby\"]");
+        assert_eq!(values["forced-hosts.\"lobby.example.com\""], "[\"lobby\"]");
+    }
+
+    #[test]
+    fn updates_values_preserving_comments() {
+        let output = set(
+            VELOCITY,
+            &[
+                ("show-max-players", "100"),
+                ("online-mode", "false"),
+                ("servers.try", "[\"lobby\", \"survival\"]"),
+                ("forced-hosts.\"lobby.example.com\"", "[\"hub\"]"),
+                ("advanced.login-ratelimit", "3000"),
+                ("query.enabled", "true"),
+            ],
+        )
+        .unwrap();
+        assert!(output.starts_with("# Config version. Do not change this\n"));
+        assert!(output.contains("# What port should the proxy be bound to?\n"));
+        assert!(output.contains("show-max-players = 100 # shown in the server list\n"));
+        assert!(output.contains("online-mode = false\n"));
+        assert!(output.contains("try = [\"lobby\", \"survival\"]\n"));
+        assert!(output.contains("\"lobby.example.com\" = [\"hub\"]\n"));
+        assert!(output.contains("compression-threshold = 256\nlogin-ratelimit = 3000\n"));
+        assert!(output.contains("[query]\nenabled = true\n"));
+
+        assert!(set(VELOCITY, &[("online-mode", "maybe")]).is_err());
+        assert!(set(VELOCITY, &[("servers", "x")]).is_err());
+        assert!(set(VELOCITY, &[("bind.port", "1")]).is_err());
+    }
+}
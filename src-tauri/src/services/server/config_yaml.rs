@@ -0,0 +1,535 @@
+//! YAML 配置（bukkit.yml、spigot.yml、paper-global.yml、插件配置等）的读取与保留结构的写入。
+//!
+//! 读取交给 serde_yaml；写入按行扫描块映射，只替换目标键所在的行，注释、空行、键顺序与引号
+//! 风格保持不变。序列项中的映射与流式映射不可按路径定位，这些键只能整体替换其父键。
+//! 写入后会重新解析并核对每个键的值，结构异常时报错而不是写出损坏的文件。
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use super::config::{
+    double_quoted_end, flatten_config_value, join_config_path, lookup_config_value,
+    split_config_path, typed_config_value,
+};
+
+/// 新建键时缺省的缩进宽度
+const DEFAULT_INDENT: usize = 2;
+
+pub fn flatten(source: &str) -> Result<HashMap<String, String>, String> {
+    Ok(flatten_config_value(&parse(source)?))
+}
+
+/// 按点号路径写入多个值，返回新的完整文本
+pub fn set_values(source: &str, values: &HashMap<String, String>) -> Result<String, String> {
+    let newline = if source.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let mut lines: Vec<String> = source.lines().map(str::to_string).collect();
+    let document = parse(source)?;
+
+    // HashMap 无序，按键排序保证新键的插入顺序稳定
+    let mut keys: Vec<&String> = values.keys().collect();
+    keys.sort();
+    let mut expected = Vec::new();
+    for key in keys {
+        let segments = split_config_path(key)?;
+        let path = join_config_path(&segments);
+        let value =
+            typed_config_value(&path, &values[key], lookup_config_value(&document, &segments))?;
+        set_value(&mut lines, &segments, &value)?;
+        expected.push((segments, value));
+    }
+
+    let mut output = lines.join(newline);
+    if !output.is_empty() {
+        output.push_str(newline);
+    }
+    let written = parse(&output)?;
+    for (segments, value) in expected {
+        if lookup_config_value(&written, &segments) != Some(&value) {
+            return Err(format!(
+                "写入 {} 后 YAML 结构异常，该键可能位于序列或流式映射中",
+                join_config_path(&segments)
+            ));
+        }
+    }
+    Ok(output)
+}
+
+fn parse(source: &str) -> Result<Value, String> {
+    let value: serde_yaml::Value =
+        serde_yaml::from_str(source).map_err(|e| format!("YAML 解析失败: {}", e))?;
+    Ok(yaml_to_json(value))
+}
+
+fn yaml_to_json(value: serde_yaml::Value) -> Value {
+    match value {
+        serde_yaml::Value::Null => Value::Null,
+        serde_yaml::Value::Bool(b) => Value::Bool(b),
+        serde_yaml::Value::Number(n) => n
+            .as_i64()
+            .map(Value::from)
+            .or_else(|| n.as_u64().map(Value::from))
+            .or_else(|| {
+                n.as_f64()
+                    .and_then(serde_json::Number::from_f64)
+                    .map(Value::Number)
+            })
+            .unwrap_or(Value::Null),
+        serde_yaml::Value::String(s) => Value::String(s),
+        serde_yaml::Value::Sequence(items) => {
+            Value::Array(items.into_iter().map(yaml_to_json).collect())
+        }
+        serde_yaml::Value::Mapping(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| {
+                    let key = match yaml_to_json(key) {
+                        Value::String(s) => s,
+                        other => other.to_string(),
+                    };
+                    (key, yaml_to_json(value))
+                })
+                .collect(),
+        ),
+        serde_yaml::Value::Tagged(tagged) => yaml_to_json(tagged.value),
+    }
+}
+
+/// 块映射中的一个键所在行
+#[derive(Debug)]
+struct KeyLine {
+    line: usize,
+    indent: usize,
+    path: Vec<String>,
+    /// 冒号之后的字节位置（相对整行）
+    value_start: usize,
+    /// 值与子节点占用到的行（不含）；末尾的空行与注释不计入
+    end: usize,
+}
+
+impl KeyLine {
+    fn inline_value<'a>(&self, lines: &'a [String]) -> &'a str {
+        let rest = &lines[self.line][self.value_start..];
+        rest[..comment_start(rest).unwrap_or(rest.len())].trim()
+    }
+}
+
+fn scan(lines: &[String]) -> Vec<KeyLine> {
+    let mut keys: Vec<KeyLine> = Vec::new();
+    let mut stack: Vec<(usize, String)> = Vec::new();
+    // 块标量与序列项的内容不参与键定位
+    let mut skip_deeper_than: Option<usize> = None;
+
+    for (i, line) in lines.iter().enumerate() {
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if let Some(limit) = skip_deeper_than {
+            if indent > limit {
+                continue;
+            }
+            skip_deeper_than = None;
+        }
+        if trimmed == "---" || trimmed == "..." {
+            stack.clear();
+            continue;
+        }
+        while stack.last().is_some_and(|(level, _)| *level > indent) {
+            stack.pop();
+        }
+        if trimmed == "-" || trimmed.starts_with("- ") {
+            skip_deeper_than = Some(indent);
+            continue;
+        }
+        let Some((key, after_colon)) = parse_key(trimmed) else {
+            continue;
+        };
+        while stack.last().is_some_and(|(level, _)| *level >= indent) {
+            stack.pop();
+        }
+
+        let mut path: Vec<String> = stack.iter().map(|(_, key)| key.clone()).collect();
+        path.push(key.clone());
+        let entry = KeyLine {
+            line: i,
+            indent,
+            path,
+            value_start: indent + after_colon,
+            end: i + 1,
+        };
+        let value = entry.inline_value(lines);
+        if value.starts_with('|') || value.starts_with('>') {
+            skip_deeper_than = Some(indent);
+        } else if value.is_empty() || is_node_property(value) {
+            stack.push((indent, key));
+        }
+        keys.push(entry);
+    }
+
+    for key in &mut keys {
+        let (line, indent) = (key.line, key.indent);
+        let opens_block = key.inline_value(lines).is_empty();
+        let mut end = line + 1;
+        for (j, next) in lines.iter().enumerate().skip(line + 1) {
+            let trimmed = next.trim_start();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            let next_indent = next.len() - trimmed.len();
+            // 序列项可以与父键同缩进
+            let same_level_item = opens_block
+                && next_indent == indent
+                && (trimmed == "-" || trimmed.starts_with("- "));
+            if next_indent > indent || same_level_item {
+                end = j + 1;
+            } else {
+                break;
+            }
+        }
+        key.end = end;
+    }
+    keys
+}
+
+/// 只有锚点或标签、值在后续行的键，如 `defaults: &defaults`
+fn is_node_property(value: &str) -> bool {
+    (value.starts_with('&') || value.starts_with('!')) && !value.contains(char::is_whitespace)
+}
+
+/// 解析 `key: value` 行中的键，返回键与冒号之后的位置
+fn parse_key(trimmed: &str) -> Option<(String, usize)> {
+    let (key, after_key) = match trimmed.chars().next()? {
+        '"' => {
+            let end = double_quoted_end(trimmed)?;
+            (serde_json::from_str(&trimmed[..end]).ok()?, end)
+        }
+        '\'' => {
+            let end = single_quoted_end(trimmed)?;
+            (trimmed[1..end - 1].replace("''", "'"), end)
+        }
+        '[' | '{' | '#' | '|' | '>' | '%' | '@' | '`' | '&' | '*' | '!' | '?' => return None,
+        _ => {
+            let colon = plain_key_colon(trimmed)?;
+            return Some((trimmed[..colon].trim_end().to_string(), colon + 1));
+        }
+    };
+    let rest = &trimmed[after_key..];
+    let colon = after_key + (rest.len() - rest.trim_start().len());
+    let after_colon = trimmed[colon..].strip_prefix(':')?;
+    if !after_colon.is_empty() && !after_colon.starts_with(char::is_whitespace) {
+        return None;
+    }
+    Some((key, colon + 1))
+}
+
+/// 未加引号的键后第一个跟随空白或位于行尾的冒号
+fn plain_key_colon(trimmed: &str) -> Option<usize> {
+    let bytes = trimmed.as_bytes();
+    for (i, &b) in bytes.iter().enumerate() {
+        if b == b'#' && i > 0 && bytes[i - 1].is_ascii_whitespace() {
+            return None;
+        }
+        if b == b':'
+            && bytes
+                .get(i + 1)
+                .is_none_or(|next| next.is_ascii_whitespace())
+        {
+            return (i > 0).then_some(i);
+        }
+    }
+    None
+}
+
+fn single_quoted_end(text: &str) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let mut i = 1;
+    while i < bytes.len() {
+        if bytes[i] == b'\'' {
+            if bytes.get(i + 1) == Some(&b'\'') {
+                i += 2;
+                continue;
+            }
+            return Some(i + 1);
+        }
+        i += 1;
+    }
+    None
+}
+
+/// 值部分中行尾注释（空白后的 `#`，引号外）的起始位置
+fn comment_start(rest: &str) -> Option<usize> {
+    let bytes = rest.as_bytes();
+    let mut quote: Option<u8> = None;
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        match quote {
+            Some(b'"') if b == b'\\' => i += 1,
+            Some(q) if b == q => quote = None,
+            Some(_) => {}
+            None if (b == b'"' || b == b'\'') && rest[..i].trim().is_empty() => quote = Some(b),
+            None if b == b'#' && (i == 0 || bytes[i - 1].is_ascii_whitespace()) => {
+                // 回退到注释前的空白，保留原有间距
+                let mut start = i;
+                while start > 0 && bytes[start - 1].is_ascii_whitespace() {
+                    start -= 1;
+                }
+                return Some(start);
+            }
+            None => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+fn set_value(lines: &mut Vec<String>, segments: &[String], value: &Value) -> Result<(), String> {
+    let keys = scan(lines);
+    if let Some(key) = keys.iter().find(|k| k.path == segments) {
+        return replace_value(lines, &keys, key, value);
+    }
+
+    // 找到已存在的最深祖先，在其子节点末尾补齐缺失的键
+    let (parent, depth) = (1..segments.len())
+        .rev()
+        .find_map(|depth| {
+            keys.iter()
+                .find(|k| k.path == segments[..depth])
+                .map(|key| (Some(key), depth))
+        })
+        .unwrap_or((None, 0));
+    let (insert_at, indent) = match parent {
+        Some(parent) => {
+            if !parent.inline_value(lines).is_empty() {
+                return Err(format!("{} 不是映射，无法添加子键", join_config_path(&parent.path)));
+            }
+            let first_child = (parent.line + 1..parent.end)
+                .map(|i| lines[i].trim_start())
+                .find(|t| !t.is_empty() && !t.starts_with('#'));
+            if first_child.is_some_and(|t| t == "-" || t.starts_with("- ")) {
+                return Err(format!("{} 是序列，无法添加子键", join_config_path(&parent.path)));
+            }
+            let child_indent = keys
+                .iter()
+                .find(|k| k.line > parent.line && k.line < parent.end && k.indent > parent.indent)
+                .map(|k| k.indent)
+                .unwrap_or(parent.indent + indent_unit(&keys));
+            (parent.end, child_indent)
+        }
+        None => {
+            let top_level = keys
+                .iter()
+                .filter(|k| k.path.len() == 1)
+                .map(|k| k.indent)
+                .min();
+            (lines.len(), top_level.unwrap_or(0))
+        }
+    };
+
+    let unit = indent_unit(&keys);
+    let mut new_lines = Vec::new();
+    for (offset, segment) in segments[depth..segments.len() - 1].iter().enumerate() {
+        new_lines.push(format!("{}{}:", " ".repeat(indent + offset * unit), render_key(segment)));
+    }
+    let indent = indent + (segments.len() - 1 - depth) * unit;
+    let key_text = format!("{}{}", " ".repeat(indent), render_key(&segments[segments.len() - 1]));
+    new_lines.extend(render_entry(&key_text, value, None, indent + unit, ""));
+    lines.splice(insert_at..insert_at, new_lines);
+    Ok(())
+}
+
+fn replace_value(
+    lines: &mut Vec<String>,
+    keys: &[KeyLine],
+    key: &KeyLine,
+    value: &Value,
+) -> Result<(), String> {
+    let has_children = keys.iter().any(|k| k.line > key.line && k.line < key.end);
+    if has_children {
+        return Err(format!("{} 是一个映射，无法设置为单个值", join_config_path(&key.path)));
+    }
+    let line = &lines[key.line];
+    let rest = &line[key.value_start..];
+    let comment = comment_start(rest)
+        .map(|start| rest[start..].to_string())
+        .unwrap_or_default();
+    let old_value = key.inline_value(lines);
+    let quote = old_value.chars().next().filter(|c| *c == '\'' || *c == '"');
+    let key_text = line[..key.value_start - 1].to_string();
+
+    // 沿用原有块序列的缩进
+    let item_indent = (key.line + 1..key.end)
+        .map(|i| &lines[i])
+        .find(|l| l.trim_start().starts_with('-'))
+        .map(|l| l.len() - l.trim_start().len())
+        .unwrap_or(key.indent + indent_unit(keys));
+    let new_lines = render_entry(&key_text, value, quote, item_indent, &comment);
+    lines.splice(key.line..key.end, new_lines);
+    Ok(())
+}
+
+fn indent_unit(keys: &[KeyLine]) -> usize {
+    keys.iter()
+        .map(|k| k.indent)
+        .filter(|indent| *indent > 0)
+        .min()
+        .unwrap_or(DEFAULT_INDENT)
+}
+
+/// 渲染 `key: value`；非空数组写成块序列
+fn render_entry(
+    key_text: &str,
+    value: &Value,
+    quote: Option<char>,
+    item_indent: usize,
+    comment: &str,
+) -> Vec<String> {
+    match value {
+        Value::Array(items) if !items.is_empty() => {
+            let mut lines = vec![format!("{}:{}", key_text, comment)];
+            lines.extend(
+                items.iter().map(|item| {
+                    format!("{}- {}", " ".repeat(item_indent), render_scalar(item, None))
+                }),
+            );
+            lines
+        }
+        _ => vec![format!("{}: {}{}", key_text, render_scalar(value, quote), comment)],
+    }
+}
+
+fn render_key(key: &str) -> String {
+    if is_plain_safe(key) && !key.contains(':') {
+        key.to_string()
+    } else {
+        serde_json::to_string(key).unwrap_or_default()
+    }
+}
+
+/// 渲染标量；字符串优先沿用原有的引号风格。数组与对象使用 JSON 形式的流式写法。
+fn render_scalar(value: &Value, quote: Option<char>) -> String {
+    match value {
+        Value::String(text) => match quote {
+            Some('\'') if !text.contains('\n') => format!("'{}'", text.replace('\'', "''")),
+            None if is_plain_safe(text) => text.clone(),
+            _ => serde_json::to_string(text).unwrap_or_default(),
+        },
+        Value::Null => "null".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// 字符串能否不加引号写出并原样读回。Bukkit 使用 YAML 1.1，yes/no/on/off 也会被当作布尔值。
+fn is_plain_safe(text: &str) -> bool {
+    const YAML11_BOOLS: [&str; 8] = ["y", "n", "yes", "no", "on", "off", "true", "false"];
+    if text.is_empty()
+        || text.trim() != text
+        || text.contains(['\n', '\t'])
+        || text.contains(": ")
+        || text.contains(" #")
+        || text.ends_with(':')
+        || text.starts_with([
+            '-', '?', ':', ',', '[', ']', '{', '}', '#', '&', '*', '!', '|', '>', '\'', '"', '%',
+            '@', '`',
+        ])
+        || YAML11_BOOLS.contains(&text.to_ascii_lowercase().as_str())
+    {
+        return false;
+    }
+    matches!(
+        serde_yaml::from_str::<serde_yaml::Value>(text),
+        Ok(serde_yaml::Value::String(parsed)) if parsed == text
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPIGOT: &str = "\
+# This is the main configuration file for Spigot.
+settings:
+  debug: false   # verbose logging
+  timeout-time: 60
+  restart-script: ./start.sh
+messages:
+  whitelist: 'You are not whitelisted on this server!'
+  unknown-command: \"Unknown command. Type \\\"/help\\\" for help.\"
+commands:
+  spam-exclusions:
+  - /skill
+  tab-complete: 0
+world-settings:
+  default:
+    verbose: false
+";
+
+    fn set(source: &str, pairs: &[(&str, &str)]) -> Result<String, String> {
+        let values = pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        set_values(source, &values)
+    }
+
+    #[test]
+    fn flattens_nested_keys() {
+        let values = flatten(SPIGOT).unwrap();
+        assert_eq!(values["settings.debug"], "false");
+        assert_eq!(values["settings.timeout-time"], "60");
+        assert_eq!(values["messages.unknown-command"], "Unknown command. Type \"/help\" for help.");
+        assert_eq!(values["commands.spam-exclusions"], "[\"/skill\"]");
+        assert_eq!(values["world-settings.default.verbose"], "false");
+    }
+
+    #[test]
+    fn updates_values_preserving_comments_and_quotes() {
+        let output = set(
+            SPIGOT,
+            &[
+                ("settings.debug", "true"),
+                ("settings.timeout-time", "90"),
+                ("messages.whitelist", "It's closed"),
+                ("commands.spam-exclusions", "[\"/skill\", \"/spawn\"]"),
+            ],
+        )
+        .unwrap();
+        assert!(output.starts_with("# This is the main configuration file for Spigot.\n"));
+        assert!(output.contains("  debug: true   # verbose logging\n"));
+        assert!(output.contains("  timeout-time: 90\n"));
+        assert!(output.contains("  whitelist: 'It''s closed'\n"));
+        assert!(output.contains("  spam-exclusions:\n  - /skill\n  - /spawn\n  tab-complete: 0\n"));
+        assert!(set(SPIGOT, &[("settings.timeout-time", "soon")]).is_err());
+    }
+
+    #[test]
+    fn inserts_missing_keys_under_existing_parents() {
+        let output = set(
+            SPIGOT,
+            &[
+                ("world-settings.default.mob-spawn-range", "8"),
+                ("world-settings.nether.verbose", "true"),
+                ("advancements.disable-saving", "yes"),
+            ],
+        )
+        .unwrap();
+        assert!(output.contains(
+            "world-settings:\n  default:\n    verbose: false\n    mob-spawn-range: 8\n  nether:\n    verbose: true\n"
+        ));
+        assert!(output.ends_with("advancements:\n  disable-saving: \"yes\"\n"));
+        assert!(set(SPIGOT, &[("settings.debug.level", "1")]).is_err());
+        assert!(set(SPIGOT, &[("world-settings.default", "1")]).is_err());
+    }
+
+    #[test]
+    fn rejects_keys_inside_sequences() {
+        let source = "groups:\n- name: admin\n  prefix: '[A]'\n";
+        assert!(set(source, &[("groups.name", "mod")]).is_err());
+        assert_eq!(flatten(source).unwrap()["groups"], "[{\"name\":\"admin\",\"prefix\":\"[A]\"}]");
+    }
+}
@@ -0,0 +1,808 @@
+//! 服务器异常退出后的崩溃诊断。
+//!
+//! crash_guard.rs 判定为崩溃后，manager.rs 调用 [`diagnose_crash`] 扫描以下来源：
+//! - `crash-reports/*.txt`：游戏 / 加载器生成的崩溃报告，提取 "Description"、异常与堆栈，
+//!   以及 Forge / NeoForge 报告中的 "Suspected Mod" 段落；
+//! - `hs_err_pid*.log`：JVM 本地崩溃日志，提取错误信号、内存分配失败原因与 "Problematic frame"；
+//! - 日志数据库末尾的服务器输出：没有崩溃报告时从中取最后一段异常堆栈，并检查 OutOfMemoryError。
+//!
+//! 只分析本次启动之后修改过的文件。堆栈帧按包名映射到 `mods/`、`plugins/` 中的 jar，
+//! 得出疑似引起崩溃的模组 / 插件。诊断结果按服务器保存在应用数据目录，
+//! 每台服务器保留最近 `CRASH_DIAGNOSES_KEEP` 条。
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use rusqlite::params;
+use zip::ZipArchive;
+
+use crate::models::crash::{CrashCause, CrashReport, CrashSuspect};
+
+use super::log_pipeline::{open_or_create_log_db, resolve_server_path};
+use super::manager::current_timestamp_secs;
+
+///此处常量见 utils/constants.rs
+use crate::utils::constants::{
+    CRASH_DIAGNOSES_DIR_NAME, CRASH_DIAGNOSES_KEEP, CRASH_FILE_MAX_AGE_SECS,
+    CRASH_IGNORED_PACKAGES, CRASH_LOG_TAIL_LINES, CRASH_STACK_MAX_LINES,
+};
+
+static EXCEPTION_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"^(?:Caused by: |Exception in thread "[^"]*" )?(?P<class>(?:[A-Za-z_$][\w$]*\.)+[\w$]*(?:Exception|Error|Throwable))(?::\s*(?P<message>.*))?$"#,
+    )
+    .unwrap()
+});
+/// `at pkg.Class.method(...)`，兼容 `java.base/` 模块前缀与 Forge 的 `TRANSFORMER/modid@1.0/` 前缀
+static FRAME_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\s*at (?:[^\s(]*/)?(?P<class>[\w$]+(?:\.[\w$]+)+)\.[\w$<>]+\(").unwrap()
+});
+static FORGE_SUSPECT_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\t(?P<name>[^\t(]+?)(?: \((?P<id>[^)]+)\))?(?:, Version: .*)?$").unwrap()
+});
+static MODS_TOML_NAME_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?m)^\s*displayName\s*=\s*"(?P<name>[^"]+)""#).unwrap());
+
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+/// 分析服务器最近一次异常退出并保存诊断结果。
+///
+/// `started_at` 为本次启动时间（秒），早于它的崩溃报告与日志不参与分析；
+/// 缺省时只看最近 `CRASH_FILE_MAX_AGE_SECS` 秒内的文件。
+pub fn diagnose_crash(
+    server_id: &str,
+    exit_code: Option<i32>,
+    started_at: Option<u64>,
+) -> Result<CrashReport, String> {
+    let server_path = resolve_server_path(server_id)?;
+    let since = started_at
+        .unwrap_or_else(|| current_timestamp_secs().saturating_sub(CRASH_FILE_MAX_AGE_SECS));
+    let report = analyze(server_id, &server_path, exit_code, since)?;
+    save_report(&report)?;
+    Ok(report)
+}
+
+/// 服务器的崩溃诊断记录，最新的在前
+pub fn get_crash_reports(server_id: &str) -> Result<Vec<CrashReport>, String> {
+    let path = reports_path(server_id)?;
+    let _guard = STORE_LOCK
+        .lock()
+        .map_err(|_| "crash report store lock poisoned".to_string())?;
+    Ok(load_reports(&path))
+}
+
+pub fn remove_crash_reports(server_id: &str) {
+    if let Ok(path) = reports_path(server_id) {
+        let _guard = STORE_LOCK.lock();
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+fn analyze(
+    server_id: &str,
+    server_path: &Path,
+    exit_code: Option<i32>,
+    since_secs: u64,
+) -> Result<CrashReport, String> {
+    let since = UNIX_EPOCH + Duration::from_secs(since_secs);
+    let crash_report =
+        newest_file(&server_path.join("crash-reports"), since, |name| name.ends_with(".txt"))
+            .and_then(|path| read_text(&path).map(|content| (path, content)));
+    let hs_err = newest_file(server_path, since, |name| {
+        name.starts_with("hs_err_pid") && name.ends_with(".log")
+    })
+    .and_then(|path| read_text(&path).map(|content| (path, content)));
+    let log_tail = read_log_tail(server_path, since_secs as i64 * 1000)?;
+    let index = JarIndex::build(server_path);
+
+    let mut report = build_report(
+        server_id,
+        exit_code,
+        crash_report.as_ref().map(|(_, content)| content.as_str()),
+        hs_err.as_ref().map(|(_, content)| content.as_str()),
+        &log_tail,
+        &index,
+    );
+    report.crash_report_file = crash_report.map(|(path, _)| relative_path(server_path, &path));
+    report.hs_err_file = hs_err.map(|(path, _)| relative_path(server_path, &path));
+    Ok(report)
+}
+
+fn build_report(
+    server_id: &str,
+    exit_code: Option<i32>,
+    crash_report: Option<&str>,
+    hs_err: Option<&str>,
+    log_tail: &[String],
+    index: &JarIndex,
+) -> CrashReport {
+    let report_facts = crash_report.map(parse_crash_report).unwrap_or_default();
+    let hs_err_facts = hs_err.map(parse_hs_err);
+    let trace = report_facts
+        .trace
+        .or_else(|| last_trace(log_tail.iter().map(String::as_str)));
+
+    let oom_in_log = log_tail
+        .iter()
+        .find_map(|line| out_of_memory_detail(line.trim()));
+    let oom_in_trace = trace
+        .as_ref()
+        .filter(|trace| trace.class.ends_with("OutOfMemoryError"))
+        .map(|trace| trace.message.clone().unwrap_or_default());
+    let native_oom = hs_err_facts
+        .as_ref()
+        .filter(|facts| facts.out_of_memory)
+        .map(|facts| facts.cause.clone().unwrap_or_default());
+
+    let (cause, jvm_cause) = if let Some(detail) = native_oom.or(oom_in_trace).or(oom_in_log) {
+        let detail = (!detail.is_empty()).then_some(detail);
+        (CrashCause::OutOfMemory, detail)
+    } else if let Some(facts) = &hs_err_facts {
+        (CrashCause::NativeCrash, facts.cause.clone())
+    } else if trace.is_some() {
+        (CrashCause::JavaException, None)
+    } else {
+        (CrashCause::Unknown, None)
+    };
+
+    let stack_trace = trace
+        .as_ref()
+        .map(|trace| trace.lines.clone())
+        .unwrap_or_default();
+    let suspects = find_suspects(index, &stack_trace, &report_facts.suspected_mods);
+
+    let mut report = CrashReport {
+        id: uuid::Uuid::new_v4().to_string(),
+        server_id: server_id.to_string(),
+        created_at: current_timestamp_secs(),
+        exit_code,
+        cause,
+        summary: String::new(),
+        description: report_facts.description,
+        exception: trace.as_ref().map(|trace| trace.class.clone()),
+        exception_message: trace.and_then(|trace| trace.message),
+        jvm_cause,
+        problematic_frame: hs_err_facts.and_then(|facts| facts.problematic_frame),
+        suspects,
+        stack_trace: stack_trace
+            .into_iter()
+            .take(CRASH_STACK_MAX_LINES)
+            .collect(),
+        crash_report_file: None,
+        hs_err_file: None,
+    };
+    report.summary = summarize(&report);
+    report
+}
+
+fn summarize(report: &CrashReport) -> String {
+    let exception = report.exception.as_deref().map(|class| {
+        let short = class.rsplit('.').next().unwrap_or(class);
+        match report
+            .exception_message
+            .as_deref()
+            .filter(|m| !m.is_empty())
+        {
+            Some(message) => format!("{}: {}", short, message),
+            None => short.to_string(),
+        }
+    });
+
+    let mut summary = match report.cause {
+        CrashCause::OutOfMemory => {
+            format!("内存不足（{}）", report.jvm_cause.as_deref().unwrap_or("OutOfMemoryError"))
+        }
+        CrashCause::NativeCrash => {
+            format!("JVM 本地崩溃（{}）", report.jvm_cause.as_deref().unwrap_or("未知错误"))
+        }
+        CrashCause::JavaException => match (&report.description, exception) {
+            (Some(description), Some(exception)) => format!("{}：{}", description, exception),
+            (_, exception) => exception.unwrap_or_default(),
+        },
+        CrashCause::Unknown => match report.exit_code {
+            Some(137) => "进程被 SIGKILL 终止，可能是系统内存不足".to_string(),
+            Some(code) => format!("未找到崩溃报告或异常堆栈（退出码：{}）", code),
+            None => "进程被强制终止，未找到崩溃报告".to_string(),
+        },
+    };
+    if let Some(suspect) = report.suspects.first() {
+        summary.push_str(&format!("，疑似与 {} 有关", suspect.name));
+    }
+    summary
+}
+
+/// 一段异常堆栈：首行异常及其后的 `at` / `Caused by` 行
+#[derive(Debug, Clone, Default, PartialEq)]
+struct ExceptionTrace {
+    class: String,
+    message: Option<String>,
+    lines: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct CrashReportFacts {
+    description: Option<String>,
+    trace: Option<ExceptionTrace>,
+    /// "Suspected Mod" 段落中列出的模组，如 `Create (create)`
+    suspected_mods: Vec<String>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct HsErrFacts {
+    cause: Option<String>,
+    out_of_memory: bool,
+    problematic_frame: Option<String>,
+}
+
+fn parse_crash_report(content: &str) -> CrashReportFacts {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut facts = CrashReportFacts::default();
+
+    if let Some(at) = lines
+        .iter()
+        .position(|line| line.starts_with("Description:"))
+    {
+        let description = lines[at]["Description:".len()..].trim();
+        facts.description = (!description.is_empty()).then(|| description.to_string());
+        facts.trace = lines[at + 1..]
+            .iter()
+            .position(|line| EXCEPTION_RE.is_match(line.trim()))
+            .and_then(|offset| read_trace(&lines[at + 1 + offset..]));
+    }
+
+    if let Some(at) = lines
+        .iter()
+        .position(|line| line.starts_with("Suspected Mod"))
+    {
+        for line in lines[at + 1..]
+            .iter()
+            .take_while(|line| line.starts_with('\t'))
+        {
+            if line.starts_with("\t\t") || line.trim_start().starts_with("at ") {
+                continue;
+            }
+            if let Some(caps) = FORGE_SUSPECT_RE.captures(line) {
+                let name = caps["name"].trim();
+                facts.suspected_mods.push(match caps.name("id") {
+                    Some(id) => format!("{} ({})", name, id.as_str()),
+                    None => name.to_string(),
+                });
+            }
+        }
+    }
+    facts
+}
+
+fn parse_hs_err(content: &str) -> HsErrFacts {
+    let header: Vec<&str> = content
+        .lines()
+        .take_while(|line| line.starts_with('#') || line.trim().is_empty())
+        .map(|line| line.trim_start_matches('#').trim())
+        .filter(|line| !line.is_empty())
+        .collect();
+    let next = |at: usize| header.get(at + 1).map(|line| line.to_string());
+
+    let mut facts = HsErrFacts::default();
+    for (at, line) in header.iter().enumerate() {
+        if line.contains("insufficient memory for the Java Runtime Environment") {
+            facts.out_of_memory = true;
+            facts.cause = next(at);
+        } else if line.contains("A fatal error has been detected") && facts.cause.is_none() {
+            facts.cause = next(at);
+        } else if line.starts_with("Problematic frame:") {
+            facts.problematic_frame = next(at);
+        }
+    }
+    facts
+}
+
+/// 最后一段完整的异常堆栈（异常行后至少跟一行 `at`）
+fn last_trace<'a>(lines: impl Iterator<Item = &'a str>) -> Option<ExceptionTrace> {
+    let lines: Vec<&str> = lines.collect();
+    (0..lines.len())
+        .rev()
+        .filter(|&at| {
+            let line = lines[at].trim();
+            !line.starts_with("Caused by:")
+                && EXCEPTION_RE.is_match(line)
+                && lines
+                    .get(at + 1)
+                    .is_some_and(|next| FRAME_RE.is_match(next))
+        })
+        .find_map(|at| read_trace(&lines[at..]))
+}
+
+fn read_trace(lines: &[&str]) -> Option<ExceptionTrace> {
+    let (first, rest) = lines.split_first()?;
+    let caps = EXCEPTION_RE.captures(first.trim())?;
+    let mut trace = ExceptionTrace {
+        class: caps["class"].to_string(),
+        message: caps
+            .name("message")
+            .map(|m| m.as_str().trim().to_string())
+            .filter(|m| !m.is_empty()),
+        lines: vec![first.trim_end().to_string()],
+    };
+    for line in rest {
+        let trimmed = line.trim();
+        let continues = trimmed.starts_with("at ")
+            || trimmed.starts_with("...")
+            || trimmed.starts_with("Caused by:")
+            || trimmed.starts_with("Suppressed:");
+        if !continues {
+            break;
+        }
+        trace.lines.push(line.trim_end().to_string());
+    }
+    Some(trace)
+}
+
+/// `java.lang.OutOfMemoryError: Java heap space` → `Java heap space`
+fn out_of_memory_detail(line: &str) -> Option<String> {
+    let at = line.find("java.lang.OutOfMemoryError")?;
+    let rest = &line[at + "java.lang.OutOfMemoryError".len()..];
+    Some(rest.trim_start_matches(':').trim().to_string())
+}
+
+/// `mods/`、`plugins/` 中 jar 的类包名索引
+#[derive(Debug, Default)]
+struct JarIndex {
+    jars: Vec<JarInfo>,
+    packages: HashMap<String, Vec<usize>>,
+}
+
+#[derive(Debug)]
+struct JarInfo {
+    name: String,
+    jar: String,
+}
+
+impl JarIndex {
+    fn build(server_path: &Path) -> Self {
+        let mut index = JarIndex::default();
+        for dir_name in ["mods", "plugins"] {
+            let Ok(entries) = std::fs::read_dir(server_path.join(dir_name)) else {
+                continue;
+            };
+            let mut jars: Vec<PathBuf> = entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.is_file()
+                        && path
+                            .extension()
+                            .is_some_and(|ext| ext.eq_ignore_ascii_case("jar"))
+                })
+                .collect();
+            jars.sort();
+            for path in jars {
+                if let Some((name, packages)) = read_jar(&path) {
+                    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+                    index.insert(name, format!("{}/{}", dir_name, file_name), packages);
+                }
+            }
+        }
+        index
+    }
+
+    fn insert(&mut self, name: String, jar: String, packages: Vec<String>) {
+        let id = self.jars.len();
+        self.jars.push(JarInfo { name, jar });
+        for package in packages {
+            self.packages.entry(package).or_default().push(id);
+        }
+    }
+}
+
+/// 读取 jar 的显示名称与其中所有类的包名
+fn read_jar(path: &Path) -> Option<(String, Vec<String>)> {
+    let file = std::fs::File::open(path).ok()?;
+    let mut archive = ZipArchive::new(file).ok()?;
+
+    let mut packages: Vec<String> = archive
+        .file_names()
+        .filter(|name| name.ends_with(".class") && !name.starts_with("META-INF/"))
+        .filter_map(|name| name.rsplit_once('/').map(|(dir, _)| dir.replace('/', ".")))
+        .collect();
+    packages.sort();
+    packages.dedup();
+
+    let name = jar_display_name(&mut archive).unwrap_or_else(|| {
+        path.file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string()
+    });
+    Some((name, packages))
+}
+
+fn jar_display_name(archive: &mut ZipArchive<std::fs::File>) -> Option<String> {
+    for file in ["plugin.yml", "paper-plugin.yml", "bungee.yml"] {
+        let name = read_entry(archive, file).and_then(|content| {
+            content.lines().find_map(|line| {
+                let value = line.strip_prefix("name:")?.trim().trim_matches(['"', '\'']);
+                (!value.is_empty()).then(|| value.to_string())
+            })
+        });
+        if name.is_some() {
+            return name;
+        }
+    }
+    for file in ["fabric.mod.json", "velocity-plugin.json"] {
+        let name = read_entry(archive, file)
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+            .and_then(|json| {
+                json.get("name")
+                    .or_else(|| json.get("id"))
+                    .and_then(|value| value.as_str())
+                    .map(str::to_string)
+            });
+        if name.is_some() {
+            return name;
+        }
+    }
+    for file in ["META-INF/mods.toml", "META-INF/neoforge.mods.toml"] {
+        let name = read_entry(archive, file).and_then(|content| {
+            MODS_TOML_NAME_RE
+                .captures(&content)
+                .map(|caps| caps["name"].to_string())
+        });
+        if name.is_some() {
+            return name;
+        }
+    }
+    None
+}
+
+fn read_entry(archive: &mut ZipArchive<std::fs::File>, name: &str) -> Option<String> {
+    let mut entry = archive.by_name(name).ok()?;
+    let mut content = String::new();
+    entry.read_to_string(&mut content).ok()?;
+    Some(content)
+}
+
+/// 按堆栈帧数排序的疑似模组 / 插件，加载器报告中列出的排在最前
+fn find_suspects(index: &JarIndex, stack: &[String], reported: &[String]) -> Vec<CrashSuspect> {
+    let mut suspects: Vec<CrashSuspect> = Vec::new();
+    let mut by_jar: HashMap<usize, usize> = HashMap::new();
+
+    for line in stack {
+        let Some(caps) = FRAME_RE.captures(line) else {
+            continue;
+        };
+        let class = &caps["class"];
+        if CRASH_IGNORED_PACKAGES
+            .iter()
+            .any(|prefix| class.starts_with(prefix))
+        {
+            continue;
+        }
+        let Some((package, _)) = class.rsplit_once('.') else {
+            continue;
+        };
+        for &jar in index.packages.get(package).into_iter().flatten() {
+            let at = *by_jar.entry(jar).or_insert_with(|| {
+                suspects.push(CrashSuspect {
+                    name: index.jars[jar].name.clone(),
+                    jar: Some(index.jars[jar].jar.clone()),
+                    frame_count: 0,
+                    reported_by_loader: false,
+                });
+                suspects.len() - 1
+            });
+            suspects[at].frame_count += 1;
+        }
+    }
+
+    for entry in reported {
+        let name = entry.split(" (").next().unwrap_or(entry);
+        match suspects
+            .iter_mut()
+            .find(|suspect| suspect.name.eq_ignore_ascii_case(name))
+        {
+            Some(suspect) => suspect.reported_by_loader = true,
+            None => {
+                let jar = index
+                    .jars
+                    .iter()
+                    .find(|info| info.name.eq_ignore_ascii_case(name))
+                    .map(|info| info.jar.clone());
+                suspects.push(CrashSuspect {
+                    name: name.to_string(),
+                    jar,
+                    frame_count: 0,
+                    reported_by_loader: true,
+                });
+            }
+        }
+    }
+
+    // 稳定排序：同等条件下保持堆栈中首次出现的顺序
+    suspects.sort_by(|a, b| {
+        b.reported_by_loader
+            .cmp(&a.reported_by_loader)
+            .then(b.frame_count.cmp(&a.frame_count))
+    });
+    suspects
+}
+
+fn newest_file(dir: &Path, since: SystemTime, matches: impl Fn(&str) -> bool) -> Option<PathBuf> {
+    std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter(|entry| matches(&entry.file_name().to_string_lossy()))
+        .filter_map(|entry| {
+            let modified = entry.metadata().ok()?.modified().ok()?;
+            (modified >= since).then(|| (modified, entry.path()))
+        })
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)
+}
+
+fn read_text(path: &Path) -> Option<String> {
+    std::fs::read(path)
+        .ok()
+        .map(|bytes| String::from_utf8_lossy(&bytes).to_string())
+}
+
+/// 本次启动后最后 CRASH_LOG_TAIL_LINES 行服务器输出，取解析后的正文
+fn read_log_tail(server_path: &Path, since_ms: i64) -> Result<Vec<String>, String> {
+    let conn = open_or_create_log_db(server_path)?;
+    let mut stmt = conn
+        .prepare(
+            r#"SELECT text FROM (
+                 SELECT id, COALESCE(message, line) AS text FROM log_lines
+                 WHERE source = 'server' AND timestamp >= ?1
+                 ORDER BY id DESC LIMIT ?2
+               ) recent
+               ORDER BY id ASC"#,
+        )
+        .map_err(|e| format!("准备日志读取失败: {}", e))?;
+    let rows = stmt
+        .query_map(params![since_ms, CRASH_LOG_TAIL_LINES as i64], |row| row.get(0))
+        .map_err(|e| format!("读取日志失败: {}", e))?;
+    rows.collect::<Result<Vec<String>, _>>()
+        .map_err(|e| format!("读取日志失败: {}", e))
+}
+
+fn relative_path(server_path: &Path, path: &Path) -> String {
+    path.strip_prefix(server_path)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+fn reports_path(server_id: &str) -> Result<PathBuf, String> {
+    if server_id.is_empty() || server_id.contains(['/', '\\']) || server_id.contains("..") {
+        return Err(format!("非法的服务器 ID: {}", server_id));
+    }
+    Ok(PathBuf::from(crate::utils::path::get_or_create_app_data_dir())
+        .join(CRASH_DIAGNOSES_DIR_NAME)
+        .join(format!("{}.json", server_id)))
+}
+
+fn load_reports(path: &Path) -> Vec<CrashReport> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_report(report: &CrashReport) -> Result<(), String> {
+    let path = reports_path(&report.server_id)?;
+    let _guard = STORE_LOCK
+        .lock()
+        .map_err(|_| "crash report store lock poisoned".to_string())?;
+    let mut reports = load_reports(&path);
+    reports.insert(0, report.clone());
+    reports.truncate(CRASH_DIAGNOSES_KEEP);
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("创建崩溃诊断目录失败: {}", e))?;
+    }
+    let json =
+        serde_json::to_string_pretty(&reports).map_err(|e| format!("序列化崩溃诊断失败: {}", e))?;
+    std::fs::write(&path, json).map_err(|e| format!("保存崩溃诊断失败: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_utils::temp_dir;
+    use std::io::Write;
+
+    const FORGE_REPORT: &str = "---- Minecraft Crash Report ----
+// Don't be sad, have a hug! <3
+
+Time: 2026-10-18 12:00:00
+Description: Ticking block entity
+
+java.lang.NullPointerException: Cannot invoke \"Object.hashCode()\" because \"key\" is null
+\tat java.base/java.util.HashMap.hash(HashMap.java:338)
+\tat TRANSFORMER/examplemod@1.0/com.example.machines.Grinder.tick(Grinder.java:42)
+\tat TRANSFORMER/examplemod@1.0/com.example.machines.Grinder.serverTick(Grinder.java:30)
+\tat TRANSFORMER/minecraft@1.20.1/net.minecraft.world.level.Level.tickBlockEntities(Level.java:480)
+\tat TRANSFORMER/lib@2.0/org.lib.util.Events.post(Events.java:12)
+
+
+A detailed walkthrough of the error, its code path and all known details is as follows:
+---------------------------------------------------------------------------------------
+
+-- Head --
+Thread: Server thread
+Suspected Mod:
+\tExample Mod (examplemod), Version: 1.0
+\t\tIssue tracker URL: https://example.com/issues
+\t\tat TRANSFORMER/examplemod@1.0/com.example.machines.Grinder.tick(Grinder.java:42)
+\tOther Mod (othermod), Version: 2.0
+Stacktrace:
+\tat java.base/java.util.HashMap.hash(HashMap.java:338)
+";
+
+    const HS_ERR_NATIVE: &str = "#
+# A fatal error has been detected by the Java Runtime Environment:
+#
+#  SIGSEGV (0xb) at pc=0x00007f1c2d3e4f50, pid=4242, tid=4243
+#
+# JRE version: OpenJDK Runtime Environment (17.0.8+7) (build 17.0.8+7)
+# Problematic frame:
+# C  [liblwjgl.so+0x1f50]  Java_org_lwjgl_system_JNI_invokePV+0x10
+#
+
+---------------  S U M M A R Y ------------
+";
+
+    const HS_ERR_OOM: &str = "#
+# There is insufficient memory for the Java Runtime Environment to continue.
+# Native memory allocation (mmap) failed to map 16777216 bytes for committing reserved memory.
+# Possible reasons:
+#   The system is out of physical RAM or swap space
+";
+
+    fn write_jar(path: &Path, files: &[(&str, &str)]) {
+        let mut writer = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+        for (name, content) in files {
+            writer
+                .start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    fn lines(text: &[&str]) -> Vec<String> {
+        text.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_crash_report_and_maps_frames_to_jars() {
+        let dir = temp_dir("crash");
+        std::fs::create_dir_all(dir.join("mods")).unwrap();
+        write_jar(
+            &dir.join("mods/example-1.0.jar"),
+            &[
+                (
+                    "META-INF/mods.toml",
+                    "[[mods]]\nmodId=\"examplemod\"\ndisplayName=\"Example Mod\"\n",
+                ),
+                ("com/example/machines/Grinder.class", ""),
+                ("com/example/ExampleMod.class", ""),
+            ],
+        );
+        write_jar(&dir.join("mods/library.jar"), &[("org/lib/util/Events.class", "")]);
+        let index = JarIndex::build(&dir);
+        assert_eq!(index.jars.len(), 2);
+
+        let report = build_report("test", Some(1), Some(FORGE_REPORT), None, &[], &index);
+        assert_eq!(report.cause, CrashCause::JavaException);
+        assert_eq!(report.description.as_deref(), Some("Ticking block entity"));
+        assert_eq!(report.exception.as_deref(), Some("java.lang.NullPointerException"));
+        assert_eq!(report.stack_trace.len(), 6);
+        assert_eq!(
+            report.suspects,
+            vec![
+                CrashSuspect {
+                    name: "Example Mod".to_string(),
+                    jar: Some("mods/example-1.0.jar".to_string()),
+                    frame_count: 2,
+                    reported_by_loader: true,
+                },
+                CrashSuspect {
+                    name: "Other Mod".to_string(),
+                    jar: None,
+                    frame_count: 0,
+                    reported_by_loader: true,
+                },
+                CrashSuspect {
+                    name: "library".to_string(),
+                    jar: Some("mods/library.jar".to_string()),
+                    frame_count: 1,
+                    reported_by_loader: false,
+                },
+            ]
+        );
+        assert!(report
+            .summary
+            .starts_with("Ticking block entity：NullPointerException"));
+        assert!(report.summary.ends_with("疑似与 Example Mod 有关"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn detects_native_crash_and_out_of_memory() {
+        let index = JarIndex::default();
+        let native = build_report("test", Some(134), None, Some(HS_ERR_NATIVE), &[], &index);
+        assert_eq!(native.cause, CrashCause::NativeCrash);
+        assert_eq!(
+            native.jvm_cause.as_deref(),
+            Some("SIGSEGV (0xb) at pc=0x00007f1c2d3e4f50, pid=4242, tid=4243")
+        );
+        assert_eq!(
+            native.problematic_frame.as_deref(),
+            Some("C  [liblwjgl.so+0x1f50]  Java_org_lwjgl_system_JNI_invokePV+0x10")
+        );
+
+        let native_oom = build_report("test", Some(1), None, Some(HS_ERR_OOM), &[], &index);
+        assert_eq!(native_oom.cause, CrashCause::OutOfMemory);
+        assert!(native_oom
+            .jvm_cause
+            .as_deref()
+            .unwrap()
+            .starts_with("Native memory allocation (mmap) failed"));
+
+        let tail = lines(&[
+            "Can't keep up! Is the server overloaded?",
+            "Exception in thread \"Server thread\" java.lang.OutOfMemoryError: Java heap space",
+            "\tat java.base/java.util.Arrays.copyOf(Arrays.java:3537)",
+        ]);
+        let heap = build_report("test", Some(1), None, None, &tail, &index);
+        assert_eq!(heap.cause, CrashCause::OutOfMemory);
+        assert_eq!(heap.jvm_cause.as_deref(), Some("Java heap space"));
+        assert_eq!(heap.summary, "内存不足（Java heap space）");
+
+        let unknown = build_report("test", Some(137), None, None, &[], &index);
+        assert_eq!(unknown.cause, CrashCause::Unknown);
+        assert_eq!(unknown.summary, "进程被 SIGKILL 终止，可能是系统内存不足");
+    }
+
+    #[test]
+    fn takes_last_trace_from_log_tail() {
+        let tail = lines(&[
+            "java.lang.IllegalStateException: old failure",
+            "\tat com.example.Old.run(Old.java:1)",
+            "Preparing spawn area: 0%",
+            "Encountered an unexpected exception",
+            "java.lang.RuntimeException: Exception ticking world",
+            "\tat net.minecraft.server.MinecraftServer.tick(MinecraftServer.java:900)",
+            "Caused by: java.lang.ClassCastException: class a cannot be cast to class b",
+            "\tat com.example.plugin.Listener.onTick(Listener.java:55)",
+            "\t... 4 more",
+            "Stopping server",
+        ]);
+        let trace = last_trace(tail.iter().map(String::as_str)).unwrap();
+        assert_eq!(trace.class, "java.lang.RuntimeException");
+        assert_eq!(trace.message.as_deref(), Some("Exception ticking world"));
+        assert_eq!(trace.lines.len(), 5);
+
+        let mut index = JarIndex::default();
+        index.insert(
+            "ExamplePlugin".to_string(),
+            "plugins/ExamplePlugin.jar".to_string(),
+            vec!["com.example.plugin".to_string()],
+        );
+        let report = build_report("test", Some(1), None, None, &tail, &index);
+        assert_eq!(report.cause, CrashCause::JavaException);
+        assert_eq!(report.suspects.len(), 1);
+        assert_eq!(report.suspects[0].jar.as_deref(), Some("plugins/ExamplePlugin.jar"));
+        assert_eq!(
+            report.summary,
+            "RuntimeException: Exception ticking world，疑似与 ExamplePlugin 有关"
+        );
+    }
+}
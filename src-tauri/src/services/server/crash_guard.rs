@@ -0,0 +1,227 @@
+//! 崩溃检测与自动重启策略。
+//!
+//! ServerManager 在子进程退出时判断是否属于“未经请求的退出”（即崩溃），
+//! 本模块只负责记录每个服务器的崩溃状态，并按 [`RestartPolicy`] 计算下一步动作：
+//! - 窗口期（`window_secs`）内的自动重启次数超过 `max_restarts` 时放弃；
+//! - 连续未进入就绪状态的崩溃次数达到 `give_up_after` 时放弃；
+//! - 否则按指数退避计算延迟：`backoff_initial_secs * 2^(n-1)`，上限 `backoff_max_secs`。
+//!
+//! 进程监视、日志输出与真正的重启调度都在 manager.rs 中完成，这里保持纯逻辑便于测试。
+
+use std::collections::VecDeque;
+
+use crate::models::server::RestartPolicy;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RestartDecision {
+    /// 策略未启用，保持 Error 状态等待人工处理
+    Disabled,
+    /// 在 `delay_secs` 秒后进行第 `attempt` 次自动重启
+    Restart { attempt: u32, delay_secs: u64 },
+    /// 超过阈值，放弃自动重启
+    GiveUp { reason: String },
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CrashState {
+    /// 窗口期内已发起的自动重启时间戳（秒）
+    restart_times: VecDeque<u64>,
+    /// 连续未能进入就绪状态的崩溃次数
+    consecutive_failures: u32,
+    pub last_exit_code: Option<i32>,
+    /// 最近一次崩溃的描述，供状态查询时展示
+    pub last_message: Option<String>,
+    pub crashed_at: Option<u64>,
+    pub next_restart_at: Option<u64>,
+    /// 每次调度重启都会递增，延迟线程据此判断调度是否已被取消
+    pub restart_token: u64,
+    /// 崩溃信息是否已经通过 get_server_status 上报过一次
+    pub reported: bool,
+}
+
+impl CrashState {
+    /// 记录一次崩溃并给出重启决策。
+    pub fn record_crash(
+        &mut self,
+        policy: &RestartPolicy,
+        exit_code: Option<i32>,
+        now: u64,
+    ) -> RestartDecision {
+        self.last_exit_code = exit_code;
+        self.crashed_at = Some(now);
+        self.next_restart_at = None;
+        self.reported = false;
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+
+        if !policy.enabled {
+            return RestartDecision::Disabled;
+        }
+
+        let window_start = now.saturating_sub(policy.window_secs);
+        while self
+            .restart_times
+            .front()
+            .map(|ts| *ts < window_start)
+            .unwrap_or(false)
+        {
+            self.restart_times.pop_front();
+        }
+
+        if policy.give_up_after > 0 && self.consecutive_failures >= policy.give_up_after {
+            return RestartDecision::GiveUp {
+                reason: format!("连续 {} 次在就绪前崩溃", self.consecutive_failures),
+            };
+        }
+
+        if self.restart_times.len() as u32 >= policy.max_restarts {
+            return RestartDecision::GiveUp {
+                reason: format!(
+                    "{} 秒内已自动重启 {} 次",
+                    policy.window_secs,
+                    self.restart_times.len()
+                ),
+            };
+        }
+
+        self.restart_times.push_back(now);
+        let attempt = self.restart_times.len() as u32;
+        let delay_secs = backoff_delay_secs(policy, attempt);
+        self.next_restart_at = Some(now.saturating_add(delay_secs));
+        self.restart_token = self.restart_token.wrapping_add(1);
+        RestartDecision::Restart { attempt, delay_secs }
+    }
+
+    /// 服务器进入就绪状态，清零连续失败计数。
+    pub fn mark_ready(&mut self) {
+        self.consecutive_failures = 0;
+    }
+
+    /// 取消尚未执行的自动重启。
+    pub fn cancel_pending_restart(&mut self) {
+        if self.next_restart_at.take().is_some() {
+            self.restart_token = self.restart_token.wrapping_add(1);
+        }
+    }
+
+    /// 是否应当以 Error 状态展示（崩溃后尚未被人工启动/停止清除）。
+    pub fn is_error(&self) -> bool {
+        self.crashed_at.is_some()
+    }
+
+    /// 当前窗口期内已经发起的自动重启次数。
+    pub fn restart_attempts(&self) -> u32 {
+        self.restart_times.len() as u32
+    }
+}
+
+fn backoff_delay_secs(policy: &RestartPolicy, attempt: u32) -> u64 {
+    let exponent = attempt.saturating_sub(1).min(32);
+    policy
+        .backoff_initial_secs
+        .saturating_mul(1u64 << exponent)
+        .min(policy.backoff_max_secs.max(policy.backoff_initial_secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled_policy() -> RestartPolicy {
+        RestartPolicy {
+            enabled: true,
+            max_restarts: 3,
+            window_secs: 600,
+            backoff_initial_secs: 5,
+            backoff_max_secs: 30,
+            give_up_after: 0,
+        }
+    }
+
+    #[test]
+    fn disabled_policy_never_restarts() {
+        let mut state = CrashState::default();
+        let decision = state.record_crash(&RestartPolicy::default(), Some(1), 100);
+
+        assert_eq!(decision, RestartDecision::Disabled);
+        assert!(state.is_error());
+        assert_eq!(state.last_exit_code, Some(1));
+    }
+
+    #[test]
+    fn backoff_grows_exponentially_and_is_capped() {
+        let policy = RestartPolicy { max_restarts: 10, ..enabled_policy() };
+        let mut state = CrashState::default();
+
+        let delays: Vec<u64> = (0..5)
+            .map(|i| match state.record_crash(&policy, Some(1), 100 + i) {
+                RestartDecision::Restart { delay_secs, .. } => delay_secs,
+                other => panic!("unexpected decision: {:?}", other),
+            })
+            .collect();
+
+        assert_eq!(delays, vec![5, 10, 20, 30, 30]);
+    }
+
+    #[test]
+    fn gives_up_when_window_limit_exceeded() {
+        let policy = enabled_policy();
+        let mut state = CrashState::default();
+        for i in 0..3 {
+            assert!(matches!(
+                state.record_crash(&policy, Some(1), 100 + i),
+                RestartDecision::Restart { .. }
+            ));
+        }
+
+        let decision = state.record_crash(&policy, Some(1), 110);
+        assert!(matches!(decision, RestartDecision::GiveUp { .. }));
+    }
+
+    #[test]
+    fn old_restarts_fall_out_of_window() {
+        let policy = enabled_policy();
+        let mut state = CrashState::default();
+        for i in 0..3 {
+            state.record_crash(&policy, Some(1), 100 + i);
+        }
+
+        let decision = state.record_crash(&policy, Some(1), 100 + 601);
+        assert!(matches!(decision, RestartDecision::Restart { .. }));
+    }
+
+    #[test]
+    fn gives_up_after_consecutive_failures_unless_ready() {
+        let policy = RestartPolicy {
+            give_up_after: 2,
+            max_restarts: 10,
+            ..enabled_policy()
+        };
+        let mut state = CrashState::default();
+
+        assert!(matches!(
+            state.record_crash(&policy, Some(1), 100),
+            RestartDecision::Restart { .. }
+        ));
+        state.mark_ready();
+        assert!(matches!(
+            state.record_crash(&policy, Some(1), 200),
+            RestartDecision::Restart { .. }
+        ));
+        assert!(matches!(
+            state.record_crash(&policy, Some(1), 300),
+            RestartDecision::GiveUp { .. }
+        ));
+    }
+
+    #[test]
+    fn cancel_pending_restart_invalidates_token() {
+        let mut state = CrashState::default();
+        state.record_crash(&enabled_policy(), Some(1), 100);
+        let token = state.restart_token;
+
+        state.cancel_pending_restart();
+
+        assert_ne!(state.restart_token, token);
+        assert_eq!(state.next_restart_at, None);
+    }
+}
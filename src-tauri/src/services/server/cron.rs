@@ -0,0 +1,248 @@
+//! 五段式 cron 表达式解析（分 时 日 月 周），供定时任务使用。
+//!
+//! 支持的语法：
+//! - `*`、`?`、单值、区间 `a-b`、步长 `*/n` / `a-b/n` / `a/n`、列表 `a,b,c`
+//! - 月份与星期可以使用英文缩写（`JAN`、`MON` 等），星期 `0` 与 `7` 都表示周日
+//! - 预设别名：`@yearly`/`@annually`、`@monthly`、`@weekly`、`@daily`/`@midnight`、`@hourly`
+//!
+//! 与常见 cron 实现一致：日与星期同时被限定时，两者满足其一即触发。
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Timelike};
+
+const MONTH_NAMES: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+const WEEKDAY_NAMES: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// 向后搜索的最长跨度，覆盖 2 月 29 日这类四年一遇的表达式
+const MAX_SEARCH_DAYS: i64 = 366 * 5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronExpression {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    day_of_month_restricted: bool,
+    day_of_week_restricted: bool,
+}
+
+impl CronExpression {
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let expression = expression.trim();
+        let expanded = match expression.to_ascii_lowercase().as_str() {
+            "@yearly" | "@annually" => "0 0 1 1 *".to_string(),
+            "@monthly" => "0 0 1 * *".to_string(),
+            "@weekly" => "0 0 * * 0".to_string(),
+            "@daily" | "@midnight" => "0 0 * * *".to_string(),
+            "@hourly" => "0 * * * *".to_string(),
+            _ => expression.to_string(),
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!(
+                "cron 表达式需要 5 个字段（分 时 日 月 周），实际为 {} 个",
+                fields.len()
+            ));
+        }
+
+        let minutes = parse_field(fields[0], 0, 59, &[]).map_err(|e| format!("分钟字段{}", e))?;
+        let hours = parse_field(fields[1], 0, 23, &[]).map_err(|e| format!("小时字段{}", e))?;
+        let days_of_month =
+            parse_field(fields[2], 1, 31, &[]).map_err(|e| format!("日期字段{}", e))?;
+        let months =
+            parse_field(fields[3], 1, 12, &MONTH_NAMES).map_err(|e| format!("月份字段{}", e))?;
+        let mut days_of_week =
+            parse_field(fields[4], 0, 7, &WEEKDAY_NAMES).map_err(|e| format!("星期字段{}", e))?;
+        // 7 与 0 同为周日
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+
+        Ok(CronExpression {
+            minutes,
+            hours,
+            days_of_month,
+            months,
+            days_of_week,
+            day_of_month_restricted: is_restricted(fields[2]),
+            day_of_week_restricted: is_restricted(fields[4]),
+        })
+    }
+
+    /// 返回严格晚于 `after` 的下一个触发时间（精确到分钟）。
+    pub fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut t = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = t + Duration::days(MAX_SEARCH_DAYS);
+
+        while t <= limit {
+            if !bit_set(self.months, t.month()) {
+                let (year, month) = if t.month() == 12 {
+                    (t.year() + 1, 1)
+                } else {
+                    (t.year(), t.month() + 1)
+                };
+                t = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !self.day_matches(t.date()) {
+                t = t.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !bit_set(self.hours, t.hour()) {
+                t = t.date().and_hms_opt(t.hour(), 0, 0)? + Duration::hours(1);
+                continue;
+            }
+            if !bit_set(self.minutes, t.minute()) {
+                t += Duration::minutes(1);
+                continue;
+            }
+            return Some(t);
+        }
+        None
+    }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let dom = bit_set(self.days_of_month, date.day());
+        let dow = bit_set(self.days_of_week, date.weekday().num_days_from_sunday());
+        match (self.day_of_month_restricted, self.day_of_week_restricted) {
+            (true, true) => dom || dow,
+            (true, false) => dom,
+            (false, true) => dow,
+            (false, false) => true,
+        }
+    }
+}
+
+fn is_restricted(field: &str) -> bool {
+    !(field.starts_with('*') || field == "?")
+}
+
+fn bit_set(mask: u64, value: u32) -> bool {
+    value < 64 && mask & (1u64 << value) != 0
+}
+
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<u64, String> {
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        if part.is_empty() {
+            return Err(" 存在空的列表项".to_string());
+        }
+
+        let (range_part, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step = step
+                    .parse::<u32>()
+                    .map_err(|_| format!(" 步长无效: {}", step))?;
+                if step == 0 {
+                    return Err(" 步长不能为 0".to_string());
+                }
+                if step > max {
+                    return Err(format!(" 步长不能大于 {}: {}", max, step));
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = if range_part == "*" || range_part == "?" {
+            (min, max)
+        } else if let Some((start, end)) = range_part.split_once('-') {
+            (parse_value(start, min, names)?, parse_value(end, min, names)?)
+        } else {
+            let value = parse_value(range_part, min, names)?;
+            // `a/n` 表示从 a 开始到字段上限，按步长取值
+            if part.contains('/') {
+                (value, max)
+            } else {
+                (value, value)
+            }
+        };
+
+        if start < min || end > max || start > end {
+            return Err(format!(" 取值超出范围 {}-{}: {}", min, max, part));
+        }
+
+        let mut value = start;
+        while value <= end {
+            mask |= 1u64 << value;
+            value += step;
+        }
+    }
+    Ok(mask)
+}
+
+fn parse_value(raw: &str, min: u32, names: &[&str]) -> Result<u32, String> {
+    if let Ok(value) = raw.parse::<u32>() {
+        return Ok(value);
+    }
+    names
+        .iter()
+        .position(|name| name.eq_ignore_ascii_case(raw))
+        .map(|index| index as u32 + min)
+        .ok_or_else(|| format!(" 无法识别的取值: {}", raw))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, mo, d)
+            .unwrap()
+            .and_hms_opt(h, mi, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn daily_restart_at_four() {
+        let cron = CronExpression::parse("0 4 * * *").unwrap();
+        assert_eq!(cron.next_after(at(2024, 5, 1, 3, 59)), Some(at(2024, 5, 1, 4, 0)));
+        assert_eq!(cron.next_after(at(2024, 5, 1, 4, 0)), Some(at(2024, 5, 2, 4, 0)));
+    }
+
+    #[test]
+    fn steps_ranges_and_lists() {
+        let cron = CronExpression::parse("*/15 9-17 * * MON-FRI").unwrap();
+        // 2024-05-04 是周六，应跳到周一 09:00
+        assert_eq!(cron.next_after(at(2024, 5, 4, 12, 0)), Some(at(2024, 5, 6, 9, 0)));
+        assert_eq!(cron.next_after(at(2024, 5, 6, 9, 0)), Some(at(2024, 5, 6, 9, 15)));
+
+        let cron = CronExpression::parse("5,35 * * * *").unwrap();
+        assert_eq!(cron.next_after(at(2024, 5, 6, 9, 6)), Some(at(2024, 5, 6, 9, 35)));
+    }
+
+    #[test]
+    fn day_of_month_or_day_of_week() {
+        // 每月 1 日或每周日
+        let cron = CronExpression::parse("0 0 1 * 0").unwrap();
+        assert_eq!(cron.next_after(at(2024, 5, 1, 0, 0)), Some(at(2024, 5, 5, 0, 0)));
+    }
+
+    #[test]
+    fn sunday_as_seven_and_aliases() {
+        let seven = CronExpression::parse("0 0 * * 7").unwrap();
+        let weekly = CronExpression::parse("@weekly").unwrap();
+        assert_eq!(seven, weekly);
+    }
+
+    #[test]
+    fn leap_day_is_found() {
+        let cron = CronExpression::parse("0 0 29 2 *").unwrap();
+        assert_eq!(cron.next_after(at(2025, 3, 1, 0, 0)), Some(at(2028, 2, 29, 0, 0)));
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        assert!(CronExpression::parse("* * * *").is_err());
+        assert!(CronExpression::parse("60 * * * *").is_err());
+        assert!(CronExpression::parse("*/0 * * * *").is_err());
+        assert!(CronExpression::parse("1/4294967295 * * * *").is_err());
+        assert!(CronExpression::parse("0 0-23/24 * * *").is_err());
+        assert!(CronExpression::parse("*/4294967296 * * * *").is_err());
+        assert!(CronExpression::parse("0/59 * * * *").is_ok());
+        assert!(CronExpression::parse("0 0 * FOO *").is_err());
+    }
+}
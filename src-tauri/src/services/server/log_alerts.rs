@@ -0,0 +1,836 @@
+//! 日志驱动的告警规则。
+//!
+//! 每条规则绑定一个服务器，按正则、结构化级别或 logger 匹配服务器输出（见 log_parser.rs），
+//! `threshold.window_secs` 秒内命中 `threshold.count` 次即触发，`cooldown_secs`（至少
+//! `ALERT_MIN_COOLDOWN_SECS`）内不会重复触发；规则自己下发控制台命令后，冷却期内的命中不计数，
+//! 避免命令输出再次命中规则而循环触发。
+//! 触发后交给唯一的后台线程依次执行动作（桌面通知、Webhook、控制台命令、标记不健康），结果写入触发历史；
+//! 待执行队列最多 `ALERT_ACTION_QUEUE_CAPACITY` 项，队列满时跳过本次动作，只记录触发。
+//!
+//! 规则与触发历史分别持久化在应用数据目录的 `ALERT_RULES_FILE` / `ALERT_HISTORY_FILE` 中；
+//! 滑动窗口计数只保存在内存，应用重启后重新计数。
+//! `test_rule` 用日志数据库中的历史日志回放规则，只统计触发点，不执行任何动作。
+
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::sync::{mpsc, Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use regex::Regex;
+use rusqlite::params;
+
+use crate::models::alert::{
+    AlertAction, AlertActionResult, AlertCondition, AlertFiring, AlertRule, AlertRuleRequest,
+    AlertTestFiring, AlertTestResult, AlertThreshold,
+};
+use crate::services::global;
+
+use super::log_parser::{normalize_level, parse_log_line, ParsedLogLine};
+use super::log_pipeline::{
+    self as server_log_pipeline, open_or_create_log_db, resolve_server_path,
+};
+use super::manager::{current_timestamp_millis, current_timestamp_secs};
+
+///此处常量见 utils/constants.rs
+use crate::utils::constants::{
+    ALERT_ACTION_QUEUE_CAPACITY, ALERT_HISTORY_FILE, ALERT_HISTORY_LIMIT, ALERT_MIN_COOLDOWN_SECS,
+    ALERT_RULES_FILE, ALERT_TEST_MAX_FIRINGS, ALERT_TEST_MAX_LINES, ALERT_WEBHOOK_TIMEOUT_SECS,
+};
+
+/// 桌面通知的发送方式由宿主注册（Tauri 模式下为系统通知），参数为标题与正文
+pub type AlertNotifier = Arc<dyn Fn(&str, &str) -> Result<(), String> + Send + Sync>;
+
+static ALERT_NOTIFIER: OnceLock<AlertNotifier> = OnceLock::new();
+
+pub fn set_alert_notifier(notifier: AlertNotifier) -> Result<(), String> {
+    ALERT_NOTIFIER
+        .set(notifier)
+        .map_err(|_| "alert notifier already set".to_string())
+}
+
+/// 编译后的匹配条件
+struct RuleMatcher {
+    regex: Option<Regex>,
+    levels: Vec<&'static str>,
+    logger: Option<String>,
+}
+
+impl RuleMatcher {
+    fn compile(condition: &AlertCondition) -> Result<Self, String> {
+        let regex = match condition
+            .pattern
+            .as_deref()
+            .filter(|p| !p.trim().is_empty())
+        {
+            Some(pattern) => {
+                Some(Regex::new(pattern).map_err(|e| format!("正则表达式无效: {}", e))?)
+            }
+            None => None,
+        };
+        let levels = condition
+            .levels
+            .iter()
+            .map(|level| normalize_level(level).ok_or_else(|| format!("未知的日志级别: {}", level)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let logger = condition
+            .logger
+            .as_deref()
+            .map(str::trim)
+            .filter(|logger| !logger.is_empty())
+            .map(str::to_string);
+
+        if regex.is_none() && levels.is_empty() && logger.is_none() {
+            return Err("告警条件至少需要正则、日志级别或 logger 中的一项".to_string());
+        }
+        Ok(Self { regex, levels, logger })
+    }
+
+    /// 是否需要结构化解析结果
+    fn needs_parse(&self) -> bool {
+        !self.levels.is_empty() || self.logger.is_some()
+    }
+
+    fn matches(&self, line: &str, level: Option<&str>, logger: Option<&str>) -> bool {
+        if self
+            .regex
+            .as_ref()
+            .is_some_and(|regex| !regex.is_match(line))
+        {
+            return false;
+        }
+        if !self.levels.is_empty() && !level.is_some_and(|level| self.levels.contains(&level)) {
+            return false;
+        }
+        if let Some(expected) = &self.logger {
+            if !logger.is_some_and(|logger| logger.eq_ignore_ascii_case(expected)) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// 滑动窗口计数
+#[derive(Default)]
+struct RuleWindow {
+    hits: VecDeque<i64>,
+    last_fired: Option<i64>,
+    /// 规则自己的控制台命令最近一次下发的时间
+    command_sent: Option<i64>,
+}
+
+impl RuleWindow {
+    /// 记录一次命中（毫秒时间戳），达到阈值且不在冷却期时返回窗口内的命中次数
+    fn hit(&mut self, now: i64, threshold: &AlertThreshold, cooldown_secs: u64) -> Option<usize> {
+        // 手动编辑的规则文件可能绕过校验，这里同样保证最短冷却
+        let cooldown_ms = cooldown_secs
+            .max(ALERT_MIN_COOLDOWN_SECS)
+            .saturating_mul(1000) as i64;
+        // 命令输出可能命中规则自身，冷却期内不计入窗口
+        if self
+            .command_sent
+            .is_some_and(|sent| now - sent < cooldown_ms)
+        {
+            return None;
+        }
+
+        let window_ms = threshold.window_secs.saturating_mul(1000) as i64;
+        self.hits.push_back(now);
+        while self
+            .hits
+            .front()
+            .is_some_and(|first| now - *first >= window_ms)
+        {
+            self.hits.pop_front();
+        }
+        if self.hits.len() < threshold.count.max(1) as usize {
+            return None;
+        }
+        if self.last_fired.is_some_and(|last| now - last < cooldown_ms) {
+            return None;
+        }
+
+        let count = self.hits.len();
+        self.hits.clear();
+        self.last_fired = Some(now);
+        Some(count)
+    }
+}
+
+struct ActiveRule {
+    rule: AlertRule,
+    /// 持久化文件被手动改坏时为 None，该规则不参与匹配
+    matcher: Option<RuleMatcher>,
+    window: RuleWindow,
+}
+
+impl ActiveRule {
+    fn new(rule: AlertRule) -> Self {
+        let matcher = RuleMatcher::compile(&rule.condition).ok();
+        Self {
+            rule,
+            matcher,
+            window: RuleWindow::default(),
+        }
+    }
+}
+
+/// 等待执行动作的一次触发
+struct PendingFiring {
+    rule: AlertRule,
+    match_count: usize,
+    line: String,
+    fired_at: i64,
+}
+
+pub struct AlertManager {
+    rules: Mutex<Vec<ActiveRule>>,
+    /// 按时间正序保存，最多 ALERT_HISTORY_LIMIT 条
+    history: Mutex<Vec<AlertFiring>>,
+    /// 被告警标记为不健康的服务器及原因
+    unhealthy: Mutex<HashMap<String, String>>,
+    /// 动作执行线程的有界队列，首次触发时创建
+    action_queue: OnceLock<mpsc::SyncSender<PendingFiring>>,
+    data_dir: String,
+}
+
+impl AlertManager {
+    pub fn new() -> Self {
+        let data_dir = crate::utils::path::get_or_create_app_data_dir();
+        let rules = load_json::<Vec<AlertRule>>(&data_dir, ALERT_RULES_FILE)
+            .into_iter()
+            .map(ActiveRule::new)
+            .collect();
+        let history = load_json(&data_dir, ALERT_HISTORY_FILE);
+
+        AlertManager {
+            rules: Mutex::new(rules),
+            history: Mutex::new(history),
+            unhealthy: Mutex::new(HashMap::new()),
+            action_queue: OnceLock::new(),
+            data_dir,
+        }
+    }
+
+    pub fn list_rules(&self, server_id: Option<&str>) -> Result<Vec<AlertRule>, String> {
+        Ok(self
+            .lock_rules()?
+            .iter()
+            .filter(|active| {
+                server_id
+                    .map(|id| active.rule.server_id == id)
+                    .unwrap_or(true)
+            })
+            .map(|active| active.rule.clone())
+            .collect())
+    }
+
+    pub fn create_rule(&self, request: AlertRuleRequest) -> Result<AlertRule, String> {
+        let name = validate_rule_request(&request)?;
+        let rule = AlertRule {
+            id: uuid::Uuid::new_v4().to_string(),
+            server_id: request.server_id,
+            name,
+            enabled: request.enabled,
+            condition: request.condition,
+            threshold: request.threshold,
+            cooldown_secs: request.cooldown_secs,
+            actions: request.actions,
+            created_at: current_timestamp_secs(),
+            last_fired_at: None,
+        };
+
+        let mut rules = self.lock_rules()?;
+        rules.push(ActiveRule::new(rule.clone()));
+        self.save_rules(&rules);
+        Ok(rule)
+    }
+
+    pub fn update_rule(&self, id: &str, request: AlertRuleRequest) -> Result<AlertRule, String> {
+        let name = validate_rule_request(&request)?;
+        let mut rules = self.lock_rules()?;
+        let active = rules
+            .iter_mut()
+            .find(|active| active.rule.id == id)
+            .ok_or_else(|| "未找到告警规则".to_string())?;
+
+        let mut rule = active.rule.clone();
+        rule.server_id = request.server_id;
+        rule.name = name;
+        rule.enabled = request.enabled;
+        rule.condition = request.condition;
+        rule.threshold = request.threshold;
+        rule.cooldown_secs = request.cooldown_secs;
+        rule.actions = request.actions;
+        // 条件变化后旧的窗口计数没有意义，重新计数
+        *active = ActiveRule::new(rule.clone());
+
+        self.save_rules(&rules);
+        Ok(rule)
+    }
+
+    pub fn set_rule_enabled(&self, id: &str, enabled: bool) -> Result<AlertRule, String> {
+        let mut rules = self.lock_rules()?;
+        let active = rules
+            .iter_mut()
+            .find(|active| active.rule.id == id)
+            .ok_or_else(|| "未找到告警规则".to_string())?;
+        active.rule.enabled = enabled;
+        active.window = RuleWindow::default();
+
+        let rule = active.rule.clone();
+        self.save_rules(&rules);
+        Ok(rule)
+    }
+
+    pub fn delete_rule(&self, id: &str) -> Result<(), String> {
+        let mut rules = self.lock_rules()?;
+        let before = rules.len();
+        rules.retain(|active| active.rule.id != id);
+        if rules.len() == before {
+            return Err("未找到告警规则".to_string());
+        }
+        self.save_rules(&rules);
+        Ok(())
+    }
+
+    /// 删除服务器时一并清理它的告警规则与不健康标记。
+    pub fn remove_rules_for_server(&self, server_id: &str) {
+        if let Ok(mut rules) = self.lock_rules() {
+            let before = rules.len();
+            rules.retain(|active| active.rule.server_id != server_id);
+            if rules.len() != before {
+                self.save_rules(&rules);
+            }
+        }
+        self.clear_unhealthy(server_id);
+    }
+
+    /// 触发历史，按时间倒序
+    pub fn list_firings(
+        &self,
+        server_id: Option<&str>,
+        rule_id: Option<&str>,
+        limit: Option<usize>,
+    ) -> Result<Vec<AlertFiring>, String> {
+        let history = self
+            .history
+            .lock()
+            .map_err(|_| "alert history lock poisoned".to_string())?;
+        Ok(history
+            .iter()
+            .rev()
+            .filter(|firing| server_id.map(|id| firing.server_id == id).unwrap_or(true))
+            .filter(|firing| rule_id.map(|id| firing.rule_id == id).unwrap_or(true))
+            .take(limit.unwrap_or(ALERT_HISTORY_LIMIT))
+            .cloned()
+            .collect())
+    }
+
+    pub fn unhealthy_reason(&self, server_id: &str) -> Option<String> {
+        self.unhealthy
+            .lock()
+            .ok()
+            .and_then(|unhealthy| unhealthy.get(server_id).cloned())
+    }
+
+    pub fn clear_unhealthy(&self, server_id: &str) {
+        if let Ok(mut unhealthy) = self.unhealthy.lock() {
+            unhealthy.remove(server_id);
+        }
+    }
+
+    /// 用一行服务器输出匹配该服务器的规则，触发的规则交给动作执行线程。
+    pub fn evaluate(&'static self, server_id: &str, line: &str) {
+        let now = current_timestamp_millis();
+        let fired = {
+            let mut rules = match self.lock_rules() {
+                Ok(rules) => rules,
+                Err(_) => return,
+            };
+            let mut parsed: Option<ParsedLogLine> = None;
+            let mut fired = Vec::new();
+            for active in rules.iter_mut() {
+                if !active.rule.enabled || active.rule.server_id != server_id {
+                    continue;
+                }
+                let Some(matcher) = &active.matcher else {
+                    continue;
+                };
+                let (level, logger) = if matcher.needs_parse() {
+                    let parsed = parsed.get_or_insert_with(|| parse_log_line(line));
+                    (parsed.level, parsed.logger.as_deref())
+                } else {
+                    (None, None)
+                };
+                if !matcher.matches(line, level, logger) {
+                    continue;
+                }
+                let rule = &active.rule;
+                if let Some(count) = active.window.hit(now, &rule.threshold, rule.cooldown_secs) {
+                    active.rule.last_fired_at = Some((now / 1000) as u64);
+                    fired.push((active.rule.clone(), count));
+                }
+            }
+            fired
+        };
+
+        for (rule, match_count) in fired {
+            let pending = PendingFiring {
+                rule,
+                match_count,
+                line: line.to_string(),
+                fired_at: now,
+            };
+            if let Err(mpsc::TrySendError::Full(pending)) = self.action_queue().try_send(pending) {
+                let results = pending
+                    .rule
+                    .actions
+                    .iter()
+                    .map(|action| AlertActionResult {
+                        action: action.kind().to_string(),
+                        success: false,
+                        message: "待执行的告警动作过多，已跳过".to_string(),
+                    })
+                    .collect();
+                self.record_firing(&pending, results);
+            }
+        }
+    }
+
+    /// 动作执行线程的队列；所有触发共用一个线程，动作按触发顺序依次执行
+    fn action_queue(&'static self) -> &'static mpsc::SyncSender<PendingFiring> {
+        self.action_queue.get_or_init(|| {
+            let (tx, rx) = mpsc::sync_channel(ALERT_ACTION_QUEUE_CAPACITY);
+            std::thread::spawn(move || {
+                for pending in rx {
+                    self.fire(&pending);
+                }
+            });
+            tx
+        })
+    }
+
+    /// 执行规则的全部动作并记录触发历史
+    fn fire(&self, pending: &PendingFiring) {
+        let rule = &pending.rule;
+        let results = rule
+            .actions
+            .iter()
+            .map(|action| {
+                if matches!(action, AlertAction::Command { .. }) {
+                    self.note_command_sent(&rule.id);
+                }
+                let outcome = execute_action(
+                    rule,
+                    action,
+                    pending.match_count,
+                    &pending.line,
+                    pending.fired_at,
+                );
+                let success = outcome.is_ok();
+                AlertActionResult {
+                    action: action.kind().to_string(),
+                    success,
+                    message: outcome.unwrap_or_else(|err| err),
+                }
+            })
+            .collect();
+        self.record_firing(pending, results);
+    }
+
+    fn record_firing(&self, pending: &PendingFiring, results: Vec<AlertActionResult>) {
+        let PendingFiring { rule, match_count, line, fired_at } = pending;
+
+        // 告警信息写入 SeaLantern 来源的日志，规则只匹配服务器输出，不会被自己再次触发
+        let _ = server_log_pipeline::append_sealantern_log(
+            &rule.server_id,
+            &format!(
+                "[Sea Lantern] 告警「{}」已触发（{} 秒内命中 {} 次）: {}",
+                rule.name, rule.threshold.window_secs, match_count, line
+            ),
+        );
+
+        if let Ok(mut history) = self.history.lock() {
+            history.push(AlertFiring {
+                id: uuid::Uuid::new_v4().to_string(),
+                rule_id: rule.id.clone(),
+                rule_name: rule.name.clone(),
+                server_id: rule.server_id.clone(),
+                fired_at: *fired_at,
+                match_count: *match_count,
+                line: line.clone(),
+                results,
+            });
+            let overflow = history.len().saturating_sub(ALERT_HISTORY_LIMIT);
+            history.drain(..overflow);
+            save_json(&self.data_dir, ALERT_HISTORY_FILE, &*history);
+        }
+        if let Ok(rules) = self.lock_rules() {
+            self.save_rules(&rules);
+        }
+    }
+
+    /// 记录规则下发控制台命令的时间，之后冷却期内该规则的命中不计数
+    fn note_command_sent(&self, rule_id: &str) {
+        if let Ok(mut rules) = self.lock_rules() {
+            if let Some(active) = rules.iter_mut().find(|active| active.rule.id == rule_id) {
+                active.window.command_sent = Some(current_timestamp_millis());
+            }
+        }
+    }
+
+    fn mark_unhealthy(&self, server_id: &str, reason: String) {
+        if let Ok(mut unhealthy) = self.unhealthy.lock() {
+            unhealthy.insert(server_id.to_string(), reason);
+        }
+    }
+
+    fn lock_rules(&self) -> Result<std::sync::MutexGuard<'_, Vec<ActiveRule>>, String> {
+        self.rules
+            .lock()
+            .map_err(|_| "alert rules lock poisoned".to_string())
+    }
+
+    fn save_rules(&self, rules: &[ActiveRule]) {
+        let rules = rules.iter().map(|active| &active.rule).collect::<Vec<_>>();
+        save_json(&self.data_dir, ALERT_RULES_FILE, &rules);
+    }
+}
+
+impl Default for AlertManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 服务器输出的入口，由 log_pipeline 在推送日志事件时调用
+pub fn handle_server_line(server_id: &str, line: &str) {
+    global::alert_manager().evaluate(server_id, line);
+}
+
+/// 用日志数据库中 `since`（毫秒时间戳）之后的服务器输出回放规则，不执行动作
+pub fn test_rule(
+    request: &AlertRuleRequest,
+    since: Option<i64>,
+) -> Result<AlertTestResult, String> {
+    let matcher = RuleMatcher::compile(&request.condition)?;
+    validate_threshold(&request.threshold)?;
+    let server_path = resolve_server_path(&request.server_id)?;
+    replay_history(&server_path, &matcher, request, since)
+}
+
+fn replay_history(
+    server_path: &Path,
+    matcher: &RuleMatcher,
+    request: &AlertRuleRequest,
+    since: Option<i64>,
+) -> Result<AlertTestResult, String> {
+    let conn = open_or_create_log_db(server_path)?;
+    // 只回放最近的 ALERT_TEST_MAX_LINES 行，按写入顺序计算窗口
+    let mut stmt = conn
+        .prepare(
+            r#"SELECT id, timestamp, line, level, logger FROM (
+                 SELECT id, timestamp, line, level, logger FROM log_lines
+                 WHERE source = 'server' AND timestamp >= ?1
+                 ORDER BY id DESC LIMIT ?2
+               ) recent
+               ORDER BY id ASC"#,
+        )
+        .map_err(|e| format!("准备日志读取失败: {}", e))?;
+    let mut rows = stmt
+        .query(params![since.unwrap_or(0), ALERT_TEST_MAX_LINES as i64])
+        .map_err(|e| format!("读取日志失败: {}", e))?;
+
+    let mut window = RuleWindow::default();
+    let mut result = AlertTestResult {
+        scanned_lines: 0,
+        matched_lines: 0,
+        firings: Vec::new(),
+        truncated: false,
+    };
+    while let Some(row) = rows.next().map_err(|e| format!("读取日志失败: {}", e))? {
+        let id: i64 = row.get(0).map_err(|e| e.to_string())?;
+        let timestamp: i64 = row.get(1).map_err(|e| e.to_string())?;
+        let line: String = row.get(2).map_err(|e| e.to_string())?;
+        let level: Option<String> = row.get(3).map_err(|e| e.to_string())?;
+        let logger: Option<String> = row.get(4).map_err(|e| e.to_string())?;
+
+        result.scanned_lines += 1;
+        if !matcher.matches(&line, level.as_deref(), logger.as_deref()) {
+            continue;
+        }
+        result.matched_lines += 1;
+        let Some(match_count) = window.hit(timestamp, &request.threshold, request.cooldown_secs)
+        else {
+            continue;
+        };
+        if result.firings.len() == ALERT_TEST_MAX_FIRINGS {
+            result.truncated = true;
+            continue;
+        }
+        result.firings.push(AlertTestFiring {
+            fired_at: timestamp,
+            match_count,
+            line_id: id,
+            line,
+        });
+    }
+    Ok(result)
+}
+
+fn execute_action(
+    rule: &AlertRule,
+    action: &AlertAction,
+    match_count: usize,
+    line: &str,
+    fired_at: i64,
+) -> Result<String, String> {
+    match action {
+        AlertAction::Notify => {
+            let notifier = ALERT_NOTIFIER
+                .get()
+                .ok_or_else(|| "当前运行环境不支持桌面通知".to_string())?;
+            notifier(
+                &format!("Sea Lantern - 告警「{}」", rule.name),
+                &format!("服务器「{}」{}", server_name(&rule.server_id), line),
+            )?;
+            Ok("已发送桌面通知".to_string())
+        }
+        AlertAction::Webhook { url } => {
+            let payload = serde_json::json!({
+                "rule_id": rule.id,
+                "rule_name": rule.name,
+                "server_id": rule.server_id,
+                "server_name": server_name(&rule.server_id),
+                "fired_at": fired_at,
+                "match_count": match_count,
+                "window_secs": rule.threshold.window_secs,
+                "line": line,
+            });
+            let client = reqwest::blocking::Client::builder()
+                .timeout(Duration::from_secs(ALERT_WEBHOOK_TIMEOUT_SECS))
+                .build()
+                .map_err(|e| format!("创建 HTTP 客户端失败: {}", e))?;
+            let response = client
+                .post(url)
+                .json(&payload)
+                .send()
+                .map_err(|e| format!("调用 Webhook 失败: {}", e))?;
+            let status = response.status();
+            if !status.is_success() {
+                return Err(format!("调用 Webhook 失败: HTTP {}", status));
+            }
+            Ok(format!("Webhook 返回 HTTP {}", status))
+        }
+        AlertAction::Command { command } => {
+            global::server_manager().send_command(&rule.server_id, command)?;
+            Ok(format!("已发送命令: {}", command))
+        }
+        AlertAction::MarkUnhealthy => {
+            global::alert_manager()
+                .mark_unhealthy(&rule.server_id, format!("告警「{}」: {}", rule.name, line));
+            Ok("已将服务器标记为不健康".to_string())
+        }
+    }
+}
+
+fn server_name(server_id: &str) -> String {
+    global::server_manager()
+        .get_server_list()
+        .into_iter()
+        .find(|server| server.id == server_id)
+        .map(|server| server.name)
+        .unwrap_or_else(|| server_id.to_string())
+}
+
+fn validate_rule_request(request: &AlertRuleRequest) -> Result<String, String> {
+    let name = request.name.trim();
+    if name.is_empty() {
+        return Err("告警规则名称不能为空".to_string());
+    }
+    if name.chars().count() > 64 {
+        return Err("告警规则名称不能超过64个字符".to_string());
+    }
+
+    let server_exists = global::server_manager()
+        .get_server_list()
+        .iter()
+        .any(|server| server.id == request.server_id);
+    if !server_exists {
+        return Err("未找到服务器".to_string());
+    }
+
+    RuleMatcher::compile(&request.condition)?;
+    validate_threshold(&request.threshold)?;
+    validate_actions(&request.actions)?;
+    // 控制台命令等动作会反过来产生日志，没有冷却时可能自我循环触发
+    if request.cooldown_secs < ALERT_MIN_COOLDOWN_SECS {
+        return Err(format!("冷却时间至少为 {} 秒", ALERT_MIN_COOLDOWN_SECS));
+    }
+    Ok(name.to_string())
+}
+
+fn validate_threshold(threshold: &AlertThreshold) -> Result<(), String> {
+    if threshold.count == 0 {
+        return Err("触发次数至少为 1".to_string());
+    }
+    if threshold.window_secs == 0 {
+        return Err("统计窗口至少为 1 秒".to_string());
+    }
+    Ok(())
+}
+
+fn validate_actions(actions: &[AlertAction]) -> Result<(), String> {
+    if actions.is_empty() {
+        return Err("告警规则至少需要一个动作".to_string());
+    }
+    for action in actions {
+        match action {
+            AlertAction::Webhook { url } => {
+                let parsed =
+                    reqwest::Url::parse(url).map_err(|_| format!("Webhook 地址无效: {}", url))?;
+                if parsed.scheme() != "http" && parsed.scheme() != "https" {
+                    return Err(format!("Webhook 地址只支持 http / https: {}", url));
+                }
+            }
+            AlertAction::Command { command } if command.trim().is_empty() => {
+                return Err("控制台命令不能为空".to_string());
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn load_json<T: serde::de::DeserializeOwned + Default>(dir: &str, file: &str) -> T {
+    std::fs::read_to_string(Path::new(dir).join(file))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_json<T: serde::Serialize + ?Sized>(dir: &str, file: &str, value: &T) {
+    if let Ok(json) = serde_json::to_string_pretty(value) {
+        let _ = std::fs::write(Path::new(dir).join(file), json);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::log_pipeline::insert_log_line;
+    use super::*;
+    use crate::utils::test_utils::temp_dir;
+
+    fn condition(pattern: Option<&str>, levels: &[&str]) -> AlertCondition {
+        AlertCondition {
+            pattern: pattern.map(str::to_string),
+            levels: levels.iter().map(|level| level.to_string()).collect(),
+            logger: None,
+        }
+    }
+
+    fn request(condition: AlertCondition, count: u32, window_secs: u64) -> AlertRuleRequest {
+        AlertRuleRequest {
+            server_id: "test".to_string(),
+            name: "test".to_string(),
+            enabled: true,
+            condition,
+            threshold: AlertThreshold { count, window_secs },
+            cooldown_secs: 0,
+            actions: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn matcher_combines_pattern_level_and_logger() {
+        let matcher = RuleMatcher::compile(&AlertCondition {
+            pattern: Some(r"(?i)exception".to_string()),
+            levels: vec!["severe".to_string()],
+            logger: Some("luckperms".to_string()),
+        })
+        .unwrap();
+        assert!(matcher.needs_parse());
+        assert!(matcher.matches("Exception in task", Some("ERROR"), Some("LuckPerms")));
+        assert!(!matcher.matches("Exception in task", Some("WARN"), Some("LuckPerms")));
+        assert!(!matcher.matches("Exception in task", Some("ERROR"), None));
+        assert!(!matcher.matches("all good", Some("ERROR"), Some("LuckPerms")));
+
+        assert!(RuleMatcher::compile(&AlertCondition::default()).is_err());
+        assert!(RuleMatcher::compile(&condition(Some("("), &[])).is_err());
+        assert!(RuleMatcher::compile(&condition(None, &["LOUD"])).is_err());
+    }
+
+    #[test]
+    fn window_fires_on_threshold_and_respects_cooldown() {
+        let threshold = AlertThreshold { count: 3, window_secs: 60 };
+        let mut window = RuleWindow::default();
+        assert_eq!(window.hit(0, &threshold, 300), None);
+        assert_eq!(window.hit(10_000, &threshold, 300), None);
+        // 第一次命中已滑出窗口
+        assert_eq!(window.hit(61_000, &threshold, 300), None);
+        assert_eq!(window.hit(62_000, &threshold, 300), Some(3));
+        // 冷却期内即使再次达到阈值也不触发
+        for at in [63_000, 64_000, 65_000] {
+            assert_eq!(window.hit(at, &threshold, 300), None);
+        }
+        assert_eq!(window.hit(362_000, &threshold, 300), None);
+        assert_eq!(window.hit(363_000, &threshold, 300), None);
+        assert_eq!(window.hit(364_000, &threshold, 300), Some(3));
+
+        // 冷却时间为 0 的规则（如手动改过的规则文件）仍按最短冷却处理
+        let threshold = AlertThreshold { count: 1, window_secs: 60 };
+        let mut window = RuleWindow::default();
+        assert_eq!(window.hit(0, &threshold, 0), Some(1));
+        assert_eq!(window.hit(500, &threshold, 0), None);
+        assert_eq!(window.hit(29_999, &threshold, 0), None);
+        assert_eq!(window.hit(30_000, &threshold, 0), Some(3));
+    }
+
+    #[test]
+    fn own_command_output_does_not_retrigger_rule() {
+        let threshold = AlertThreshold { count: 2, window_secs: 600 };
+        let mut window = RuleWindow::default();
+        assert_eq!(window.hit(0, &threshold, 30), None);
+        assert_eq!(window.hit(1_000, &threshold, 30), Some(2));
+
+        // 规则的命令在 1.5 秒时下发，其输出在冷却期内再次命中规则，不计入窗口
+        window.command_sent = Some(1_500);
+        assert_eq!(window.hit(2_000, &threshold, 30), None);
+        assert_eq!(window.hit(20_000, &threshold, 30), None);
+        // 冷却结束后重新计数，需要新的两次命中才触发
+        assert_eq!(window.hit(40_000, &threshold, 30), None);
+        assert_eq!(window.hit(41_000, &threshold, 30), Some(2));
+    }
+
+    #[test]
+    fn replays_rule_against_log_history() {
+        let dir = temp_dir("log_alerts");
+        let conn = open_or_create_log_db(&dir).unwrap();
+        let lines = [
+            (1_000, "server", "[12:00:01] [Server thread/ERROR]: Exception ticking world"),
+            (2_000, "server", "[12:00:02] [Server thread/INFO]: Steve joined the game"),
+            (3_000, "server", "[12:00:03] [Server thread/ERROR]: Exception ticking entity"),
+            (4_000, "sealantern", "[Sea Lantern] ERROR 告警已触发"),
+            (200_000, "server", "[12:03:20] [Server thread/ERROR]: Exception ticking world"),
+            (201_000, "server", "[12:03:21] [Server thread/ERROR]: Exception ticking world"),
+        ];
+        for (timestamp, source, line) in lines {
+            insert_log_line(&conn, timestamp, source, line).unwrap();
+        }
+
+        let rule = request(condition(None, &["ERROR"]), 2, 60);
+        let matcher = RuleMatcher::compile(&rule.condition).unwrap();
+        let result = replay_history(&dir, &matcher, &rule, None).unwrap();
+        assert_eq!(result.scanned_lines, 5);
+        assert_eq!(result.matched_lines, 4);
+        assert_eq!(result.firings.iter().map(|f| f.line_id).collect::<Vec<_>>(), vec![3, 6]);
+        assert_eq!(result.firings[0].match_count, 2);
+
+        let since = replay_history(&dir, &matcher, &rule, Some(100_000)).unwrap();
+        assert_eq!(since.scanned_lines, 2);
+        assert_eq!(since.firings.len(), 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,210 @@
+//! 服务器日志行的结构化解析：从原始输出中拆出级别、线程、logger 与正文。
+//!
+//! 支持的格式：
+//! - 原版 / Fabric（Log4j）：`[12:00:00] [Server thread/INFO]: msg`
+//! - Forge：`[18Oct2026 12:00:00.123] [main/INFO] [cpw.mods.modlauncher.Launcher/MODLAUNCHER]: msg`、
+//!   旧版 `[12:00:00] [Server thread/INFO] [FML]: msg`
+//! - Paper / Spigot / Velocity / Waterfall：`[12:00:00 INFO]: msg`、
+//!   Velocity 插件 `[12:00:00 INFO] [luckperms]: msg`
+//! - BungeeCord：`12:00:00 [INFO] msg`
+//!
+//! Bukkit 系插件日志以 `[PluginName] msg` 开头，这种前缀也视为 logger。
+//! 无法识别的行（堆栈、SeaLantern 自身输出等）只保留正文，级别等字段为空。
+
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+
+/// 可识别的日志级别及其别名（java.util.logging 的 WARNING / SEVERE 等）
+const LEVEL_ALIASES: [(&str, &str); 8] = [
+    ("TRACE", "TRACE"),
+    ("DEBUG", "DEBUG"),
+    ("INFO", "INFO"),
+    ("WARN", "WARN"),
+    ("WARNING", "WARN"),
+    ("ERROR", "ERROR"),
+    ("SEVERE", "ERROR"),
+    ("FATAL", "FATAL"),
+];
+
+static LOG4J_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"^\[[^\]]+\] \[(?P<thread>[^\]]+)/(?P<level>[A-Za-z]+)\](?: \[(?P<logger>[^\]]*)\]| \((?P<paren_logger>[^)]*)\))?: ?(?P<message>.*)$",
+    )
+    .unwrap()
+});
+static PAPER_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"^\[\d{1,2}:\d{2}:\d{2}(?:[.,]\d+)? (?P<level>[A-Za-z]+)\](?: \[(?P<logger>[^\]]+)\])?: ?(?P<message>.*)$",
+    )
+    .unwrap()
+});
+static BUNGEE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\d{1,2}:\d{2}:\d{2} \[(?P<level>[A-Za-z]+)\]:? ?(?P<message>.*)$").unwrap()
+});
+static PLUGIN_PREFIX_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\[(?P<logger>[^\]\s]+)\] (?P<message>.*)$").unwrap());
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParsedLogLine {
+    pub level: Option<&'static str>,
+    pub thread: Option<String>,
+    pub logger: Option<String>,
+    pub message: String,
+}
+
+/// 把级别名称统一为大写的标准名称，未知级别返回 None
+pub fn normalize_level(level: &str) -> Option<&'static str> {
+    let upper = level.trim().to_ascii_uppercase();
+    LEVEL_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == upper)
+        .map(|(_, level)| *level)
+}
+
+pub fn parse_log_line(line: &str) -> ParsedLogLine {
+    if let Some(parsed) = parse_with(&LOG4J_RE, line, false) {
+        return parsed;
+    }
+    for re in [&*PAPER_RE, &*BUNGEE_RE] {
+        if let Some(parsed) = parse_with(re, line, true) {
+            return parsed;
+        }
+    }
+    ParsedLogLine {
+        message: line.to_string(),
+        ..Default::default()
+    }
+}
+
+fn parse_with(re: &Regex, line: &str, plugin_prefix: bool) -> Option<ParsedLogLine> {
+    let caps = re.captures(line)?;
+    let level = normalize_level(&caps["level"])?;
+    let mut message = caps["message"].to_string();
+    let mut logger = logger_name(&caps);
+
+    if logger.is_none() && plugin_prefix {
+        if let Some(prefix) = PLUGIN_PREFIX_RE.captures(&message) {
+            logger = Some(prefix["logger"].to_string());
+            message = prefix["message"].to_string();
+        }
+    }
+
+    Some(ParsedLogLine {
+        level: Some(level),
+        thread: caps.name("thread").map(|m| m.as_str().to_string()),
+        logger,
+        message,
+    })
+}
+
+/// Forge 的 logger 形如 `net.minecraftforge.common.ForgeMod/` 或 `...Launcher/MODLAUNCHER`，
+/// 斜杠后是 marker，只取前半部分
+fn logger_name(caps: &Captures<'_>) -> Option<String> {
+    let raw = caps
+        .name("logger")
+        .or_else(|| caps.name("paren_logger"))?
+        .as_str();
+    let name = raw.split('/').next().unwrap_or(raw).trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(
+        level: &'static str,
+        thread: Option<&str>,
+        logger: Option<&str>,
+        message: &str,
+    ) -> ParsedLogLine {
+        ParsedLogLine {
+            level: Some(level),
+            thread: thread.map(str::to_string),
+            logger: logger.map(str::to_string),
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_vanilla_and_forge_lines() {
+        assert_eq!(
+            parse_log_line("[12:00:00] [Server thread/WARN]: Can't keep up!"),
+            parsed("WARN", Some("Server thread"), None, "Can't keep up!")
+        );
+        assert_eq!(
+            parse_log_line(
+                "[18Oct2026 12:00:00.123] [main/INFO] [cpw.mods.modlauncher.Launcher/MODLAUNCHER]: \
+                 ModLauncher running"
+            ),
+            parsed(
+                "INFO",
+                Some("main"),
+                Some("cpw.mods.modlauncher.Launcher"),
+                "ModLauncher running"
+            )
+        );
+        assert_eq!(
+            parse_log_line(
+                "[12:00:00] [Server thread/ERROR] [net.minecraftforge.common.ForgeMod/]: boom"
+            ),
+            parsed(
+                "ERROR",
+                Some("Server thread"),
+                Some("net.minecraftforge.common.ForgeMod"),
+                "boom"
+            )
+        );
+        assert_eq!(
+            parse_log_line("[12:00:00] [Server thread/INFO] [FML]: Forge Mod Loader version"),
+            parsed("INFO", Some("Server thread"), Some("FML"), "Forge Mod Loader version")
+        );
+        assert_eq!(
+            parse_log_line("[12:00:00] [main/INFO]: message mentions /ERROR] later"),
+            parsed("INFO", Some("main"), None, "message mentions /ERROR] later")
+        );
+    }
+
+    #[test]
+    fn parses_proxy_and_plugin_lines() {
+        assert_eq!(
+            parse_log_line("[12:00:00 ERROR]: Could not pass event"),
+            parsed("ERROR", None, None, "Could not pass event")
+        );
+        assert_eq!(
+            parse_log_line("[12:00:00 INFO]: [LuckPerms] Loading configuration..."),
+            parsed("INFO", None, Some("LuckPerms"), "Loading configuration...")
+        );
+        assert_eq!(
+            parse_log_line("[12:00:00 INFO] [luckperms]: Loading configuration..."),
+            parsed("INFO", None, Some("luckperms"), "Loading configuration...")
+        );
+        assert_eq!(
+            parse_log_line("12:00:00 [WARNING] Plugin is outdated"),
+            parsed("WARN", None, None, "Plugin is outdated")
+        );
+        assert_eq!(
+            parse_log_line("12:00:00 [SEVERE] [Geyser] Failed to bind"),
+            parsed("ERROR", None, Some("Geyser"), "Failed to bind")
+        );
+    }
+
+    #[test]
+    fn keeps_unrecognized_lines_as_message() {
+        for line in [
+            "\tat net.minecraft.server.MinecraftServer.run(MinecraftServer.java:100)",
+            "服务器启动完成",
+            "[12:00:00] [Server thread/VERBOSE]: unknown level",
+        ] {
+            assert_eq!(
+                parse_log_line(line),
+                ParsedLogLine {
+                    message: line.to_string(),
+                    ..Default::default()
+                }
+            );
+        }
+        assert_eq!(normalize_level(" warning "), Some("WARN"));
+        assert_eq!(normalize_level("verbose"), None);
+    }
+}
@@ -9,7 +9,9 @@
 //!    - append_log_by_id
 //!    - append_log
 //!    - 每个 server_id 对应一个常驻 Writer 线程（run_log_writer）
-//!    - 按批次短事务写入 SQLite（flush_batch）
+//!    - 按批次短事务写入 SQLite（flush_batch），同时把每行解析为 level / thread / logger / message 列
+//!      （insert_log_line，格式识别见 log_parser.rs）
+//!    - 空闲或两批之间周期执行保留策略（run_log_maintenance，实现见 log_retention.rs）
 //!
 //!    这样做的原因：
 //!    - 旧实现是“每行日志都 open + pragma + 事务 + commit”，高并发输出下会放大 I/O 和锁竞争。
@@ -17,9 +19,12 @@
 //!    - 事务仍然是短事务（每批提交），避免长时间持有写锁，兼顾吞吐和并发读取。
 //!
 //! 2) 读取链路（按需、强调稳定）
-//!    get_logs / get_all_logs
+//!    get_logs / get_log_entries / get_all_logs
 //!    - read_logs
-//!    - 独立连接读取 SQLite
+//!    get_log_entries_after（插件按页顺序读取）
+//!    - read_log_page
+//!    - 独立连接读取 SQLite，以自增 ID 作为读取游标（保留策略裁剪旧行后游标仍然有效）
+//!    - 全文检索见 log_search.rs，依赖这里建立的 log_lines_fts 索引与同步触发器
 //!
 //!    写读解耦的意义：
 //!    - 写入是否突发，不会直接阻塞“读取函数的调用结构”。
@@ -33,6 +38,7 @@
 //! 可调参数：
 //! - LOG_BATCH_SIZE: 每批最多写入条数（越大吞吐更高，单批延迟也会增大）
 //! - LOG_FLUSH_INTERVAL_MS: 批处理等待窗口（越小实时性越好，事务次数更多）
+//! - LOG_RETENTION_CHECK_INTERVAL_SECS: 保留策略的执行周期，保留上限本身来自应用设置
 //!
 //! 维护者注意：
 //! - 不要把 read_logs 改为复用 Writer 连接（读写耦合会放大故障面）。
@@ -47,10 +53,17 @@ use std::sync::{mpsc, Arc, Mutex, OnceLock};
 use std::thread;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use rusqlite::{params, Connection, TransactionBehavior};
+use rusqlite::{params, Connection, Transaction, TransactionBehavior};
+use serde::Serialize;
+
+use super::log_parser::parse_log_line;
+use super::log_retention::{enforce_retention, LogRetentionPolicy};
 
 ///此处常量见 utils/constants.rs
-use crate::utils::constants::{LATEST_LOG_DB_FILE, LOG_BATCH_SIZE, LOG_FLUSH_INTERVAL_MS};
+use crate::utils::constants::{
+    LATEST_LOG_DB_FILE, LOG_BATCH_SIZE, LOG_FLUSH_INTERVAL_MS, LOG_MIGRATION_CHUNK,
+    LOG_RETENTION_CHECK_INTERVAL_SECS, LOG_RETENTION_INITIAL_DELAY_SECS,
+};
 
 pub type ServerLogEventHandler = Arc<dyn Fn(&str, &str) -> Result<(), String> + Send + Sync>;
 pub type ServerLogProcessor = Arc<dyn Fn(&str, &str) -> String + Send + Sync>;
@@ -68,6 +81,8 @@ struct LogWriteEntry {
 
 enum WriterCommand {
     Append(LogWriteEntry),
+    /// 写入已收到的日志后回复
+    Flush(mpsc::Sender<()>),
     Shutdown,
 }
 
@@ -137,6 +152,24 @@ pub fn shutdown_writer(server_id: &str) {
     }
 }
 
+/// 等待 Writer 把已收到的日志写入数据库，Writer 保持运行；没有 Writer 时直接返回。
+pub fn flush_writer(server_id: &str) {
+    let (ack_tx, ack_rx) = mpsc::channel();
+    let sent = log_writers()
+        .lock()
+        .ok()
+        .and_then(|writers| {
+            writers
+                .get(server_id)
+                .map(|writer| writer.sender.send(WriterCommand::Flush(ack_tx)).is_ok())
+        })
+        .unwrap_or(false);
+    if sent {
+        // Writer 异常退出时发送端随之释放，recv 立即返回
+        let _ = ack_rx.recv();
+    }
+}
+
 pub fn append_sealantern_log(server_id: &str, message: &str) -> Result<(), String> {
     append_log_by_id(server_id, message, LogSource::SeaLantern)
 }
@@ -145,13 +178,86 @@ pub fn append_server_log(server_id: &str, message: &str) -> Result<(), String> {
     append_log_by_id(server_id, message, LogSource::Server)
 }
 
+/// 读取日志行 ID 大于 `since` 的日志；`since` 为 0 时从头读取。
 pub fn get_logs(server_id: &str, since: usize, recent_limit: Option<usize>) -> Vec<String> {
+    get_log_entries(server_id, since, recent_limit)
+        .into_iter()
+        .map(|(_, line)| line)
+        .collect()
+}
+
+/// 一次增量读取的结果，`next_since` 为最后一行的 ID，没有新日志时等于传入的 `since`
+#[derive(Debug, Clone, Serialize)]
+pub struct LogPage {
+    pub lines: Vec<String>,
+    pub next_since: usize,
+}
+
+/// 同 `get_logs`，附带下一次读取的 `since` 游标。
+pub fn get_log_page(server_id: &str, since: usize, recent_limit: Option<usize>) -> LogPage {
+    let entries = get_log_entries(server_id, since, recent_limit);
+    let next_since = entries.last().map_or(since, |(id, _)| *id);
+    LogPage {
+        lines: entries.into_iter().map(|(_, line)| line).collect(),
+        next_since,
+    }
+}
+
+/// 读取 `since` 之后最早的 `limit` 行（附带 ID）；与 `recent_limit` 取最新若干行不同，
+/// 以最后一行的 ID 作为下一次的 `since` 可以不遗漏地逐页读完全部日志。
+pub fn get_log_entries_after(server_id: &str, since: usize, limit: usize) -> Vec<(usize, String)> {
+    resolve_server_path(server_id)
+        .ok()
+        .and_then(|server_path| read_log_page(&server_path, since as u64, limit).ok())
+        .map(|logs| {
+            logs.into_iter()
+                .map(|(id, line)| (id as usize, line))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 同 `get_logs`，附带每行的 ID，调用方以最后一行的 ID 作为下一次读取的 `since`。
+pub fn get_log_entries(
+    server_id: &str,
+    since: usize,
+    recent_limit: Option<usize>,
+) -> Vec<(usize, String)> {
     resolve_server_path(server_id)
         .ok()
         .and_then(|server_path| read_logs(&server_path, since as u64, recent_limit).ok())
+        .map(|logs| {
+            logs.into_iter()
+                .map(|(id, line)| (id as usize, line))
+                .collect()
+        })
         .unwrap_or_default()
 }
 
+/// 当前已持久化的最新日志行 ID，可作为 `get_logs` 的 `since` 起点只读取之后的新日志。
+pub fn get_latest_log_id(server_id: &str) -> usize {
+    resolve_server_path(server_id)
+        .ok()
+        .and_then(|server_path| latest_log_id(&server_path).ok())
+        .map(|id| id as usize)
+        .unwrap_or(0)
+}
+
+/// 轮询 ID 大于 `since` 的新日志，直到出现包含 `needle` 的行或超时，返回是否等到。
+pub fn wait_for_log_line(server_id: &str, since: usize, needle: &str, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(500));
+        if get_logs(server_id, since, None)
+            .iter()
+            .any(|line| line.contains(needle))
+        {
+            return true;
+        }
+    }
+    false
+}
+
 pub fn get_all_logs() -> Vec<(String, Vec<String>)> {
     let server_ids = crate::services::global::server_manager()
         .get_server_list()
@@ -219,16 +325,27 @@ fn run_log_writer(server_id: String, server_path: PathBuf, rx: mpsc::Receiver<Wr
     };
 
     let flush_interval = Duration::from_millis(LOG_FLUSH_INTERVAL_MS);
+    let maintenance_interval = Duration::from_secs(LOG_RETENTION_CHECK_INTERVAL_SECS);
+    let mut next_maintenance =
+        Instant::now() + Duration::from_secs(LOG_RETENTION_INITIAL_DELAY_SECS);
     let mut batch = Vec::<LogWriteEntry>::with_capacity(LOG_BATCH_SIZE);
 
     // Writer 主循环：
     // - 至少取到一条日志后再进入“时间窗口聚合”，减少空转
     // - 到达批大小上限，或时间窗口耗尽，就立刻 flush
     // - 收到 Shutdown/断连时确保尽力刷盘后退出
+    // - 两批之间到达维护时间时执行保留策略（此时没有未提交的批次）
     loop {
-        let first = match rx.recv() {
+        if Instant::now() >= next_maintenance {
+            run_log_maintenance(&server_id, &server_path, &conn);
+            next_maintenance = Instant::now() + maintenance_interval;
+        }
+
+        let idle = next_maintenance.saturating_duration_since(Instant::now());
+        let first = match rx.recv_timeout(idle) {
             Ok(cmd) => cmd,
-            Err(_) => {
+            Err(mpsc::RecvTimeoutError::Timeout) => continue,
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
                 if !batch.is_empty() {
                     let _ = flush_batch(&mut conn, &batch);
                 }
@@ -240,6 +357,7 @@ fn run_log_writer(server_id: String, server_path: PathBuf, rx: mpsc::Receiver<Wr
             WriterCommand::Append(entry) => {
                 batch.push(entry);
                 let deadline = Instant::now() + flush_interval;
+                let mut flush_ack = None;
                 while batch.len() < LOG_BATCH_SIZE {
                     let remain = deadline.saturating_duration_since(Instant::now());
                     if remain.is_zero() {
@@ -247,6 +365,10 @@ fn run_log_writer(server_id: String, server_path: PathBuf, rx: mpsc::Receiver<Wr
                     }
                     match rx.recv_timeout(remain) {
                         Ok(WriterCommand::Append(entry)) => batch.push(entry),
+                        Ok(WriterCommand::Flush(ack)) => {
+                            flush_ack = Some(ack);
+                            break;
+                        }
                         Ok(WriterCommand::Shutdown) => {
                             let _ = flush_batch(&mut conn, &batch);
                             return;
@@ -268,6 +390,13 @@ fn run_log_writer(server_id: String, server_path: PathBuf, rx: mpsc::Receiver<Wr
                     );
                 }
                 batch.clear();
+                if let Some(ack) = flush_ack {
+                    let _ = ack.send(());
+                }
+            }
+            // 批次在每轮结束时已提交，这里没有待写入的日志
+            WriterCommand::Flush(ack) => {
+                let _ = ack.send(());
             }
             WriterCommand::Shutdown => {
                 if !batch.is_empty() {
@@ -279,6 +408,36 @@ fn run_log_writer(server_id: String, server_path: PathBuf, rx: mpsc::Receiver<Wr
     }
 }
 
+/// 按当前设置执行日志保留策略：裁剪、归档、checkpoint / VACUUM，失败只记录不中断写入
+fn run_log_maintenance(server_id: &str, server_path: &Path, conn: &Connection) {
+    let settings = crate::services::global::settings_manager().get();
+    let policy = LogRetentionPolicy::from_settings(&settings);
+    let now_ms = super::manager::current_timestamp_millis();
+
+    match enforce_retention(conn, server_path, &policy, now_ms) {
+        Ok(outcome) if outcome.pruned_rows > 0 => {
+            eprintln!(
+                "[server_log_pipeline] pruned {} log lines id={} archive={}",
+                outcome.pruned_rows,
+                server_id,
+                outcome
+                    .archive
+                    .map(|path| path.display().to_string())
+                    .unwrap_or_default()
+            );
+        }
+        Ok(_) => {}
+        Err(err) => {
+            eprintln!(
+                "[server_log_pipeline] log retention failed id={} path={} err={}",
+                server_id,
+                server_path.display(),
+                err
+            );
+        }
+    }
+}
+
 fn flush_batch(conn: &mut Connection, batch: &[LogWriteEntry]) -> Result<(), String> {
     if batch.is_empty() {
         return Ok(());
@@ -289,20 +448,41 @@ fn flush_batch(conn: &mut Connection, batch: &[LogWriteEntry]) -> Result<(), Str
         .transaction_with_behavior(TransactionBehavior::Immediate)
         .map_err(|e| format!("打开日志写事务失败: {}", e))?;
 
-    {
-        let mut stmt = tx
-            .prepare("INSERT INTO log_lines (timestamp, source, line) VALUES (?1, ?2, ?3)")
-            .map_err(|e| format!("准备日志写入失败: {}", e))?;
-        for entry in batch {
-            stmt.execute(params![entry.timestamp, entry.source.as_str(), entry.message])
-                .map_err(|e| format!("写入日志失败: {}", e))?;
-        }
+    for entry in batch {
+        insert_log_line(&tx, entry.timestamp, entry.source.as_str(), &entry.message)?;
     }
 
     tx.commit()
         .map_err(|e| format!("提交日志写事务失败: {}", e))
 }
 
+/// 解析并写入一行日志；解析放在 Writer 线程里做，不占用 stdout/stderr 消费线程
+pub(super) fn insert_log_line(
+    conn: &Connection,
+    timestamp: i64,
+    source: &str,
+    line: &str,
+) -> Result<(), String> {
+    let parsed = parse_log_line(line);
+    let mut stmt = conn
+        .prepare_cached(
+            r#"INSERT INTO log_lines (timestamp, source, line, level, thread, logger, message)
+               VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"#,
+        )
+        .map_err(|e| format!("准备日志写入失败: {}", e))?;
+    stmt.execute(params![
+        timestamp,
+        source,
+        line,
+        parsed.level,
+        parsed.thread,
+        parsed.logger,
+        parsed.message
+    ])
+    .map(|_| ())
+    .map_err(|e| format!("写入日志失败: {}", e))
+}
+
 pub fn append_log(
     server_id: &str,
     server_path: &Path,
@@ -329,7 +509,7 @@ pub fn append_log(
             .send(WriterCommand::Append(entry))
             .map_err(|e| format!("提交日志写入队列失败: {}", e))?;
     }
-    emit_server_log_line(server_id, message);
+    emit_server_log_line(server_id, message, source);
     Ok(())
 }
 
@@ -338,35 +518,40 @@ fn append_log_by_id(server_id: &str, message: &str, source: LogSource) -> Result
     append_log(server_id, &server_path, message, source)
 }
 
+/// 读取 ID 大于 `since` 的日志行及其 ID；`recent_limit` 限制为其中最新的若干行。
+///
+/// 保留策略会从头部裁剪旧行，因此游标用自增 ID 而不是行偏移，裁剪后游标依然有效。
 pub fn read_logs(
     server_path: &Path,
     since: u64,
     recent_limit: Option<usize>,
-) -> Result<Vec<String>, String> {
+) -> Result<Vec<(u64, String)>, String> {
     let conn = open_or_create_log_db(server_path)?;
+    let map_row =
+        |row: &rusqlite::Row| Ok((row.get::<_, i64>(0)? as u64, row.get::<_, String>(1)?));
     let mut logs = Vec::new();
 
     if let Some(limit) = recent_limit.filter(|v| *v > 0) {
         let mut stmt = conn
             .prepare(
-                r#"SELECT line FROM (
-                       SELECT rowid, line FROM log_lines ORDER BY rowid DESC LIMIT ?1
+                r#"SELECT id, line FROM (
+                       SELECT id, line FROM log_lines WHERE id > ?2 ORDER BY id DESC LIMIT ?1
                    ) recent
-                   ORDER BY rowid ASC LIMIT -1 OFFSET ?2"#,
+                   ORDER BY id ASC"#,
             )
             .map_err(|e| format!("准备日志读取失败: {}", e))?;
         let rows = stmt
-            .query_map(params![limit as i64, since as i64], |row| row.get::<_, String>(0))
+            .query_map(params![limit as i64, since as i64], map_row)
             .map_err(|e| format!("读取日志失败: {}", e))?;
         for line in rows {
             logs.push(line.map_err(|e| format!("解析日志失败: {}", e))?);
         }
     } else {
         let mut stmt = conn
-            .prepare("SELECT line FROM log_lines ORDER BY rowid ASC LIMIT -1 OFFSET ?1")
+            .prepare("SELECT id, line FROM log_lines WHERE id > ?1 ORDER BY id ASC")
             .map_err(|e| format!("准备日志读取失败: {}", e))?;
         let rows = stmt
-            .query_map(params![since as i64], |row| row.get::<_, String>(0))
+            .query_map(params![since as i64], map_row)
             .map_err(|e| format!("读取日志失败: {}", e))?;
         for line in rows {
             logs.push(line.map_err(|e| format!("解析日志失败: {}", e))?);
@@ -375,6 +560,32 @@ pub fn read_logs(
     Ok(logs)
 }
 
+/// 从 `since` 之后按写入顺序向前读取至多 `limit` 行，用于逐页遍历完整日志
+pub fn read_log_page(
+    server_path: &Path,
+    since: u64,
+    limit: usize,
+) -> Result<Vec<(u64, String)>, String> {
+    let conn = open_or_create_log_db(server_path)?;
+    let mut stmt = conn
+        .prepare("SELECT id, line FROM log_lines WHERE id > ?1 ORDER BY id ASC LIMIT ?2")
+        .map_err(|e| format!("准备日志读取失败: {}", e))?;
+    let rows = stmt
+        .query_map(params![since as i64, limit as i64], |row| {
+            Ok((row.get::<_, i64>(0)? as u64, row.get::<_, String>(1)?))
+        })
+        .map_err(|e| format!("读取日志失败: {}", e))?;
+    rows.map(|line| line.map_err(|e| format!("解析日志失败: {}", e)))
+        .collect()
+}
+
+fn latest_log_id(server_path: &Path) -> Result<u64, String> {
+    let conn = open_or_create_log_db(server_path)?;
+    conn.query_row("SELECT COALESCE(MAX(id), 0) FROM log_lines", [], |row| row.get::<_, i64>(0))
+        .map(|id| id.max(0) as u64)
+        .map_err(|e| format!("读取日志失败: {}", e))
+}
+
 pub fn spawn_server_output_reader<R>(server_id: String, reader: R)
 where
     R: Read + Send + 'static,
@@ -395,19 +606,28 @@ where
                     }
 
                     let _ = append_server_log(&server_id, &line);
+                    super::player_sessions::handle_log_line(&server_id, &line);
 
                     if line.contains("Done (") && line.contains(")! For help") {
-                        crate::services::global::server_manager().clear_starting(&server_id);
+                        crate::services::global::server_manager().mark_server_ready(&server_id);
                         let _ = crate::plugins::api::emit_server_ready(&server_id);
                     }
                 }
                 Err(_) => break,
             }
         }
+
+        // 输出流关闭意味着进程已退出，结束仍在线玩家的会话
+        super::player_sessions::close_all(&server_id, "服务器已停止");
     });
 }
 
-fn emit_server_log_line(server_id: &str, line: &str) {
+fn emit_server_log_line(server_id: &str, line: &str, source: LogSource) {
+    // 告警规则只匹配服务器自身输出，按处理器改写前的原始行匹配
+    if matches!(source, LogSource::Server) {
+        super::log_alerts::handle_server_line(server_id, line);
+    }
+
     let processed_line = process_log_line(server_id, line);
     if let Some(handler) = SERVER_LOG_EVENT_HANDLER.get() {
         let _ = handler(server_id, &processed_line);
@@ -438,7 +658,7 @@ fn process_log_line(server_id: &str, line: &str) -> String {
     processed_line
 }
 
-fn open_or_create_log_db(server_path: &Path) -> Result<Connection, String> {
+pub(super) fn open_or_create_log_db(server_path: &Path) -> Result<Connection, String> {
     let db_path = server_path.join(LATEST_LOG_DB_FILE);
     match init_sqlite_log_db(&db_path) {
         Ok(conn) => Ok(conn),
@@ -470,7 +690,11 @@ fn init_sqlite_log_db(db_path: &Path) -> Result<Connection, String> {
              id INTEGER PRIMARY KEY AUTOINCREMENT,
              timestamp INTEGER NOT NULL,
              source TEXT NOT NULL CHECK(source IN ('sealantern','server')),
-             line TEXT NOT NULL
+             line TEXT NOT NULL,
+             level TEXT,
+             thread TEXT,
+             logger TEXT,
+             message TEXT
          );"#,
     )
     .map_err(|e| e.to_string())?;
@@ -479,20 +703,119 @@ fn init_sqlite_log_db(db_path: &Path) -> Result<Connection, String> {
     let has_source = table_has_column(&conn, "log_lines", "source")?;
     if !has_timestamp || !has_source {
         conn.execute_batch(
-            r#"DROP TABLE IF EXISTS log_lines;
+            r#"DROP TABLE IF EXISTS log_lines_fts;
+             DROP TABLE IF EXISTS log_lines;
              CREATE TABLE log_lines (
                id INTEGER PRIMARY KEY AUTOINCREMENT,
                timestamp INTEGER NOT NULL,
                source TEXT NOT NULL CHECK(source IN ('sealantern','server')),
-               line TEXT NOT NULL
+               line TEXT NOT NULL,
+               level TEXT,
+               thread TEXT,
+               logger TEXT,
+               message TEXT
              );"#,
         )
         .map_err(|e| e.to_string())?;
     }
 
+    ensure_parsed_columns(&conn)?;
+    conn.execute_batch(
+        r#"CREATE INDEX IF NOT EXISTS idx_log_lines_level ON log_lines(level);
+         CREATE INDEX IF NOT EXISTS idx_log_lines_logger ON log_lines(logger);"#,
+    )
+    .map_err(|e| e.to_string())?;
+    ensure_fts_index(&conn)?;
     Ok(conn)
 }
 
+/// 结构化列迁移：旧库只有原始 line，补上 level / thread / logger / message 列并把已有日志解析回填。
+/// 放在 IMMEDIATE 事务里，Writer 与读取连接同时打开旧库时只有一方执行迁移。
+fn ensure_parsed_columns(conn: &Connection) -> Result<(), String> {
+    if table_has_column(conn, "log_lines", "message")? {
+        return Ok(());
+    }
+
+    let tx = Transaction::new_unchecked(conn, TransactionBehavior::Immediate)
+        .map_err(|e| e.to_string())?;
+    for column in ["level", "thread", "logger", "message"] {
+        if !table_has_column(&tx, "log_lines", column)? {
+            tx.execute_batch(&format!("ALTER TABLE log_lines ADD COLUMN {} TEXT", column))
+                .map_err(|e| e.to_string())?;
+        }
+    }
+
+    let mut last_id = 0_i64;
+    loop {
+        let rows = {
+            let mut stmt = tx
+                .prepare_cached(
+                    "SELECT id, line FROM log_lines WHERE id > ?1 AND message IS NULL \
+                     ORDER BY id LIMIT ?2",
+                )
+                .map_err(|e| e.to_string())?;
+            let rows = stmt
+                .query_map(params![last_id, LOG_MIGRATION_CHUNK as i64], |row| {
+                    Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+                })
+                .map_err(|e| e.to_string())?;
+            rows.collect::<Result<Vec<_>, _>>()
+                .map_err(|e| e.to_string())?
+        };
+        let Some((id, _)) = rows.last() else {
+            break;
+        };
+        last_id = *id;
+
+        let mut update = tx
+            .prepare_cached(
+                "UPDATE log_lines SET level = ?2, thread = ?3, logger = ?4, message = ?5 \
+                 WHERE id = ?1",
+            )
+            .map_err(|e| e.to_string())?;
+        for (id, line) in &rows {
+            let parsed = parse_log_line(line);
+            update
+                .execute(params![id, parsed.level, parsed.thread, parsed.logger, parsed.message])
+                .map_err(|e| e.to_string())?;
+        }
+    }
+
+    tx.commit().map_err(|e| e.to_string())
+}
+
+/// 全文索引：trigram 分词的外部内容 FTS5 表，由触发器随 log_lines 的插入 / 删除同步。
+/// 没有索引的旧数据库在首次打开时从 log_lines 重建一次。
+fn ensure_fts_index(conn: &Connection) -> Result<(), String> {
+    let exists: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE name = 'log_lines_fts')",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+
+    conn.execute_batch(
+        r#"CREATE VIRTUAL TABLE IF NOT EXISTS log_lines_fts USING fts5(
+             line, content='log_lines', content_rowid='id', tokenize='trigram'
+         );
+         CREATE TRIGGER IF NOT EXISTS log_lines_fts_insert AFTER INSERT ON log_lines BEGIN
+           INSERT INTO log_lines_fts(rowid, line) VALUES (new.id, new.line);
+         END;
+         CREATE TRIGGER IF NOT EXISTS log_lines_fts_delete AFTER DELETE ON log_lines BEGIN
+           INSERT INTO log_lines_fts(log_lines_fts, rowid, line)
+           VALUES ('delete', old.id, old.line);
+         END;"#,
+    )
+    .map_err(|e| e.to_string())?;
+
+    if !exists {
+        conn.execute("INSERT INTO log_lines_fts(log_lines_fts) VALUES ('rebuild')", [])
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
 fn table_has_column(conn: &Connection, table: &str, column: &str) -> Result<bool, String> {
     let sql = format!("PRAGMA table_info({})", table);
     let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
@@ -506,7 +829,7 @@ fn table_has_column(conn: &Connection, table: &str, column: &str) -> Result<bool
     Ok(false)
 }
 
-fn resolve_server_path(server_id: &str) -> Result<PathBuf, String> {
+pub(super) fn resolve_server_path(server_id: &str) -> Result<PathBuf, String> {
     let manager = crate::services::global::server_manager();
     let servers = manager.get_server_list();
     servers
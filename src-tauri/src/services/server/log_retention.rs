@@ -0,0 +1,474 @@
+//! 日志数据库保留策略。
+//!
+//! log_lines 会随服务器运行无限增长，这里按三条规则裁剪最旧的日志：
+//! - 保留天数：早于 `now - N 天` 的行
+//! - 最大行数：超出上限的最旧行
+//! - 数据库大小：已用页超过上限时，按平均行大小估算，裁剪到上限的 LOG_RETENTION_SIZE_TARGET_PERCENT
+//!
+//! 被裁剪的区间先写入服务器目录下 LOG_ARCHIVE_DIR 中的 `.log.gz`，归档成功后才分块删除，
+//! 随后合并全文索引段、执行 WAL checkpoint，空闲页比例过高时再 VACUUM 回收磁盘空间。
+//! 由 log_pipeline 的 Writer 线程周期调用，复用写入连接，不与写入链路争抢写锁。
+
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, TimeZone};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+
+use super::log_pipeline::{open_or_create_log_db, resolve_server_path};
+use crate::models::settings::AppSettings;
+
+///此处常量见 utils/constants.rs
+use crate::utils::constants::{
+    LATEST_LOG_DB_FILE, LOG_ARCHIVE_DIR, LOG_RETENTION_DELETE_CHUNK,
+    LOG_RETENTION_SIZE_TARGET_PERCENT, LOG_VACUUM_FREELIST_PERCENT,
+};
+
+const DAY_MILLIS: i64 = 24 * 60 * 60 * 1000;
+
+/// 保留策略，各项为 0 表示不限制
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct LogRetentionPolicy {
+    pub max_age_days: u32,
+    pub max_rows: u64,
+    pub max_db_bytes: u64,
+}
+
+impl LogRetentionPolicy {
+    pub fn from_settings(settings: &AppSettings) -> Self {
+        Self {
+            max_age_days: settings.log_retention_days,
+            max_rows: settings.log_retention_max_rows,
+            max_db_bytes: u64::from(settings.log_retention_max_db_mb) * 1024 * 1024,
+        }
+    }
+}
+
+/// 一次保留维护的结果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RetentionOutcome {
+    pub pruned_rows: usize,
+    pub archive: Option<PathBuf>,
+    pub vacuumed: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LogStorageStatus {
+    /// 数据库文件与 WAL 文件的总字节数
+    pub db_size_bytes: u64,
+    pub row_count: u64,
+    /// 当前保留的最旧 / 最新日志时间（毫秒时间戳），没有日志时为 None
+    pub oldest_timestamp: Option<i64>,
+    pub newest_timestamp: Option<i64>,
+    pub archive_count: usize,
+    pub archive_size_bytes: u64,
+    pub policy: LogRetentionPolicy,
+}
+
+pub fn get_log_storage_status(server_id: &str) -> Result<LogStorageStatus, String> {
+    let server_path = resolve_server_path(server_id)?;
+    let settings = crate::services::global::settings_manager().get();
+    storage_status(&server_path, LogRetentionPolicy::from_settings(&settings))
+}
+
+/// 按策略裁剪并归档旧日志，然后做 checkpoint / VACUUM。
+/// `conn` 应为 Writer 线程持有的连接，调用方需保证此时没有未提交的批次。
+pub fn enforce_retention(
+    conn: &Connection,
+    server_path: &Path,
+    policy: &LogRetentionPolicy,
+    now_ms: i64,
+) -> Result<RetentionOutcome, String> {
+    let mut outcome = RetentionOutcome::default();
+    if let Some(cutoff_id) = prune_cutoff(conn, policy, now_ms)? {
+        // 先归档再删除：归档失败时保留原数据，下个周期重试
+        outcome.archive = archive_through(conn, server_path, cutoff_id)?;
+        outcome.pruned_rows = delete_through(conn, cutoff_id)?;
+    }
+    if outcome.pruned_rows > 0 {
+        // FTS5 的删除只写入墓碑记录，合并段后索引才会真正变小
+        conn.execute("INSERT INTO log_lines_fts(log_lines_fts) VALUES ('optimize')", [])
+            .map_err(|e| format!("整理日志全文索引失败: {}", e))?;
+    }
+    outcome.vacuumed = checkpoint_and_vacuum(conn)?;
+    Ok(outcome)
+}
+
+/// 计算需要裁剪到的最大 id（含），不需要裁剪时返回 None
+fn prune_cutoff(
+    conn: &Connection,
+    policy: &LogRetentionPolicy,
+    now_ms: i64,
+) -> Result<Option<i64>, String> {
+    let mut cutoff: Option<i64> = None;
+
+    if policy.max_age_days > 0 {
+        let before = now_ms.saturating_sub(i64::from(policy.max_age_days) * DAY_MILLIS);
+        let id: Option<i64> = conn
+            .query_row(
+                "SELECT MAX(id) FROM log_lines WHERE timestamp < ?1",
+                params![before],
+                |row| row.get(0),
+            )
+            .map_err(|e| format!("读取日志保留范围失败: {}", e))?;
+        cutoff = cutoff.max(id);
+    }
+
+    if policy.max_rows > 0 {
+        let remaining = count_after(conn, cutoff)?;
+        if remaining > policy.max_rows {
+            cutoff = cutoff.max(nth_id_after(conn, cutoff, remaining - policy.max_rows)?);
+        }
+    }
+
+    if policy.max_db_bytes > 0 {
+        let used = used_db_bytes(conn)?;
+        if used > policy.max_db_bytes {
+            // 按整库平均行大小估算还能保留多少行
+            let total = count_after(conn, None)?;
+            let target = policy.max_db_bytes / 100 * LOG_RETENTION_SIZE_TARGET_PERCENT;
+            let keep = (u128::from(total) * u128::from(target) / u128::from(used)) as u64;
+            let remaining = count_after(conn, cutoff)?;
+            if remaining > keep {
+                cutoff = cutoff.max(nth_id_after(conn, cutoff, remaining - keep)?);
+            }
+        }
+    }
+
+    Ok(cutoff)
+}
+
+fn count_after(conn: &Connection, after: Option<i64>) -> Result<u64, String> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM log_lines WHERE id > ?1",
+        params![after.unwrap_or(0)],
+        |row| row.get::<_, i64>(0),
+    )
+    .map(|count| count.max(0) as u64)
+    .map_err(|e| format!("统计日志行数失败: {}", e))
+}
+
+/// id 大于 `after` 的第 `n` 行（从 1 开始）的 id
+fn nth_id_after(conn: &Connection, after: Option<i64>, n: u64) -> Result<Option<i64>, String> {
+    conn.query_row(
+        "SELECT id FROM log_lines WHERE id > ?1 ORDER BY id LIMIT 1 OFFSET ?2",
+        params![after.unwrap_or(0), n.saturating_sub(1) as i64],
+        |row| row.get(0),
+    )
+    .optional()
+    .map_err(|e| format!("读取日志保留范围失败: {}", e))
+}
+
+fn pragma_u64(conn: &Connection, name: &str) -> Result<u64, String> {
+    conn.pragma_query_value(None, name, |row| row.get::<_, i64>(0))
+        .map(|value| value.max(0) as u64)
+        .map_err(|e| format!("读取数据库信息 {} 失败: {}", name, e))
+}
+
+/// 已使用的页（不含空闲页）占用的字节数，包含 FTS 索引
+fn used_db_bytes(conn: &Connection) -> Result<u64, String> {
+    let page_count = pragma_u64(conn, "page_count")?;
+    let freelist = pragma_u64(conn, "freelist_count")?;
+    let page_size = pragma_u64(conn, "page_size")?;
+    Ok(page_count.saturating_sub(freelist) * page_size)
+}
+
+/// 把 id 不大于 `cutoff_id` 的日志写入一个新的 .log.gz，没有日志时返回 None
+fn archive_through(
+    conn: &Connection,
+    server_path: &Path,
+    cutoff_id: i64,
+) -> Result<Option<PathBuf>, String> {
+    let archive_dir = server_path.join(LOG_ARCHIVE_DIR);
+    fs::create_dir_all(&archive_dir).map_err(|e| format!("创建日志归档目录失败: {}", e))?;
+    let temp_path = archive_dir.join(format!(".archive-{}.tmp", uuid::Uuid::new_v4()));
+
+    let result = write_archive(conn, &temp_path, cutoff_id);
+    let (first, last) = match result {
+        Ok(Some(range)) => range,
+        Ok(None) => {
+            let _ = fs::remove_file(&temp_path);
+            return Ok(None);
+        }
+        Err(err) => {
+            let _ = fs::remove_file(&temp_path);
+            return Err(err);
+        }
+    };
+
+    let stem = format!("{}_{}", format_archive_stamp(first), format_archive_stamp(last));
+    let mut archive_path = archive_dir.join(format!("{}.log.gz", stem));
+    let mut suffix = 1;
+    while archive_path.exists() {
+        archive_path = archive_dir.join(format!("{}-{}.log.gz", stem, suffix));
+        suffix += 1;
+    }
+    fs::rename(&temp_path, &archive_path).map_err(|e| format!("保存日志归档失败: {}", e))?;
+    Ok(Some(archive_path))
+}
+
+/// 写入归档内容，返回首尾日志的时间戳
+fn write_archive(
+    conn: &Connection,
+    path: &Path,
+    cutoff_id: i64,
+) -> Result<Option<(i64, i64)>, String> {
+    let file = File::create(path).map_err(|e| format!("创建日志归档失败: {}", e))?;
+    let mut encoder = GzEncoder::new(BufWriter::new(file), Compression::default());
+
+    let mut stmt = conn
+        .prepare("SELECT timestamp, source, line FROM log_lines WHERE id <= ?1 ORDER BY id")
+        .map_err(|e| format!("准备日志归档失败: {}", e))?;
+    let mut rows = stmt
+        .query(params![cutoff_id])
+        .map_err(|e| format!("读取待归档日志失败: {}", e))?;
+
+    let mut range: Option<(i64, i64)> = None;
+    while let Some(row) = rows
+        .next()
+        .map_err(|e| format!("读取待归档日志失败: {}", e))?
+    {
+        let timestamp: i64 = row.get(0).map_err(|e| e.to_string())?;
+        let source: String = row.get(1).map_err(|e| e.to_string())?;
+        let line: String = row.get(2).map_err(|e| e.to_string())?;
+        writeln!(encoder, "[{}] [{}] {}", format_log_time(timestamp), source, line)
+            .map_err(|e| format!("写入日志归档失败: {}", e))?;
+        range = Some(range.map_or((timestamp, timestamp), |(first, _)| (first, timestamp)));
+    }
+
+    encoder
+        .finish()
+        .and_then(|mut writer| writer.flush())
+        .map_err(|e| format!("写入日志归档失败: {}", e))?;
+    Ok(range)
+}
+
+fn format_log_time(timestamp: i64) -> String {
+    Local
+        .timestamp_millis_opt(timestamp)
+        .single()
+        .map(|t| t.format("%Y-%m-%d %H:%M:%S%.3f").to_string())
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
+fn format_archive_stamp(timestamp: i64) -> String {
+    Local
+        .timestamp_millis_opt(timestamp)
+        .single()
+        .map(|t| t.format("%Y%m%d-%H%M%S").to_string())
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
+/// 分块删除，每块一个短事务，避免长时间持有写锁
+fn delete_through(conn: &Connection, cutoff_id: i64) -> Result<usize, String> {
+    let mut total = 0;
+    loop {
+        let deleted = conn
+            .execute(
+                "DELETE FROM log_lines WHERE id IN (
+                   SELECT id FROM log_lines WHERE id <= ?1 ORDER BY id LIMIT ?2
+                 )",
+                params![cutoff_id, LOG_RETENTION_DELETE_CHUNK as i64],
+            )
+            .map_err(|e| format!("清理旧日志失败: {}", e))?;
+        total += deleted;
+        if deleted < LOG_RETENTION_DELETE_CHUNK {
+            return Ok(total);
+        }
+    }
+}
+
+/// 截断 WAL；空闲页比例达到 LOG_VACUUM_FREELIST_PERCENT 时 VACUUM，返回是否执行了 VACUUM
+fn checkpoint_and_vacuum(conn: &Connection) -> Result<bool, String> {
+    checkpoint(conn)?;
+
+    let page_count = pragma_u64(conn, "page_count")?;
+    let freelist = pragma_u64(conn, "freelist_count")?;
+    if page_count == 0 || freelist * 100 < page_count * LOG_VACUUM_FREELIST_PERCENT {
+        return Ok(false);
+    }
+
+    conn.execute_batch("VACUUM")
+        .map_err(|e| format!("压缩日志数据库失败: {}", e))?;
+    checkpoint(conn)?;
+    Ok(true)
+}
+
+fn checkpoint(conn: &Connection) -> Result<(), String> {
+    // 有读连接占用时 checkpoint 只能部分完成，不视为错误，下个周期会继续
+    conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))
+        .map_err(|e| format!("日志数据库 checkpoint 失败: {}", e))
+}
+
+fn storage_status(
+    server_path: &Path,
+    policy: LogRetentionPolicy,
+) -> Result<LogStorageStatus, String> {
+    let conn = open_or_create_log_db(server_path)?;
+    let (row_count, oldest_timestamp, newest_timestamp) = conn
+        .query_row("SELECT COUNT(*), MIN(timestamp), MAX(timestamp) FROM log_lines", [], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get(1)?, row.get(2)?))
+        })
+        .map_err(|e| format!("读取日志统计失败: {}", e))?;
+
+    let db_path = server_path.join(LATEST_LOG_DB_FILE);
+    let wal_path = server_path.join(format!("{}-wal", LATEST_LOG_DB_FILE));
+    let db_size_bytes = [db_path, wal_path]
+        .iter()
+        .filter_map(|path| fs::metadata(path).ok())
+        .map(|meta| meta.len())
+        .sum();
+
+    let mut archive_count = 0;
+    let mut archive_size_bytes = 0;
+    if let Ok(entries) = fs::read_dir(server_path.join(LOG_ARCHIVE_DIR)) {
+        for entry in entries.flatten() {
+            if !entry.file_name().to_string_lossy().ends_with(".log.gz") {
+                continue;
+            }
+            archive_count += 1;
+            archive_size_bytes += entry.metadata().map(|meta| meta.len()).unwrap_or(0);
+        }
+    }
+
+    Ok(LogStorageStatus {
+        db_size_bytes,
+        row_count: row_count.max(0) as u64,
+        oldest_timestamp,
+        newest_timestamp,
+        archive_count,
+        archive_size_bytes,
+        policy,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::server::log_pipeline::{read_log_page, read_logs};
+    use crate::utils::test_utils::temp_dir;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    fn server_with_logs(timestamps: impl IntoIterator<Item = i64>) -> (PathBuf, Connection) {
+        let dir = temp_dir("log_retention");
+        let mut conn = open_or_create_log_db(&dir).unwrap();
+        let tx = conn.transaction().unwrap();
+        for timestamp in timestamps {
+            tx.execute(
+                "INSERT INTO log_lines (timestamp, source, line) VALUES (?1, 'server', ?2)",
+                params![timestamp, format!("line at {}", timestamp)],
+            )
+            .unwrap();
+        }
+        tx.commit().unwrap();
+        (dir, conn)
+    }
+
+    fn read_archive(path: &Path) -> Vec<String> {
+        let mut text = String::new();
+        GzDecoder::new(File::open(path).unwrap())
+            .read_to_string(&mut text)
+            .unwrap();
+        text.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn prunes_by_age_and_row_count_into_archive() {
+        let now = 100 * DAY_MILLIS;
+        let (dir, conn) = server_with_logs((0..10).map(|day| (90 + day) * DAY_MILLIS));
+
+        let policy = LogRetentionPolicy {
+            max_age_days: 7,
+            max_rows: 5,
+            max_db_bytes: 0,
+        };
+        let outcome = enforce_retention(&conn, &dir, &policy, now).unwrap();
+
+        // 按天数会删掉前 3 行，按行数还要再删 2 行
+        assert_eq!(outcome.pruned_rows, 5);
+        let archive = outcome.archive.unwrap();
+        assert!(archive.starts_with(dir.join(LOG_ARCHIVE_DIR)));
+        let lines = read_archive(&archive);
+        assert_eq!(lines.len(), 5);
+        assert!(lines[0].ends_with(&format!("[server] line at {}", 90 * DAY_MILLIS)));
+
+        let status = storage_status(&dir, policy).unwrap();
+        assert_eq!(status.row_count, 5);
+        assert_eq!(status.oldest_timestamp, Some(95 * DAY_MILLIS));
+        assert_eq!(status.archive_count, 1);
+
+        // 再次维护不会产生空归档
+        let again = enforce_retention(&conn, &dir, &policy, now).unwrap();
+        assert_eq!(again.pruned_rows, 0);
+        assert_eq!(again.archive, None);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn prunes_to_size_target_and_vacuums() {
+        let (dir, conn) = server_with_logs(0..20_000);
+        let used = used_db_bytes(&conn).unwrap();
+
+        let policy = LogRetentionPolicy {
+            max_age_days: 0,
+            max_rows: 0,
+            max_db_bytes: used / 2,
+        };
+        let outcome = enforce_retention(&conn, &dir, &policy, 0).unwrap();
+
+        assert!(outcome.pruned_rows > 10_000);
+        assert!(outcome.vacuumed);
+        assert!(used_db_bytes(&conn).unwrap() <= policy.max_db_bytes);
+        let status = storage_status(&dir, policy).unwrap();
+        assert_eq!(status.row_count as usize, 20_000 - outcome.pruned_rows);
+        assert_eq!(status.newest_timestamp, Some(19_999));
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn read_cursor_survives_pruning() {
+        let (dir, conn) = server_with_logs(0..10);
+        let cursor = read_logs(&dir, 0, None).unwrap().last().unwrap().0;
+
+        let policy = LogRetentionPolicy {
+            max_age_days: 0,
+            max_rows: 3,
+            max_db_bytes: 0,
+        };
+        enforce_retention(&conn, &dir, &policy, 0).unwrap();
+        conn.execute(
+            "INSERT INTO log_lines (timestamp, source, line) VALUES (10, 'server', 'new line')",
+            [],
+        )
+        .unwrap();
+
+        let newer = read_logs(&dir, cursor, None).unwrap();
+        assert_eq!(newer.len(), 1);
+        assert_eq!(newer[0].1, "new line");
+        assert_eq!(read_logs(&dir, 0, Some(2)).unwrap().len(), 2);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn log_pages_walk_forward_without_gaps() {
+        let (dir, _conn) = server_with_logs(0..10);
+        let mut cursor = 0;
+        let mut seen = Vec::new();
+        loop {
+            let page = read_log_page(&dir, cursor, 4).unwrap();
+            let Some(&(last, _)) = page.last() else {
+                break;
+            };
+            seen.extend(page.into_iter().map(|(_, line)| line));
+            cursor = last;
+        }
+        let expected: Vec<String> = (0..10).map(|t| format!("line at {}", t)).collect();
+        assert_eq!(seen, expected);
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,400 @@
+//! 服务器日志检索。
+//!
+//! 文本检索是不区分大小写的子串匹配：关键词不少于 3 个字符时走 log_lines_fts（trigram 分词）索引，
+//! 更短的关键词退化为 LIKE 扫描。级别与 logger 按写入时解析出的列过滤，正则在取出候选行后过滤。
+//! 结果按时间倒序分页，下一页以上一页返回的 `next_before_id` 作为 `before_id`。
+
+use std::path::Path;
+
+use regex::Regex;
+use rusqlite::{params, params_from_iter, types::Value, Connection, Row};
+use serde::{Deserialize, Serialize};
+
+use super::log_parser::normalize_level;
+use super::log_pipeline::{open_or_create_log_db, resolve_server_path};
+
+///此处常量见 utils/constants.rs
+use crate::utils::constants::{
+    LOG_SEARCH_DEFAULT_LIMIT, LOG_SEARCH_MAX_CONTEXT, LOG_SEARCH_MAX_LIMIT,
+};
+
+const LINE_COLUMNS: &str = "id, timestamp, source, level, thread, logger, message, line";
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LogSearchQuery {
+    /// 关键词；`regex` 为 true 时按正则表达式匹配
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub regex: bool,
+    /// 时间范围（毫秒时间戳，闭区间）
+    #[serde(default)]
+    pub since: Option<i64>,
+    #[serde(default)]
+    pub until: Option<i64>,
+    /// `sealantern` 或 `server`
+    #[serde(default)]
+    pub source: Option<String>,
+    /// 日志级别，如 `WARN`、`ERROR`；为空时不过滤
+    #[serde(default)]
+    pub levels: Vec<String>,
+    /// logger 名称（Forge 的类名、插件名等），不区分大小写
+    #[serde(default)]
+    pub logger: Option<String>,
+    /// 分页游标：只返回 id 小于该值的行
+    #[serde(default)]
+    pub before_id: Option<i64>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+    /// 每条结果前后附带的上下文行数
+    #[serde(default)]
+    pub context: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LogLine {
+    pub id: i64,
+    pub timestamp: i64,
+    pub source: String,
+    pub level: Option<String>,
+    pub thread: Option<String>,
+    pub logger: Option<String>,
+    /// 去掉时间、线程与级别前缀后的正文
+    pub message: String,
+    pub line: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LogSearchMatch {
+    #[serde(flatten)]
+    pub line: LogLine,
+    pub before: Vec<LogLine>,
+    pub after: Vec<LogLine>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LogSearchPage {
+    pub matches: Vec<LogSearchMatch>,
+    /// 还有更早的结果时为下一页的 `before_id`
+    pub next_before_id: Option<i64>,
+}
+
+pub fn search_logs(server_id: &str, query: &LogSearchQuery) -> Result<LogSearchPage, String> {
+    search_in(&resolve_server_path(server_id)?, query)
+}
+
+fn search_in(server_path: &Path, query: &LogSearchQuery) -> Result<LogSearchPage, String> {
+    let limit = query
+        .limit
+        .unwrap_or(LOG_SEARCH_DEFAULT_LIMIT)
+        .clamp(1, LOG_SEARCH_MAX_LIMIT);
+    let context = query.context.unwrap_or(0).min(LOG_SEARCH_MAX_CONTEXT);
+    let text = query.text.as_deref().filter(|text| !text.trim().is_empty());
+    let regex = match text.filter(|_| query.regex) {
+        Some(pattern) => Some(Regex::new(pattern).map_err(|e| format!("正则表达式无效: {}", e))?),
+        None => None,
+    };
+    let levels = query
+        .levels
+        .iter()
+        .map(|level| normalize_level(level).ok_or_else(|| format!("未知的日志级别: {}", level)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut sql = format!("SELECT {} FROM log_lines WHERE 1 = 1", LINE_COLUMNS);
+    let mut args = Vec::<Value>::new();
+    if let Some(before_id) = query.before_id {
+        sql.push_str(" AND id < ?");
+        args.push(before_id.into());
+    }
+    if let Some(since) = query.since {
+        sql.push_str(" AND timestamp >= ?");
+        args.push(since.into());
+    }
+    if let Some(until) = query.until {
+        sql.push_str(" AND timestamp <= ?");
+        args.push(until.into());
+    }
+    if let Some(source) = query.source.as_deref().filter(|s| !s.is_empty()) {
+        if source != "sealantern" && source != "server" {
+            return Err(format!("未知的日志来源: {}", source));
+        }
+        sql.push_str(" AND source = ?");
+        args.push(source.to_string().into());
+    }
+    if !levels.is_empty() {
+        sql.push_str(&format!(" AND level IN ({})", vec!["?"; levels.len()].join(", ")));
+        args.extend(levels.iter().map(|level| Value::from(level.to_string())));
+    }
+    if let Some(logger) = query
+        .logger
+        .as_deref()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+    {
+        sql.push_str(" AND logger = ? COLLATE NOCASE");
+        args.push(logger.to_string().into());
+    }
+    if let Some(text) = text.filter(|_| regex.is_none()) {
+        if text.chars().count() >= 3 {
+            sql.push_str(
+                " AND id IN (SELECT rowid FROM log_lines_fts WHERE log_lines_fts MATCH ?)",
+            );
+            args.push(format!("\"{}\"", text.replace('"', "\"\"")).into());
+        } else {
+            sql.push_str(" AND line LIKE ? ESCAPE '\\'");
+            let escaped = text
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_");
+            args.push(format!("%{}%", escaped).into());
+        }
+    }
+    sql.push_str(" ORDER BY id DESC");
+
+    let conn = open_or_create_log_db(server_path)?;
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| format!("准备日志检索失败: {}", e))?;
+    let mut rows = stmt
+        .query(params_from_iter(args))
+        .map_err(|e| format!("检索日志失败: {}", e))?;
+
+    let mut found = Vec::new();
+    let mut has_more = false;
+    while let Some(row) = rows.next().map_err(|e| format!("检索日志失败: {}", e))? {
+        let line = read_line(row).map_err(|e| format!("解析日志失败: {}", e))?;
+        if regex
+            .as_ref()
+            .is_some_and(|regex| !regex.is_match(&line.line))
+        {
+            continue;
+        }
+        if found.len() == limit {
+            has_more = true;
+            break;
+        }
+        found.push(line);
+    }
+    drop(rows);
+
+    let next_before_id = if has_more {
+        found.last().map(|line| line.id)
+    } else {
+        None
+    };
+    let matches = found
+        .into_iter()
+        .map(|line| {
+            let (before, after) = if context > 0 {
+                context_lines(&conn, line.id, context)?
+            } else {
+                (Vec::new(), Vec::new())
+            };
+            Ok(LogSearchMatch { line, before, after })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Ok(LogSearchPage { matches, next_before_id })
+}
+
+fn context_lines(
+    conn: &Connection,
+    id: i64,
+    count: usize,
+) -> Result<(Vec<LogLine>, Vec<LogLine>), String> {
+    let query = |sql: &str| -> Result<Vec<LogLine>, String> {
+        let mut stmt = conn
+            .prepare_cached(sql)
+            .map_err(|e| format!("准备日志读取失败: {}", e))?;
+        let rows = stmt
+            .query_map(params![id, count as i64], read_line)
+            .map_err(|e| format!("读取日志失败: {}", e))?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("解析日志失败: {}", e))
+    };
+
+    let mut before = query(&format!(
+        "SELECT {} FROM log_lines WHERE id < ?1 ORDER BY id DESC LIMIT ?2",
+        LINE_COLUMNS
+    ))?;
+    before.reverse();
+    let after = query(&format!(
+        "SELECT {} FROM log_lines WHERE id > ?1 ORDER BY id ASC LIMIT ?2",
+        LINE_COLUMNS
+    ))?;
+    Ok((before, after))
+}
+
+fn read_line(row: &Row<'_>) -> rusqlite::Result<LogLine> {
+    let line: String = row.get(7)?;
+    Ok(LogLine {
+        id: row.get(0)?,
+        timestamp: row.get(1)?,
+        source: row.get(2)?,
+        level: row.get(3)?,
+        thread: row.get(4)?,
+        logger: row.get(5)?,
+        message: row
+            .get::<_, Option<String>>(6)?
+            .unwrap_or_else(|| line.clone()),
+        line,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::log_pipeline::insert_log_line;
+    use super::*;
+    use crate::utils::test_utils::temp_dir;
+
+    fn server_with_logs(lines: &[(i64, &str, &str)]) -> std::path::PathBuf {
+        let dir = temp_dir("log_search");
+        let conn = open_or_create_log_db(&dir).unwrap();
+        for (timestamp, source, line) in lines {
+            insert_log_line(&conn, *timestamp, source, line).unwrap();
+        }
+        dir
+    }
+
+    fn ids(page: &LogSearchPage) -> Vec<i64> {
+        page.matches.iter().map(|m| m.line.id).collect()
+    }
+
+    #[test]
+    fn searches_text_levels_and_pages_with_context() {
+        let dir = server_with_logs(&[
+            (1000, "server", "[12:00:00] [Server thread/INFO]: Starting minecraft server"),
+            (
+                2000,
+                "server",
+                "[12:00:01] [Server thread/WARN]: Can't keep up! Running 2000ms behind",
+            ),
+            (3000, "sealantern", "服务器启动完成"),
+            (4000, "server", "[12:00:03] [Server thread/INFO]: Steve joined the game"),
+            (
+                5000,
+                "server",
+                "[12:00:04] [Server thread/WARN]: Can't keep up! Running 5000ms behind",
+            ),
+            (
+                6000,
+                "server",
+                "[12:00:05] [Server thread/ERROR]: Encountered an unexpected exception",
+            ),
+            (7000, "server", "[12:00:06 WARN]: [LuckPerms] Storage is slow"),
+        ]);
+
+        let keep_up = LogSearchQuery {
+            text: Some("can't KEEP".to_string()),
+            context: Some(1),
+            ..Default::default()
+        };
+        let page = search_in(&dir, &keep_up).unwrap();
+        assert_eq!(ids(&page), vec![5, 2]);
+        assert_eq!(page.matches[0].before[0].id, 4);
+        assert_eq!(page.matches[0].after[0].id, 6);
+        assert_eq!(page.matches[1].line.level.as_deref(), Some("WARN"));
+        assert_eq!(page.matches[1].line.thread.as_deref(), Some("Server thread"));
+        assert_eq!(page.matches[1].line.message, "Can't keep up! Running 2000ms behind");
+
+        let first_page =
+            search_in(&dir, &LogSearchQuery { limit: Some(1), ..keep_up.clone() }).unwrap();
+        assert_eq!(ids(&first_page), vec![5]);
+        let second_page = search_in(
+            &dir,
+            &LogSearchQuery {
+                limit: Some(1),
+                before_id: first_page.next_before_id,
+                ..keep_up.clone()
+            },
+        )
+        .unwrap();
+        assert_eq!(ids(&second_page), vec![2]);
+        assert_eq!(second_page.next_before_id, None);
+
+        let short = LogSearchQuery {
+            text: Some("启动".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(ids(&search_in(&dir, &short).unwrap()), vec![3]);
+
+        let regex = LogSearchQuery {
+            text: Some(r"Running \d{4}ms".to_string()),
+            regex: true,
+            since: Some(3000),
+            ..Default::default()
+        };
+        assert_eq!(ids(&search_in(&dir, &regex).unwrap()), vec![5]);
+
+        let errors = LogSearchQuery {
+            levels: vec!["error".to_string(), "severe".to_string()],
+            source: Some("server".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(ids(&search_in(&dir, &errors).unwrap()), vec![6]);
+
+        let plugin = LogSearchQuery {
+            levels: vec!["warning".to_string()],
+            logger: Some("luckperms".to_string()),
+            ..Default::default()
+        };
+        let page = search_in(&dir, &plugin).unwrap();
+        assert_eq!(ids(&page), vec![7]);
+        assert_eq!(page.matches[0].line.message, "Storage is slow");
+
+        assert!(search_in(
+            &dir,
+            &LogSearchQuery {
+                text: Some("(".to_string()),
+                regex: true,
+                ..Default::default()
+            }
+        )
+        .is_err());
+        assert!(search_in(
+            &dir,
+            &LogSearchQuery {
+                levels: vec!["LOUD".to_string()],
+                ..Default::default()
+            }
+        )
+        .is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn migrates_and_indexes_existing_rows_when_upgrading() {
+        let dir = temp_dir("log_search");
+        let conn = Connection::open(dir.join(crate::utils::constants::LATEST_LOG_DB_FILE)).unwrap();
+        conn.execute_batch(
+            r#"CREATE TABLE log_lines (
+                 id INTEGER PRIMARY KEY AUTOINCREMENT,
+                 timestamp INTEGER NOT NULL,
+                 source TEXT NOT NULL CHECK(source IN ('sealantern','server')),
+                 line TEXT NOT NULL
+               );
+               INSERT INTO log_lines (timestamp, source, line)
+               VALUES (1, 'server', '[12:00:00] [Server thread/INFO]: Done (3.2s)! For help'),
+                      (2, 'server', '[12:00:01] [Server thread/ERROR]: Exception ticking world');"#,
+        )
+        .unwrap();
+        drop(conn);
+
+        let query = LogSearchQuery {
+            text: Some("for help".to_string()),
+            ..Default::default()
+        };
+        let page = search_in(&dir, &query).unwrap();
+        assert_eq!(ids(&page), vec![1]);
+        assert_eq!(page.matches[0].line.message, "Done (3.2s)! For help");
+
+        let errors = LogSearchQuery {
+            levels: vec!["ERROR".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(ids(&search_in(&dir, &errors).unwrap()), vec![2]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -8,11 +8,20 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use crate::models::server::*;
 use serde::{Deserialize, Serialize};
 
+use super::crash_analyzer;
+use super::crash_guard::{CrashState, RestartDecision};
 use super::installer;
 use super::log_pipeline as server_log_pipeline;
+use super::ping::{self, PingCache};
+use super::player_sessions;
+use super::rcon::{RconClient, RconSettings};
+use super::shutdown;
 
 ///此处常量见 utils/constants.rs
-use crate::utils::constants::{DATA_FILE, RUN_PATH_MAP_FILE};
+use crate::utils::constants::{
+    DATA_FILE, PING_CACHE_SECS, RUN_PATH_MAP_FILE, STOP_DEFAULT_TIMEOUT_SECS,
+    STOP_SAVE_TIMEOUT_SECS,
+};
 
 /// 验证服务器名称，防止路径遍历攻击
 /// 返回清理后的名称或错误信息
@@ -112,9 +121,15 @@ pub struct ServerManager {
     pub stopping_servers: Mutex<HashSet<String>>,
     pub starting_servers: Mutex<HashSet<String>>,
     pub pending_force_stop_tokens: Mutex<HashMap<String, (String, u64)>>,
+    pub crash_states: Mutex<HashMap<String, CrashState>>,
+    pub expected_exits: Mutex<HashSet<String>>,
+    pub ping_cache: PingCache,
     pub data_dir: Mutex<String>,
 }
 
+/// 子进程退出监视的轮询间隔
+const EXIT_MONITOR_INTERVAL_MS: u64 = 1000;
+
 impl ServerManager {
     pub fn new() -> Self {
         let data_dir = get_data_dir();
@@ -125,6 +140,9 @@ impl ServerManager {
             stopping_servers: Mutex::new(HashSet::new()),
             starting_servers: Mutex::new(HashSet::new()),
             pending_force_stop_tokens: Mutex::new(HashMap::new()),
+            crash_states: Mutex::new(HashMap::new()),
+            expected_exits: Mutex::new(HashSet::new()),
+            ping_cache: PingCache::new(),
             data_dir: Mutex::new(data_dir),
         }
     }
@@ -146,6 +164,8 @@ impl ServerManager {
         if let Ok(mut stopping) = self.stopping_servers.lock() {
             stopping.remove(id);
         }
+        // 停服结束后丢弃旧的 ping 结果，避免刚停止的服务器被误判为仍在外部运行
+        self.ping_cache.invalidate(id);
     }
 
     fn is_starting(&self, id: &str) -> bool {
@@ -167,7 +187,43 @@ impl ServerManager {
         }
     }
 
-    pub fn request_stop_server(&self, id: &str) -> Result<(), String> {
+    /// 服务器输出 `Done (...)! For help` 后调用：结束启动态并清零连续崩溃计数。
+    pub fn mark_server_ready(&self, id: &str) {
+        self.clear_starting(id);
+        if let Ok(mut states) = self.crash_states.lock() {
+            if let Some(state) = states.get_mut(id) {
+                state.mark_ready();
+            }
+        }
+    }
+
+    fn mark_expected_exit(&self, id: &str) {
+        if let Ok(mut expected) = self.expected_exits.lock() {
+            expected.insert(id.to_string());
+        }
+    }
+
+    fn take_expected_exit(&self, id: &str) -> bool {
+        self.expected_exits
+            .lock()
+            .map(|mut expected| expected.remove(id))
+            .unwrap_or(false)
+    }
+
+    /// 人工启动/停止/删除时清除崩溃记录，同时取消尚未执行的自动重启。
+    fn reset_crash_state(&self, id: &str) {
+        if let Ok(mut states) = self.crash_states.lock() {
+            if let Some(mut state) = states.remove(id) {
+                state.cancel_pending_restart();
+            }
+        }
+    }
+
+    pub fn request_stop_server(
+        &self,
+        id: &str,
+        countdown: Option<StopCountdown>,
+    ) -> Result<(), String> {
         if self.is_stopping(id) {
             return Ok(());
         }
@@ -176,7 +232,7 @@ impl ServerManager {
         let sid = id.to_string();
         std::thread::spawn(move || {
             let manager = crate::services::global::server_manager();
-            if let Err(err) = manager.stop_server(&sid) {
+            if let Err(err) = manager.stop_server_with_countdown(&sid, countdown.as_ref()) {
                 let _ = server_log_pipeline::append_sealantern_log(
                     &sid,
                     &format!("[Sea Lantern] 停止失败: {}", err),
@@ -230,6 +286,7 @@ impl ServerManager {
         pending.remove(id);
         drop(pending);
 
+        self.reset_crash_state(id);
         self.mark_stopping(id);
 
         let mut procs = self.lock_processes()?;
@@ -369,6 +426,7 @@ impl ServerManager {
             port: req.port,
             created_at: now,
             last_started_at: None,
+            restart_policy: RestartPolicy::default(),
         };
         self.lock_servers()?.push(server.clone());
         self.save()?;
@@ -468,6 +526,7 @@ impl ServerManager {
             port,
             created_at: now,
             last_started_at: None,
+            restart_policy: RestartPolicy::default(),
         };
 
         self.lock_servers()?.push(server.clone());
@@ -647,6 +706,7 @@ impl ServerManager {
             crate::services::config_parser::write_properties(
                 server_properties_path.to_str().unwrap_or_default(),
                 &updates,
+                &crate::services::server::config_history::ConfigOrigin::System,
             )
             .map_err(|e| format!("更新 server.properties 失败: {}", e))?;
         } else {
@@ -687,6 +747,7 @@ impl ServerManager {
             port,
             created_at: now,
             last_started_at: None,
+            restart_policy: RestartPolicy::default(),
         };
 
         println!(
@@ -789,6 +850,7 @@ impl ServerManager {
             port,
             created_at: now,
             last_started_at: None,
+            restart_policy: RestartPolicy::default(),
         };
 
         self.lock_servers()?.push(server.clone());
@@ -797,6 +859,14 @@ impl ServerManager {
     }
 
     pub fn start_server(&self, id: &str) -> Result<StartServerReport, String> {
+        // 人工启动视为对崩溃的处理，重新开始计算自动重启次数
+        self.reset_crash_state(id);
+        self.launch_server(id)
+    }
+
+    fn launch_server(&self, id: &str) -> Result<StartServerReport, String> {
+        // 重新启动后此前由告警标记的不健康状态不再适用
+        crate::services::global::alert_manager().clear_unhealthy(id);
         let server = {
             let servers = self.lock_servers()?;
             servers
@@ -1277,9 +1347,12 @@ impl ServerManager {
 
         let stdout = child.stdout.take();
         let stderr = child.stderr.take();
+        let pid = child.id();
 
+        self.take_expected_exit(id);
         self.lock_processes()?.insert(id.to_string(), child);
         self.mark_starting(id);
+        self.spawn_exit_monitor(id, pid);
 
         {
             let mut servers = self.lock_servers()?;
@@ -1295,6 +1368,7 @@ impl ServerManager {
         self.save()?;
         let _ = server_log_pipeline::append_sealantern_log(id, "[Sea Lantern] 服务器启动中...");
 
+        player_sessions::reset(id);
         if let Some(stdout) = stdout {
             server_log_pipeline::spawn_server_output_reader(id.to_string(), stdout);
         }
@@ -1310,12 +1384,24 @@ impl ServerManager {
     }
 
     pub fn stop_server(&self, id: &str) -> Result<(), String> {
+        self.stop_server_with_countdown(id, None)
+    }
+
+    /// 停止服务器；传入 `countdown` 时先按提醒点广播倒计时并 `save-all`，再发送 `stop`。
+    pub fn stop_server_with_countdown(
+        &self,
+        id: &str,
+        countdown: Option<&StopCountdown>,
+    ) -> Result<(), String> {
         // 日志 Writer 生命周期说明：
         // 1) 停服流程中“最后一条 Sea Lantern 提示日志”要先入队，随后再 shutdown_writer。
         //    这样可以保证提示日志也被刷盘，不会因为先关 Writer 而丢失。
         // 2) shutdown_writer 会触发 writer 线程 flush+join，确保 SQLite 句柄被释放。
         //    这对 Windows 很关键，可避免删除目录或外部工具读取 DB 时遇到句柄占用。
         // 3) 所有 return 分支都要覆盖 shutdown_writer，避免异常路径漏清理。
+        // 停止请求会取消尚未执行的崩溃自动重启
+        self.reset_crash_state(id);
+
         // Check if actually running first
         let is_running = {
             let mut procs = self.lock_processes()?;
@@ -1340,15 +1426,29 @@ impl ServerManager {
 
         if !is_running {
             self.clear_stopping(id);
-            let _ = server_log_pipeline::append_sealantern_log(id, "[Sea Lantern] 服务器未运行");
+            // 不是由 SeaLantern 启动的服务器：确认世界锁被占用且开启了 RCON 时通过 RCON 停止
+            let message = match self.send_rcon_command(id, "stop") {
+                Ok(_) => "[Sea Lantern] 已通过 RCON 发送停止命令",
+                Err(_) => "[Sea Lantern] 服务器未运行",
+            };
+            let _ = server_log_pipeline::append_sealantern_log(id, message);
             server_log_pipeline::shutdown_writer(id);
             return Ok(());
         }
 
+        // stop_all_servers 等路径会直接调用本函数，这里补标记，避免退出监视把停服误判为崩溃
+        self.mark_stopping(id);
+        let timeout_secs = match countdown {
+            Some(countdown) => {
+                self.run_stop_countdown(id, countdown);
+                countdown.force_stop_after_secs.max(1)
+            }
+            None => STOP_DEFAULT_TIMEOUT_SECS,
+        };
         let _ = server_log_pipeline::append_sealantern_log(id, "[Sea Lantern] 正在发送停止命令...");
         let _ = self.send_command(id, "stop");
 
-        for _ in 0..20 {
+        for _ in 0..timeout_secs * 2 {
             std::thread::sleep(std::time::Duration::from_millis(500));
             let mut procs = self.lock_processes()?;
             if let Some(child) = procs.get_mut(id) {
@@ -1393,18 +1493,144 @@ impl ServerManager {
         Ok(())
     }
 
-    pub fn send_command(&self, id: &str, command: &str) -> Result<(), String> {
-        let mut procs = self.lock_processes()?;
-        let child = procs
-            .get_mut(id)
+    /// 倒计时阶段：在每个提醒点广播一次，结束后 `save-all` 并等待落盘。
+    /// 进程在倒计时期间自行退出时提前返回，由调用方的等待循环收尾。
+    fn run_stop_countdown(&self, id: &str, countdown: &StopCountdown) {
+        let schedule = shutdown::warning_schedule(countdown);
+        let total = schedule.first().copied().unwrap_or(0);
+        let _ = server_log_pipeline::append_sealantern_log(
+            id,
+            &format!("[Sea Lantern] 将在 {} 后停止服务器", shutdown::format_remaining(total)),
+        );
+
+        let mut remaining = total;
+        for warning in schedule {
+            if !self.wait_while_running(id, remaining - warning) {
+                return;
+            }
+            remaining = warning;
+            let _ = self.send_command(id, &shutdown::broadcast_command(countdown, warning));
+        }
+        if !self.wait_while_running(id, remaining) {
+            return;
+        }
+
+        let baseline = server_log_pipeline::get_latest_log_id(id);
+        if self.send_command(id, "save-all").is_ok() {
+            let timeout = std::time::Duration::from_secs(STOP_SAVE_TIMEOUT_SECS);
+            if !server_log_pipeline::wait_for_log_line(id, baseline, "Saved the game", timeout) {
+                let _ = server_log_pipeline::append_sealantern_log(
+                    id,
+                    "[Sea Lantern] 等待存档保存超时，继续停止服务器",
+                );
+            }
+        }
+    }
+
+    /// 最多等待 `secs` 秒，进程提前退出时返回 false。
+    fn wait_while_running(&self, id: &str, secs: u64) -> bool {
+        for _ in 0..secs {
+            if !self.is_process_alive(id) {
+                return false;
+            }
+            std::thread::sleep(std::time::Duration::from_secs(1));
+        }
+        self.is_process_alive(id)
+    }
+
+    fn is_process_alive(&self, id: &str) -> bool {
+        self.lock_processes()
+            .map(|mut procs| {
+                procs
+                    .get_mut(id)
+                    .is_some_and(|child| matches!(child.try_wait(), Ok(None)))
+            })
+            .unwrap_or(false)
+    }
+
+    /// 向服务器发送控制台命令。
+    ///
+    /// 由 SeaLantern 启动的进程直接写入 stdin，输出会出现在控制台日志中，返回 `None`；
+    /// 其他服务器（如导入后由外部脚本运行的）回退到 RCON，返回命令输出。
+    pub fn send_command(&self, id: &str, command: &str) -> Result<Option<String>, String> {
+        {
+            let mut procs = self.lock_processes()?;
+            if let Some(child) = procs.get_mut(id) {
+                if is_stop_console_command(command) {
+                    self.mark_expected_exit(id);
+                }
+                if let Some(ref mut stdin) = child.stdin {
+                    writeln!(stdin, "{}", command)
+                        .map_err(|e| format!("发送失败（id={}）: {}", id, e))?;
+                    stdin
+                        .flush()
+                        .map_err(|e| format!("发送失败（id={}）: {}", id, e))?;
+                }
+                return Ok(None);
+            }
+        }
+
+        self.send_rcon_command(id, command).map(Some)
+    }
+
+    /// 通过 server.properties 中配置的 RCON 执行命令；未启用 RCON 时视为服务器未运行。
+    ///
+    /// 复制出来的服务器通常共用 RCON 端口与密码，因此只在这台服务器的世界锁被占用、
+    /// 且没有其他由 SeaLantern 运行的服务器使用同一 RCON 端口时才连接，避免命令发到别的服务器。
+    fn send_rcon_command(&self, id: &str, command: &str) -> Result<String, String> {
+        let servers = self.get_server_list();
+        let server_path = servers
+            .iter()
+            .find(|s| s.id == id)
+            .map(|s| s.path.clone())
             .ok_or_else(|| format!("服务器未运行: {}", id))?;
-        if let Some(ref mut stdin) = child.stdin {
-            writeln!(stdin, "{}", command).map_err(|e| format!("发送失败（id={}）: {}", id, e))?;
-            stdin
-                .flush()
-                .map_err(|e| format!("发送失败（id={}）: {}", id, e))?;
+        let settings = RconSettings::load(Path::new(&server_path))
+            .ok_or_else(|| format!("服务器未运行: {}", id))?;
+        if !crate::services::world::is_world_in_use(Path::new(&server_path)) {
+            return Err(format!("服务器未运行: {}", id));
         }
-        Ok(())
+        let running = self.get_running_server_ids();
+        let port_taken = servers.iter().any(|s| {
+            s.id != id
+                && running.contains(&s.id)
+                && RconSettings::load(Path::new(&s.path)).is_some_and(|o| o.port == settings.port)
+        });
+        if port_taken {
+            return Err(format!(
+                "RCON 端口 {} 被另一台运行中的服务器占用，无法确认目标（id={}）",
+                settings.port, id
+            ));
+        }
+
+        RconClient::connect(&settings)
+            .and_then(|mut client| client.command(command))
+            .map_err(|e| format!("服务器未由 SeaLantern 启动，RCON 执行失败（id={}）: {}", id, e))
+    }
+
+    /// 服务器是否正在运行：由 SeaLantern 启动的看子进程，其余的在确认身份后查询一次端口。
+    ///
+    /// 与 `get_server_status` 不同，这里不依赖 Server List Ping 缓存，
+    /// 供离线修改名单文件前判断，避免改动被运行中的服务端覆盖。
+    pub fn is_server_online(&self, id: &str) -> bool {
+        if self.get_running_server_ids().iter().any(|sid| sid == id) {
+            return true;
+        }
+        self.external_probe_port(id)
+            .is_some_and(|port| ping::ping_server("127.0.0.1", port).is_ok())
+    }
+
+    /// 未由 SeaLantern 启动的服务器可用于端口探测的端口。
+    ///
+    /// 端口上的监听者不一定是这台服务器（多数服务器共用 25565），
+    /// 因此只有世界锁被占用、或没有其他已登记的服务器使用同一端口时才返回端口。
+    fn external_probe_port(&self, id: &str) -> Option<u16> {
+        let servers = self.get_server_list();
+        let server = servers.iter().find(|s| s.id == id)?;
+        let port_shared = servers.iter().any(|s| s.id != id && s.port == server.port);
+        if port_shared && !crate::services::world::is_world_in_use(Path::new(&server.path)) {
+            return None;
+        }
+        Some(server.port)
     }
 
     pub fn get_server_list(&self) -> Vec<ServerInstance> {
@@ -1414,74 +1640,60 @@ impl ServerManager {
     }
 
     pub fn get_server_status(&self, id: &str) -> ServerStatusInfo {
-        let mut exit_code: Option<i32> = None;
         let mut error_message: Option<String> = None;
+        let mut status_check_failed = false;
+
+        let exited = self.lock_processes().ok().and_then(|mut procs| {
+            let child = procs.get_mut(id)?;
+            let exit_code = match child.try_wait() {
+                Ok(None) => return None,
+                Ok(Some(status)) => status.code(),
+                Err(_) => {
+                    status_check_failed = true;
+                    None
+                }
+            };
+            procs.remove(id);
+            // 持有 processes 锁时判定是否为预期退出，与 stop_server 的轮询保持一致的顺序
+            let expected = self.is_stopping(id) || self.take_expected_exit(id);
+            Some((exit_code, expected))
+        });
+
+        if status_check_failed {
+            error_message = Some("获取服务器状态失败".to_string());
+            let _ =
+                server_log_pipeline::append_sealantern_log(id, "[Sea Lantern] 获取服务器状态失败");
+            self.clear_starting(id);
+            server_log_pipeline::shutdown_writer(id);
+        } else if let Some((exit_code, expected)) = exited {
+            self.handle_process_exit(id, exit_code, expected);
+        }
 
-        let is_running = self
+        let pid = self
             .lock_processes()
             .ok()
-            .map(|mut procs| {
-                if let Some(child) = procs.get_mut(id) {
-                    match child.try_wait() {
-                        Ok(Some(status)) => {
-                            exit_code = status.code();
-                            // 根据退出码设置错误信息
-                            match &exit_code {
-                                Some(0) => {
-                                    // 正常退出，记录日志
-                                    let _ = server_log_pipeline::append_sealantern_log(
-                                        id,
-                                        "[Sea Lantern] 服务器已正常退出",
-                                    );
-                                }
-                                Some(code) => {
-                                    error_message =
-                                        Some(format!("服务器异常退出 (退出码：{})", code));
-                                    let _ = server_log_pipeline::append_sealantern_log(
-                                        id,
-                                        &format!("[Sea Lantern] 服务器异常退出 (退出码：{})", code),
-                                    );
-                                }
-                                None => {
-                                    error_message = Some("服务器被强制终止".to_string());
-                                    let _ = server_log_pipeline::append_sealantern_log(
-                                        id,
-                                        "[Sea Lantern] 服务器被强制终止",
-                                    );
-                                }
-                            }
+            .and_then(|mut procs| procs.get_mut(id).map(|child| child.id()));
+        let is_running = pid.is_some();
 
-                            procs.remove(id);
-                            server_log_pipeline::shutdown_writer(id);
-                            self.clear_starting(id);
-                            false
-                        }
-                        Ok(None) => true,
-                        Err(_) => {
-                            error_message = Some("获取服务器状态失败".to_string());
-                            let _ = server_log_pipeline::append_sealantern_log(
-                                id,
-                                "[Sea Lantern] 获取服务器状态失败",
-                            );
-                            procs.remove(id);
-                            server_log_pipeline::shutdown_writer(id);
-                            self.clear_starting(id);
-                            false
-                        }
+        let mut crashed = false;
+        let mut exit_code: Option<i32> = None;
+        let mut restart_attempts = 0;
+        let mut next_restart_at: Option<u64> = None;
+        if let Ok(mut states) = self.crash_states.lock() {
+            if let Some(state) = states.get_mut(id) {
+                restart_attempts = state.restart_attempts();
+                if !is_running && state.is_error() {
+                    crashed = true;
+                    exit_code = state.last_exit_code;
+                    next_restart_at = state.next_restart_at;
+                    // 崩溃信息只上报一次，避免前端轮询时重复弹出通知
+                    if !state.reported {
+                        state.reported = true;
+                        error_message = state.last_message.clone();
                     }
-                } else {
-                    false
                 }
-            })
-            .unwrap_or(false);
-
-        let pid = if is_running {
-            self.lock_processes()
-                .ok()
-                .and_then(|mut procs| procs.get_mut(id).map(|child| child.id()))
-        } else {
-            None
-        };
+            }
+        }
 
         let uptime = self
             .lock_servers()
@@ -1494,6 +1706,10 @@ impl ServerManager {
             })
             .and_then(|started_at| current_timestamp_secs().checked_sub(started_at));
 
+        // 未由 SeaLantern 启动、但能确认身份且端口有应答的服务器（如导入后由外部运行的）同样视为运行中
+        let ping = self.server_ping(id, is_running);
+        let externally_running = !is_running && !crashed && ping.is_some();
+
         ServerStatusInfo {
             id: id.to_string(),
             status: if self.is_stopping(id) {
@@ -1502,16 +1718,269 @@ impl ServerManager {
                 ServerStatus::Starting
             } else if is_running {
                 ServerStatus::Running
+            } else if crashed {
+                ServerStatus::Error
+            } else if externally_running {
+                ServerStatus::Running
             } else {
                 ServerStatus::Stopped
             },
             pid,
             uptime,
             error_message,
+            exit_code,
+            restart_attempts,
+            next_restart_at,
+            ping,
+            unhealthy_reason: crate::services::global::alert_manager().unhealthy_reason(id),
+        }
+    }
+
+    /// 返回缓存的 Server List Ping 结果，过期时在后台线程刷新。
+    ///
+    /// 服务器未运行且端口正被另一个由 SeaLantern 启动的服务器占用时不查询，
+    /// 否则会把那台服务器的应答当成本服务器的。
+    fn server_ping(&self, id: &str, is_running: bool) -> Option<ServerPingInfo> {
+        let port = if is_running {
+            self.lock_servers().ok()?.iter().find(|s| s.id == id)?.port
+        } else {
+            self.external_probe_port(id)?
+        };
+
+        let max_age = std::time::Duration::from_secs(PING_CACHE_SECS);
+        let (cached, refresh) = self.ping_cache.get(id, max_age);
+        if let Some(generation) = refresh {
+            let sid = id.to_string();
+            std::thread::spawn(move || {
+                let result = ping::ping_server("127.0.0.1", port).ok();
+                crate::services::global::server_manager()
+                    .ping_cache
+                    .store(&sid, generation, result);
+            });
         }
+        cached
+    }
+
+    /// 为刚启动的子进程创建退出监视线程。
+    ///
+    /// 监视线程以 PID 识别自己负责的进程：一旦 processes 中的进程被移除或被新进程替换
+    /// （停止、强制停止、重启），线程即退出，不会重复处理。
+    fn spawn_exit_monitor(&self, id: &str, pid: u32) {
+        let sid = id.to_string();
+        std::thread::spawn(move || {
+            let manager = crate::services::global::server_manager();
+            loop {
+                std::thread::sleep(std::time::Duration::from_millis(EXIT_MONITOR_INTERVAL_MS));
+
+                let (exit_code, expected) = {
+                    let Ok(mut procs) = manager.lock_processes() else {
+                        return;
+                    };
+                    let Some(child) = procs.get_mut(&sid) else {
+                        return;
+                    };
+                    if child.id() != pid {
+                        return;
+                    }
+                    let exit_code = match child.try_wait() {
+                        Ok(None) => continue,
+                        Ok(Some(status)) => status.code(),
+                        Err(_) => None,
+                    };
+                    procs.remove(&sid);
+                    let expected = manager.is_stopping(&sid) || manager.take_expected_exit(&sid);
+                    (exit_code, expected)
+                };
+
+                manager.handle_process_exit(&sid, exit_code, expected);
+                return;
+            }
+        });
+    }
+
+    /// 处理已从 processes 中移除的退出进程。
+    ///
+    /// `expected` 表示退出前收到过停服请求（stop_server / request_stop_server，
+    /// 或通过控制台发送了 stop 类命令）；其余退出一律视为崩溃。
+    fn handle_process_exit(&self, id: &str, exit_code: Option<i32>, expected: bool) {
+        self.clear_starting(id);
+        self.ping_cache.invalidate(id);
+
+        if expected {
+            // stop_server 流程会自行输出提示并回收 Writer
+            if !self.is_stopping(id) {
+                let _ = server_log_pipeline::append_sealantern_log(
+                    id,
+                    "[Sea Lantern] 服务器已正常退出",
+                );
+                server_log_pipeline::shutdown_writer(id);
+            }
+            return;
+        }
+
+        let message = match exit_code {
+            Some(code) => format!("服务器异常退出 (退出码：{})", code),
+            None => "服务器被强制终止".to_string(),
+        };
+        self.handle_server_crash(id, exit_code, message, true);
+    }
+
+    /// `diagnose` 为 true 时在调度自动重启、回收 Writer 之前分析崩溃原因，
+    /// 诊断结论写入本次运行的日志
+    fn handle_server_crash(
+        &self,
+        id: &str,
+        exit_code: Option<i32>,
+        message: String,
+        diagnose: bool,
+    ) {
+        let policy = self
+            .lock_servers()
+            .ok()
+            .and_then(|servers| {
+                servers
+                    .iter()
+                    .find(|s| s.id == id)
+                    .map(|s| s.restart_policy.clone())
+            })
+            .unwrap_or_default();
+
+        let (decision, token) = {
+            let mut states = match self.crash_states.lock() {
+                Ok(states) => states,
+                Err(_) => return,
+            };
+            let state = states.entry(id.to_string()).or_default();
+            let decision = state.record_crash(&policy, exit_code, current_timestamp_secs());
+            state.last_message = Some(message.clone());
+            (decision, state.restart_token)
+        };
+
+        let _ =
+            server_log_pipeline::append_sealantern_log(id, &format!("[Sea Lantern] {}", message));
+        if diagnose {
+            self.diagnose_crash(id, exit_code);
+        }
+        match decision {
+            RestartDecision::Disabled => {}
+            RestartDecision::Restart { attempt, delay_secs } => {
+                let _ = server_log_pipeline::append_sealantern_log(
+                    id,
+                    &format!("[Sea Lantern] 将在 {} 秒后进行第 {} 次自动重启", delay_secs, attempt),
+                );
+                self.schedule_restart(id, token, delay_secs);
+            }
+            RestartDecision::GiveUp { reason } => {
+                let _ = server_log_pipeline::append_sealantern_log(
+                    id,
+                    &format!("[Sea Lantern] 已放弃自动重启：{}", reason),
+                );
+            }
+        }
+        server_log_pipeline::shutdown_writer(id);
+    }
+
+    fn diagnose_crash(&self, id: &str, exit_code: Option<i32>) {
+        // 尚未调度重启，这里读到的仍是崩溃这次运行的启动时间
+        let started_at = self.lock_servers().ok().and_then(|servers| {
+            servers
+                .iter()
+                .find(|s| s.id == id)
+                .and_then(|s| s.last_started_at)
+        });
+        // 崩溃前的输出可能还在 Writer 的批次中
+        server_log_pipeline::flush_writer(id);
+        match crash_analyzer::diagnose_crash(id, exit_code, started_at) {
+            Ok(report) => {
+                let _ = server_log_pipeline::append_sealantern_log(
+                    id,
+                    &format!("[Sea Lantern] 崩溃诊断：{}", report.summary),
+                );
+            }
+            Err(err) => eprintln!("[server_manager] crash diagnosis failed id={} err={}", id, err),
+        }
+    }
+
+    fn schedule_restart(&self, id: &str, token: u64, delay_secs: u64) {
+        let sid = id.to_string();
+        std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_secs(delay_secs));
+            let manager = crate::services::global::server_manager();
+
+            // 等待期间若人工启动/停止/删除，token 会失效，此处直接放弃
+            let claimed = manager
+                .crash_states
+                .lock()
+                .ok()
+                .and_then(|mut states| {
+                    let state = states.get_mut(&sid)?;
+                    if state.restart_token != token || state.next_restart_at.is_none() {
+                        return None;
+                    }
+                    state.next_restart_at = None;
+                    Some(())
+                })
+                .is_some();
+            if !claimed {
+                return;
+            }
+
+            let _ = server_log_pipeline::append_sealantern_log(
+                &sid,
+                "[Sea Lantern] 正在自动重启服务器...",
+            );
+            match manager.launch_server(&sid) {
+                Ok(_) => {
+                    if let Ok(mut states) = manager.crash_states.lock() {
+                        if let Some(state) = states.get_mut(&sid) {
+                            state.crashed_at = None;
+                        }
+                    }
+                }
+                Err(err) => {
+                    manager.handle_server_crash(
+                        &sid,
+                        None,
+                        format!("自动重启失败: {}", err),
+                        false,
+                    );
+                }
+            }
+        });
+    }
+
+    pub fn update_restart_policy(
+        &self,
+        id: &str,
+        policy: RestartPolicy,
+    ) -> Result<ServerInstance, String> {
+        self.update_server_instance(id, |server| {
+            server.restart_policy = policy;
+            Ok(())
+        })
+    }
+
+    /// 修改服务器实例设置；`apply` 返回错误时不做任何修改。启动参数在下次启动时生效。
+    pub fn update_server_instance(
+        &self,
+        id: &str,
+        apply: impl FnOnce(&mut ServerInstance) -> Result<(), String>,
+    ) -> Result<ServerInstance, String> {
+        let mut servers = self.lock_servers()?;
+        let server = servers
+            .iter_mut()
+            .find(|s| s.id == id)
+            .ok_or_else(|| "未找到服务器".to_string())?;
+        let mut updated_server = server.clone();
+        apply(&mut updated_server)?;
+        *server = updated_server.clone();
+        drop(servers);
+        self.save()?;
+        Ok(updated_server)
     }
 
     pub fn delete_server(&self, id: &str) -> Result<(), String> {
+        self.reset_crash_state(id);
         {
             let procs = self.lock_processes()?;
             if procs.contains_key(id) {
@@ -1534,6 +2003,9 @@ impl ServerManager {
         }
 
         self.lock_servers()?.retain(|s| s.id != id);
+        crate::services::global::scheduler().remove_jobs_for_server(id);
+        crate::services::global::alert_manager().remove_rules_for_server(id);
+        crash_analyzer::remove_crash_reports(id);
         let data_dir = self.data_dir_value()?;
         remove_run_path_mapping(&data_dir, id);
         self.save()?;
@@ -1576,6 +2048,8 @@ impl ServerManager {
 
         let mut servers = self.lock_servers()?;
         if let Some(server) = servers.iter_mut().find(|s| s.id == id) {
+            let previous_core_type = server.core_type.clone();
+
             // 更新路径
             server.path = new_path.to_string();
 
@@ -1619,6 +2093,13 @@ impl ServerManager {
             let data_dir = self.data_dir_value()?;
             update_run_path_mapping(&data_dir, id, new_path);
 
+            convert_world_layout_for_core_change(
+                id,
+                Path::new(new_path),
+                &previous_core_type,
+                &updated_server.core_type,
+            );
+
             Ok(updated_server)
         } else {
             Err("未找到服务器".to_string())
@@ -1626,13 +2107,26 @@ impl ServerManager {
     }
 
     pub fn stop_all_servers(&self) {
-        let ids: Vec<String> = self
-            .lock_processes()
-            .map(|procs| procs.keys().cloned().collect())
-            .unwrap_or_default();
-        for id in ids {
-            let _ = self.stop_server(&id);
+        self.stop_all_servers_with_countdown(None);
+    }
+
+    /// 带倒计时时各服务器并行倒计时，避免逐台等待；返回前所有服务器均已停止。
+    pub fn stop_all_servers_with_countdown(&self, countdown: Option<&StopCountdown>) {
+        let ids = self.get_running_server_ids();
+        if countdown.is_none() {
+            for id in ids {
+                let _ = self.stop_server(&id);
+            }
+            return;
         }
+
+        std::thread::scope(|scope| {
+            for id in &ids {
+                scope.spawn(move || {
+                    let _ = self.stop_server_with_countdown(id, countdown);
+                });
+            }
+        });
     }
 }
 
@@ -1656,20 +2150,29 @@ fn escape_cmd_arg(s: &str) -> String {
     out
 }
 
-fn current_timestamp_secs() -> u64 {
+pub(crate) fn current_timestamp_secs() -> u64 {
     std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .map(|duration| duration.as_secs())
         .unwrap_or(0)
 }
 
-fn current_timestamp_millis() -> u128 {
+pub(crate) fn current_timestamp_millis() -> i64 {
     std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
-        .map(|duration| duration.as_millis())
+        .map(|duration| duration.as_millis() as i64)
         .unwrap_or(0)
 }
 
+/// 判断控制台命令是否会让服务端自行退出（vanilla/Paper 的 stop、BungeeCord 的 end、Velocity 的 shutdown）
+fn is_stop_console_command(command: &str) -> bool {
+    let trimmed = command.trim().trim_start_matches('/');
+    let keyword = trimmed.split_whitespace().next().unwrap_or_default();
+    ["stop", "end", "shutdown"]
+        .iter()
+        .any(|candidate| keyword.eq_ignore_ascii_case(candidate))
+}
+
 fn get_data_dir() -> String {
     // 使用统一的应用数据目录，确保 MSI 安装时数据存储在 %AppData%
     crate::utils::path::get_or_create_app_data_dir()
@@ -2129,6 +2632,53 @@ fn save_servers(dir: &str, servers: &[ServerInstance]) {
     }
 }
 
+/// 核心在原版系与 Bukkit 系之间切换时，转换主世界的维度目录布局，
+/// 否则新核心会找不到原有的下界与末地并重新生成。转换失败只记录日志，不影响核心更新。
+fn convert_world_layout_for_core_change(
+    id: &str,
+    server_dir: &Path,
+    previous_core_type: &str,
+    core_type: &str,
+) {
+    use crate::services::world::{is_world_in_use, layout, level_name};
+
+    let (Some(previous), Some(target)) = (
+        layout::layout_for_core_type(previous_core_type),
+        layout::layout_for_core_type(core_type),
+    ) else {
+        return;
+    };
+    let world = level_name(server_dir);
+    if previous == target || !server_dir.join(&world).is_dir() {
+        return;
+    }
+    // 目录下的服务端可能不是由 SeaLantern 启动的，运行中移动维度目录会损坏世界
+    if is_world_in_use(server_dir) {
+        let _ = server_log_pipeline::append_sealantern_log(
+            id,
+            &format!(
+                "[Sea Lantern] 世界 {} 正在被运行中的服务端使用，未转换维度目录；\
+                 请停止服务端后在世界管理中手动转换",
+                world
+            ),
+        );
+        return;
+    }
+
+    let message = match layout::convert_layout(server_dir, &world, target) {
+        Ok(result) if result.moved.is_empty() => return,
+        Ok(result) => format!(
+            "[Sea Lantern] 核心类型由 {} 变为 {}，已转换世界 {} 的维度目录: {}",
+            previous_core_type,
+            core_type,
+            world,
+            result.moved.join(", ")
+        ),
+        Err(e) => format!("[Sea Lantern] 转换世界 {} 的维度目录失败: {}", world, e),
+    };
+    let _ = server_log_pipeline::append_sealantern_log(id, &message);
+}
+
 fn copy_dir_recursive(src: &std::path::Path, dst: &std::path::Path) -> std::io::Result<()> {
     if !dst.exists() {
         std::fs::create_dir_all(dst)?;
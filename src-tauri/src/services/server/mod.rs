@@ -4,20 +4,48 @@
 //! 当前阶段迁移以下文件且保持对外 API 稳定：
 //! - manager.rs（原 services/server_manager.rs）
 //! - log_pipeline.rs（原 services/server_log_pipeline.rs）
+//! - log_alerts.rs：按日志匹配与频率阈值触发的告警规则
+//! - log_parser.rs：日志行的级别 / 线程 / logger / 正文解析
+//! - log_search.rs：基于 FTS5 索引的日志全文检索
+//! - log_retention.rs：日志数据库的保留策略、.log.gz 归档与 VACUUM
 //! - installer.rs（原 services/server_installer.rs）
 //! - downloader.rs（原 services/server_downloader.rs）
 //! - id_manager.rs（原 services/server_id_manager.rs）
 //! - player.rs（原 services/player_manager.rs）
 //! - config.rs（原 services/config_parser.rs）
+//! - config_yaml.rs / config_toml.rs：保留注释与键顺序的 YAML / TOML 配置编辑
+//! - config_history.rs：配置写入前的修订版本记录、diff 与恢复
+//! - config_sync.rs：多台服务器的配置对比、同步与配置预设
+//! - crash_analyzer.rs：异常退出后分析崩溃报告、hs_err 与日志，定位疑似模组 / 插件
+//! - properties_schema.rs：server.properties 配置项的类型、范围与版本校验
 //! - join.rs（原 services/join_manager.rs）
 //!
 //! 注意：为保持向后兼容，顶层 services 仍通过转发导出旧路径。
 
+pub mod backup;
+pub mod backup_store;
 pub mod config;
+pub mod config_history;
+pub mod config_sync;
+pub mod config_toml;
+pub mod config_yaml;
+pub mod crash_analyzer;
+pub mod crash_guard;
+pub mod cron;
 pub mod downloader;
 pub mod id_manager;
 pub mod installer;
 pub mod join;
+pub mod log_alerts;
+pub mod log_parser;
 pub mod log_pipeline;
+pub mod log_retention;
+pub mod log_search;
 pub mod manager;
+pub mod ping;
 pub mod player;
+pub mod player_sessions;
+pub mod properties_schema;
+pub mod rcon;
+pub mod scheduler;
+pub mod shutdown;
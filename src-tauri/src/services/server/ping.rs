@@ -0,0 +1,489 @@
+//! Minecraft Server List Ping 客户端。
+//!
+//! 进程存活并不代表服务器已经可以连接，这里通过服务器列表协议确认端口上确实有
+//! Minecraft 服务端在应答，并取回在线人数、MOTD、版本与图标：
+//! - 现代协议（1.7+）：握手包（next state = 1）→ 状态请求 → 状态 JSON → ping/pong 测延迟；
+//! - 旧协议（1.4 - 1.6 以及更早）：发送 `0xFE 0x01`，服务端以 `0xFF` + UTF-16BE 字符串回应。
+//!
+//! 查询结果由 [`PingCache`] 缓存并在后台线程刷新，避免状态轮询被网络超时阻塞。
+
+use std::collections::HashMap;
+use std::io::{Cursor, Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+use crate::models::server::ServerPingInfo;
+
+///此处常量见 utils/constants.rs
+use crate::utils::constants::{PING_CONNECT_TIMEOUT_MS, PING_READ_TIMEOUT_MS};
+
+/// 状态响应上限（含 base64 图标），防止异常数据导致分配过大内存
+const MAX_STATUS_PACKET_LEN: usize = 2 * 1024 * 1024;
+/// 握手时声明的协议版本，-1 表示仅查询状态
+const STATUS_PROTOCOL_VERSION: i32 = -1;
+
+/// 查询服务器状态：优先使用现代协议，失败后回退到旧版 `0xFE` 协议。
+pub fn ping_server(host: &str, port: u16) -> Result<ServerPingInfo, String> {
+    let stream = connect(host, port)?;
+    match ping_modern(stream, host, port) {
+        Ok(info) => Ok(info),
+        Err(modern_err) => connect(host, port)
+            .and_then(ping_legacy)
+            .map_err(|_| modern_err),
+    }
+}
+
+fn connect(host: &str, port: u16) -> Result<TcpStream, String> {
+    let addr: SocketAddr = (host, port)
+        .to_socket_addrs()
+        .map_err(|e| format!("解析地址失败: {}", e))?
+        .next()
+        .ok_or_else(|| format!("无法解析地址: {}", host))?;
+    let stream = TcpStream::connect_timeout(&addr, Duration::from_millis(PING_CONNECT_TIMEOUT_MS))
+        .map_err(|e| format!("连接 {} 失败: {}", addr, e))?;
+    let timeout = Some(Duration::from_millis(PING_READ_TIMEOUT_MS));
+    stream
+        .set_read_timeout(timeout)
+        .and_then(|_| stream.set_write_timeout(timeout))
+        .map_err(|e| format!("设置超时失败: {}", e))?;
+    Ok(stream)
+}
+
+fn ping_modern(mut stream: TcpStream, host: &str, port: u16) -> Result<ServerPingInfo, String> {
+    let mut handshake = Vec::new();
+    write_varint(&mut handshake, 0x00);
+    write_varint(&mut handshake, STATUS_PROTOCOL_VERSION);
+    write_string(&mut handshake, host);
+    handshake.extend_from_slice(&port.to_be_bytes());
+    write_varint(&mut handshake, 1);
+
+    let started = Instant::now();
+    write_packet(&mut stream, &handshake)?;
+    write_packet(&mut stream, &[0x00])?;
+
+    let mut packet = read_packet(&mut stream)?;
+    let mut latency_ms = started.elapsed().as_millis() as u64;
+    if read_varint(&mut packet)? != 0x00 {
+        return Err("状态响应包 id 异常".to_string());
+    }
+    let json = read_string(&mut packet)?;
+    let mut info = parse_status_json(&json)?;
+
+    // 部分服务端不响应 ping，此时沿用状态请求的往返时间
+    let mut ping = Vec::new();
+    write_varint(&mut ping, 0x01);
+    ping.extend_from_slice(&0x5EA1_A7E2_i64.to_be_bytes());
+    let ping_started = Instant::now();
+    if write_packet(&mut stream, &ping).is_ok() {
+        if let Ok(mut pong) = read_packet(&mut stream) {
+            if read_varint(&mut pong).ok() == Some(0x01) {
+                latency_ms = ping_started.elapsed().as_millis() as u64;
+            }
+        }
+    }
+
+    info.latency_ms = latency_ms;
+    Ok(info)
+}
+
+fn ping_legacy(mut stream: TcpStream) -> Result<ServerPingInfo, String> {
+    let started = Instant::now();
+    stream
+        .write_all(&[0xFE, 0x01])
+        .map_err(|e| format!("发送旧版 ping 失败: {}", e))?;
+
+    let mut header = [0u8; 3];
+    stream
+        .read_exact(&mut header)
+        .map_err(|e| format!("读取旧版 ping 响应失败: {}", e))?;
+    if header[0] != 0xFF {
+        return Err("旧版 ping 响应格式错误".to_string());
+    }
+    let chars = u16::from_be_bytes([header[1], header[2]]) as usize;
+    let mut payload = vec![0u8; chars * 2];
+    stream
+        .read_exact(&mut payload)
+        .map_err(|e| format!("读取旧版 ping 响应失败: {}", e))?;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    let units: Vec<u16> = payload
+        .chunks_exact(2)
+        .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+        .collect();
+    let text = String::from_utf16_lossy(&units);
+    let mut info = parse_legacy_response(&text)?;
+    info.latency_ms = latency_ms;
+    Ok(info)
+}
+
+/// 解析现代协议的状态 JSON。
+pub fn parse_status_json(json: &str) -> Result<ServerPingInfo, String> {
+    let value: Value = serde_json::from_str(json).map_err(|e| format!("状态 JSON 无效: {}", e))?;
+    let players = &value["players"];
+    let sample = players["sample"]
+        .as_array()
+        .map(|list| {
+            list.iter()
+                .filter_map(|p| p["name"].as_str())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(ServerPingInfo {
+        online_players: players["online"].as_u64().unwrap_or(0) as u32,
+        max_players: players["max"].as_u64().unwrap_or(0) as u32,
+        sample,
+        version: value["version"]["name"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        protocol: value["version"]["protocol"].as_i64().unwrap_or(-1) as i32,
+        motd: strip_formatting(&flatten_chat(&value["description"])),
+        latency_ms: 0,
+        favicon: value["favicon"].as_str().map(str::to_string),
+        legacy: false,
+    })
+}
+
+/// 解析旧版协议响应：1.4+ 为 `§1\0协议\0版本\0MOTD\0在线\0上限`，更早版本为 `MOTD§在线§上限`。
+pub fn parse_legacy_response(text: &str) -> Result<ServerPingInfo, String> {
+    let (protocol, version, motd, online, max) = if let Some(rest) = text.strip_prefix("§1\0") {
+        let fields: Vec<&str> = rest.split('\0').collect();
+        if fields.len() < 5 {
+            return Err("旧版 ping 响应字段不足".to_string());
+        }
+        let protocol = fields[0].parse::<i32>().unwrap_or(-1);
+        (protocol, fields[1].to_string(), fields[2], fields[3], fields[4])
+    } else {
+        let fields: Vec<&str> = text.rsplitn(3, '§').collect();
+        if fields.len() < 3 {
+            return Err("旧版 ping 响应字段不足".to_string());
+        }
+        (-1, String::new(), fields[2], fields[1], fields[0])
+    };
+
+    Ok(ServerPingInfo {
+        online_players: online.trim().parse().unwrap_or(0),
+        max_players: max.trim().parse().unwrap_or(0),
+        sample: Vec::new(),
+        version,
+        protocol,
+        motd: strip_formatting(motd),
+        latency_ms: 0,
+        favicon: None,
+        legacy: true,
+    })
+}
+
+/// 将聊天组件（字符串 / 对象 / 数组）拼接为纯文本。
+fn flatten_chat(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Array(items) => items.iter().map(flatten_chat).collect(),
+        Value::Object(map) => {
+            let mut text = map
+                .get("text")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string();
+            if let Some(Value::Array(extra)) = map.get("extra") {
+                for item in extra {
+                    text.push_str(&flatten_chat(item));
+                }
+            }
+            text
+        }
+        _ => String::new(),
+    }
+}
+
+/// 去掉 `§` 格式代码。
+fn strip_formatting(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '§' {
+            chars.next();
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn write_varint(buf: &mut Vec<u8>, value: i32) {
+    let mut value = value as u32;
+    loop {
+        if value & !0x7F == 0 {
+            buf.push(value as u8);
+            return;
+        }
+        buf.push((value & 0x7F) as u8 | 0x80);
+        value >>= 7;
+    }
+}
+
+fn read_varint(reader: &mut impl Read) -> Result<i32, String> {
+    let mut result: u32 = 0;
+    for shift in 0..5 {
+        let mut byte = [0u8; 1];
+        reader
+            .read_exact(&mut byte)
+            .map_err(|e| format!("读取数据失败: {}", e))?;
+        result |= ((byte[0] & 0x7F) as u32) << (7 * shift);
+        if byte[0] & 0x80 == 0 {
+            return Ok(result as i32);
+        }
+    }
+    Err("VarInt 过长".to_string())
+}
+
+fn write_string(buf: &mut Vec<u8>, value: &str) {
+    write_varint(buf, value.len() as i32);
+    buf.extend_from_slice(value.as_bytes());
+}
+
+fn read_string(reader: &mut impl Read) -> Result<String, String> {
+    let len = read_varint(reader)?;
+    if len < 0 || len as usize > MAX_STATUS_PACKET_LEN {
+        return Err(format!("字符串长度异常: {}", len));
+    }
+    let mut buf = vec![0u8; len as usize];
+    reader
+        .read_exact(&mut buf)
+        .map_err(|e| format!("读取数据失败: {}", e))?;
+    String::from_utf8(buf).map_err(|e| format!("字符串不是有效的 UTF-8: {}", e))
+}
+
+fn write_packet(stream: &mut impl Write, payload: &[u8]) -> Result<(), String> {
+    let mut buf = Vec::with_capacity(payload.len() + 5);
+    write_varint(&mut buf, payload.len() as i32);
+    buf.extend_from_slice(payload);
+    stream
+        .write_all(&buf)
+        .and_then(|_| stream.flush())
+        .map_err(|e| format!("发送数据失败: {}", e))
+}
+
+fn read_packet(stream: &mut impl Read) -> Result<Cursor<Vec<u8>>, String> {
+    let len = read_varint(stream)?;
+    if len <= 0 || len as usize > MAX_STATUS_PACKET_LEN {
+        return Err(format!("数据包长度异常: {}", len));
+    }
+    let mut buf = vec![0u8; len as usize];
+    stream
+        .read_exact(&mut buf)
+        .map_err(|e| format!("读取数据失败: {}", e))?;
+    Ok(Cursor::new(buf))
+}
+
+struct PingCacheEntry {
+    checked_at: Option<Instant>,
+    /// 每次失效都会递增，后台刷新据此丢弃过期结果
+    generation: u64,
+    refreshing: bool,
+    result: Option<ServerPingInfo>,
+}
+
+/// 按服务器缓存的 ping 结果。
+#[derive(Default)]
+pub struct PingCache {
+    entries: Mutex<HashMap<String, PingCacheEntry>>,
+}
+
+impl PingCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 返回缓存结果；若超过 `max_age` 且没有正在进行的刷新，同时返回刷新所需的 generation。
+    pub fn get(&self, id: &str, max_age: Duration) -> (Option<ServerPingInfo>, Option<u64>) {
+        let Ok(mut entries) = self.entries.lock() else {
+            return (None, None);
+        };
+        let entry = entries.entry(id.to_string()).or_insert(PingCacheEntry {
+            checked_at: None,
+            generation: 0,
+            refreshing: false,
+            result: None,
+        });
+        let stale = entry.checked_at.is_none_or(|at| at.elapsed() >= max_age);
+        let refresh = if stale && !entry.refreshing {
+            entry.refreshing = true;
+            Some(entry.generation)
+        } else {
+            None
+        };
+        (entry.result.clone(), refresh)
+    }
+
+    /// 写入刷新结果；期间缓存被失效过则丢弃。
+    pub fn store(&self, id: &str, generation: u64, result: Option<ServerPingInfo>) {
+        if let Ok(mut entries) = self.entries.lock() {
+            if let Some(entry) = entries.get_mut(id) {
+                if entry.generation == generation {
+                    entry.refreshing = false;
+                    entry.checked_at = Some(Instant::now());
+                    entry.result = result;
+                }
+            }
+        }
+    }
+
+    /// 服务器停止或退出后调用，清除旧结果并让进行中的刷新作废。
+    pub fn invalidate(&self, id: &str) {
+        if let Ok(mut entries) = self.entries.lock() {
+            if let Some(entry) = entries.get_mut(id) {
+                entry.generation = entry.generation.wrapping_add(1);
+                entry.refreshing = false;
+                entry.checked_at = None;
+                entry.result = None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    const STATUS_JSON: &str = r#"{
+        "version": {"name": "1.21.1", "protocol": 767},
+        "players": {"max": 20, "online": 2, "sample": [
+            {"name": "Alex", "id": "00000000-0000-0000-0000-000000000001"},
+            {"name": "Steve", "id": "00000000-0000-0000-0000-000000000002"}
+        ]},
+        "description": {"text": "§aSea ", "extra": [{"text": "Lantern"}, "!"]},
+        "favicon": "data:image/png;base64,AAAA"
+    }"#;
+
+    fn spawn_modern_stand_in() -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut handshake = read_packet(&mut stream).unwrap();
+            assert_eq!(read_varint(&mut handshake).unwrap(), 0x00);
+            assert_eq!(read_varint(&mut handshake).unwrap(), STATUS_PROTOCOL_VERSION);
+            assert_eq!(read_string(&mut handshake).unwrap(), "127.0.0.1");
+            let request = read_packet(&mut stream).unwrap();
+            assert_eq!(request.into_inner(), vec![0x00]);
+
+            let mut status = Vec::new();
+            write_varint(&mut status, 0x00);
+            write_string(&mut status, STATUS_JSON);
+            write_packet(&mut stream, &status).unwrap();
+
+            let ping = read_packet(&mut stream).unwrap();
+            write_packet(&mut stream, &ping.into_inner()).unwrap();
+        });
+        port
+    }
+
+    fn spawn_legacy_stand_in() -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            // 第一次连接是现代握手，旧版服务端读不懂直接断开
+            let (first, _) = listener.accept().unwrap();
+            drop(first);
+
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 2];
+            stream.read_exact(&mut request).unwrap();
+            assert_eq!(request, [0xFE, 0x01]);
+
+            let text = "§1\u{0}61\u{0}1.5.2\u{0}Old MOTD\u{0}3\u{0}10";
+            let units: Vec<u16> = text.encode_utf16().collect();
+            let mut reply = vec![0xFF];
+            reply.extend_from_slice(&(units.len() as u16).to_be_bytes());
+            for unit in units {
+                reply.extend_from_slice(&unit.to_be_bytes());
+            }
+            stream.write_all(&reply).unwrap();
+        });
+        port
+    }
+
+    #[test]
+    fn varint_round_trip() {
+        for value in [0, 1, 127, 128, 255, 25565, 2_097_151, i32::MAX, -1] {
+            let mut buf = Vec::new();
+            write_varint(&mut buf, value);
+            assert_eq!(read_varint(&mut buf.as_slice()).unwrap(), value);
+        }
+        let mut buf = Vec::new();
+        write_varint(&mut buf, -1);
+        assert_eq!(buf.len(), 5);
+    }
+
+    #[test]
+    fn parses_status_json() {
+        let info = parse_status_json(STATUS_JSON).unwrap();
+        assert_eq!(info.online_players, 2);
+        assert_eq!(info.max_players, 20);
+        assert_eq!(info.sample, vec!["Alex", "Steve"]);
+        assert_eq!(info.version, "1.21.1");
+        assert_eq!(info.protocol, 767);
+        assert_eq!(info.motd, "Sea Lantern!");
+        assert_eq!(info.favicon.as_deref(), Some("data:image/png;base64,AAAA"));
+        assert!(!info.legacy);
+    }
+
+    #[test]
+    fn parses_legacy_responses() {
+        let info = parse_legacy_response("§1\u{0}61\u{0}1.5.2\u{0}§eHello\u{0}3\u{0}10").unwrap();
+        assert_eq!((info.protocol, info.version.as_str()), (61, "1.5.2"));
+        assert_eq!(info.motd, "Hello");
+        assert_eq!((info.online_players, info.max_players), (3, 10));
+
+        let beta = parse_legacy_response("Beta MOTD§5§20").unwrap();
+        assert_eq!(beta.motd, "Beta MOTD");
+        assert_eq!((beta.online_players, beta.max_players), (5, 20));
+        assert!(beta.legacy);
+    }
+
+    #[test]
+    fn pings_modern_server() {
+        let port = spawn_modern_stand_in();
+        let info = ping_server("127.0.0.1", port).unwrap();
+        assert_eq!(info.online_players, 2);
+        assert_eq!(info.motd, "Sea Lantern!");
+    }
+
+    #[test]
+    fn falls_back_to_legacy_ping() {
+        let port = spawn_legacy_stand_in();
+        let info = ping_server("127.0.0.1", port).unwrap();
+        assert!(info.legacy);
+        assert_eq!(info.version, "1.5.2");
+        assert_eq!((info.online_players, info.max_players), (3, 10));
+    }
+
+    #[test]
+    fn cache_discards_results_after_invalidate() {
+        let cache = PingCache::new();
+        let max_age = Duration::from_secs(60);
+        let (cached, refresh) = cache.get("a", max_age);
+        assert!(cached.is_none());
+        let generation = refresh.unwrap();
+        // 刷新进行中不会重复发起
+        assert_eq!(cache.get("a", max_age).1, None);
+
+        cache.invalidate("a");
+        cache.store("a", generation, Some(parse_status_json(STATUS_JSON).unwrap()));
+        let (cached, refresh) = cache.get("a", max_age);
+        assert!(cached.is_none());
+
+        let generation = refresh.unwrap();
+        cache.store("a", generation, Some(parse_status_json(STATUS_JSON).unwrap()));
+        let (cached, refresh) = cache.get("a", max_age);
+        assert_eq!(cached.unwrap().online_players, 2);
+        assert_eq!(refresh, None);
+    }
+}
@@ -1,4 +1,11 @@
+use md5::{Digest, Md5};
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::Path;
+use std::time::Duration;
+
+///此处常量见 utils/constants.rs
+use crate::utils::constants::{MOJANG_PROFILE_API, MOJANG_PROFILE_TIMEOUT_SECS};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlayerEntry {
@@ -20,6 +27,19 @@ pub struct BanEntry {
     pub expires: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BannedIpEntry {
+    pub ip: String,
+    #[serde(default)]
+    pub reason: String,
+    #[serde(default)]
+    pub source: String,
+    #[serde(default)]
+    pub created: String,
+    #[serde(default)]
+    pub expires: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpEntry {
     pub uuid: String,
@@ -38,6 +58,10 @@ pub fn read_banned_players(server_path: &str) -> Result<Vec<BanEntry>, String> {
     read_json_list(server_path, "banned-players.json")
 }
 
+pub fn read_banned_ips(server_path: &str) -> Result<Vec<BannedIpEntry>, String> {
+    read_json_list(server_path, "banned-ips.json")
+}
+
 pub fn read_ops(server_path: &str) -> Result<Vec<OpEntry>, String> {
     read_json_list(server_path, "ops.json")
 }
@@ -58,3 +82,434 @@ fn read_json_list<T: serde::de::DeserializeOwned>(
     }
     serde_json::from_str(trimmed).map_err(|e| format!("解析{}失败: {}", filename, e))
 }
+
+// ---- 离线编辑名单文件 ----
+//
+// 服务器停止时直接改写 whitelist.json / ops.json / banned-*.json。
+// 文件按 JSON 值读写，条目中 SeaLantern 不认识的字段（插件或新版本加入的）原样保留。
+
+const WHITELIST_FILE: &str = "whitelist.json";
+const OPS_FILE: &str = "ops.json";
+const BANNED_PLAYERS_FILE: &str = "banned-players.json";
+const BANNED_IPS_FILE: &str = "banned-ips.json";
+/// 离线写入封禁条目时记录的来源
+const OFFLINE_SOURCE: &str = "Sea Lantern";
+
+/// 解析出的玩家身份
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedPlayer {
+    pub name: String,
+    pub uuid: String,
+}
+
+/// 玩家名 → UUID 解析器，离线添加名单条目时用于补全 UUID。
+pub trait UuidResolver {
+    fn resolve(&self, name: &str) -> Result<ResolvedPlayer, String>;
+}
+
+/// 离线模式：UUID 由玩家名计算，与服务端 `UUID.nameUUIDFromBytes("OfflinePlayer:<name>")` 一致。
+pub struct OfflineUuidResolver;
+
+impl UuidResolver for OfflineUuidResolver {
+    fn resolve(&self, name: &str) -> Result<ResolvedPlayer, String> {
+        Ok(ResolvedPlayer {
+            name: name.to_string(),
+            uuid: offline_uuid(name),
+        })
+    }
+}
+
+/// 正版模式：查询 Mojang 档案接口，返回的玩家名为正确的大小写。
+pub struct MojangUuidResolver;
+
+impl UuidResolver for MojangUuidResolver {
+    fn resolve(&self, name: &str) -> Result<ResolvedPlayer, String> {
+        #[derive(Deserialize)]
+        struct Profile {
+            id: String,
+            name: String,
+        }
+
+        let client = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(MOJANG_PROFILE_TIMEOUT_SECS))
+            .build()
+            .map_err(|e| format!("创建 HTTP 客户端失败: {}", e))?;
+        let response = client
+            .get(format!("{}/{}", MOJANG_PROFILE_API, name))
+            .send()
+            .map_err(|e| format!("查询正版 UUID 失败: {}", e))?;
+        let status = response.status();
+        if status == reqwest::StatusCode::NO_CONTENT || status == reqwest::StatusCode::NOT_FOUND {
+            return Err(format!("正版玩家不存在: {}", name));
+        }
+        if !status.is_success() {
+            return Err(format!("查询正版 UUID 失败: HTTP {}", status));
+        }
+        let profile: Profile = response
+            .json()
+            .map_err(|e| format!("解析正版 UUID 失败: {}", e))?;
+        let uuid = hyphenate_uuid(&profile.id)
+            .ok_or_else(|| format!("正版 UUID 格式异常: {}", profile.id))?;
+        Ok(ResolvedPlayer { name: profile.name, uuid })
+    }
+}
+
+/// 先查服务端的 usercache.json，未命中再交给 `fallback`。
+pub struct UsercacheResolver {
+    entries: Vec<ResolvedPlayer>,
+    fallback: Box<dyn UuidResolver + Send + Sync>,
+}
+
+impl UsercacheResolver {
+    pub fn new(server_dir: &Path, fallback: Box<dyn UuidResolver + Send + Sync>) -> Self {
+        UsercacheResolver {
+            entries: read_usercache(server_dir),
+            fallback,
+        }
+    }
+}
+
+impl UuidResolver for UsercacheResolver {
+    fn resolve(&self, name: &str) -> Result<ResolvedPlayer, String> {
+        match self
+            .entries
+            .iter()
+            .find(|e| e.name.eq_ignore_ascii_case(name))
+        {
+            Some(entry) => Ok(entry.clone()),
+            None => self.fallback.resolve(name),
+        }
+    }
+}
+
+/// 读取服务端的 usercache.json（玩家名与 UUID 的对应关系），文件不存在或损坏时为空。
+pub fn read_usercache(server_dir: &Path) -> Vec<ResolvedPlayer> {
+    #[derive(Deserialize)]
+    struct CacheEntry {
+        name: String,
+        uuid: String,
+    }
+
+    std::fs::read_to_string(server_dir.join("usercache.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<Vec<CacheEntry>>(&content).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|entry| ResolvedPlayer { name: entry.name, uuid: entry.uuid })
+        .collect()
+}
+
+/// 按 server.properties 的 `online-mode`（缺省为 true）选择解析器。
+///
+/// 只有正版模式才查询 usercache.json：切换过模式的服务器缓存里可能是另一种 UUID，
+/// 而离线 UUID 本身就能直接算出来。
+pub fn resolver_for_server(server_dir: &Path) -> Box<dyn UuidResolver + Send + Sync> {
+    let online_mode = server_dir
+        .join("server.properties")
+        .to_str()
+        .and_then(|path| super::config::read_properties(path).ok())
+        .and_then(|props| {
+            props
+                .get("online-mode")
+                .map(|v| v.trim().to_ascii_lowercase())
+        })
+        .map(|value| value != "false")
+        .unwrap_or(true);
+
+    if online_mode {
+        Box::new(UsercacheResolver::new(server_dir, Box::new(MojangUuidResolver)))
+    } else {
+        Box::new(OfflineUuidResolver)
+    }
+}
+
+/// 新 OP 的权限等级，取 server.properties 的 `op-permission-level`（缺省为 4）。
+pub fn default_op_level(server_dir: &Path) -> u32 {
+    server_dir
+        .join("server.properties")
+        .to_str()
+        .and_then(|path| super::config::read_properties(path).ok())
+        .and_then(|props| props.get("op-permission-level")?.trim().parse::<u32>().ok())
+        .filter(|level| (1..=4).contains(level))
+        .unwrap_or(4)
+}
+
+/// 计算离线模式玩家的 UUID（名称型 v3，命名空间为空）。
+pub fn offline_uuid(name: &str) -> String {
+    let mut bytes: [u8; 16] = Md5::digest(format!("OfflinePlayer:{}", name)).into();
+    bytes[6] = (bytes[6] & 0x0f) | 0x30;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    uuid::Uuid::from_bytes(bytes).hyphenated().to_string()
+}
+
+/// 将 32 位十六进制的 UUID 转为带连字符的形式，已带连字符的原样规范化。
+fn hyphenate_uuid(raw: &str) -> Option<String> {
+    uuid::Uuid::parse_str(raw.trim())
+        .ok()
+        .map(|id| id.hyphenated().to_string())
+}
+
+/// 添加白名单条目；已存在时返回 false。
+pub fn add_whitelist_entry(server_dir: &Path, player: &ResolvedPlayer) -> Result<bool, String> {
+    let mut list = read_json_values(server_dir, WHITELIST_FILE)?;
+    if list.iter().any(|entry| matches_player(entry, player)) {
+        return Ok(false);
+    }
+    list.push(json!({ "uuid": player.uuid, "name": player.name }));
+    write_json_values(server_dir, WHITELIST_FILE, &list)?;
+    Ok(true)
+}
+
+pub fn remove_whitelist_entry(server_dir: &Path, name: &str) -> Result<bool, String> {
+    remove_matching(server_dir, WHITELIST_FILE, |entry| matches_name(entry, name))
+}
+
+/// 添加或更新 OP 条目。
+pub fn add_op_entry(
+    server_dir: &Path,
+    player: &ResolvedPlayer,
+    level: u32,
+    bypasses_player_limit: bool,
+) -> Result<(), String> {
+    let mut list = read_json_values(server_dir, OPS_FILE)?;
+    upsert_entry(
+        &mut list,
+        player,
+        &[("level", json!(level)), ("bypassesPlayerLimit", json!(bypasses_player_limit))],
+    );
+    write_json_values(server_dir, OPS_FILE, &list)
+}
+
+pub fn remove_op_entry(server_dir: &Path, name: &str) -> Result<bool, String> {
+    remove_matching(server_dir, OPS_FILE, |entry| matches_name(entry, name))
+}
+
+/// 添加或更新玩家封禁条目（永久封禁）。
+pub fn add_ban_entry(
+    server_dir: &Path,
+    player: &ResolvedPlayer,
+    reason: &str,
+) -> Result<(), String> {
+    let mut list = read_json_values(server_dir, BANNED_PLAYERS_FILE)?;
+    upsert_entry(&mut list, player, &ban_fields(reason));
+    write_json_values(server_dir, BANNED_PLAYERS_FILE, &list)
+}
+
+pub fn remove_ban_entry(server_dir: &Path, name: &str) -> Result<bool, String> {
+    remove_matching(server_dir, BANNED_PLAYERS_FILE, |entry| matches_name(entry, name))
+}
+
+/// 添加或更新 IP 封禁条目（永久封禁）。
+pub fn add_ip_ban_entry(server_dir: &Path, ip: &str, reason: &str) -> Result<(), String> {
+    let mut list = read_json_values(server_dir, BANNED_IPS_FILE)?;
+    let fields = ban_fields(reason);
+    match list
+        .iter_mut()
+        .find(|entry| entry.get("ip").and_then(Value::as_str) == Some(ip))
+    {
+        Some(entry) => set_fields(entry, &fields),
+        None => {
+            let mut entry = json!({ "ip": ip });
+            set_fields(&mut entry, &fields);
+            list.push(entry);
+        }
+    }
+    write_json_values(server_dir, BANNED_IPS_FILE, &list)
+}
+
+pub fn remove_ip_ban_entry(server_dir: &Path, ip: &str) -> Result<bool, String> {
+    remove_matching(server_dir, BANNED_IPS_FILE, |entry| {
+        entry.get("ip").and_then(Value::as_str) == Some(ip)
+    })
+}
+
+/// 与原版一致的封禁字段；`created` 的格式为 `yyyy-MM-dd HH:mm:ss Z`。
+fn ban_fields(reason: &str) -> Vec<(&'static str, Value)> {
+    let reason = if reason.trim().is_empty() {
+        "Banned by an operator."
+    } else {
+        reason.trim()
+    };
+    vec![
+        (
+            "created",
+            json!(chrono::Local::now()
+                .format("%Y-%m-%d %H:%M:%S %z")
+                .to_string()),
+        ),
+        ("source", json!(OFFLINE_SOURCE)),
+        ("expires", json!("forever")),
+        ("reason", json!(reason)),
+    ]
+}
+
+fn matches_name(entry: &Value, name: &str) -> bool {
+    entry
+        .get("name")
+        .and_then(Value::as_str)
+        .is_some_and(|n| n.eq_ignore_ascii_case(name))
+}
+
+fn matches_player(entry: &Value, player: &ResolvedPlayer) -> bool {
+    let same_uuid = entry
+        .get("uuid")
+        .and_then(Value::as_str)
+        .and_then(hyphenate_uuid)
+        .is_some_and(|uuid| uuid.eq_ignore_ascii_case(&player.uuid));
+    same_uuid || matches_name(entry, &player.name)
+}
+
+/// 更新已有条目的字段（保留其余字段），不存在时追加新条目。
+fn upsert_entry(list: &mut Vec<Value>, player: &ResolvedPlayer, fields: &[(&str, Value)]) {
+    let mut identity = vec![("uuid", json!(player.uuid)), ("name", json!(player.name))];
+    identity.extend(fields.iter().cloned());
+    match list.iter_mut().find(|entry| matches_player(entry, player)) {
+        Some(entry) => set_fields(entry, &identity),
+        None => {
+            let mut entry = json!({});
+            set_fields(&mut entry, &identity);
+            list.push(entry);
+        }
+    }
+}
+
+fn set_fields(entry: &mut Value, fields: &[(&str, Value)]) {
+    if let Some(map) = entry.as_object_mut() {
+        for (key, value) in fields {
+            map.insert(key.to_string(), value.clone());
+        }
+    }
+}
+
+fn remove_matching(
+    server_dir: &Path,
+    filename: &str,
+    matches: impl Fn(&Value) -> bool,
+) -> Result<bool, String> {
+    let mut list = read_json_values(server_dir, filename)?;
+    let before = list.len();
+    list.retain(|entry| !matches(entry));
+    if list.len() == before {
+        return Ok(false);
+    }
+    write_json_values(server_dir, filename, &list)?;
+    Ok(true)
+}
+
+fn read_json_values(server_dir: &Path, filename: &str) -> Result<Vec<Value>, String> {
+    let path = server_dir.join(filename);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content =
+        std::fs::read_to_string(&path).map_err(|e| format!("读取{}失败: {}", filename, e))?;
+    if content.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    serde_json::from_str(content.trim()).map_err(|e| format!("解析{}失败: {}", filename, e))
+}
+
+/// 先写临时文件再替换，避免写入中途失败留下损坏的名单。
+fn write_json_values(server_dir: &Path, filename: &str, list: &[Value]) -> Result<(), String> {
+    let path = server_dir.join(filename);
+    let content =
+        serde_json::to_string_pretty(list).map_err(|e| format!("序列化{}失败: {}", filename, e))?;
+    let tmp_path = path.with_extension("json.tmp");
+    std::fs::write(&tmp_path, content).map_err(|e| format!("写入{}失败: {}", filename, e))?;
+    std::fs::rename(&tmp_path, &path).map_err(|e| {
+        let _ = std::fs::remove_file(&tmp_path);
+        format!("写入{}失败: {}", filename, e)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_utils::temp_dir;
+
+    fn player(name: &str) -> ResolvedPlayer {
+        OfflineUuidResolver.resolve(name).unwrap()
+    }
+
+    #[test]
+    fn computes_offline_uuids() {
+        assert_eq!(offline_uuid("Notch"), "b50ad385-829d-3141-a216-7e7d7539ba7f");
+        assert_eq!(offline_uuid("jeb_"), "a762f560-4fce-3236-812a-b80efff0b62b");
+    }
+
+    #[test]
+    fn usercache_is_consulted_before_fallback() {
+        let dir = temp_dir("usercache");
+        std::fs::write(
+            dir.join("usercache.json"),
+            r#"[{"name":"Notch","uuid":"069a79f4-44e9-4726-a5be-fca90e38aaf5","expiresOn":"x"}]"#,
+        )
+        .unwrap();
+        let resolver = UsercacheResolver::new(&dir, Box::new(OfflineUuidResolver));
+
+        let cached = resolver.resolve("notch").unwrap();
+        assert_eq!(cached.name, "Notch");
+        assert_eq!(cached.uuid, "069a79f4-44e9-4726-a5be-fca90e38aaf5");
+        assert_eq!(resolver.resolve("jeb_").unwrap().uuid, offline_uuid("jeb_"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn edits_lists_and_preserves_unknown_fields() {
+        let dir = temp_dir("lists");
+        std::fs::write(
+            dir.join(OPS_FILE),
+            format!(
+                r#"[{{"uuid":"{}","name":"Notch","level":2,"bypassesPlayerLimit":false,"note":"keep"}}]"#,
+                offline_uuid("Notch")
+            ),
+        )
+        .unwrap();
+
+        add_op_entry(&dir, &player("Notch"), 4, true).unwrap();
+        add_op_entry(&dir, &player("jeb_"), 4, false).unwrap();
+        let raw = read_json_values(&dir, OPS_FILE).unwrap();
+        assert_eq!(raw.len(), 2);
+        assert_eq!(raw[0]["note"], "keep");
+        assert_eq!(raw[0]["level"], 4);
+        assert_eq!(raw[0]["bypassesPlayerLimit"], true);
+        let ops = read_ops(dir.to_str().unwrap()).unwrap();
+        assert_eq!(ops[1].name, "jeb_");
+        assert_eq!(ops[1].uuid, offline_uuid("jeb_"));
+
+        assert!(remove_op_entry(&dir, "NOTCH").unwrap());
+        assert!(!remove_op_entry(&dir, "Notch").unwrap());
+
+        assert!(add_whitelist_entry(&dir, &player("Notch")).unwrap());
+        assert!(!add_whitelist_entry(&dir, &player("notch")).unwrap());
+        assert_eq!(read_whitelist(dir.to_str().unwrap()).unwrap().len(), 1);
+        assert!(remove_whitelist_entry(&dir, "notch").unwrap());
+        assert!(read_whitelist(dir.to_str().unwrap()).unwrap().is_empty());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn writes_player_and_ip_bans() {
+        let dir = temp_dir("bans");
+
+        add_ban_entry(&dir, &player("Notch"), "griefing").unwrap();
+        let bans = read_banned_players(dir.to_str().unwrap()).unwrap();
+        assert_eq!(bans.len(), 1);
+        assert_eq!(bans[0].reason, "griefing");
+        assert_eq!(bans[0].expires, "forever");
+        assert_eq!(bans[0].source, OFFLINE_SOURCE);
+        assert!(remove_ban_entry(&dir, "notch").unwrap());
+
+        add_ip_ban_entry(&dir, "10.0.0.1", "").unwrap();
+        add_ip_ban_entry(&dir, "10.0.0.1", "spam").unwrap();
+        let ips = read_banned_ips(dir.to_str().unwrap()).unwrap();
+        assert_eq!(ips.len(), 1);
+        assert_eq!(ips[0].ip, "10.0.0.1");
+        assert_eq!(ips[0].reason, "spam");
+        assert!(remove_ip_ban_entry(&dir, "10.0.0.1").unwrap());
+        assert!(read_banned_ips(dir.to_str().unwrap()).unwrap().is_empty());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,594 @@
+//! 在线玩家追踪与会话历史。
+//!
+//! 服务器输出读取线程（`log_pipeline::spawn_server_output_reader`）把每一行日志交给
+//! [`handle_log_line`]，这里识别加入 / 离开 / UUID 行，维护每个服务器的在线玩家列表，
+//! 并把每次会话写入服务器目录下的 SQLite（`player_sessions.db`）。
+//!
+//! 支持的日志格式：
+//! - 原版 / Paper / Forge：`UUID of player X is ...`、`X[/ip:port] logged in with entity id ...`、
+//!   `X joined the game`、`X lost connection: 原因`、`X left the game`
+//! - BungeeCord / Waterfall：`[X] <-> InitialHandler has connected`、`[X] -> UpstreamBridge has disconnected`
+//! - Velocity：`[connected player] X (/ip:port) has connected` / `has disconnected`
+//!
+//! 只匹配日志头之后的消息正文并要求整行匹配，玩家聊天内容无法伪造加入 / 离开事件。
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+use super::manager::current_timestamp_secs;
+
+///此处常量见 utils/constants.rs
+use crate::utils::constants::PLAYER_SESSIONS_DB_FILE;
+
+const NAME: &str = r"[A-Za-z0-9_.*]{1,32}";
+
+static HEADER_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\d{2}:\d{2}:\d{2} \[[A-Z]+\]:? ").unwrap());
+static UUID_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"^UUID of player (?P<name>{NAME}) is (?P<uuid>[0-9a-fA-F]{{8}}-?[0-9a-fA-F]{{4}}-?[0-9a-fA-F]{{4}}-?[0-9a-fA-F]{{4}}-?[0-9a-fA-F]{{12}})$"
+    ))
+    .unwrap()
+});
+static LOGIN_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(r"^(?P<name>{NAME})\[(?P<ip>[^\]]+)\] logged in with entity id \d+"))
+        .unwrap()
+});
+static JOIN_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"^(?P<name>{NAME})(?: \(formerly known as {NAME}\))? joined the game$"
+    ))
+    .unwrap()
+});
+static LOST_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(r"^(?P<name>{NAME}) lost connection: (?P<reason>.*)$")).unwrap()
+});
+static LEFT_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(&format!(r"^(?P<name>{NAME}) left the game$")).unwrap());
+static BUNGEE_CONNECT_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"^\[(?:(?P<ip1>/[^|\]]+)\|)?(?P<name>{NAME})(?:\|(?P<ip2>/[^\]]+))?\] <-> InitialHandler has connected$"
+    ))
+    .unwrap()
+});
+static BUNGEE_DISCONNECT_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"^\[(?:/[^|\]]+\|)?(?P<name>{NAME})(?:\|/[^\]]+)?\] -> UpstreamBridge has disconnected$"
+    ))
+    .unwrap()
+});
+static VELOCITY_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"^\[connected player\] (?P<name>{NAME}) \((?P<ip>[^)]+)\) has (?P<action>connected|disconnected)$"
+    ))
+    .unwrap()
+});
+
+static TRACKERS: OnceLock<Mutex<HashMap<String, ServerPlayers>>> = OnceLock::new();
+
+/// 当前在线的玩家
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OnlinePlayer {
+    pub name: String,
+    pub uuid: Option<String>,
+    pub ip: Option<String>,
+    pub joined_at: u64,
+}
+
+/// 一次完整（或仍在进行）的游戏会话
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PlayerSession {
+    pub id: i64,
+    pub player: String,
+    pub uuid: Option<String>,
+    pub ip: Option<String>,
+    pub joined_at: u64,
+    pub left_at: Option<u64>,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum PlayerLogEvent {
+    Uuid {
+        name: String,
+        uuid: String,
+    },
+    Login {
+        name: String,
+        ip: Option<String>,
+    },
+    Join {
+        name: String,
+    },
+    Leave {
+        name: String,
+        reason: Option<String>,
+    },
+}
+
+/// 去掉日志头（`[时间] [线程/级别]: `、`[时间 级别]: `、`时间 [级别] `），返回消息正文。
+fn message_body(line: &str) -> &str {
+    if let Some(pos) = line.find("]: ") {
+        return &line[pos + 3..];
+    }
+    match HEADER_RE.find(line) {
+        Some(m) => &line[m.end()..],
+        None => line,
+    }
+}
+
+/// `/127.0.0.1:25565` → `127.0.0.1`，IPv6 与无法识别的格式只去掉前导 `/`。
+fn normalize_ip(raw: &str) -> String {
+    let trimmed = raw.trim().trim_start_matches('/');
+    if let Some((host, port)) = trimmed.rsplit_once(':') {
+        if !host.contains(':') && port.chars().all(|c| c.is_ascii_digit()) {
+            return host.to_string();
+        }
+    }
+    trimmed.to_string()
+}
+
+fn parse_event(line: &str) -> Option<PlayerLogEvent> {
+    let body = message_body(line).trim_end();
+    let name = |caps: &regex::Captures| caps["name"].to_string();
+
+    if let Some(caps) = UUID_RE.captures(body) {
+        return Some(PlayerLogEvent::Uuid {
+            name: name(&caps),
+            uuid: caps["uuid"].to_string(),
+        });
+    }
+    if let Some(caps) = LOGIN_RE.captures(body) {
+        let ip = Some(normalize_ip(&caps["ip"])).filter(|ip| ip != "local");
+        return Some(PlayerLogEvent::Login { name: name(&caps), ip });
+    }
+    if let Some(caps) = JOIN_RE.captures(body) {
+        return Some(PlayerLogEvent::Join { name: name(&caps) });
+    }
+    if let Some(caps) = LOST_RE.captures(body) {
+        let reason = Some(caps["reason"].trim().to_string()).filter(|r| !r.is_empty());
+        return Some(PlayerLogEvent::Leave { name: name(&caps), reason });
+    }
+    if let Some(caps) = LEFT_RE.captures(body) {
+        return Some(PlayerLogEvent::Leave { name: name(&caps), reason: None });
+    }
+    if let Some(caps) = BUNGEE_CONNECT_RE.captures(body) {
+        let ip = caps
+            .name("ip1")
+            .or_else(|| caps.name("ip2"))
+            .map(|m| normalize_ip(m.as_str()));
+        return Some(PlayerLogEvent::Login { name: name(&caps), ip });
+    }
+    if let Some(caps) = BUNGEE_DISCONNECT_RE.captures(body) {
+        return Some(PlayerLogEvent::Leave { name: name(&caps), reason: None });
+    }
+    if let Some(caps) = VELOCITY_RE.captures(body) {
+        return Some(if &caps["action"] == "connected" {
+            PlayerLogEvent::Login {
+                name: name(&caps),
+                ip: Some(normalize_ip(&caps["ip"])),
+            }
+        } else {
+            PlayerLogEvent::Leave { name: name(&caps), reason: None }
+        });
+    }
+    None
+}
+
+/// 会话表读写，每次操作单独打开连接（加入 / 离开的频率很低）。
+struct SessionStore {
+    db_path: PathBuf,
+}
+
+impl SessionStore {
+    fn new(server_dir: &Path) -> Self {
+        SessionStore {
+            db_path: server_dir.join(PLAYER_SESSIONS_DB_FILE),
+        }
+    }
+
+    fn open(&self) -> Result<Connection, String> {
+        let conn = Connection::open(&self.db_path)
+            .map_err(|e| format!("打开玩家会话数据库失败 ({}): {}", self.db_path.display(), e))?;
+        conn.busy_timeout(Duration::from_millis(2000))
+            .map_err(|e| e.to_string())?;
+        conn.execute_batch(
+            r#"CREATE TABLE IF NOT EXISTS player_sessions (
+                 id INTEGER PRIMARY KEY AUTOINCREMENT,
+                 player TEXT NOT NULL,
+                 uuid TEXT,
+                 ip TEXT,
+                 joined_at INTEGER NOT NULL,
+                 left_at INTEGER,
+                 reason TEXT
+               );
+               CREATE INDEX IF NOT EXISTS idx_player_sessions_player
+                 ON player_sessions(player COLLATE NOCASE);"#,
+        )
+        .map_err(|e| e.to_string())?;
+        Ok(conn)
+    }
+
+    fn insert(&self, player: &OnlinePlayer) -> Result<i64, String> {
+        let conn = self.open()?;
+        conn.execute(
+            "INSERT INTO player_sessions (player, uuid, ip, joined_at) VALUES (?1, ?2, ?3, ?4)",
+            params![player.name, player.uuid, player.ip, player.joined_at as i64],
+        )
+        .map_err(|e| format!("写入玩家会话失败: {}", e))?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    fn update_identity(&self, session_id: i64, player: &OnlinePlayer) -> Result<(), String> {
+        self.open()?
+            .execute(
+                "UPDATE player_sessions SET uuid = ?1, ip = ?2 WHERE id = ?3",
+                params![player.uuid, player.ip, session_id],
+            )
+            .map(|_| ())
+            .map_err(|e| format!("更新玩家会话失败: {}", e))
+    }
+
+    fn close(&self, session_id: i64, left_at: u64, reason: Option<&str>) -> Result<(), String> {
+        self.open()?
+            .execute(
+                "UPDATE player_sessions SET left_at = ?1, reason = ?2 WHERE id = ?3",
+                params![left_at as i64, reason, session_id],
+            )
+            .map(|_| ())
+            .map_err(|e| format!("更新玩家会话失败: {}", e))
+    }
+
+    /// 结束上次运行遗留的未关闭会话（例如 SeaLantern 异常退出时）。
+    fn close_dangling(&self, left_at: u64, reason: &str) -> Result<(), String> {
+        if !self.db_path.exists() {
+            return Ok(());
+        }
+        self.open()?
+            .execute(
+                "UPDATE player_sessions SET left_at = ?1, reason = ?2 WHERE left_at IS NULL",
+                params![left_at as i64, reason],
+            )
+            .map(|_| ())
+            .map_err(|e| format!("更新玩家会话失败: {}", e))
+    }
+
+    fn query(&self, player: Option<&str>, limit: u32) -> Result<Vec<PlayerSession>, String> {
+        if !self.db_path.exists() {
+            return Ok(Vec::new());
+        }
+        let conn = self.open()?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, player, uuid, ip, joined_at, left_at, reason FROM player_sessions
+                 WHERE ?1 IS NULL OR player = ?1 COLLATE NOCASE
+                 ORDER BY joined_at DESC, id DESC LIMIT ?2",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(params![player, limit], |row| {
+                Ok(PlayerSession {
+                    id: row.get(0)?,
+                    player: row.get(1)?,
+                    uuid: row.get(2)?,
+                    ip: row.get(3)?,
+                    joined_at: row.get::<_, i64>(4)? as u64,
+                    left_at: row.get::<_, Option<i64>>(5)?.map(|v| v as u64),
+                    reason: row.get(6)?,
+                })
+            })
+            .map_err(|e| format!("读取玩家会话失败: {}", e))?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("读取玩家会话失败: {}", e))
+    }
+}
+
+struct OnlineEntry {
+    player: OnlinePlayer,
+    session_id: Option<i64>,
+}
+
+/// 单个服务器的追踪状态，键为小写玩家名
+#[derive(Default)]
+struct ServerPlayers {
+    online: BTreeMap<String, OnlineEntry>,
+    /// 认证线程先于登录输出 UUID，暂存到玩家真正上线
+    pending_uuids: HashMap<String, String>,
+}
+
+impl ServerPlayers {
+    fn apply(&mut self, event: PlayerLogEvent, now: u64, store: &SessionStore) {
+        match event {
+            PlayerLogEvent::Uuid { name, uuid } => {
+                let key = name.to_lowercase();
+                if let Some(entry) = self.online.get_mut(&key) {
+                    entry.player.uuid = Some(uuid);
+                    if let Some(id) = entry.session_id {
+                        let _ = store.update_identity(id, &entry.player);
+                    }
+                } else {
+                    self.pending_uuids.insert(key, uuid);
+                }
+            }
+            PlayerLogEvent::Login { name, ip } => self.open_session(name, ip, now, store),
+            PlayerLogEvent::Join { name } => self.open_session(name, None, now, store),
+            PlayerLogEvent::Leave { name, reason } => {
+                if let Some(entry) = self.online.remove(&name.to_lowercase()) {
+                    if let Some(id) = entry.session_id {
+                        let _ = store.close(id, now, reason.as_deref());
+                    }
+                }
+            }
+        }
+    }
+
+    fn open_session(&mut self, name: String, ip: Option<String>, now: u64, store: &SessionStore) {
+        let key = name.to_lowercase();
+        if let Some(entry) = self.online.get_mut(&key) {
+            // 原版先输出 logged in（带 IP）再输出 joined the game，只补全缺失的信息
+            if ip.is_some() && entry.player.ip.is_none() {
+                entry.player.ip = ip;
+                if let Some(id) = entry.session_id {
+                    let _ = store.update_identity(id, &entry.player);
+                }
+            }
+            return;
+        }
+
+        let player = OnlinePlayer {
+            name,
+            uuid: self.pending_uuids.remove(&key),
+            ip,
+            joined_at: now,
+        };
+        let session_id = store.insert(&player).ok();
+        self.online.insert(key, OnlineEntry { player, session_id });
+    }
+
+    fn close_all(&mut self, now: u64, reason: &str, store: &SessionStore) {
+        for (_, entry) in std::mem::take(&mut self.online) {
+            if let Some(id) = entry.session_id {
+                let _ = store.close(id, now, Some(reason));
+            }
+        }
+        self.pending_uuids.clear();
+    }
+
+    fn online_players(&self) -> Vec<OnlinePlayer> {
+        let mut players: Vec<OnlinePlayer> = self
+            .online
+            .values()
+            .map(|entry| entry.player.clone())
+            .collect();
+        players.sort_by_key(|p| p.joined_at);
+        players
+    }
+}
+
+fn trackers() -> &'static Mutex<HashMap<String, ServerPlayers>> {
+    TRACKERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn server_dir(server_id: &str) -> Result<PathBuf, String> {
+    crate::services::global::server_manager()
+        .get_server_list()
+        .into_iter()
+        .find(|server| server.id == server_id)
+        .map(|server| PathBuf::from(server.path))
+        .ok_or_else(|| format!("未找到服务器: {}", server_id))
+}
+
+/// 服务器启动前调用：清空在线列表，并结束上次运行遗留的未关闭会话。
+pub fn reset(server_id: &str) {
+    if let Ok(mut trackers) = trackers().lock() {
+        trackers.remove(server_id);
+    }
+    if let Ok(dir) = server_dir(server_id) {
+        let _ = SessionStore::new(&dir).close_dangling(current_timestamp_secs(), "会话未正常结束");
+    }
+}
+
+/// 处理一行服务器输出。
+pub fn handle_log_line(server_id: &str, line: &str) {
+    let Some(event) = parse_event(line) else {
+        return;
+    };
+    let Ok(dir) = server_dir(server_id) else {
+        return;
+    };
+    if let Ok(mut trackers) = trackers().lock() {
+        trackers.entry(server_id.to_string()).or_default().apply(
+            event,
+            current_timestamp_secs(),
+            &SessionStore::new(&dir),
+        );
+    }
+}
+
+/// 服务器输出结束（进程退出）时结束所有在线会话。
+pub fn close_all(server_id: &str, reason: &str) {
+    let Ok(dir) = server_dir(server_id) else {
+        return;
+    };
+    if let Ok(mut trackers) = trackers().lock() {
+        if let Some(players) = trackers.get_mut(server_id) {
+            players.close_all(current_timestamp_secs(), reason, &SessionStore::new(&dir));
+        }
+    }
+}
+
+pub fn get_online_players(server_id: &str) -> Vec<OnlinePlayer> {
+    trackers()
+        .lock()
+        .ok()
+        .and_then(|trackers| trackers.get(server_id).map(ServerPlayers::online_players))
+        .unwrap_or_default()
+}
+
+/// 按加入时间倒序返回会话，`player` 不区分大小写。
+pub fn get_player_sessions(
+    server_id: &str,
+    player: Option<&str>,
+    limit: u32,
+) -> Result<Vec<PlayerSession>, String> {
+    let dir = server_dir(server_id)?;
+    SessionStore::new(&dir).query(player, limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_utils::temp_dir;
+
+    fn temp_store(name: &str) -> (PathBuf, SessionStore) {
+        let dir = temp_dir(name);
+        let store = SessionStore::new(&dir);
+        (dir, store)
+    }
+
+    #[test]
+    fn parses_vanilla_paper_and_forge_lines() {
+        assert_eq!(
+            parse_event("[12:00:00] [User Authenticator #1/INFO]: UUID of player Steve is 069a79f4-44e9-4726-a5be-fca90e38aaf5"),
+            Some(PlayerLogEvent::Uuid {
+                name: "Steve".to_string(),
+                uuid: "069a79f4-44e9-4726-a5be-fca90e38aaf5".to_string()
+            })
+        );
+        assert_eq!(
+            parse_event("[12:00:01] [Server thread/INFO]: Steve[/127.0.0.1:54321] logged in with entity id 123 at (0.5, 64.0, 0.5)"),
+            Some(PlayerLogEvent::Login { name: "Steve".to_string(), ip: Some("127.0.0.1".to_string()) })
+        );
+        assert_eq!(
+            parse_event("[12:00:01 INFO]: Steve joined the game"),
+            Some(PlayerLogEvent::Join { name: "Steve".to_string() })
+        );
+        assert_eq!(
+            parse_event(
+                "[12:00:01] [Server thread/INFO] [minecraft/PlayerList]: Alex joined the game"
+            ),
+            Some(PlayerLogEvent::Join { name: "Alex".to_string() })
+        );
+        assert_eq!(
+            parse_event("[12:05:00] [Server thread/INFO]: Steve lost connection: Disconnected"),
+            Some(PlayerLogEvent::Leave {
+                name: "Steve".to_string(),
+                reason: Some("Disconnected".to_string())
+            })
+        );
+        assert_eq!(
+            parse_event("[12:05:00] [Server thread/INFO]: Steve left the game"),
+            Some(PlayerLogEvent::Leave { name: "Steve".to_string(), reason: None })
+        );
+    }
+
+    #[test]
+    fn parses_proxy_lines() {
+        assert_eq!(
+            parse_event("12:00:00 [INFO] [/10.0.0.2:50000|Steve] <-> InitialHandler has connected"),
+            Some(PlayerLogEvent::Login {
+                name: "Steve".to_string(),
+                ip: Some("10.0.0.2".to_string())
+            })
+        );
+        assert_eq!(
+            parse_event("[12:00:00 INFO]: [Steve] -> UpstreamBridge has disconnected"),
+            Some(PlayerLogEvent::Leave { name: "Steve".to_string(), reason: None })
+        );
+        assert_eq!(
+            parse_event("[12:00:00 INFO]: [connected player] Alex (/10.0.0.3:50001) has connected"),
+            Some(PlayerLogEvent::Login {
+                name: "Alex".to_string(),
+                ip: Some("10.0.0.3".to_string())
+            })
+        );
+        assert_eq!(
+            parse_event(
+                "[12:00:00 INFO]: [connected player] Alex (/10.0.0.3:50001) has disconnected"
+            ),
+            Some(PlayerLogEvent::Leave { name: "Alex".to_string(), reason: None })
+        );
+    }
+
+    #[test]
+    fn chat_cannot_spoof_events() {
+        assert_eq!(
+            parse_event("[12:00:00] [Server thread/INFO]: <Steve> Alex joined the game"),
+            None
+        );
+        assert_eq!(
+            parse_event("[12:00:00] [Server thread/INFO]: [Server] Alex left the game"),
+            None
+        );
+    }
+
+    #[test]
+    fn tracks_sessions_through_join_and_leave() {
+        let (dir, store) = temp_store("track");
+        let mut players = ServerPlayers::default();
+        let lines = [
+            "[12:00:00] [User Authenticator #1/INFO]: UUID of player Steve is 069a79f4-44e9-4726-a5be-fca90e38aaf5",
+            "[12:00:01] [Server thread/INFO]: Steve[/127.0.0.1:54321] logged in with entity id 1 at (0.0, 64.0, 0.0)",
+            "[12:00:01] [Server thread/INFO]: Steve joined the game",
+            "[12:00:02] [Server thread/INFO]: Alex joined the game",
+        ];
+        for line in lines {
+            players.apply(parse_event(line).unwrap(), 100, &store);
+        }
+
+        let online = players.online_players();
+        assert_eq!(online.len(), 2);
+        let steve = online.iter().find(|p| p.name == "Steve").unwrap();
+        assert_eq!(steve.ip.as_deref(), Some("127.0.0.1"));
+        assert_eq!(steve.uuid.as_deref(), Some("069a79f4-44e9-4726-a5be-fca90e38aaf5"));
+
+        players.apply(
+            parse_event("[12:10:00] [Server thread/INFO]: Steve lost connection: Timed out")
+                .unwrap(),
+            200,
+            &store,
+        );
+        players.apply(
+            parse_event("[12:10:00] [Server thread/INFO]: Steve left the game").unwrap(),
+            201,
+            &store,
+        );
+        assert_eq!(players.online_players().len(), 1);
+
+        players.close_all(300, "服务器已停止", &store);
+        assert!(players.online_players().is_empty());
+
+        let sessions = store.query(None, 10).unwrap();
+        assert_eq!(sessions.len(), 2);
+        let steve = store.query(Some("steve"), 10).unwrap().remove(0);
+        assert_eq!(steve.joined_at, 100);
+        assert_eq!(steve.left_at, Some(200));
+        assert_eq!(steve.reason.as_deref(), Some("Timed out"));
+        assert_eq!(steve.ip.as_deref(), Some("127.0.0.1"));
+        let alex = store.query(Some("Alex"), 10).unwrap().remove(0);
+        assert_eq!(alex.left_at, Some(300));
+        assert_eq!(alex.reason.as_deref(), Some("服务器已停止"));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn dangling_sessions_are_closed() {
+        let (dir, store) = temp_store("dangling");
+        let mut players = ServerPlayers::default();
+        players.apply(PlayerLogEvent::Join { name: "Steve".to_string() }, 100, &store);
+
+        store.close_dangling(500, "会话未正常结束").unwrap();
+        let session = store.query(None, 10).unwrap().remove(0);
+        assert_eq!(session.left_at, Some(500));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
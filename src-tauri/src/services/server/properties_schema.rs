@@ -0,0 +1,552 @@
+//! server.properties 各配置项的类型、取值范围与版本信息。
+//!
+//! 版本号按 Java 版正式版比较（`1.21.4`、`26.1` 等）；快照等无法识别的版本只做类型校验。
+//! 已移除或尚未引入的键只给出警告，未知键可能来自服务端核心或模组，同样只警告不拦截。
+
+use std::collections::HashMap;
+
+use crate::models::config::{PropertyIssue, PropertyIssueSeverity};
+
+#[derive(Debug, Clone, Copy)]
+pub enum PropertyKind {
+    Boolean,
+    Integer {
+        min: i64,
+        max: i64,
+    },
+    /// 可选值；`numeric` 表示同时接受旧式的数字 ID（如 `difficulty=2`）
+    Select {
+        options: &'static [&'static str],
+        numeric: bool,
+    },
+    Text,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct PropertySchema {
+    pub key: &'static str,
+    pub description: &'static str,
+    pub kind: PropertyKind,
+    pub default_value: &'static str,
+    pub category: &'static str,
+    /// 引入该键的版本
+    pub since: Option<&'static str>,
+    /// 移除该键的版本
+    pub removed_in: Option<&'static str>,
+}
+
+impl PropertyKind {
+    /// 前端使用的类型名
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            PropertyKind::Boolean => "boolean",
+            PropertyKind::Integer { .. } => "number",
+            PropertyKind::Select { .. } => "select",
+            PropertyKind::Text => "string",
+        }
+    }
+}
+
+const INT_MAX: i64 = i32::MAX as i64;
+const PORT: PropertyKind = PropertyKind::Integer { min: 1, max: 65535 };
+
+const fn schema(
+    key: &'static str,
+    description: &'static str,
+    kind: PropertyKind,
+    default_value: &'static str,
+    category: &'static str,
+) -> PropertySchema {
+    PropertySchema {
+        key,
+        description,
+        kind,
+        default_value,
+        category,
+        since: None,
+        removed_in: None,
+    }
+}
+
+impl PropertySchema {
+    const fn since(mut self, version: &'static str) -> Self {
+        self.since = Some(version);
+        self
+    }
+
+    const fn removed_in(mut self, version: &'static str) -> Self {
+        self.removed_in = Some(version);
+        self
+    }
+}
+
+const SCHEMAS: &[PropertySchema] = &[
+    schema(
+        "accepts-transfers",
+        "允许接收其他服务器转移来的玩家",
+        PropertyKind::Boolean,
+        "false",
+        "network",
+    )
+    .since("1.20.5"),
+    schema("allow-flight", "允许飞行", PropertyKind::Boolean, "false", "game"),
+    schema("allow-nether", "允许下界", PropertyKind::Boolean, "true", "world").removed_in("1.21.9"),
+    schema(
+        "announce-player-achievements",
+        "广播玩家成就",
+        PropertyKind::Boolean,
+        "true",
+        "display",
+    )
+    .removed_in("1.12"),
+    schema(
+        "broadcast-console-to-ops",
+        "向管理员广播控制台命令输出",
+        PropertyKind::Boolean,
+        "true",
+        "other",
+    ),
+    schema(
+        "broadcast-rcon-to-ops",
+        "向管理员广播 RCON 命令输出",
+        PropertyKind::Boolean,
+        "true",
+        "other",
+    ),
+    schema(
+        "bug-report-link",
+        "断开连接界面的问题反馈链接",
+        PropertyKind::Text,
+        "",
+        "display",
+    )
+    .since("1.21"),
+    schema(
+        "difficulty",
+        "游戏难度",
+        PropertyKind::Select {
+            options: &["peaceful", "easy", "normal", "hard"],
+            numeric: true,
+        },
+        "easy",
+        "game",
+    ),
+    schema("enable-command-block", "启用命令方块", PropertyKind::Boolean, "false", "game"),
+    schema(
+        "enable-jmx-monitoring",
+        "启用 JMX 监控",
+        PropertyKind::Boolean,
+        "false",
+        "performance",
+    )
+    .since("1.16"),
+    schema("enable-query", "启用Query协议", PropertyKind::Boolean, "false", "network"),
+    schema("enable-rcon", "启用RCON远程控制", PropertyKind::Boolean, "false", "network"),
+    schema("enable-status", "启用服务器列表状态", PropertyKind::Boolean, "true", "network")
+        .since("1.16"),
+    schema(
+        "enforce-secure-profile",
+        "要求玩家使用 Mojang 签名的公钥",
+        PropertyKind::Boolean,
+        "true",
+        "player",
+    )
+    .since("1.19"),
+    schema("enforce-whitelist", "强制白名单", PropertyKind::Boolean, "false", "player")
+        .since("1.13"),
+    schema(
+        "entity-broadcast-range-percentage",
+        "实体同步距离百分比",
+        PropertyKind::Integer { min: 10, max: 1000 },
+        "100",
+        "performance",
+    )
+    .since("1.16"),
+    schema("force-gamemode", "强制游戏模式", PropertyKind::Boolean, "false", "game"),
+    schema(
+        "function-permission-level",
+        "函数的默认权限等级",
+        PropertyKind::Integer { min: 1, max: 4 },
+        "2",
+        "game",
+    )
+    .since("1.14.4"),
+    schema(
+        "gamemode",
+        "默认游戏模式",
+        PropertyKind::Select {
+            options: &["survival", "creative", "adventure", "spectator"],
+            numeric: true,
+        },
+        "survival",
+        "game",
+    ),
+    schema("generate-structures", "生成结构", PropertyKind::Boolean, "true", "world"),
+    schema("generator-settings", "自定义世界生成设置", PropertyKind::Text, "{}", "world"),
+    schema("hardcore", "极限模式", PropertyKind::Boolean, "false", "game"),
+    schema(
+        "hide-online-players",
+        "在服务器列表中隐藏在线玩家",
+        PropertyKind::Boolean,
+        "false",
+        "display",
+    )
+    .since("1.18"),
+    schema(
+        "initial-disabled-packs",
+        "创建世界时禁用的数据包",
+        PropertyKind::Text,
+        "",
+        "world",
+    )
+    .since("1.19.3"),
+    schema(
+        "initial-enabled-packs",
+        "创建世界时启用的数据包",
+        PropertyKind::Text,
+        "vanilla",
+        "world",
+    )
+    .since("1.19.3"),
+    schema("level-name", "世界名称", PropertyKind::Text, "world", "world"),
+    schema("level-seed", "世界种子", PropertyKind::Text, "", "world"),
+    schema("level-type", "世界类型", PropertyKind::Text, "minecraft:normal", "world"),
+    schema("log-ips", "在日志中记录玩家 IP", PropertyKind::Boolean, "true", "network")
+        .since("1.20.2"),
+    schema(
+        "max-build-height",
+        "最大建筑高度",
+        PropertyKind::Integer { min: 1, max: 256 },
+        "256",
+        "world",
+    )
+    .removed_in("1.17"),
+    schema(
+        "max-chained-neighbor-updates",
+        "连锁方块更新上限，负数为不限制",
+        PropertyKind::Integer { min: -1, max: INT_MAX },
+        "1000000",
+        "performance",
+    )
+    .since("1.19"),
+    schema(
+        "max-players",
+        "最大玩家数",
+        PropertyKind::Integer { min: 0, max: INT_MAX },
+        "20",
+        "player",
+    ),
+    schema(
+        "max-tick-time",
+        "最大tick时间(ms)，-1为禁用",
+        PropertyKind::Integer { min: -1, max: i64::MAX },
+        "60000",
+        "performance",
+    ),
+    schema(
+        "max-world-size",
+        "世界边界半径",
+        PropertyKind::Integer { min: 1, max: 29999984 },
+        "29999984",
+        "world",
+    ),
+    schema("motd", "服务器描述(MOTD)", PropertyKind::Text, "A Minecraft Server", "display"),
+    schema(
+        "network-compression-threshold",
+        "网络压缩阈值",
+        PropertyKind::Integer { min: -1, max: INT_MAX },
+        "256",
+        "performance",
+    ),
+    schema("online-mode", "正版验证", PropertyKind::Boolean, "true", "player"),
+    schema(
+        "op-permission-level",
+        "管理员默认权限等级",
+        PropertyKind::Integer { min: 0, max: 4 },
+        "4",
+        "player",
+    ),
+    schema(
+        "pause-when-empty-seconds",
+        "无玩家多少秒后暂停服务器，0 或负数为不暂停",
+        PropertyKind::Integer { min: i32::MIN as i64, max: INT_MAX },
+        "60",
+        "performance",
+    )
+    .since("1.21.2"),
+    schema(
+        "player-idle-timeout",
+        "挂机踢出时间(分钟)，0为禁用",
+        PropertyKind::Integer { min: 0, max: INT_MAX },
+        "0",
+        "player",
+    ),
+    schema(
+        "prevent-proxy-connections",
+        "阻止通过代理连接",
+        PropertyKind::Boolean,
+        "false",
+        "network",
+    ),
+    schema("previews-chat", "启用聊天预览", PropertyKind::Boolean, "false", "display")
+        .since("1.19")
+        .removed_in("1.19.3"),
+    schema("pvp", "允许PVP", PropertyKind::Boolean, "true", "game").removed_in("1.21.9"),
+    schema("query.port", "Query 端口", PORT, "25565", "network"),
+    schema(
+        "rate-limit",
+        "每秒数据包上限，0为不限制",
+        PropertyKind::Integer { min: 0, max: INT_MAX },
+        "0",
+        "network",
+    ),
+    schema("rcon.password", "RCON 密码", PropertyKind::Text, "", "network"),
+    schema("rcon.port", "RCON 端口", PORT, "25575", "network"),
+    schema(
+        "region-file-compression",
+        "区域文件压缩算法",
+        PropertyKind::Select {
+            options: &["deflate", "lz4", "none"],
+            numeric: false,
+        },
+        "deflate",
+        "performance",
+    )
+    .since("1.20.5"),
+    schema(
+        "require-resource-pack",
+        "强制使用资源包",
+        PropertyKind::Boolean,
+        "false",
+        "display",
+    )
+    .since("1.17"),
+    schema("resource-pack", "资源包下载地址", PropertyKind::Text, "", "display"),
+    schema("resource-pack-id", "资源包 UUID", PropertyKind::Text, "", "display").since("1.20.3"),
+    schema("resource-pack-prompt", "资源包提示信息", PropertyKind::Text, "", "display")
+        .since("1.17"),
+    schema("resource-pack-sha1", "资源包 SHA-1 校验值", PropertyKind::Text, "", "display"),
+    schema("server-ip", "服务器绑定IP，留空表示所有", PropertyKind::Text, "", "network"),
+    schema("server-port", "服务器端口", PORT, "25565", "network"),
+    schema(
+        "simulation-distance",
+        "模拟距离",
+        PropertyKind::Integer { min: 3, max: 32 },
+        "10",
+        "performance",
+    )
+    .since("1.18"),
+    schema(
+        "snooper-enabled",
+        "向 Mojang 发送统计数据",
+        PropertyKind::Boolean,
+        "true",
+        "other",
+    )
+    .removed_in("1.18"),
+    schema("spawn-animals", "生成动物", PropertyKind::Boolean, "true", "world")
+        .removed_in("1.21.2"),
+    schema("spawn-monsters", "生成怪物", PropertyKind::Boolean, "true", "world")
+        .removed_in("1.21.9"),
+    schema("spawn-npcs", "生成NPC", PropertyKind::Boolean, "true", "world").removed_in("1.21.2"),
+    schema(
+        "spawn-protection",
+        "出生点保护半径",
+        PropertyKind::Integer { min: 0, max: INT_MAX },
+        "16",
+        "world",
+    ),
+    schema(
+        "sync-chunk-writes",
+        "同步区块写入",
+        PropertyKind::Boolean,
+        "true",
+        "performance",
+    )
+    .since("1.16"),
+    schema("text-filtering-config", "文本过滤配置", PropertyKind::Text, "", "other")
+        .since("1.16.4"),
+    schema(
+        "use-native-transport",
+        "使用 Linux 原生网络传输",
+        PropertyKind::Boolean,
+        "true",
+        "performance",
+    ),
+    schema(
+        "view-distance",
+        "视距",
+        PropertyKind::Integer { min: 2, max: 32 },
+        "10",
+        "performance",
+    ),
+    schema("white-list", "启用白名单", PropertyKind::Boolean, "false", "player"),
+];
+
+pub fn property_schema(key: &str) -> Option<&'static PropertySchema> {
+    SCHEMAS.iter().find(|schema| schema.key == key)
+}
+
+/// 解析正式版版本号：`1.x[.y]` 返回 `(x, y)`，按年份命名的 `26.1` 等返回 `(26, 1)`；
+/// 新版本号的首段大于旧版本号的次版本号，可以直接比较。
+fn parse_version(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.trim().split('.');
+    let major = parts.next()?.parse::<u32>().ok()?;
+    let mut number = || match parts.next() {
+        Some(part) => part.parse::<u32>().ok(),
+        None => Some(0),
+    };
+    let parsed = if major == 1 {
+        (number()?, number()?)
+    } else {
+        (major, number()?)
+    };
+    parts.next().is_none().then_some(parsed)
+}
+
+/// 校验配置项；`mc_version` 为空或无法识别时跳过版本相关检查。结果按键名排序。
+pub fn validate_properties(
+    values: &HashMap<String, String>,
+    mc_version: Option<&str>,
+) -> Vec<PropertyIssue> {
+    let version = mc_version.and_then(|v| parse_version(v).map(|parsed| (v.trim(), parsed)));
+    let mut keys: Vec<&String> = values.keys().collect();
+    keys.sort();
+
+    let mut issues = Vec::new();
+    for key in keys {
+        let value = values[key].trim();
+        let issue =
+            |severity, message: String| PropertyIssue { key: key.clone(), severity, message };
+
+        let Some(schema) = property_schema(key) else {
+            issues.push(issue(
+                PropertyIssueSeverity::Warning,
+                "未知的配置项，可能来自服务端核心或模组，将原样写入".to_string(),
+            ));
+            continue;
+        };
+
+        if let Some((name, current)) = version {
+            if let Some(since) = schema.since.filter(|v| parse_version(v) > Some(current)) {
+                issues.push(issue(
+                    PropertyIssueSeverity::Warning,
+                    format!("该配置项自 {} 起才支持，{} 会忽略它", since, name),
+                ));
+            }
+            if let Some(removed) = schema
+                .removed_in
+                .filter(|v| parse_version(v) <= Some(current))
+            {
+                issues.push(issue(
+                    PropertyIssueSeverity::Warning,
+                    format!("该配置项已在 {} 移除，{} 会忽略它", removed, name),
+                ));
+            }
+        }
+
+        match schema.kind {
+            PropertyKind::Boolean => {
+                if !value.eq_ignore_ascii_case("true") && !value.eq_ignore_ascii_case("false") {
+                    issues.push(issue(
+                        PropertyIssueSeverity::Error,
+                        format!("需要 true 或 false，实际为 \"{}\"", value),
+                    ));
+                }
+            }
+            PropertyKind::Integer { min, max } => match value.parse::<i64>() {
+                Ok(number) if number < min || number > max => issues.push(issue(
+                    PropertyIssueSeverity::Error,
+                    format!("取值范围为 {} ~ {}，实际为 {}", min, max, number),
+                )),
+                Ok(_) => {}
+                Err(_) => issues.push(issue(
+                    PropertyIssueSeverity::Error,
+                    format!("需要整数，实际为 \"{}\"", value),
+                )),
+            },
+            PropertyKind::Select { options, numeric } => {
+                if options.contains(&value) {
+                    continue;
+                }
+                match value.parse::<usize>().ok().and_then(|id| options.get(id)) {
+                    Some(name) if numeric => issues.push(issue(
+                        PropertyIssueSeverity::Warning,
+                        format!("数字写法已过时，建议改为 {}", name),
+                    )),
+                    _ => issues.push(issue(
+                        PropertyIssueSeverity::Error,
+                        format!("可选值为 {}，实际为 \"{}\"", options.join(" / "), value),
+                    )),
+                }
+            }
+            PropertyKind::Text => {}
+        }
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validate(pairs: &[(&str, &str)], version: Option<&str>) -> Vec<(String, bool)> {
+        let values = pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        validate_properties(&values, version)
+            .into_iter()
+            .map(|issue| (issue.key, issue.severity == PropertyIssueSeverity::Error))
+            .collect()
+    }
+
+    #[test]
+    fn parses_release_versions() {
+        assert_eq!(parse_version("1.20.4"), Some((20, 4)));
+        assert_eq!(parse_version("1.21"), Some((21, 0)));
+        assert_eq!(parse_version("26.1"), Some((26, 1)));
+        assert!(parse_version("26.1") > parse_version("1.21.10"));
+        assert_eq!(parse_version("24w14a"), None);
+        assert_eq!(parse_version("1.21-pre1"), None);
+    }
+
+    #[test]
+    fn reports_type_range_and_version_issues() {
+        let values = [
+            ("difficulty", "extreme"),
+            ("gamemode", "1"),
+            ("max-players", "-5"),
+            ("online-mode", "yes"),
+            ("view-distance", "12"),
+            ("motd", "Hello"),
+        ];
+        assert_eq!(
+            validate(&values, Some("1.20.4")),
+            vec![
+                ("difficulty".to_string(), true),
+                ("gamemode".to_string(), false),
+                ("max-players".to_string(), true),
+                ("online-mode".to_string(), true),
+            ]
+        );
+
+        let versioned = [
+            ("simulation-distance", "10"),
+            ("snooper-enabled", "true"),
+            ("custom-plugin-key", "1"),
+        ];
+        assert_eq!(
+            validate(&versioned, Some("1.16.5")),
+            vec![
+                ("custom-plugin-key".to_string(), false),
+                ("simulation-distance".to_string(), false),
+            ]
+        );
+        assert_eq!(
+            validate(&versioned, Some("1.20.1")),
+            vec![("custom-plugin-key".to_string(), false), ("snooper-enabled".to_string(), false),]
+        );
+        assert_eq!(validate(&versioned, None), vec![("custom-plugin-key".to_string(), false)]);
+    }
+}
@@ -0,0 +1,321 @@
+//! Source RCON 客户端。
+//!
+//! 用于控制不是由 SeaLantern 启动的服务器（例如通过 `add_existing_server` 导入、
+//! 由外部脚本运行的服务端）：此时没有子进程的 stdin 可写，只能通过 RCON 下发命令。
+//!
+//! 数据包格式（小端）：`length:i32 | id:i32 | type:i32 | body | 0x00 | 0x00`。
+//! 较长的命令输出会被拆成多个响应包，因此每条命令后额外发送一个空的
+//! `SERVERDATA_RESPONSE_VALUE` 包作为哨兵，收到哨兵的回应即表示输出已读完。
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::path::Path;
+use std::time::Duration;
+
+///此处常量见 utils/constants.rs
+use crate::utils::constants::{RCON_CONNECT_TIMEOUT_MS, RCON_READ_TIMEOUT_MS};
+
+const PACKET_AUTH: i32 = 3;
+const PACKET_AUTH_RESPONSE: i32 = 2;
+const PACKET_EXEC_COMMAND: i32 = 2;
+const PACKET_RESPONSE_VALUE: i32 = 0;
+
+/// 原版服务端接受的最大命令长度（字节）
+const MAX_COMMAND_LEN: usize = 1446;
+/// 单个数据包长度上限，防止异常数据导致分配过大内存
+const MAX_PACKET_LEN: usize = 64 * 1024;
+const DEFAULT_RCON_PORT: u16 = 25575;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RconPacket {
+    pub id: i32,
+    pub kind: i32,
+    pub body: String,
+}
+
+impl RconPacket {
+    pub fn encode(&self) -> Vec<u8> {
+        let body = self.body.as_bytes();
+        let length = (body.len() + 10) as i32;
+        let mut buf = Vec::with_capacity(body.len() + 14);
+        buf.extend_from_slice(&length.to_le_bytes());
+        buf.extend_from_slice(&self.id.to_le_bytes());
+        buf.extend_from_slice(&self.kind.to_le_bytes());
+        buf.extend_from_slice(body);
+        buf.extend_from_slice(&[0, 0]);
+        buf
+    }
+
+    pub fn read_from(reader: &mut impl Read) -> Result<Self, String> {
+        let mut header = [0u8; 4];
+        reader
+            .read_exact(&mut header)
+            .map_err(|e| format!("读取 RCON 数据失败: {}", e))?;
+        let length = i32::from_le_bytes(header);
+        if length < 10 || length as usize > MAX_PACKET_LEN {
+            return Err(format!("RCON 数据包长度异常: {}", length));
+        }
+
+        let mut payload = vec![0u8; length as usize];
+        reader
+            .read_exact(&mut payload)
+            .map_err(|e| format!("读取 RCON 数据失败: {}", e))?;
+        let id = i32::from_le_bytes([payload[0], payload[1], payload[2], payload[3]]);
+        let kind = i32::from_le_bytes([payload[4], payload[5], payload[6], payload[7]]);
+        let body_bytes = &payload[8..payload.len() - 2];
+        Ok(RconPacket {
+            id,
+            kind,
+            body: String::from_utf8_lossy(body_bytes).to_string(),
+        })
+    }
+}
+
+/// 从 server.properties 读取的 RCON 连接信息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RconSettings {
+    pub host: String,
+    pub port: u16,
+    pub password: String,
+}
+
+impl RconSettings {
+    /// `enable-rcon=true` 且设置了密码时返回连接信息；`server-ip` 为空时连接本机。
+    pub fn from_properties(props: &HashMap<String, String>) -> Option<Self> {
+        let enabled = props
+            .get("enable-rcon")
+            .is_some_and(|value| value.trim().eq_ignore_ascii_case("true"));
+        let password = props.get("rcon.password").map(|value| value.trim())?;
+        if !enabled || password.is_empty() {
+            return None;
+        }
+
+        let port = props
+            .get("rcon.port")
+            .and_then(|value| value.trim().parse::<u16>().ok())
+            .unwrap_or(DEFAULT_RCON_PORT);
+        let host = props
+            .get("server-ip")
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty() && *value != "0.0.0.0")
+            .unwrap_or("127.0.0.1")
+            .to_string();
+
+        Some(RconSettings {
+            host,
+            port,
+            password: password.to_string(),
+        })
+    }
+
+    pub fn load(server_dir: &Path) -> Option<Self> {
+        let path = server_dir.join("server.properties");
+        let props = super::config::read_properties(path.to_str()?).ok()?;
+        Self::from_properties(&props)
+    }
+}
+
+pub struct RconClient {
+    stream: TcpStream,
+    next_id: i32,
+}
+
+impl RconClient {
+    /// 建立连接并完成认证。
+    pub fn connect(settings: &RconSettings) -> Result<Self, String> {
+        let addr: SocketAddr = (settings.host.as_str(), settings.port)
+            .to_socket_addrs()
+            .map_err(|e| format!("解析 RCON 地址失败: {}", e))?
+            .next()
+            .ok_or_else(|| format!("无法解析 RCON 地址: {}", settings.host))?;
+        let stream =
+            TcpStream::connect_timeout(&addr, Duration::from_millis(RCON_CONNECT_TIMEOUT_MS))
+                .map_err(|e| format!("连接 RCON 失败（{}）: {}", addr, e))?;
+        stream
+            .set_read_timeout(Some(Duration::from_millis(RCON_READ_TIMEOUT_MS)))
+            .map_err(|e| format!("设置 RCON 超时失败: {}", e))?;
+        stream
+            .set_write_timeout(Some(Duration::from_millis(RCON_READ_TIMEOUT_MS)))
+            .map_err(|e| format!("设置 RCON 超时失败: {}", e))?;
+
+        let mut client = RconClient { stream, next_id: 1 };
+        client.authenticate(&settings.password)?;
+        Ok(client)
+    }
+
+    fn authenticate(&mut self, password: &str) -> Result<(), String> {
+        let id = self.allocate_id();
+        self.send(id, PACKET_AUTH, password)?;
+        // Source 规范中服务端会先回一个空的 RESPONSE_VALUE，再回 AUTH_RESPONSE
+        loop {
+            let packet = RconPacket::read_from(&mut self.stream)?;
+            if packet.kind != PACKET_AUTH_RESPONSE {
+                continue;
+            }
+            if packet.id == -1 {
+                return Err("RCON 认证失败，请检查 rcon.password".to_string());
+            }
+            if packet.id != id {
+                return Err(format!("RCON 认证响应 id 不匹配: {}", packet.id));
+            }
+            return Ok(());
+        }
+    }
+
+    /// 执行命令并返回完整输出（多个响应包会被拼接）。
+    pub fn command(&mut self, command: &str) -> Result<String, String> {
+        if command.len() > MAX_COMMAND_LEN {
+            return Err(format!("命令过长（最多 {} 字节）", MAX_COMMAND_LEN));
+        }
+
+        let id = self.allocate_id();
+        let sentinel_id = self.allocate_id();
+        self.send(id, PACKET_EXEC_COMMAND, command)?;
+        self.send(sentinel_id, PACKET_RESPONSE_VALUE, "")?;
+
+        let mut output = String::new();
+        loop {
+            let packet = RconPacket::read_from(&mut self.stream)?;
+            if packet.id == sentinel_id {
+                return Ok(output);
+            }
+            if packet.id == -1 {
+                return Err("RCON 会话未认证".to_string());
+            }
+            if packet.id == id && packet.kind == PACKET_RESPONSE_VALUE {
+                output.push_str(&packet.body);
+            }
+        }
+    }
+
+    fn allocate_id(&mut self) -> i32 {
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1).max(1);
+        id
+    }
+
+    fn send(&mut self, id: i32, kind: i32, body: &str) -> Result<(), String> {
+        let packet = RconPacket { id, kind, body: body.to_string() };
+        self.stream
+            .write_all(&packet.encode())
+            .and_then(|_| self.stream.flush())
+            .map_err(|e| format!("发送 RCON 数据失败: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::thread::JoinHandle;
+
+    /// 简易 RCON 服务端：校验密码，按 1000 字节拆分命令回显，
+    /// 对非命令包按原版行为回复 "Unknown request"。
+    fn spawn_stand_in(password: &'static str) -> (u16, JoinHandle<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let handle = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut authed = false;
+            while let Ok(packet) = RconPacket::read_from(&mut stream) {
+                let replies = match packet.kind {
+                    PACKET_AUTH => {
+                        authed = packet.body == password;
+                        let id = if authed { packet.id } else { -1 };
+                        vec![RconPacket {
+                            id,
+                            kind: PACKET_AUTH_RESPONSE,
+                            body: String::new(),
+                        }]
+                    }
+                    PACKET_EXEC_COMMAND if authed => {
+                        let text = format!("echo:{}", packet.body);
+                        text.as_bytes()
+                            .chunks(1000)
+                            .map(|chunk| RconPacket {
+                                id: packet.id,
+                                kind: PACKET_RESPONSE_VALUE,
+                                body: String::from_utf8_lossy(chunk).to_string(),
+                            })
+                            .collect()
+                    }
+                    _ => vec![RconPacket {
+                        id: packet.id,
+                        kind: PACKET_RESPONSE_VALUE,
+                        body: format!("Unknown request {:x}", packet.kind),
+                    }],
+                };
+                for reply in replies {
+                    stream.write_all(&reply.encode()).unwrap();
+                }
+            }
+        });
+        (port, handle)
+    }
+
+    fn settings(port: u16, password: &str) -> RconSettings {
+        RconSettings {
+            host: "127.0.0.1".to_string(),
+            port,
+            password: password.to_string(),
+        }
+    }
+
+    #[test]
+    fn packet_round_trip() {
+        let packet = RconPacket {
+            id: 7,
+            kind: PACKET_EXEC_COMMAND,
+            body: "list".to_string(),
+        };
+        let bytes = packet.encode();
+        assert_eq!(&bytes[..4], &14i32.to_le_bytes());
+        let decoded = RconPacket::read_from(&mut bytes.as_slice()).unwrap();
+        assert_eq!(decoded, packet);
+    }
+
+    #[test]
+    fn rejects_oversized_packet_length() {
+        let mut bytes = (MAX_PACKET_LEN as i32 + 1).to_le_bytes().to_vec();
+        bytes.extend_from_slice(&[0; 16]);
+        assert!(RconPacket::read_from(&mut bytes.as_slice()).is_err());
+    }
+
+    #[test]
+    fn executes_command_and_joins_multi_packet_response() {
+        let (port, handle) = spawn_stand_in("secret");
+        let mut client = RconClient::connect(&settings(port, "secret")).unwrap();
+
+        assert_eq!(client.command("list").unwrap(), "echo:list");
+
+        let long = "x".repeat(1400);
+        assert_eq!(client.command(&long).unwrap(), format!("echo:{}", long));
+
+        drop(client);
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn wrong_password_is_rejected() {
+        let (port, handle) = spawn_stand_in("secret");
+        let err = RconClient::connect(&settings(port, "wrong")).err().unwrap();
+        assert!(err.contains("认证失败"));
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn settings_require_enabled_rcon_and_password() {
+        let mut props = HashMap::new();
+        props.insert("enable-rcon".to_string(), "true".to_string());
+        props.insert("rcon.port".to_string(), "25580".to_string());
+        assert_eq!(RconSettings::from_properties(&props), None);
+
+        props.insert("rcon.password".to_string(), "pw".to_string());
+        props.insert("server-ip".to_string(), "0.0.0.0".to_string());
+        assert_eq!(RconSettings::from_properties(&props), Some(settings(25580, "pw")));
+
+        props.insert("enable-rcon".to_string(), "false".to_string());
+        assert_eq!(RconSettings::from_properties(&props), None);
+    }
+}
@@ -0,0 +1,457 @@
+//! 服务器定时任务调度。
+//!
+//! 每个任务绑定一个服务器，按 cron 表达式或固定间隔触发启动/停止/重启/控制台命令等动作。
+//! 任务列表持久化在应用数据目录的 `SCHEDULED_JOBS_FILE` 中，应用重启后自动恢复；
+//! 应用未运行期间错过的触发不会补跑，而是顺延到下一个触发点。
+//!
+//! 调度线程每 `SCHEDULER_TICK_INTERVAL_MS` 检查一次到期任务，每个任务在独立线程中执行，
+//! 同一任务上一次执行尚未结束时不会重复触发。
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{Local, TimeZone};
+
+use crate::models::backup::BackupKind;
+use crate::models::scheduler::{
+    JobAction, JobRunResult, JobSchedule, ScheduledJob, ScheduledJobRequest,
+};
+use crate::services::global;
+
+use super::cron::CronExpression;
+use super::log_pipeline as server_log_pipeline;
+use super::manager::current_timestamp_secs;
+
+///此处常量见 utils/constants.rs
+use crate::utils::constants::{
+    SCHEDULED_JOBS_FILE, SCHEDULER_MIN_INTERVAL_SECS, SCHEDULER_TICK_INTERVAL_MS,
+};
+
+pub struct Scheduler {
+    jobs: Mutex<Vec<ScheduledJob>>,
+    /// 正在执行中的任务 ID
+    running: Mutex<HashSet<String>>,
+    data_dir: String,
+    started: AtomicBool,
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        let data_dir = crate::utils::path::get_or_create_app_data_dir();
+        let now = current_timestamp_secs();
+        let mut jobs = load_jobs(&data_dir);
+        for job in jobs.iter_mut() {
+            job.next_run_at = if job.enabled {
+                compute_next_run(&job.schedule, job.last_run_at.unwrap_or(job.created_at), now)
+            } else {
+                None
+            };
+        }
+
+        Scheduler {
+            jobs: Mutex::new(jobs),
+            running: Mutex::new(HashSet::new()),
+            data_dir,
+            started: AtomicBool::new(false),
+        }
+    }
+
+    /// 启动调度线程，重复调用只会启动一次。
+    pub fn start(&'static self) {
+        if self.started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        std::thread::spawn(move || loop {
+            std::thread::sleep(Duration::from_millis(SCHEDULER_TICK_INTERVAL_MS));
+            self.tick();
+        });
+    }
+
+    pub fn list_jobs(&self, server_id: Option<&str>) -> Result<Vec<ScheduledJob>, String> {
+        let jobs = self.lock_jobs()?;
+        Ok(jobs
+            .iter()
+            .filter(|job| server_id.map(|id| job.server_id == id).unwrap_or(true))
+            .cloned()
+            .collect())
+    }
+
+    pub fn create_job(&self, request: ScheduledJobRequest) -> Result<ScheduledJob, String> {
+        let name = validate_job_request(&request)?;
+        let now = current_timestamp_secs();
+        let job = ScheduledJob {
+            id: uuid::Uuid::new_v4().to_string(),
+            server_id: request.server_id,
+            name,
+            enabled: request.enabled,
+            next_run_at: if request.enabled {
+                compute_next_run(&request.schedule, now, now)
+            } else {
+                None
+            },
+            schedule: request.schedule,
+            action: request.action,
+            created_at: now,
+            last_run_at: None,
+            last_result: None,
+        };
+
+        let mut jobs = self.lock_jobs()?;
+        jobs.push(job.clone());
+        self.save(&jobs);
+        Ok(job)
+    }
+
+    pub fn update_job(
+        &self,
+        id: &str,
+        request: ScheduledJobRequest,
+    ) -> Result<ScheduledJob, String> {
+        let name = validate_job_request(&request)?;
+        let now = current_timestamp_secs();
+        let mut jobs = self.lock_jobs()?;
+        let job = jobs
+            .iter_mut()
+            .find(|job| job.id == id)
+            .ok_or_else(|| "未找到定时任务".to_string())?;
+
+        job.server_id = request.server_id;
+        job.name = name;
+        job.enabled = request.enabled;
+        job.schedule = request.schedule;
+        job.action = request.action;
+        job.next_run_at = if job.enabled {
+            compute_next_run(&job.schedule, now, now)
+        } else {
+            None
+        };
+
+        let updated = job.clone();
+        self.save(&jobs);
+        Ok(updated)
+    }
+
+    pub fn delete_job(&self, id: &str) -> Result<(), String> {
+        let mut jobs = self.lock_jobs()?;
+        let before = jobs.len();
+        jobs.retain(|job| job.id != id);
+        if jobs.len() == before {
+            return Err("未找到定时任务".to_string());
+        }
+        self.save(&jobs);
+        Ok(())
+    }
+
+    /// 删除服务器时一并清理它的定时任务。
+    pub fn remove_jobs_for_server(&self, server_id: &str) {
+        if let Ok(mut jobs) = self.lock_jobs() {
+            let before = jobs.len();
+            jobs.retain(|job| job.server_id != server_id);
+            if jobs.len() != before {
+                self.save(&jobs);
+            }
+        }
+    }
+
+    /// 立即执行一次任务（同步等待结果），不影响原有的触发计划。
+    pub fn run_job_now(&self, id: &str) -> Result<JobRunResult, String> {
+        let job = self
+            .lock_jobs()?
+            .iter()
+            .find(|job| job.id == id)
+            .cloned()
+            .ok_or_else(|| "未找到定时任务".to_string())?;
+
+        if !self.mark_running(&job.id) {
+            return Err("该定时任务正在执行中".to_string());
+        }
+        Ok(self.run_job(&job))
+    }
+
+    fn tick(&'static self) {
+        let now = current_timestamp_secs();
+        let due: Vec<ScheduledJob> = {
+            let mut jobs = match self.lock_jobs() {
+                Ok(jobs) => jobs,
+                Err(_) => return,
+            };
+            let mut due = Vec::new();
+            for job in jobs.iter_mut() {
+                let is_due = job.enabled && job.next_run_at.map(|at| at <= now).unwrap_or(false);
+                if !is_due {
+                    continue;
+                }
+                job.next_run_at = compute_next_run(&job.schedule, now, now);
+                if self.mark_running(&job.id) {
+                    due.push(job.clone());
+                }
+            }
+            due
+        };
+
+        for job in due {
+            std::thread::spawn(move || {
+                self.run_job(&job);
+            });
+        }
+    }
+
+    /// 执行任务并记录结果；调用前需已通过 `mark_running` 占用该任务。
+    fn run_job(&self, job: &ScheduledJob) -> JobRunResult {
+        let started_at = current_timestamp_secs();
+        let outcome = execute_action(&job.server_id, &job.action);
+        let finished_at = current_timestamp_secs();
+
+        let result = match outcome {
+            Ok(message) => JobRunResult {
+                success: true,
+                message,
+                started_at,
+                finished_at,
+            },
+            Err(message) => JobRunResult {
+                success: false,
+                message,
+                started_at,
+                finished_at,
+            },
+        };
+
+        let status = if result.success { "成功" } else { "失败" };
+        let _ = server_log_pipeline::append_sealantern_log(
+            &job.server_id,
+            &format!("[Sea Lantern] 定时任务「{}」执行{}: {}", job.name, status, result.message),
+        );
+
+        if let Ok(mut jobs) = self.lock_jobs() {
+            if let Some(stored) = jobs.iter_mut().find(|stored| stored.id == job.id) {
+                stored.last_run_at = Some(started_at);
+                stored.last_result = Some(result.clone());
+            }
+            self.save(&jobs);
+        }
+        if let Ok(mut running) = self.running.lock() {
+            running.remove(&job.id);
+        }
+
+        result
+    }
+
+    fn mark_running(&self, id: &str) -> bool {
+        self.running
+            .lock()
+            .map(|mut running| running.insert(id.to_string()))
+            .unwrap_or(false)
+    }
+
+    fn lock_jobs(&self) -> Result<std::sync::MutexGuard<'_, Vec<ScheduledJob>>, String> {
+        self.jobs
+            .lock()
+            .map_err(|_| "scheduled jobs lock poisoned".to_string())
+    }
+
+    fn save(&self, jobs: &[ScheduledJob]) {
+        save_jobs(&self.data_dir, jobs);
+    }
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn execute_action(server_id: &str, action: &JobAction) -> Result<String, String> {
+    let manager = global::server_manager();
+    let running = manager
+        .get_running_server_ids()
+        .iter()
+        .any(|id| id == server_id);
+    // 未由 SeaLantern 启动的服务端同样占用世界与端口，不能再启动一个
+    let online = || running || manager.is_server_online(server_id);
+
+    match action {
+        JobAction::Start => {
+            if online() {
+                return Ok("服务器已在运行，跳过启动".to_string());
+            }
+            manager.start_server(server_id)?;
+            Ok("服务器已启动".to_string())
+        }
+        JobAction::Stop { countdown } => {
+            if !running {
+                return Ok("服务器未运行，跳过停止".to_string());
+            }
+            manager.stop_server_with_countdown(server_id, countdown.as_ref())?;
+            Ok("服务器已停止".to_string())
+        }
+        JobAction::Restart { countdown } => {
+            if running {
+                manager.stop_server_with_countdown(server_id, countdown.as_ref())?;
+            } else if online() {
+                return Err("服务器不是由 SeaLantern 启动的，无法重启".to_string());
+            }
+            manager.start_server(server_id)?;
+            Ok("服务器已重启".to_string())
+        }
+        JobAction::Command { command } => {
+            // 未由 SeaLantern 启动的服务器会走 RCON，可直接拿到命令输出
+            match manager.send_command(server_id, command)? {
+                Some(response) if !response.trim().is_empty() => {
+                    Ok(format!("已发送命令: {}\n{}", command, response.trim_end()))
+                }
+                _ => Ok(format!("已发送命令: {}", command)),
+            }
+        }
+        JobAction::Backup { options } => {
+            let entry = global::backup_manager().create_backup(
+                server_id,
+                options.clone(),
+                BackupKind::Scheduled,
+            )?;
+            Ok(format!("已创建备份: {}", entry.file_name))
+        }
+    }
+}
+
+fn validate_job_request(request: &ScheduledJobRequest) -> Result<String, String> {
+    let name = request.name.trim();
+    if name.is_empty() {
+        return Err("定时任务名称不能为空".to_string());
+    }
+    if name.chars().count() > 64 {
+        return Err("定时任务名称不能超过64个字符".to_string());
+    }
+
+    let server_exists = global::server_manager()
+        .get_server_list()
+        .iter()
+        .any(|server| server.id == request.server_id);
+    if !server_exists {
+        return Err("未找到服务器".to_string());
+    }
+
+    validate_schedule(&request.schedule)?;
+
+    if let JobAction::Command { command } = &request.action {
+        if command.trim().is_empty() {
+            return Err("控制台命令不能为空".to_string());
+        }
+    }
+
+    Ok(name.to_string())
+}
+
+fn validate_schedule(schedule: &JobSchedule) -> Result<(), String> {
+    match schedule {
+        JobSchedule::Cron { expression } => CronExpression::parse(expression).map(|_| ()),
+        JobSchedule::Interval { seconds } => {
+            if *seconds < SCHEDULER_MIN_INTERVAL_SECS {
+                return Err(format!("执行间隔不能小于 {} 秒", SCHEDULER_MIN_INTERVAL_SECS));
+            }
+            Ok(())
+        }
+    }
+}
+
+/// 计算严格晚于 `now` 的下一次触发时间（Unix 秒）。
+///
+/// 固定间隔任务以 `anchor`（创建时间或上次执行时间）为基准对齐，错过的触发直接跳过。
+fn compute_next_run(schedule: &JobSchedule, anchor: u64, now: u64) -> Option<u64> {
+    match schedule {
+        JobSchedule::Interval { seconds } => {
+            let seconds = (*seconds).max(1);
+            let mut next = anchor.saturating_add(seconds);
+            if next <= now {
+                let missed = (now - next) / seconds + 1;
+                next = next.saturating_add(missed.saturating_mul(seconds));
+            }
+            Some(next)
+        }
+        JobSchedule::Cron { expression } => {
+            let cron = CronExpression::parse(expression).ok()?;
+            let mut after = Local.timestamp_opt(now as i64, 0).single()?.naive_local();
+            // 夏令时切换会让部分本地时间不存在或重复，最多顺延几次
+            for _ in 0..4 {
+                let next = cron.next_after(after)?;
+                if let Some(local) = Local.from_local_datetime(&next).earliest() {
+                    let timestamp = local.timestamp();
+                    if timestamp > now as i64 {
+                        return Some(timestamp as u64);
+                    }
+                }
+                after = next;
+            }
+            None
+        }
+    }
+}
+
+fn load_jobs(dir: &str) -> Vec<ScheduledJob> {
+    let p = std::path::Path::new(dir).join(SCHEDULED_JOBS_FILE);
+    if !p.exists() {
+        return Vec::new();
+    }
+    std::fs::read_to_string(&p)
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_jobs(dir: &str, jobs: &[ScheduledJob]) {
+    let p = std::path::Path::new(dir).join(SCHEDULED_JOBS_FILE);
+    if let Ok(j) = serde_json::to_string_pretty(jobs) {
+        let _ = std::fs::write(&p, j);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interval_runs_after_anchor() {
+        let schedule = JobSchedule::Interval { seconds: 60 };
+        assert_eq!(compute_next_run(&schedule, 1000, 1000), Some(1060));
+        assert_eq!(compute_next_run(&schedule, 1000, 1030), Some(1060));
+    }
+
+    #[test]
+    fn interval_skips_missed_runs_and_stays_aligned() {
+        let schedule = JobSchedule::Interval { seconds: 60 };
+        assert_eq!(compute_next_run(&schedule, 1000, 1060), Some(1120));
+        assert_eq!(compute_next_run(&schedule, 1000, 1305), Some(1360));
+    }
+
+    #[test]
+    fn cron_next_run_is_in_the_future() {
+        let schedule = JobSchedule::Cron { expression: "*/5 * * * *".to_string() };
+        let now = 1_700_000_000;
+        let next = compute_next_run(&schedule, now, now).unwrap();
+        assert!(next > now);
+        assert!(next - now <= 5 * 60);
+    }
+
+    #[test]
+    fn schedule_validation() {
+        assert!(validate_schedule(&JobSchedule::Interval { seconds: 5 }).is_err());
+        assert!(validate_schedule(&JobSchedule::Interval { seconds: 3600 }).is_ok());
+        assert!(
+            validate_schedule(&JobSchedule::Cron { expression: "0 4 * * *".to_string() }).is_ok()
+        );
+        assert!(validate_schedule(&JobSchedule::Cron { expression: "bad".to_string() }).is_err());
+    }
+
+    #[test]
+    fn job_schedule_serializes_with_type_tag() {
+        let json = serde_json::to_value(JobSchedule::Cron { expression: "0 4 * * *".to_string() })
+            .unwrap();
+        assert_eq!(json["type"], "cron");
+        let action: JobAction =
+            serde_json::from_value(serde_json::json!({"type": "command", "command": "say hi"}))
+                .unwrap();
+        assert_eq!(action, JobAction::Command { command: "say hi".to_string() });
+    }
+}
@@ -0,0 +1,114 @@
+//! 倒计时停服的纯逻辑部分。
+//!
+//! ServerManager 按 [`StopCountdown`] 依次在剩余 N 秒时广播提醒，倒计时结束后
+//! 执行 `save-all` 与 `stop`，超过期限仍未退出则强制终止进程。
+//! 这里只负责整理提醒时间点和拼接控制台命令，计时与进程控制在 manager.rs 中完成。
+
+use crate::models::server::StopCountdown;
+
+/// 去重并按剩余时间从大到小排列提醒点，忽略 0 秒。
+pub fn warning_schedule(countdown: &StopCountdown) -> Vec<u64> {
+    let mut warnings: Vec<u64> = countdown
+        .warnings
+        .iter()
+        .copied()
+        .filter(|secs| *secs > 0)
+        .collect();
+    warnings.sort_unstable_by(|a, b| b.cmp(a));
+    warnings.dedup();
+    warnings
+}
+
+/// 将剩余秒数格式化为“1 小时 5 分钟”“30 秒”这样的文本。
+pub fn format_remaining(secs: u64) -> String {
+    let hours = secs / 3600;
+    let minutes = secs % 3600 / 60;
+    let seconds = secs % 60;
+
+    let mut parts = Vec::new();
+    if hours > 0 {
+        parts.push(format!("{} 小时", hours));
+    }
+    if minutes > 0 {
+        parts.push(format!("{} 分钟", minutes));
+    }
+    if seconds > 0 || parts.is_empty() {
+        parts.push(format!("{} 秒", seconds));
+    }
+    parts.join(" ")
+}
+
+/// 生成剩余 `remaining_secs` 秒时的提醒文本。
+pub fn warning_message(countdown: &StopCountdown, remaining_secs: u64) -> String {
+    let mut message = countdown
+        .message
+        .replace("{time}", &format_remaining(remaining_secs));
+    if let Some(reason) = countdown
+        .reason
+        .as_deref()
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+    {
+        message.push_str(&format!("（{}）", reason));
+    }
+    message
+}
+
+/// 拼接广播提醒的控制台命令（`say` 或 `tellraw @a`）。
+pub fn broadcast_command(countdown: &StopCountdown, remaining_secs: u64) -> String {
+    let message = warning_message(countdown, remaining_secs).replace(['\r', '\n'], " ");
+    if countdown.use_tellraw {
+        let component = serde_json::json!({ "text": message, "color": "yellow" });
+        format!("tellraw @a {}", component)
+    } else {
+        format!("say {}", message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schedule_is_sorted_deduplicated_and_skips_zero() {
+        let countdown = StopCountdown {
+            warnings: vec![10, 0, 300, 60, 10],
+            ..StopCountdown::default()
+        };
+        assert_eq!(warning_schedule(&countdown), vec![300, 60, 10]);
+    }
+
+    #[test]
+    fn formats_remaining_time() {
+        assert_eq!(format_remaining(0), "0 秒");
+        assert_eq!(format_remaining(10), "10 秒");
+        assert_eq!(format_remaining(300), "5 分钟");
+        assert_eq!(format_remaining(90), "1 分钟 30 秒");
+        assert_eq!(format_remaining(3900), "1 小时 5 分钟");
+    }
+
+    #[test]
+    fn message_includes_time_and_reason() {
+        let countdown = StopCountdown {
+            reason: Some(" 更新插件 ".to_string()),
+            ..StopCountdown::default()
+        };
+        assert_eq!(warning_message(&countdown, 60), "服务器将在 1 分钟后关闭（更新插件）");
+    }
+
+    #[test]
+    fn builds_say_and_tellraw_commands() {
+        let mut countdown = StopCountdown {
+            message: "Restart in {time}\n\"now\"".to_string(),
+            ..StopCountdown::default()
+        };
+        assert_eq!(broadcast_command(&countdown, 10), "say Restart in 10 秒 \"now\"");
+
+        countdown.use_tellraw = true;
+        let command = broadcast_command(&countdown, 10);
+        let json = command.strip_prefix("tellraw @a ").unwrap();
+        let value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(value["text"], "Restart in 10 秒 \"now\"");
+        assert_eq!(value["color"], "yellow");
+    }
+}
@@ -0,0 +1,203 @@
+//! 地图渲染使用的方块颜色表。
+//!
+//! 只收录常见的地表方块；表中没有的方块按名称关键字（染色、木材、石材等）推测颜色，
+//! 仍无法判断时使用中性灰色。
+
+/// 不遮挡视线、渲染时跳过的方块
+pub fn is_transparent(name: &str) -> bool {
+    matches!(
+        strip_namespace(name),
+        "air" | "cave_air" | "void_air" | "barrier" | "light" | "structure_void"
+    )
+}
+
+/// 方块的地图颜色（RGB）
+pub fn block_color(name: &str) -> [u8; 3] {
+    let name = strip_namespace(name);
+    if let Some(color) = exact_color(name) {
+        return color;
+    }
+    if let Some(color) = dye_color(name) {
+        return color;
+    }
+    keyword_color(name).unwrap_or([128, 128, 128])
+}
+
+fn strip_namespace(name: &str) -> &str {
+    name.strip_prefix("minecraft:").unwrap_or(name)
+}
+
+fn exact_color(name: &str) -> Option<[u8; 3]> {
+    let color = match name {
+        "grass_block" => [124, 176, 76],
+        "short_grass" | "grass" | "tall_grass" | "fern" | "large_fern" => [109, 163, 64],
+        "dirt" | "coarse_dirt" | "rooted_dirt" | "farmland" | "dirt_path" => [134, 96, 67],
+        "podzol" => [106, 74, 42],
+        "mycelium" => [111, 99, 105],
+        "mud" => [60, 57, 61],
+        "moss_block" | "moss_carpet" => [89, 110, 45],
+        "water" | "bubble_column" => [63, 118, 228],
+        "lava" => [207, 92, 20],
+        "ice" | "frosted_ice" => [145, 183, 253],
+        "packed_ice" => [141, 180, 250],
+        "blue_ice" => [116, 167, 253],
+        "snow" | "snow_block" | "powder_snow" => [249, 254, 254],
+        "sand" => [219, 207, 163],
+        "red_sand" => [190, 102, 33],
+        "sandstone" | "cut_sandstone" | "smooth_sandstone" => [216, 203, 155],
+        "red_sandstone" | "cut_red_sandstone" | "smooth_red_sandstone" => [186, 99, 29],
+        "gravel" => [131, 127, 126],
+        "clay" => [160, 166, 179],
+        "stone" | "stone_bricks" | "smooth_stone" | "cobblestone" | "mossy_cobblestone" => {
+            [125, 125, 125]
+        }
+        "andesite" | "polished_andesite" => [136, 136, 137],
+        "diorite" | "polished_diorite" => [188, 188, 188],
+        "granite" | "polished_granite" => [149, 103, 85],
+        "deepslate" | "cobbled_deepslate" | "polished_deepslate" => [80, 80, 82],
+        "tuff" => [108, 109, 102],
+        "calcite" => [223, 224, 220],
+        "dripstone_block" | "pointed_dripstone" => [134, 107, 92],
+        "bedrock" => [85, 85, 85],
+        "obsidian" | "crying_obsidian" => [21, 18, 30],
+        "netherrack" => [97, 38, 38],
+        "nether_wart_block" => [115, 3, 3],
+        "warped_wart_block" => [22, 119, 121],
+        "crimson_nylium" => [130, 31, 31],
+        "warped_nylium" => [43, 114, 101],
+        "soul_sand" | "soul_soil" => [81, 62, 50],
+        "basalt" | "polished_basalt" | "smooth_basalt" => [73, 72, 77],
+        "blackstone" | "polished_blackstone" => [42, 36, 41],
+        "magma_block" => [142, 63, 31],
+        "glowstone" | "shroomlight" => [240, 180, 90],
+        "end_stone" | "end_stone_bricks" => [219, 222, 158],
+        "purpur_block" | "purpur_pillar" => [169, 125, 169],
+        "chorus_plant" | "chorus_flower" => [93, 57, 93],
+        "cactus" => [85, 127, 43],
+        "sugar_cane" | "bamboo" => [148, 192, 101],
+        "pumpkin" | "carved_pumpkin" | "jack_o_lantern" => [198, 118, 24],
+        "melon" => [111, 145, 30],
+        "hay_block" => [166, 136, 38],
+        "lily_pad" => [32, 128, 48],
+        "seagrass" | "tall_seagrass" | "kelp" | "kelp_plant" => [24, 94, 60],
+        "sea_lantern" => [172, 199, 190],
+        "prismarine" | "prismarine_bricks" | "dark_prismarine" => [99, 156, 151],
+        "bricks" => [150, 97, 83],
+        "mud_bricks" => [137, 103, 79],
+        "quartz_block" | "smooth_quartz" | "quartz_pillar" | "quartz_bricks" => [235, 229, 222],
+        "iron_block" => [220, 220, 220],
+        "gold_block" => [246, 208, 61],
+        "diamond_block" => [98, 237, 228],
+        "emerald_block" => [42, 203, 87],
+        "lapis_block" => [31, 67, 140],
+        "redstone_block" => [175, 24, 5],
+        "coal_block" => [16, 15, 15],
+        "copper_block" | "cut_copper" => [192, 107, 79],
+        "bookshelf" => [117, 94, 59],
+        "glass" | "glass_pane" => [200, 220, 230],
+        "torch" | "wall_torch" | "lantern" => [255, 216, 0],
+        "rail" | "powered_rail" | "detector_rail" | "activator_rail" => [120, 108, 90],
+        "mushroom_stem" => [203, 196, 185],
+        "brown_mushroom_block" => [149, 111, 81],
+        "red_mushroom_block" => [200, 46, 45],
+        "dandelion" | "sunflower" => [245, 220, 50],
+        "poppy" | "rose_bush" | "red_tulip" => [200, 30, 30],
+        "sculk" | "sculk_catalyst" | "sculk_vein" => [13, 30, 36],
+        _ => return None,
+    };
+    Some(color)
+}
+
+const DYE_COLORS: [(&str, [u8; 3]); 16] = [
+    ("white", [233, 236, 236]),
+    ("orange", [240, 118, 19]),
+    ("magenta", [189, 68, 179]),
+    ("light_blue", [58, 175, 217]),
+    ("yellow", [248, 197, 39]),
+    ("lime", [112, 185, 25]),
+    ("pink", [237, 141, 172]),
+    ("gray", [62, 68, 71]),
+    ("light_gray", [142, 142, 134]),
+    ("cyan", [21, 137, 145]),
+    ("purple", [121, 42, 172]),
+    ("blue", [53, 57, 157]),
+    ("brown", [114, 71, 40]),
+    ("green", [84, 109, 27]),
+    ("red", [161, 39, 34]),
+    ("black", [20, 21, 25]),
+];
+
+/// 羊毛、混凝土、陶瓦、玻璃、地毯等染色方块
+fn dye_color(name: &str) -> Option<[u8; 3]> {
+    const DYED: [&str; 8] = [
+        "_wool",
+        "_carpet",
+        "_concrete",
+        "_concrete_powder",
+        "_terracotta",
+        "_glazed_terracotta",
+        "_stained_glass",
+        "_stained_glass_pane",
+    ];
+    if !DYED.iter().any(|suffix| name.ends_with(suffix)) {
+        return None;
+    }
+    // light_blue / light_gray 需先于 blue / gray 匹配
+    DYE_COLORS
+        .iter()
+        .filter(|(dye, _)| name.starts_with(&format!("{}_", dye)))
+        .max_by_key(|(dye, _)| dye.len())
+        .map(|(_, color)| *color)
+}
+
+fn keyword_color(name: &str) -> Option<[u8; 3]> {
+    const KEYWORDS: [(&str, [u8; 3]); 19] = [
+        ("leaves", [72, 120, 40]),
+        ("azalea", [101, 124, 47]),
+        ("sapling", [80, 140, 50]),
+        ("crimson", [126, 58, 86]),
+        ("warped", [58, 142, 140]),
+        ("cherry", [226, 178, 172]),
+        ("birch", [196, 176, 118]),
+        ("spruce", [104, 78, 47]),
+        ("dark_oak", [66, 43, 20]),
+        ("mangrove", [117, 54, 48]),
+        ("jungle", [160, 115, 80]),
+        ("acacia", [168, 90, 50]),
+        ("oak", [162, 130, 78]),
+        ("_log", [109, 85, 50]),
+        ("_wood", [109, 85, 50]),
+        ("planks", [162, 130, 78]),
+        ("terracotta", [152, 94, 67]),
+        ("_ore", [125, 125, 125]),
+        ("coral", [200, 100, 150]),
+    ];
+    KEYWORDS
+        .iter()
+        .find(|(keyword, _)| name.contains(keyword))
+        .map(|(_, color)| *color)
+        .or_else(|| {
+            // 台阶、楼梯、墙等变体使用其材质的颜色，如 stone_brick_stairs -> stone_bricks
+            ["_slab", "_stairs", "_wall", "_fence", "_fence_gate", "_door", "_trapdoor"]
+                .iter()
+                .find_map(|suffix| name.strip_suffix(suffix))
+                .and_then(|base| exact_color(base).or_else(|| exact_color(&format!("{}s", base))))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_colors_by_name() {
+        assert_eq!(block_color("minecraft:water"), [63, 118, 228]);
+        assert_eq!(block_color("minecraft:light_blue_wool"), [58, 175, 217]);
+        assert_eq!(block_color("minecraft:blue_concrete"), [53, 57, 157]);
+        assert_eq!(block_color("minecraft:oak_leaves"), [72, 120, 40]);
+        assert_eq!(block_color("minecraft:stone_brick_stairs"), [125, 125, 125]);
+        assert_eq!(block_color("modded:unknown_block"), [128, 128, 128]);
+        assert!(is_transparent("minecraft:cave_air"));
+        assert!(!is_transparent("minecraft:glass"));
+    }
+}
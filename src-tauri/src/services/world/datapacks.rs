@@ -0,0 +1,488 @@
+//! 主世界 `<world>/datapacks` 中数据包的列出、安装、删除与启用状态编辑。
+//!
+//! 数据包可以是含 pack.mcmeta 的目录或 zip 压缩包，在 level.dat 的 `DataPacks.Enabled` /
+//! `DataPacks.Disabled` 中以 `file/<文件名>` 记录。这里只负责离线修改 level.dat；
+//! 服务器运行时由调用方改为下发 `/datapack enable|disable`。
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use super::nbt::{Compound, NbtDocument, Tag};
+use crate::models::world::DatapackInfo;
+
+///此处常量见 utils/constants.rs
+use crate::utils::constants::{DATAPACK_FORMATS, DATAPACK_FORMATS_LAST_KNOWN};
+
+/// pack.mcmeta 的大小上限
+const MAX_MCMETA_BYTES: u64 = 1024 * 1024;
+
+/// 服务器版本对应的数据包格式；快照、1.13 之前或比内置表更新的版本返回 `None`。
+pub fn expected_pack_format(mc_version: &str) -> Option<u32> {
+    let mut parts = mc_version.trim().strip_prefix("1.")?.split('.');
+    let minor: u32 = parts.next()?.parse().ok()?;
+    let patch: u32 = match parts.next() {
+        Some(patch) => patch.parse().ok()?,
+        None => 0,
+    };
+    if (minor, patch) > DATAPACK_FORMATS_LAST_KNOWN {
+        return None;
+    }
+    DATAPACK_FORMATS
+        .iter()
+        .rev()
+        .find(|(m, p, _)| (*m, *p) <= (minor, patch))
+        .map(|(_, _, format)| *format)
+}
+
+pub fn pack_id(name: &str) -> String {
+    format!("file/{}", name)
+}
+
+/// 服务器运行时启用 / 禁用数据包的控制台命令
+pub fn datapack_command(name: &str, enabled: bool) -> String {
+    let escaped = pack_id(name).replace('\\', "\\\\").replace('"', "\\\"");
+    format!("datapack {} \"{}\"", if enabled { "enable" } else { "disable" }, escaped)
+}
+
+pub fn datapacks_dir(server_dir: &Path) -> Result<PathBuf, String> {
+    Ok(super::world_dir(server_dir)?.join("datapacks"))
+}
+
+pub fn list_datapacks(server_dir: &Path, mc_version: &str) -> Result<Vec<DatapackInfo>, String> {
+    let dir = datapacks_dir(server_dir)?;
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return Ok(Vec::new());
+    };
+    let (enabled, disabled) = level_datapack_lists(server_dir);
+
+    let mut packs: Vec<DatapackInfo> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_string();
+            let path = entry.path();
+            let is_archive = path.is_file() && name.to_ascii_lowercase().ends_with(".zip");
+            if !is_archive && !path.join("pack.mcmeta").is_file() {
+                return None;
+            }
+            let mut info = read_pack(&path, &name, mc_version);
+            let id = pack_id(&name);
+            info.enabled = if enabled.contains(&id) {
+                Some(true)
+            } else if disabled.contains(&id) {
+                Some(false)
+            } else {
+                None
+            };
+            Some(info)
+        })
+        .collect();
+    packs.sort_by_key(|pack| pack.name.to_lowercase());
+    Ok(packs)
+}
+
+/// 把 zip 或目录形式的数据包复制到 datapacks 目录；同名数据包已存在时报错。
+pub fn install_datapack(
+    server_dir: &Path,
+    source: &Path,
+    mc_version: &str,
+) -> Result<DatapackInfo, String> {
+    let name = source
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| "数据包路径无效".to_string())?
+        .to_string();
+    super::validate_path_segment(&name, "数据包名称")?;
+
+    let is_archive = source.is_file();
+    if is_archive {
+        if !name.to_ascii_lowercase().ends_with(".zip") {
+            return Err("只支持 zip 格式的数据包压缩包".to_string());
+        }
+        read_archive_mcmeta(source)?;
+    } else if !source.join("pack.mcmeta").is_file() {
+        return Err(format!("数据包目录缺少 pack.mcmeta: {}", source.display()));
+    }
+
+    let dir = datapacks_dir(server_dir)?;
+    let target = dir.join(&name);
+    if target.exists() {
+        return Err(format!("数据包已存在: {}", name));
+    }
+    std::fs::create_dir_all(&dir).map_err(|e| format!("创建 datapacks 目录失败: {}", e))?;
+
+    // 先复制到临时位置，避免服务端重载时读到不完整的数据包
+    let staging = dir.join(format!(".sealantern-{}", uuid::Uuid::new_v4().simple()));
+    let copied = if is_archive {
+        std::fs::copy(source, &staging).map(|_| ())
+    } else {
+        super::copy_dir(source, &staging, &[])
+    }
+    .map_err(|e| format!("复制数据包失败: {}", e))
+    .and_then(|_| std::fs::rename(&staging, &target).map_err(|e| format!("移动数据包失败: {}", e)));
+    if let Err(e) = copied {
+        let _ = std::fs::remove_dir_all(&staging);
+        let _ = std::fs::remove_file(&staging);
+        return Err(e);
+    }
+
+    Ok(read_pack(&target, &name, mc_version))
+}
+
+pub fn remove_datapack(server_dir: &Path, name: &str) -> Result<(), String> {
+    super::validate_path_segment(name, "数据包名称")?;
+    let path = datapacks_dir(server_dir)?.join(name);
+    if path.is_dir() {
+        std::fs::remove_dir_all(&path)
+    } else if path.is_file() {
+        std::fs::remove_file(&path)
+    } else {
+        return Err(format!("数据包不存在: {}", name));
+    }
+    .map_err(|e| format!("删除数据包失败: {}", e))
+}
+
+/// 离线修改 level.dat 中数据包的启用状态，修改前备份 level.dat，返回备份路径。
+///
+/// 启用的数据包追加到 Enabled 末尾（最高优先级），与 `/datapack enable` 的默认行为一致。
+pub fn set_datapack_enabled(
+    server_id: &str,
+    server_dir: &Path,
+    name: &str,
+    enabled: bool,
+) -> Result<PathBuf, String> {
+    super::validate_path_segment(name, "数据包名称")?;
+    if !datapacks_dir(server_dir)?.join(name).exists() {
+        return Err(format!("数据包不存在: {}", name));
+    }
+
+    let path = super::level::level_dat_path(server_dir)?;
+    let mut doc = NbtDocument::read_file(&path)?;
+    let data = doc
+        .root
+        .get_compound_mut("Data")
+        .ok_or_else(|| "level.dat 缺少 Data 标签".to_string())?;
+    if data.get_compound("DataPacks").is_none() {
+        data.insert("DataPacks", Tag::Compound(Compound::new()));
+    }
+    let packs = data
+        .get_compound_mut("DataPacks")
+        .ok_or_else(|| "level.dat 中的 DataPacks 不是复合标签".to_string())?;
+
+    let id = pack_id(name);
+    for (key, add) in [("Enabled", enabled), ("Disabled", !enabled)] {
+        let mut items = string_list(packs, key);
+        items.retain(|item| item != &id);
+        if add {
+            items.push(id.clone());
+        }
+        packs.insert(key, Tag::list(items.into_iter().map(Tag::String).collect()));
+    }
+
+    let backup = super::level::backup_level_dat(server_id, &super::level_name(server_dir), &path)?;
+    doc.write_file(&path)?;
+    Ok(backup)
+}
+
+fn string_list(compound: &Compound, key: &str) -> Vec<String> {
+    compound
+        .get(key)
+        .and_then(Tag::as_list)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// level.dat 中的 (Enabled, Disabled)；读取失败时均为空
+fn level_datapack_lists(server_dir: &Path) -> (Vec<String>, Vec<String>) {
+    super::level::level_dat_path(server_dir)
+        .and_then(|path| NbtDocument::read_file(&path))
+        .ok()
+        .and_then(|doc| {
+            let packs = doc.root.get_compound("Data")?.get_compound("DataPacks")?;
+            Some((string_list(packs, "Enabled"), string_list(packs, "Disabled")))
+        })
+        .unwrap_or_default()
+}
+
+fn read_pack(path: &Path, name: &str, mc_version: &str) -> DatapackInfo {
+    let is_archive = path.is_file();
+    let mcmeta = if is_archive {
+        read_archive_mcmeta(path)
+    } else {
+        read_dir_mcmeta(path)
+    };
+    let mut info = DatapackInfo {
+        name: name.to_string(),
+        id: pack_id(name),
+        is_archive,
+        description: None,
+        pack_format: None,
+        supported_formats: None,
+        enabled: None,
+        size_bytes: path_size(path),
+        warning: None,
+    };
+
+    let pack = match mcmeta {
+        Ok(value) => value.get("pack").cloned().unwrap_or(Value::Null),
+        Err(e) => {
+            info.warning = Some(e);
+            return info;
+        }
+    };
+    info.description = pack
+        .get("description")
+        .map(text_component)
+        .filter(|d| !d.is_empty());
+    info.pack_format = pack.get("pack_format").and_then(format_number);
+    // 1.21.9 起使用 min_format / max_format，之前为 supported_formats
+    info.supported_formats = match (pack.get("min_format"), pack.get("max_format")) {
+        (Some(min), Some(max)) => format_number(min)
+            .zip(format_number(max))
+            .map(|(a, b)| [a, b]),
+        _ => pack.get("supported_formats").and_then(format_range),
+    };
+    if info.pack_format.is_none() && info.supported_formats.is_none() {
+        info.warning = Some("pack.mcmeta 缺少 pack_format".to_string());
+    } else if let Some(expected) = expected_pack_format(mc_version) {
+        let compatible = match info.supported_formats {
+            Some([min, max]) => (min..=max).contains(&expected),
+            None => info.pack_format == Some(expected),
+        };
+        if !compatible {
+            let declared = match info.supported_formats {
+                Some([min, max]) => format!("{}-{}", min, max),
+                None => info.pack_format.unwrap_or_default().to_string(),
+            };
+            info.warning = Some(format!(
+                "数据包格式 {} 与服务器版本 {}（格式 {}）不匹配",
+                declared, mc_version, expected
+            ));
+        }
+    }
+    info
+}
+
+fn read_dir_mcmeta(dir: &Path) -> Result<Value, String> {
+    let file = std::fs::File::open(dir.join("pack.mcmeta"))
+        .map_err(|e| format!("读取 pack.mcmeta 失败: {}", e))?;
+    parse_mcmeta(file.take(MAX_MCMETA_BYTES))
+}
+
+fn read_archive_mcmeta(archive: &Path) -> Result<Value, String> {
+    let file = std::fs::File::open(archive).map_err(|e| format!("打开数据包失败: {}", e))?;
+    let mut zip = zip::ZipArchive::new(file).map_err(|e| format!("无法解析数据包压缩包: {}", e))?;
+    let entry = zip
+        .by_name("pack.mcmeta")
+        .map_err(|_| "压缩包根目录缺少 pack.mcmeta".to_string())?;
+    parse_mcmeta(entry.take(MAX_MCMETA_BYTES))
+}
+
+fn parse_mcmeta(mut reader: impl Read) -> Result<Value, String> {
+    let mut content = String::new();
+    reader
+        .read_to_string(&mut content)
+        .map_err(|e| format!("读取 pack.mcmeta 失败: {}", e))?;
+    // 部分数据包的 pack.mcmeta 带有 UTF-8 BOM
+    serde_json::from_str(content.trim_start_matches('\u{feff}'))
+        .map_err(|e| format!("pack.mcmeta 不是有效的 JSON: {}", e))
+}
+
+/// 格式号可能是整数、小数（如 88.0）或 `[主版本, 次版本]`，只取主版本
+fn format_number(value: &Value) -> Option<u32> {
+    match value {
+        Value::Number(n) => n.as_u64().or_else(|| n.as_f64().map(|f| f as u64)),
+        Value::Array(items) => items.first().and_then(Value::as_u64),
+        _ => None,
+    }
+    .and_then(|n| u32::try_from(n).ok())
+}
+
+/// supported_formats 可能是单个整数、`[min, max]` 或 `{min_inclusive, max_inclusive}`
+fn format_range(value: &Value) -> Option<[u32; 2]> {
+    match value {
+        Value::Number(_) => format_number(value).map(|n| [n, n]),
+        Value::Array(items) if items.len() == 2 => {
+            Some([format_number(&items[0])?, format_number(&items[1])?])
+        }
+        Value::Object(map) => Some([
+            format_number(map.get("min_inclusive")?)?,
+            format_number(map.get("max_inclusive")?)?,
+        ]),
+        _ => None,
+    }
+}
+
+/// 把 JSON 文本组件展开为纯文本
+fn text_component(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Array(items) => items.iter().map(text_component).collect(),
+        Value::Object(map) => {
+            let text = map
+                .get("text")
+                .or_else(|| map.get("translate"))
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            let extra: String = map
+                .get("extra")
+                .and_then(Value::as_array)
+                .map(|items| items.iter().map(text_component).collect())
+                .unwrap_or_default();
+            format!("{}{}", text, extra)
+        }
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Null => String::new(),
+    }
+}
+
+fn path_size(path: &Path) -> u64 {
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    std::fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|entry| path_size(&entry.path()))
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::world::nbt::Compression;
+    use crate::utils::test_utils::temp_dir;
+    use std::io::Write;
+
+    fn server_with_world(name: &str) -> PathBuf {
+        let server = temp_dir(name);
+        std::fs::create_dir_all(server.join("world/datapacks")).unwrap();
+
+        let mut packs = Compound::new();
+        packs.insert(
+            "Enabled",
+            Tag::list(vec![
+                Tag::String("vanilla".to_string()),
+                Tag::String("file/folder_pack".to_string()),
+            ]),
+        );
+        packs.insert("Disabled", Tag::list(vec![Tag::String("file/zipped.zip".to_string())]));
+        let mut data = Compound::new();
+        data.insert("DataPacks", Tag::Compound(packs));
+        let mut root = Compound::new();
+        root.insert("Data", Tag::Compound(data));
+        NbtDocument {
+            name: String::new(),
+            root,
+            compression: Compression::Gzip,
+        }
+        .write_file(&server.join("world/level.dat"))
+        .unwrap();
+        server
+    }
+
+    fn write_zip_pack(path: &Path, mcmeta: &str) {
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+        zip.start_file("pack.mcmeta", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(mcmeta.as_bytes()).unwrap();
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn maps_versions_to_pack_formats() {
+        assert_eq!(expected_pack_format("1.20.1"), Some(15));
+        assert_eq!(expected_pack_format("1.20.4"), Some(26));
+        assert_eq!(expected_pack_format("1.21"), Some(48));
+        assert_eq!(expected_pack_format("1.21.8"), Some(81));
+        assert_eq!(expected_pack_format("1.12.2"), None);
+        assert_eq!(expected_pack_format("1.99"), None);
+        assert_eq!(expected_pack_format("25w02a"), None);
+        assert_eq!(
+            datapack_command("my \"pack\".zip", false),
+            "datapack disable \"file/my \\\"pack\\\".zip\""
+        );
+    }
+
+    #[test]
+    fn lists_packs_with_state_and_warnings() {
+        let server = server_with_world("list");
+        let packs_dir = server.join("world/datapacks");
+        std::fs::create_dir_all(packs_dir.join("folder_pack")).unwrap();
+        std::fs::write(
+            packs_dir.join("folder_pack/pack.mcmeta"),
+            r#"{"pack":{"pack_format":48,"description":{"text":"Hello ","extra":[{"text":"world"}]}}}"#,
+        )
+        .unwrap();
+        write_zip_pack(
+            &packs_dir.join("zipped.zip"),
+            r#"{"pack":{"pack_format":15,"description":"Old","supported_formats":[15,41]}}"#,
+        );
+        write_zip_pack(&packs_dir.join("fresh.zip"), r#"{"pack":{"pack_format":48}}"#);
+        std::fs::create_dir_all(packs_dir.join("not_a_pack")).unwrap();
+
+        let packs = list_datapacks(&server, "1.21.1").unwrap();
+        let names: Vec<_> = packs.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["folder_pack", "fresh.zip", "zipped.zip"]);
+        assert_eq!(packs[0].description.as_deref(), Some("Hello world"));
+        assert_eq!(packs[0].enabled, Some(true));
+        assert!(packs[0].warning.is_none());
+        assert_eq!(packs[1].enabled, None);
+        assert_eq!(packs[2].enabled, Some(false));
+        assert_eq!(packs[2].supported_formats, Some([15, 41]));
+        assert!(packs[2].warning.as_deref().unwrap().contains("15-41"));
+
+        let _ = std::fs::remove_dir_all(&server);
+    }
+
+    #[test]
+    fn installs_toggles_and_removes_packs() {
+        let server = server_with_world("install");
+        let source = server.join("incoming.zip");
+        write_zip_pack(&source, r#"{"pack":{"pack_format":48,"description":"New"}}"#);
+        let broken = server.join("broken.zip");
+        {
+            let mut zip = zip::ZipWriter::new(std::fs::File::create(&broken).unwrap());
+            zip.start_file("inner/pack.mcmeta", zip::write::SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(b"{}").unwrap();
+            zip.finish().unwrap();
+        }
+
+        let info = install_datapack(&server, &source, "1.21").unwrap();
+        assert_eq!(info.id, "file/incoming.zip");
+        assert!(server.join("world/datapacks/incoming.zip").is_file());
+        assert!(install_datapack(&server, &source, "1.21").is_err());
+        assert!(install_datapack(&server, &broken, "1.21").is_err());
+
+        write_zip_pack(
+            &server.join("world/datapacks/zipped.zip"),
+            r#"{"pack":{"pack_format":48}}"#,
+        );
+        set_datapack_enabled("test", &server, "incoming.zip", false).unwrap();
+        set_datapack_enabled("test", &server, "zipped.zip", true).unwrap();
+        let (enabled, disabled) = level_datapack_lists(&server);
+        assert_eq!(enabled, vec!["vanilla", "file/folder_pack", "file/zipped.zip"]);
+        assert_eq!(disabled, vec!["file/incoming.zip"]);
+        assert!(set_datapack_enabled("test", &server, "missing.zip", true).is_err());
+
+        remove_datapack(&server, "incoming.zip").unwrap();
+        assert!(!server.join("world/datapacks/incoming.zip").exists());
+        assert!(remove_datapack(&server, "../level.dat").is_err());
+
+        let _ = std::fs::remove_dir_all(&server);
+    }
+}
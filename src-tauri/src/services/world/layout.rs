@@ -0,0 +1,247 @@
+//! 原版与 Bukkit 两种世界目录布局之间的转换。
+//!
+//! 原版（含 Fabric / Forge 等）把下界、末地存放在主世界目录内的 `DIM-1`、`DIM1`；
+//! Bukkit 系服务端则拆到 `<world>_nether/DIM-1`、`<world>_the_end/DIM1`。
+//! 单人存档导入到 Paper、或切换核心类型后，若不转换，服务端会生成全新的下界与末地。
+
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::models::world::{LayoutConversion, WorldLayout};
+use crate::services::server::installer::CoreType;
+
+/// (维度目录, Bukkit 布局下的世界目录后缀)
+const DIMENSIONS: [(&str, &str); 2] = [("DIM-1", "_nether"), ("DIM1", "_the_end")];
+/// Bukkit 维度目录中除维度数据外、转换回原版后可以丢弃的文件
+const BUKKIT_DIMENSION_LEFTOVERS: [&str; 4] =
+    ["level.dat", "level.dat_old", "uid.dat", "session.lock"];
+
+/// 维度名称，与游戏内的维度 ID 一致
+pub const DIMENSION_NAMES: [&str; 3] = ["overworld", "the_nether", "the_end"];
+
+/// 维度的数据目录（其下为 region / entities / poi），两种布局都能识别；维度不存在时返回 `None`。
+pub fn dimension_dir(server_dir: &Path, world: &str, dimension: &str) -> Option<PathBuf> {
+    let world_dir = server_dir.join(world);
+    let (dim, suffix) = match dimension {
+        "overworld" => return world_dir.is_dir().then_some(world_dir),
+        "the_nether" => DIMENSIONS[0],
+        "the_end" => DIMENSIONS[1],
+        _ => return None,
+    };
+    [world_dir.join(dim), server_dir.join(format!("{}{}", world, suffix)).join(dim)]
+        .into_iter()
+        .find(|dir| dir.is_dir())
+}
+
+/// 核心类型对应的世界布局；代理端、基岩版等无法判断时返回 `None`。
+pub fn layout_for_core_type(core_type: &str) -> Option<WorldLayout> {
+    let core = CoreType::from_str(core_type.trim())
+        .unwrap_or_else(|_| CoreType::detect_from_filename(core_type));
+    match core {
+        // 混合端（Arclight / Mohist 等）由 Bukkit 实现管理世界
+        CoreType::ArclightForge
+        | CoreType::ArclightNeoforge
+        | CoreType::ArclightFabric
+        | CoreType::Youer
+        | CoreType::Mohist
+        | CoreType::Catserver
+        | CoreType::Banner
+        | CoreType::PufferfishPurpur
+        | CoreType::Pufferfish
+        | CoreType::Purpur
+        | CoreType::Paper
+        | CoreType::Folia
+        | CoreType::Leaves
+        | CoreType::Leaf
+        | CoreType::Spigot
+        | CoreType::Bukkit => Some(WorldLayout::Bukkit),
+        CoreType::Spongeforge
+        | CoreType::Spongevanilla
+        | CoreType::Neoforge
+        | CoreType::Forge
+        | CoreType::Quilt
+        | CoreType::Fabric
+        | CoreType::VanillaSnapshot
+        | CoreType::Vanilla => Some(WorldLayout::Vanilla),
+        CoreType::Nukkitx
+        | CoreType::Bedrock
+        | CoreType::Velocity
+        | CoreType::Bungeecord
+        | CoreType::Lightfall
+        | CoreType::Travertine
+        | CoreType::Unknown => None,
+    }
+}
+
+/// 将世界的下界与末地移动到 `target` 布局的位置。
+///
+/// 已处于目标布局的维度不做改动；目标位置已存在同名维度目录时整体拒绝，不做部分移动。
+pub fn convert_layout(
+    server_dir: &Path,
+    world: &str,
+    target: WorldLayout,
+) -> Result<LayoutConversion, String> {
+    super::validate_path_segment(world, "世界名称")?;
+    let world_dir = server_dir.join(world);
+    if !world_dir.is_dir() {
+        return Err(format!("世界不存在: {}", world));
+    }
+
+    let moves: Vec<_> = DIMENSIONS
+        .iter()
+        .map(|(dim, suffix)| {
+            let vanilla = world_dir.join(dim);
+            let bukkit_root = server_dir.join(format!("{}{}", world, suffix));
+            let bukkit = bukkit_root.join(dim);
+            match target {
+                WorldLayout::Bukkit => (vanilla, bukkit, bukkit_root),
+                WorldLayout::Vanilla => (bukkit, vanilla, bukkit_root),
+            }
+        })
+        .filter(|(source, _, _)| source.is_dir())
+        .collect();
+
+    if let Some((_, existing, _)) = moves.iter().find(|(_, target, _)| target.exists()) {
+        return Err(format!("目标位置已存在维度数据，无法转换: {}", existing.display()));
+    }
+
+    let mut moved = Vec::new();
+    for (source, destination, bukkit_root) in &moves {
+        if target == WorldLayout::Bukkit {
+            std::fs::create_dir_all(bukkit_root)
+                .map_err(|e| format!("创建目录 {} 失败: {}", bukkit_root.display(), e))?;
+            // Bukkit 读取维度世界时需要对应目录中的 level.dat，与其自身迁移时的做法一致
+            let level_dat = bukkit_root.join("level.dat");
+            if !level_dat.exists() && world_dir.join("level.dat").is_file() {
+                std::fs::copy(world_dir.join("level.dat"), &level_dat)
+                    .map_err(|e| format!("复制 level.dat 失败: {}", e))?;
+            }
+        }
+
+        std::fs::rename(source, destination).map_err(|e| {
+            format!("移动 {} 到 {} 失败: {}", source.display(), destination.display(), e)
+        })?;
+        moved.push(format!(
+            "{} -> {}",
+            relative(server_dir, source),
+            relative(server_dir, destination)
+        ));
+
+        if target == WorldLayout::Vanilla {
+            remove_empty_bukkit_dimension(bukkit_root);
+        }
+    }
+
+    Ok(LayoutConversion {
+        world: world.to_string(),
+        layout: target,
+        moved,
+    })
+}
+
+/// 维度数据移走后，只剩下 level.dat 等附属文件的 Bukkit 维度目录直接删除；
+/// 仍有其他文件（如插件数据、paper-world.yml）时保留。
+fn remove_empty_bukkit_dimension(dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let only_leftovers = entries.filter_map(|e| e.ok()).all(|entry| {
+        entry
+            .file_name()
+            .to_str()
+            .is_some_and(|name| BUKKIT_DIMENSION_LEFTOVERS.contains(&name))
+    });
+    if only_leftovers {
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
+
+fn relative(base: &Path, path: &Path) -> String {
+    path.strip_prefix(base)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_utils::temp_dir;
+
+    fn touch(path: &Path) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, b"data").unwrap();
+    }
+
+    #[test]
+    fn maps_core_types_to_layouts() {
+        assert_eq!(layout_for_core_type("Paper"), Some(WorldLayout::Bukkit));
+        assert_eq!(layout_for_core_type("arclight-forge"), Some(WorldLayout::Bukkit));
+        assert_eq!(layout_for_core_type("Fabric"), Some(WorldLayout::Vanilla));
+        assert_eq!(layout_for_core_type("vanilla"), Some(WorldLayout::Vanilla));
+        assert_eq!(layout_for_core_type("Velocity"), None);
+        assert_eq!(layout_for_core_type(""), None);
+    }
+
+    #[test]
+    fn converts_between_layouts_round_trip() {
+        let server = temp_dir("round-trip");
+        touch(&server.join("world/level.dat"));
+        touch(&server.join("world/region/r.0.0.mca"));
+        touch(&server.join("world/DIM-1/region/r.0.0.mca"));
+        touch(&server.join("world/DIM1/region/r.0.0.mca"));
+
+        let result = convert_layout(&server, "world", WorldLayout::Bukkit).unwrap();
+        assert_eq!(result.moved.len(), 2);
+        assert!(server.join("world_nether/DIM-1/region/r.0.0.mca").is_file());
+        assert!(server.join("world_the_end/DIM1/region/r.0.0.mca").is_file());
+        assert!(server.join("world_nether/level.dat").is_file());
+        assert!(!server.join("world/DIM-1").exists());
+
+        // 已是目标布局时不做任何改动
+        let again = convert_layout(&server, "world", WorldLayout::Bukkit).unwrap();
+        assert!(again.moved.is_empty());
+
+        touch(&server.join("world_the_end/paper-world.yml"));
+        let back = convert_layout(&server, "world", WorldLayout::Vanilla).unwrap();
+        assert_eq!(back.moved[0], "world_nether/DIM-1 -> world/DIM-1");
+        assert!(server.join("world/DIM-1/region/r.0.0.mca").is_file());
+        assert!(server.join("world/DIM1/region/r.0.0.mca").is_file());
+        assert!(!server.join("world_nether").exists());
+        assert!(server.join("world_the_end/paper-world.yml").is_file());
+
+        let _ = std::fs::remove_dir_all(&server);
+    }
+
+    #[test]
+    fn finds_dimension_dirs_in_both_layouts() {
+        let server = temp_dir("dimension-dir");
+        touch(&server.join("world/DIM-1/region/r.0.0.mca"));
+        touch(&server.join("world_the_end/DIM1/region/r.0.0.mca"));
+
+        assert_eq!(dimension_dir(&server, "world", "overworld"), Some(server.join("world")));
+        assert_eq!(dimension_dir(&server, "world", "the_nether"), Some(server.join("world/DIM-1")));
+        assert_eq!(
+            dimension_dir(&server, "world", "the_end"),
+            Some(server.join("world_the_end/DIM1"))
+        );
+        assert_eq!(dimension_dir(&server, "world", "aether"), None);
+
+        let _ = std::fs::remove_dir_all(&server);
+    }
+
+    #[test]
+    fn refuses_conflicting_dimensions_without_moving() {
+        let server = temp_dir("conflict");
+        touch(&server.join("world/DIM-1/region/r.0.0.mca"));
+        touch(&server.join("world/DIM1/region/r.0.0.mca"));
+        touch(&server.join("world_the_end/DIM1/region/r.0.0.mca"));
+
+        assert!(convert_layout(&server, "world", WorldLayout::Bukkit).is_err());
+        assert!(server.join("world/DIM-1/region/r.0.0.mca").is_file());
+        assert!(!server.join("world_nether").exists());
+        assert!(convert_layout(&server, "missing", WorldLayout::Bukkit).is_err());
+
+        let _ = std::fs::remove_dir_all(&server);
+    }
+}
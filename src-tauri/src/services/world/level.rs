@@ -0,0 +1,418 @@
+//! level.dat 的读取与编辑。
+//!
+//! 修改前会把原文件复制到应用数据目录的 `level_backups/<server_id>/`，每台服务器保留最近
+//! 若干份。是否允许修改由调用方判断：运行中的服务端保存时会用内存中的数据覆盖 level.dat。
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use super::copy_with_retention;
+use super::nbt::{Compound, NbtDocument, Tag};
+use crate::models::world::{LevelInfo, LevelUpdate, LevelUpdateResult};
+
+///此处常量见 utils/constants.rs
+use crate::utils::constants::{LEVEL_BACKUPS_DIR_NAME, LEVEL_BACKUP_KEEP};
+
+pub fn level_dat_path(server_dir: &Path) -> Result<PathBuf, String> {
+    let path = super::world_dir(server_dir)?.join("level.dat");
+    if !path.is_file() {
+        return Err(format!("未找到 level.dat: {}", path.display()));
+    }
+    Ok(path)
+}
+
+pub fn read_level_info(server_dir: &Path) -> Result<LevelInfo, String> {
+    read_level_dat(&level_dat_path(server_dir)?, &super::level_name(server_dir))
+}
+
+/// 读取任意世界目录中的 level.dat，`world` 为该世界的目录名。
+pub fn read_level_dat(path: &Path, world: &str) -> Result<LevelInfo, String> {
+    let doc = NbtDocument::read_file(path)?;
+    Ok(level_info(world.to_string(), data_compound(&doc.root)?))
+}
+
+/// 备份后修改 level.dat，返回修改后的信息与备份路径。
+pub fn update_level(
+    server_id: &str,
+    server_dir: &Path,
+    update: &LevelUpdate,
+) -> Result<LevelUpdateResult, String> {
+    validate_update(update)?;
+    let path = level_dat_path(server_dir)?;
+    let mut doc = NbtDocument::read_file(&path)?;
+    let data = doc
+        .root
+        .get_compound_mut("Data")
+        .ok_or_else(|| "level.dat 缺少 Data 标签".to_string())?;
+    apply_update(data, update)?;
+
+    let world = super::level_name(server_dir);
+    let backup_path = backup_level_dat(server_id, &world, &path)?;
+    doc.write_file(&path)?;
+
+    Ok(LevelUpdateResult {
+        info: level_info(world, data_compound(&doc.root)?),
+        backup_path: backup_path.to_string_lossy().to_string(),
+    })
+}
+
+fn data_compound(root: &Compound) -> Result<&Compound, String> {
+    root.get_compound("Data")
+        .ok_or_else(|| "level.dat 缺少 Data 标签".to_string())
+}
+
+fn level_info(world: String, data: &Compound) -> LevelInfo {
+    let spawn_pos = data
+        .get_compound("spawn")
+        .and_then(|spawn| match spawn.get("pos") {
+            Some(Tag::IntArray(pos)) if pos.len() == 3 => Some([pos[0], pos[1], pos[2]]),
+            _ => None,
+        });
+    let spawn = |axis: usize, key: &str| {
+        spawn_pos
+            .map(|pos| pos[axis])
+            .or_else(|| data.get_i64(key).map(|v| v as i32))
+    };
+
+    let datapacks = |key: &str| -> Vec<String> {
+        data.get_compound("DataPacks")
+            .and_then(|packs| packs.get(key))
+            .and_then(Tag::as_list)
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|item| item.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let version = data.get_compound("Version");
+
+    LevelInfo {
+        world,
+        level_name: data.get_str("LevelName").map(str::to_string),
+        seed: data
+            .get_compound("WorldGenSettings")
+            .and_then(|settings| settings.get_i64("seed"))
+            .or_else(|| data.get_i64("RandomSeed"))
+            .map(|seed| seed.to_string()),
+        spawn_x: spawn(0, "SpawnX"),
+        spawn_y: spawn(1, "SpawnY"),
+        spawn_z: spawn(2, "SpawnZ"),
+        difficulty: data.get_i64("Difficulty").map(|v| v as u8),
+        difficulty_locked: data.get_i64("DifficultyLocked").unwrap_or(0) != 0,
+        hardcore: data.get_i64("hardcore").unwrap_or(0) != 0,
+        game_type: data.get_i64("GameType").map(|v| v as i32),
+        game_rules: data
+            .get_compound("GameRules")
+            .map(read_game_rules)
+            .unwrap_or_default(),
+        enabled_datapacks: datapacks("Enabled"),
+        disabled_datapacks: datapacks("Disabled"),
+        last_played: data.get_i64("LastPlayed"),
+        version_name: version.and_then(|v| v.get_str("Name")).map(str::to_string),
+        data_version: data.get_i64("DataVersion").map(|v| v as i32),
+    }
+}
+
+/// 旧版本的游戏规则全部存为字符串，新版本可能使用布尔（Byte）与整数标签。
+fn read_game_rules(rules: &Compound) -> BTreeMap<String, String> {
+    rules
+        .iter()
+        .filter_map(|(key, tag)| {
+            let value = match tag {
+                Tag::String(v) => v.clone(),
+                Tag::Byte(v) => (*v != 0).to_string(),
+                other => other.as_i64()?.to_string(),
+            };
+            Some((key.to_string(), value))
+        })
+        .collect()
+}
+
+fn parse_seed(seed: &str) -> Result<i64, String> {
+    seed.trim()
+        .parse()
+        .map_err(|_| format!("种子必须是 64 位整数: {}", seed))
+}
+
+fn validate_update(update: &LevelUpdate) -> Result<(), String> {
+    if let Some(seed) = &update.seed {
+        parse_seed(seed)?;
+    }
+    if update.difficulty.is_some_and(|d| d > 3) {
+        return Err("难度只能是 0-3".to_string());
+    }
+    if update.game_type.is_some_and(|g| !(0..=3).contains(&g)) {
+        return Err("游戏模式只能是 0-3".to_string());
+    }
+    if update
+        .level_name
+        .as_deref()
+        .is_some_and(|name| name.trim().is_empty())
+    {
+        return Err("世界名称不能为空".to_string());
+    }
+    if let Some((key, _)) = update
+        .game_rules
+        .iter()
+        .find(|(key, value)| key.trim().is_empty() || value.trim().is_empty())
+    {
+        return Err(format!("游戏规则不能为空: {}", key));
+    }
+    Ok(())
+}
+
+fn apply_update(data: &mut Compound, update: &LevelUpdate) -> Result<(), String> {
+    if let Some(name) = &update.level_name {
+        data.insert("LevelName", Tag::String(name.trim().to_string()));
+    }
+
+    if let Some(seed) = update.seed.as_deref().map(parse_seed).transpose()? {
+        // 1.16 起种子位于 WorldGenSettings（1.16-1.18 的各维度生成器中也各有一份）
+        if let Some(settings) = data.get_compound_mut("WorldGenSettings") {
+            replace_seeds(settings, seed);
+        }
+        if data.get("RandomSeed").is_some() || data.get("WorldGenSettings").is_none() {
+            data.insert("RandomSeed", Tag::Long(seed));
+        }
+    }
+
+    let spawn = [update.spawn_x, update.spawn_y, update.spawn_z];
+    let spawn_pos = data
+        .get_compound_mut("spawn")
+        .and_then(|spawn| match spawn.get_mut("pos") {
+            Some(Tag::IntArray(pos)) if pos.len() == 3 => Some(pos),
+            _ => None,
+        });
+    match spawn_pos {
+        // 较新版本的出生点为 spawn.pos 整数数组
+        Some(pos) => {
+            for (axis, value) in spawn.iter().enumerate() {
+                if let Some(value) = value {
+                    pos[axis] = *value;
+                }
+            }
+        }
+        None => {
+            for (key, value) in ["SpawnX", "SpawnY", "SpawnZ"].iter().zip(spawn) {
+                if let Some(value) = value {
+                    data.insert(*key, Tag::Int(value));
+                }
+            }
+        }
+    }
+
+    if let Some(difficulty) = update.difficulty {
+        data.insert("Difficulty", Tag::Byte(difficulty as i8));
+    }
+    if let Some(locked) = update.difficulty_locked {
+        data.insert("DifficultyLocked", Tag::Byte(locked as i8));
+    }
+    if let Some(hardcore) = update.hardcore {
+        data.insert("hardcore", Tag::Byte(hardcore as i8));
+    }
+    if let Some(game_type) = update.game_type {
+        data.insert("GameType", Tag::Int(game_type));
+    }
+
+    if !update.game_rules.is_empty() {
+        if data.get_compound("GameRules").is_none() {
+            data.insert("GameRules", Tag::Compound(Compound::new()));
+        }
+        let rules = data
+            .get_compound_mut("GameRules")
+            .ok_or_else(|| "level.dat 中的 GameRules 不是 Compound".to_string())?;
+        for (key, value) in &update.game_rules {
+            let tag = game_rule_tag(rules.get(key), key, value.trim())?;
+            rules.insert(key.clone(), tag);
+        }
+    }
+    Ok(())
+}
+
+/// 按已有标签的类型写入游戏规则，新增的规则按旧版格式存为字符串。
+fn game_rule_tag(existing: Option<&Tag>, key: &str, value: &str) -> Result<Tag, String> {
+    let invalid = || format!("游戏规则 {} 的值无效: {}", key, value);
+    Ok(match existing {
+        Some(Tag::Byte(_)) => Tag::Byte(value.parse::<bool>().map_err(|_| invalid())? as i8),
+        Some(Tag::Int(_)) => Tag::Int(value.parse().map_err(|_| invalid())?),
+        _ => Tag::String(value.to_string()),
+    })
+}
+
+fn replace_seeds(compound: &mut Compound, seed: i64) {
+    if let Some(Tag::Long(existing)) = compound.get_mut("seed") {
+        *existing = seed;
+    }
+    let keys: Vec<String> = compound.iter().map(|(key, _)| key.to_string()).collect();
+    for key in keys {
+        if let Some(child) = compound.get_compound_mut(&key) {
+            replace_seeds(child, seed);
+        }
+    }
+}
+
+/// 备份 level.dat 到应用数据目录，返回备份路径
+pub(crate) fn backup_level_dat(
+    server_id: &str,
+    world: &str,
+    path: &Path,
+) -> Result<PathBuf, String> {
+    let dir = PathBuf::from(crate::utils::path::get_or_create_app_data_dir())
+        .join(LEVEL_BACKUPS_DIR_NAME)
+        .join(server_id);
+    copy_with_retention(path, &dir, world, LEVEL_BACKUP_KEEP)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::world::nbt::Compression;
+    use crate::utils::test_utils::temp_dir;
+
+    /// 1.20 风格的 level.dat
+    fn sample_level() -> NbtDocument {
+        let mut overworld_gen = Compound::new();
+        overworld_gen.insert("seed", Tag::Long(1));
+        let mut overworld = Compound::new();
+        overworld.insert("generator", Tag::Compound(overworld_gen));
+        let mut dimensions = Compound::new();
+        dimensions.insert("minecraft:overworld", Tag::Compound(overworld));
+        let mut world_gen = Compound::new();
+        world_gen.insert("seed", Tag::Long(1));
+        world_gen.insert("dimensions", Tag::Compound(dimensions));
+
+        let mut rules = Compound::new();
+        rules.insert("keepInventory", Tag::String("false".to_string()));
+        rules.insert("spawnRadius", Tag::Int(10));
+
+        let mut packs = Compound::new();
+        packs.insert("Enabled", Tag::list(vec![Tag::String("vanilla".to_string())]));
+        packs.insert("Disabled", Tag::List(crate::services::world::nbt::TAG_END, vec![]));
+
+        let mut version = Compound::new();
+        version.insert("Name", Tag::String("1.20.4".to_string()));
+
+        let mut data = Compound::new();
+        data.insert("LevelName", Tag::String("world".to_string()));
+        data.insert("WorldGenSettings", Tag::Compound(world_gen));
+        data.insert("SpawnX", Tag::Int(8));
+        data.insert("SpawnY", Tag::Int(64));
+        data.insert("SpawnZ", Tag::Int(-8));
+        data.insert("Difficulty", Tag::Byte(2));
+        data.insert("hardcore", Tag::Byte(0));
+        data.insert("GameType", Tag::Int(0));
+        data.insert("GameRules", Tag::Compound(rules));
+        data.insert("DataPacks", Tag::Compound(packs));
+        data.insert("LastPlayed", Tag::Long(1_700_000_000_000));
+        data.insert("DataVersion", Tag::Int(3700));
+        data.insert("Version", Tag::Compound(version));
+        data.insert("CustomField", Tag::String("keep".to_string()));
+
+        let mut root = Compound::new();
+        root.insert("Data", Tag::Compound(data));
+        NbtDocument {
+            name: String::new(),
+            root,
+            compression: Compression::Gzip,
+        }
+    }
+
+    #[test]
+    fn reads_level_fields() {
+        let doc = sample_level();
+        let info = level_info("world".to_string(), data_compound(&doc.root).unwrap());
+        assert_eq!(info.seed.as_deref(), Some("1"));
+        assert_eq!((info.spawn_x, info.spawn_y, info.spawn_z), (Some(8), Some(64), Some(-8)));
+        assert_eq!(info.difficulty, Some(2));
+        assert!(!info.hardcore);
+        assert_eq!(info.game_rules["keepInventory"], "false");
+        assert_eq!(info.game_rules["spawnRadius"], "10");
+        assert_eq!(info.enabled_datapacks, vec!["vanilla"]);
+        assert!(info.disabled_datapacks.is_empty());
+        assert_eq!(info.version_name.as_deref(), Some("1.20.4"));
+        assert_eq!(info.data_version, Some(3700));
+    }
+
+    #[test]
+    fn applies_update_and_keeps_other_fields() {
+        let mut doc = sample_level();
+        let update = LevelUpdate {
+            seed: Some("-4172144997902289642".to_string()),
+            spawn_y: Some(80),
+            hardcore: Some(true),
+            difficulty: Some(3),
+            game_rules: BTreeMap::from([
+                ("keepInventory".to_string(), "true".to_string()),
+                ("spawnRadius".to_string(), "0".to_string()),
+                ("doInsomnia".to_string(), "false".to_string()),
+            ]),
+            ..LevelUpdate::default()
+        };
+        validate_update(&update).unwrap();
+        apply_update(doc.root.get_compound_mut("Data").unwrap(), &update).unwrap();
+
+        let data = data_compound(&doc.root).unwrap();
+        let info = level_info("world".to_string(), data);
+        assert_eq!(info.seed.as_deref(), Some("-4172144997902289642"));
+        assert_eq!((info.spawn_x, info.spawn_y), (Some(8), Some(80)));
+        assert!(info.hardcore);
+        assert_eq!(info.game_rules["keepInventory"], "true");
+        assert_eq!(info.game_rules["doInsomnia"], "false");
+        assert_eq!(data.get_compound("GameRules").unwrap().get("spawnRadius"), Some(&Tag::Int(0)));
+        assert_eq!(data.get_str("CustomField"), Some("keep"));
+        assert!(data.get("RandomSeed").is_none());
+
+        let generator_seed = data
+            .get_compound("WorldGenSettings")
+            .and_then(|c| c.get_compound("dimensions"))
+            .and_then(|c| c.get_compound("minecraft:overworld"))
+            .and_then(|c| c.get_compound("generator"))
+            .and_then(|c| c.get_i64("seed"));
+        assert_eq!(generator_seed, Some(-4172144997902289642));
+    }
+
+    #[test]
+    fn rejects_invalid_updates() {
+        let invalid = LevelUpdate {
+            difficulty: Some(4),
+            ..LevelUpdate::default()
+        };
+        assert!(validate_update(&invalid).is_err());
+        let bad_seed = LevelUpdate {
+            seed: Some("12abc".to_string()),
+            ..LevelUpdate::default()
+        };
+        assert!(validate_update(&bad_seed).is_err());
+
+        let mut doc = sample_level();
+        let bad_rule = LevelUpdate {
+            game_rules: BTreeMap::from([("spawnRadius".to_string(), "far".to_string())]),
+            ..LevelUpdate::default()
+        };
+        assert!(apply_update(doc.root.get_compound_mut("Data").unwrap(), &bad_rule).is_err());
+    }
+
+    #[test]
+    fn reads_file_and_keeps_limited_backups() {
+        let server_dir = temp_dir("server");
+        std::fs::create_dir_all(server_dir.join("world")).unwrap();
+        let level_path = server_dir.join("world/level.dat");
+        sample_level().write_file(&level_path).unwrap();
+
+        let info = read_level_info(&server_dir).unwrap();
+        assert_eq!(info.world, "world");
+        assert_eq!(info.level_name.as_deref(), Some("world"));
+
+        let backup_dir = server_dir.join("backups");
+        for _ in 0..4 {
+            copy_with_retention(&level_path, &backup_dir, "world", 2).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+        assert_eq!(std::fs::read_dir(&backup_dir).unwrap().count(), 2);
+
+        let _ = std::fs::remove_dir_all(&server_dir);
+    }
+}
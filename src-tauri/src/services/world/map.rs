@@ -0,0 +1,479 @@
+//! 由区域文件渲染俯视地图。
+//!
+//! 每个区域文件对应一张 512×512 的 PNG 瓦片（1 像素 = 1 方块），每列取最高的非透明方块着色，
+//! 并按与北侧相邻方块的高度差做明暗处理。渲染状态记录每个区域文件的修改时间与大小，
+//! 再次渲染时只处理发生变化的区域文件。下界从基岩顶棚下方开始查找地表。
+//! 支持 1.13 起的调色板格式区块，更早的区块不渲染。
+//!
+//! 渲染只读取世界文件，服务器运行时也可以执行；正在写入的区块读取失败时会被跳过。
+
+use std::collections::BTreeMap;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use image::{ImageFormat, Rgba, RgbaImage};
+
+use super::blocks;
+use super::layout::{self, DIMENSION_NAMES};
+use super::nbt::{Compound, Tag};
+use super::region::{self, RegionFile};
+use crate::models::world::MapRenderReport;
+
+///此处常量见 utils/constants.rs
+use crate::utils::constants::{MAP_RENDER_STATE_FILE, MAP_TILES_DIR_NAME};
+
+const TILE_SIZE: u32 = 512;
+/// 20w17a 起方块状态数据不再跨越 long 存储
+const NON_SPANNING_DATA_VERSION: i64 = 2527;
+/// 下界基岩顶棚的最高层，渲染下界时从这里向下查找第一个空气之下的方块
+const NETHER_CEILING_Y: i32 = 127;
+
+/// 服务器某个维度的瓦片目录：`<应用数据>/map_tiles/<server_id>/<dimension>`
+pub fn tiles_dir(server_id: &str, dimension: &str) -> Result<PathBuf, String> {
+    validate_dimension(dimension)?;
+    super::validate_path_segment(server_id, "服务器 ID")?;
+    Ok(PathBuf::from(crate::utils::path::get_or_create_app_data_dir())
+        .join(MAP_TILES_DIR_NAME)
+        .join(server_id)
+        .join(dimension))
+}
+
+pub fn tile_file_name(x: i32, z: i32) -> String {
+    format!("r.{}.{}.png", x, z)
+}
+
+/// 解析 `r.<x>.<z>.png` 中的区域坐标
+pub fn parse_tile_file_name(name: &str) -> Option<(i32, i32)> {
+    let mut parts = name.strip_prefix("r.")?.strip_suffix(".png")?.split('.');
+    let x = parts.next()?.parse().ok()?;
+    let z = parts.next()?.parse().ok()?;
+    parts.next().is_none().then_some((x, z))
+}
+
+/// 已渲染的瓦片坐标
+pub fn list_tiles(tiles_dir: &Path) -> Vec<[i32; 2]> {
+    let Ok(entries) = std::fs::read_dir(tiles_dir) else {
+        return Vec::new();
+    };
+    let mut tiles: Vec<[i32; 2]> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| parse_tile_file_name(e.file_name().to_str()?))
+        .map(|(x, z)| [x, z])
+        .collect();
+    tiles.sort();
+    tiles
+}
+
+/// 渲染主世界（`level-name`）某个维度的地图瓦片；`force` 为 true 时忽略渲染状态全部重绘。
+pub fn render_map(
+    server_dir: &Path,
+    tiles_dir: &Path,
+    dimension: &str,
+    force: bool,
+) -> Result<MapRenderReport, String> {
+    validate_dimension(dimension)?;
+    let world = super::level_name(server_dir);
+    super::world_dir(server_dir)?;
+    let region_dir = layout::dimension_dir(server_dir, &world, dimension)
+        .ok_or_else(|| format!("世界 {} 中没有维度 {}", world, dimension))?
+        .join("region");
+
+    std::fs::create_dir_all(tiles_dir).map_err(|e| format!("创建瓦片目录失败: {}", e))?;
+    let state_path = tiles_dir.join(MAP_RENDER_STATE_FILE);
+    let previous: BTreeMap<String, [u64; 2]> = std::fs::read_to_string(&state_path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+
+    let mut regions: Vec<(String, PathBuf, (i32, i32))> = std::fs::read_dir(&region_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter_map(|e| {
+                    let name = e.file_name().to_str()?.to_string();
+                    let coords = region::parse_region_file_name(&name)?;
+                    Some((name, e.path(), coords))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    regions.sort();
+
+    let mut report = MapRenderReport {
+        world,
+        dimension: dimension.to_string(),
+        rendered: 0,
+        unchanged: 0,
+        removed: 0,
+        failed: Vec::new(),
+        tiles: Vec::new(),
+    };
+    let mut state = BTreeMap::new();
+    let ceiling = (dimension == "the_nether").then_some(NETHER_CEILING_Y);
+
+    for (name, path, (x, z)) in &regions {
+        let Some(signature) = file_signature(path) else {
+            continue;
+        };
+        let tile_path = tiles_dir.join(tile_file_name(*x, *z));
+        if !force && previous.get(name) == Some(&signature) && tile_path.is_file() {
+            report.unchanged += 1;
+        } else {
+            match render_region(path, ceiling).and_then(|image| write_png(&tile_path, &image)) {
+                Ok(()) => report.rendered += 1,
+                Err(_) => {
+                    report.failed.push(name.clone());
+                    continue;
+                }
+            }
+        }
+        state.insert(name.clone(), signature);
+    }
+
+    for [x, z] in list_tiles(tiles_dir) {
+        let region_exists = regions.iter().any(|(_, _, coords)| *coords == (x, z));
+        let failed = report.failed.contains(&region::region_file_name(x, z));
+        if !region_exists && !failed {
+            let _ = std::fs::remove_file(tiles_dir.join(tile_file_name(x, z)));
+            report.removed += 1;
+        }
+    }
+
+    let content = serde_json::to_string(&state).map_err(|e| e.to_string())?;
+    std::fs::write(&state_path, content).map_err(|e| format!("写入渲染状态失败: {}", e))?;
+    report.tiles = list_tiles(tiles_dir);
+    Ok(report)
+}
+
+fn validate_dimension(dimension: &str) -> Result<(), String> {
+    if DIMENSION_NAMES.contains(&dimension) {
+        Ok(())
+    } else {
+        Err(format!("未知的维度: {}", dimension))
+    }
+}
+
+/// (修改时间毫秒, 文件大小)
+fn file_signature(path: &Path) -> Option<[u64; 2]> {
+    let metadata = std::fs::metadata(path).ok()?;
+    let modified = metadata
+        .modified()
+        .ok()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?
+        .as_millis() as u64;
+    Some([modified, metadata.len()])
+}
+
+fn write_png(path: &Path, image: &RgbaImage) -> Result<(), String> {
+    let mut buffer = Cursor::new(Vec::new());
+    image
+        .write_to(&mut buffer, ImageFormat::Png)
+        .map_err(|e| format!("编码 PNG 失败: {}", e))?;
+    let tmp = path.with_extension("png.sealantern.tmp");
+    std::fs::write(&tmp, buffer.into_inner())
+        .map_err(|e| format!("写入 {} 失败: {}", tmp.display(), e))?;
+    std::fs::rename(&tmp, path).map_err(|e| {
+        let _ = std::fs::remove_file(&tmp);
+        format!("替换 {} 失败: {}", path.display(), e)
+    })
+}
+
+fn render_region(path: &Path, ceiling: Option<i32>) -> Result<RgbaImage, String> {
+    let region = RegionFile::open(path)?;
+    let size = TILE_SIZE as usize;
+    let mut heights = vec![i32::MIN; size * size];
+    let mut colors = vec![[0u8; 3]; size * size];
+
+    for location in region.chunks() {
+        let Ok(chunk) = region.read_chunk(&location) else {
+            continue;
+        };
+        let sections = read_sections(&chunk);
+        if sections.is_empty() {
+            continue;
+        }
+        let base_x = (location.index % 32) * 16;
+        let base_z = (location.index / 32) * 16;
+        for z in 0..16 {
+            for x in 0..16 {
+                if let Some((y, name)) = column_top(&sections, x, z, ceiling) {
+                    let pixel = (base_z + z) * size + base_x + x;
+                    heights[pixel] = y;
+                    colors[pixel] = blocks::block_color(name);
+                }
+            }
+        }
+    }
+
+    let mut image = RgbaImage::new(TILE_SIZE, TILE_SIZE);
+    for (pixel, &height) in heights.iter().enumerate() {
+        if height == i32::MIN {
+            continue;
+        }
+        // 北侧（z - 1）较低时偏亮、较高时偏暗，形成简单的地形起伏
+        let north = pixel
+            .checked_sub(size)
+            .map(|p| heights[p])
+            .unwrap_or(i32::MIN);
+        let factor = match north {
+            i32::MIN => 1.0,
+            n if height > n => 1.12,
+            n if height < n => 0.86,
+            _ => 1.0,
+        };
+        let [r, g, b] = colors[pixel].map(|c| (c as f32 * factor).min(255.0) as u8);
+        image.put_pixel((pixel % size) as u32, (pixel / size) as u32, Rgba([r, g, b, 255]));
+    }
+    Ok(image)
+}
+
+/// 区块中的一个 16×16×16 子区块
+struct Section {
+    y: i32,
+    /// 只含空气等透明方块
+    empty: bool,
+    palette: Vec<String>,
+    /// 4096 个调色板序号；调色板只有一项时为空
+    indices: Vec<u16>,
+}
+
+impl Section {
+    fn block(&self, x: usize, y: usize, z: usize) -> &str {
+        let index = self
+            .indices
+            .get((y * 16 + z) * 16 + x)
+            .map(|&i| i as usize)
+            .unwrap_or(0);
+        self.palette
+            .get(index)
+            .map(String::as_str)
+            .unwrap_or("minecraft:air")
+    }
+}
+
+/// 按 Y 从高到低返回子区块；兼容 1.18 起的 `sections` 与 1.13–1.17 的 `Level.Sections`。
+fn read_sections(chunk: &Compound) -> Vec<Section> {
+    let data_version = chunk.get_i64("DataVersion").unwrap_or(0);
+    let (list, palette_key, data_key) = match chunk.get("sections").and_then(Tag::as_list) {
+        Some(list) => (list, None, "data"),
+        None => match chunk
+            .get_compound("Level")
+            .and_then(|level| level.get("Sections"))
+            .and_then(Tag::as_list)
+        {
+            Some(list) => (list, Some("Palette"), "BlockStates"),
+            None => return Vec::new(),
+        },
+    };
+
+    let mut sections: Vec<Section> = list
+        .iter()
+        .filter_map(Tag::as_compound)
+        .filter_map(|section| {
+            let y = section.get_i64("Y")? as i32;
+            let states = match palette_key {
+                None => section.get_compound("block_states")?,
+                Some(_) => section,
+            };
+            let palette: Vec<String> = states
+                .get(palette_key.unwrap_or("palette"))
+                .and_then(Tag::as_list)?
+                .iter()
+                .filter_map(|entry| entry.as_compound()?.get_str("Name").map(str::to_string))
+                .collect();
+            if palette.is_empty() {
+                return None;
+            }
+            let empty = palette.iter().all(|name| blocks::is_transparent(name));
+            let indices = if palette.len() == 1 || empty {
+                Vec::new()
+            } else {
+                let Some(Tag::LongArray(data)) = states.get(data_key) else {
+                    return None;
+                };
+                unpack_indices(data, palette.len(), data_version < NON_SPANNING_DATA_VERSION)?
+            };
+            Some(Section { y, empty, palette, indices })
+        })
+        .collect();
+    sections.sort_by_key(|section| std::cmp::Reverse(section.y));
+    sections
+}
+
+/// 解包 4096 个调色板序号；每项至少 4 位。`spanning` 表示旧格式中一项可以跨越两个 long。
+fn unpack_indices(data: &[i64], palette_len: usize, spanning: bool) -> Option<Vec<u16>> {
+    let bits = (usize::BITS - (palette_len - 1).leading_zeros()).max(4) as usize;
+    let mask = (1u64 << bits) - 1;
+    let mut indices = Vec::with_capacity(4096);
+    for i in 0..4096 {
+        let value = if spanning {
+            let bit = i * bits;
+            let (word, offset) = (bit / 64, bit % 64);
+            let mut value = (*data.get(word)? as u64) >> offset;
+            if offset + bits > 64 {
+                value |= (*data.get(word + 1)? as u64) << (64 - offset);
+            }
+            value & mask
+        } else {
+            let per_long = 64 / bits;
+            let word = *data.get(i / per_long)? as u64;
+            (word >> ((i % per_long) * bits)) & mask
+        };
+        indices.push(value as u16);
+    }
+    Some(indices)
+}
+
+/// 一列中最高的非透明方块。
+///
+/// 设置 `ceiling` 时只查找该高度及以下、且上方至少有一格空气的方块，用于越过下界的基岩顶棚；
+/// 未保存的子区块视为空气。
+fn column_top(
+    sections: &[Section],
+    x: usize,
+    z: usize,
+    ceiling: Option<i32>,
+) -> Option<(i32, &str)> {
+    let top = ceiling.unwrap_or(i32::MAX);
+    let mut seen_air = ceiling.is_none();
+    let mut previous_y = None;
+    for section in sections {
+        let base = section.y * 16;
+        if base > top {
+            previous_y = Some(section.y);
+            continue;
+        }
+        if section.empty || previous_y.is_some_and(|y| y - 1 > section.y) {
+            seen_air = true;
+        }
+        previous_y = Some(section.y);
+        if section.empty {
+            continue;
+        }
+        for local_y in (0..16).rev() {
+            let y = base + local_y as i32;
+            if y > top {
+                continue;
+            }
+            let name = section.block(x, local_y, z);
+            if blocks::is_transparent(name) {
+                seen_air = true;
+            } else if seen_air {
+                return Some((y, name));
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::world::region::tests::build_region;
+    use crate::utils::test_utils::temp_dir;
+
+    fn palette(names: &[&str]) -> Tag {
+        Tag::list(
+            names
+                .iter()
+                .map(|name| {
+                    let mut entry = Compound::new();
+                    entry.insert("Name", Tag::String(name.to_string()));
+                    Tag::Compound(entry)
+                })
+                .collect(),
+        )
+    }
+
+    /// 1.18+ 区块：y=64 这一层为草方块，(0, 0) 列额外在 y=65 放一块石头
+    fn modern_chunk() -> Compound {
+        let mut data = vec![0i64; 256];
+        // 4 位一项，每个 long 16 项；y=0 层（局部坐标）即 4 号子区块的最底层
+        for word in data.iter_mut().take(16) {
+            *word = 0x1111_1111_1111_1111;
+        }
+        data[16] = 0x2;
+        let mut states = Compound::new();
+        states.insert(
+            "palette",
+            palette(&["minecraft:air", "minecraft:grass_block", "minecraft:stone"]),
+        );
+        states.insert("data", Tag::LongArray(data));
+        let mut section = Compound::new();
+        section.insert("Y", Tag::Byte(4));
+        section.insert("block_states", Tag::Compound(states));
+
+        let mut air_states = Compound::new();
+        air_states.insert("palette", palette(&["minecraft:air"]));
+        let mut air = Compound::new();
+        air.insert("Y", Tag::Byte(5));
+        air.insert("block_states", Tag::Compound(air_states));
+
+        let mut chunk = Compound::new();
+        chunk.insert("DataVersion", Tag::Int(3700));
+        chunk.insert("sections", Tag::list(vec![Tag::Compound(air), Tag::Compound(section)]));
+        chunk
+    }
+
+    #[test]
+    fn unpacks_spanning_and_packed_indices() {
+        // 5 位一项：旧格式中第 12 项跨越第一个 long 的最高 4 位与第二个 long 的最低 1 位；
+        // 新格式每个 long 只存 12 项，4096 项需要 342 个 long
+        let mut data = vec![0i64; 342];
+        data[0] = (0b1111u64 << 60) as i64;
+        data[1] = 0b1;
+        let spanning = unpack_indices(&data, 17, true).unwrap();
+        assert_eq!(spanning[12], 31);
+        let packed = unpack_indices(&data, 17, false).unwrap();
+        assert_eq!(packed[11], 0);
+        assert_eq!(packed[12], 1);
+        assert!(unpack_indices(&data[..10], 17, false).is_none());
+    }
+
+    #[test]
+    fn finds_column_tops() {
+        let sections = read_sections(&modern_chunk());
+        assert_eq!(sections.len(), 2);
+        assert!(sections[0].empty);
+        assert_eq!(sections[1].block(0, 1, 0), "minecraft:stone");
+        assert_eq!(column_top(&sections, 0, 0, None), Some((65, "minecraft:stone")));
+        assert_eq!(column_top(&sections, 5, 5, None), Some((64, "minecraft:grass_block")));
+        // 顶棚设为 65 时，(0, 0) 列的石头与草方块之间没有空气，整列视为被遮挡
+        assert_eq!(column_top(&sections, 0, 0, Some(65)), None);
+        assert_eq!(column_top(&sections, 5, 5, Some(65)), Some((64, "minecraft:grass_block")));
+    }
+
+    #[test]
+    fn renders_tiles_incrementally() {
+        let server = temp_dir("map");
+        let tiles = server.join("tiles");
+        std::fs::create_dir_all(server.join("world/region")).unwrap();
+        let region_path = server.join("world/region/r.0.-1.mca");
+        std::fs::write(&region_path, build_region(&[(33, modern_chunk())])).unwrap();
+
+        let report = render_map(&server, &tiles, "overworld", false).unwrap();
+        assert_eq!((report.rendered, report.unchanged), (1, 0));
+        assert_eq!(report.tiles, vec![[0, -1]]);
+        let image = image::open(tiles.join("r.0.-1.png")).unwrap().to_rgba8();
+        assert_eq!(image.dimensions(), (TILE_SIZE, TILE_SIZE));
+        assert_eq!(image.get_pixel(0, 0)[3], 0);
+        // 区块序号 33 位于 (16, 16)，(17, 17) 为草方块
+        let grass = image.get_pixel(17, 17);
+        assert_eq!(grass[3], 255);
+        assert!(grass[1] > grass[0] && grass[1] > grass[2]);
+
+        let again = render_map(&server, &tiles, "overworld", false).unwrap();
+        assert_eq!((again.rendered, again.unchanged), (0, 1));
+        let forced = render_map(&server, &tiles, "overworld", true).unwrap();
+        assert_eq!(forced.rendered, 1);
+
+        std::fs::remove_file(&region_path).unwrap();
+        let removed = render_map(&server, &tiles, "overworld", false).unwrap();
+        assert_eq!(removed.removed, 1);
+        assert!(removed.tiles.is_empty());
+        assert!(render_map(&server, &tiles, "the_end", false).is_err());
+
+        let _ = std::fs::remove_dir_all(&server);
+    }
+}
@@ -0,0 +1,170 @@
+//! world 子模块：世界存档相关服务逻辑。
+//!
+//! - nbt.rs：Java 版 NBT 编解码（gzip / zlib / 未压缩）。
+//! - datapacks.rs：世界数据包的列出、安装、删除与启用状态（level.dat DataPacks）编辑。
+//! - layout.rs：原版与 Bukkit 维度目录布局的转换。
+//! - level.rs：level.dat 的读取与带备份的编辑。
+//! - region.rs：Anvil 区域文件（.mca）的读取与紧凑重写。
+//! - prune.rs：按 InhabitedTime、出生点半径、区域坐标删除区块。
+//! - blocks.rs / map.rs：方块颜色表与俯视地图 PNG 瓦片的增量渲染。
+//! - playerdata.rs：playerdata/<uuid>.dat 中的玩家数据（背包、位置等）查看与物品删除。
+//! - worlds.rs：服务器目录中世界的列出、上传、单人存档导入、重置与切换。
+
+pub mod blocks;
+pub mod datapacks;
+pub mod layout;
+pub mod level;
+pub mod map;
+pub mod nbt;
+pub mod playerdata;
+pub mod prune;
+pub mod region;
+pub mod worlds;
+
+use std::path::{Component, Path, PathBuf};
+
+use crate::services::server::manager::current_timestamp_millis;
+
+/// 主世界目录名：server.properties 中的 `level-name`，缺省为 `world`。
+pub fn level_name(server_dir: &Path) -> String {
+    server_dir
+        .join("server.properties")
+        .to_str()
+        .and_then(|path| crate::services::server::config::read_properties(path).ok())
+        .and_then(|props| props.get("level-name").cloned())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "world".to_string())
+}
+
+/// 主世界目录；`level-name` 不是服务器目录内的相对路径时报错。
+pub fn world_dir(server_dir: &Path) -> Result<PathBuf, String> {
+    let name = level_name(server_dir);
+    let valid = Path::new(&name)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)));
+    if !valid {
+        return Err(format!("非法的世界目录名: {}", name));
+    }
+    Ok(server_dir.join(name))
+}
+
+/// 校验名称只是单个路径段（不含分隔符、`..`，也不以 `.` 开头），`kind` 用于错误信息。
+pub(crate) fn validate_path_segment(name: &str, kind: &str) -> Result<(), String> {
+    let mut components = Path::new(name).components();
+    let single =
+        matches!(components.next(), Some(Component::Normal(_))) && components.next().is_none();
+    if !single || name.starts_with('.') || name.contains(['/', '\\']) {
+        return Err(format!("非法的{}: {}", kind, name));
+    }
+    Ok(())
+}
+
+/// 主世界的 `session.lock` 是否被某个服务端进程锁定。
+///
+/// 1.16 起服务端运行期间会一直持有该锁，可据此确认端口 / RCON 另一端就是这台服务器，
+/// 而不是共用端口的其他服务器；更早的版本不加锁，此时返回 false。
+pub fn is_world_in_use(server_dir: &Path) -> bool {
+    let Ok(world) = world_dir(server_dir) else {
+        return false;
+    };
+    std::fs::File::open(world.join("session.lock"))
+        .map(|file| session_lock_held(&file))
+        .unwrap_or(false)
+}
+
+/// JVM 在 Unix 上用 fcntl 记录锁实现 `FileChannel.tryLock`，flock 探测不到，需用 F_GETLK 查询。
+#[cfg(unix)]
+fn session_lock_held(file: &std::fs::File) -> bool {
+    use std::os::unix::io::AsRawFd;
+
+    // SAFETY: flock 是纯数据结构体，全零即合法初值；fd 在 file 存活期间有效
+    let mut lock: libc::flock = unsafe { std::mem::zeroed() };
+    lock.l_type = libc::F_WRLCK as libc::c_short;
+    lock.l_whence = libc::SEEK_SET as libc::c_short;
+    let result = unsafe { libc::fcntl(file.as_raw_fd(), libc::F_GETLK, &mut lock) };
+    result == 0 && libc::c_int::from(lock.l_type) != libc::F_UNLCK
+}
+
+/// Windows 上的 JVM 用 LockFileEx 独占锁定整个文件，其他进程读取被锁区域会得到
+/// ERROR_LOCK_VIOLATION。这里只读取一个字节，不取得锁，不会让随后启动的服务端加锁失败。
+#[cfg(windows)]
+fn session_lock_held(file: &std::fs::File) -> bool {
+    use std::io::Read;
+    use windows::Win32::Foundation::ERROR_LOCK_VIOLATION;
+
+    let mut buf = [0u8; 1];
+    matches!(
+        (&*file).read(&mut buf),
+        Err(err) if err.raw_os_error() == Some(ERROR_LOCK_VIOLATION.0 as i32)
+    )
+}
+
+#[cfg(not(any(unix, windows)))]
+fn session_lock_held(_file: &std::fs::File) -> bool {
+    false
+}
+
+/// 递归复制目录，跳过符号链接与名称在 `skip` 中的文件。
+pub(crate) fn copy_dir(source: &Path, target: &Path, skip: &[&str]) -> std::io::Result<()> {
+    std::fs::create_dir_all(target)?;
+    for entry in std::fs::read_dir(source)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let destination = target.join(entry.file_name());
+        if file_type.is_dir() {
+            copy_dir(&entry.path(), &destination, skip)?;
+        } else if file_type.is_file()
+            && !entry
+                .file_name()
+                .to_str()
+                .is_some_and(|n| skip.contains(&n))
+        {
+            std::fs::copy(entry.path(), &destination)?;
+        }
+    }
+    Ok(())
+}
+
+/// 把 `path` 复制到 `dir` 下的 `<name>-<毫秒时间戳>.dat`，同名前缀的备份只保留最近 `keep` 份。
+pub(crate) fn copy_with_retention(
+    path: &Path,
+    dir: &Path,
+    name: &str,
+    keep: usize,
+) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("创建备份目录失败: {}", e))?;
+    let prefix: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>()
+        + "-";
+    let millis = current_timestamp_millis();
+    let target = dir.join(format!("{}{}.dat", prefix, millis));
+    std::fs::copy(path, &target).map_err(|e| format!("备份 {} 失败: {}", path.display(), e))?;
+
+    let mut existing: Vec<(i64, PathBuf)> = std::fs::read_dir(dir)
+        .map_err(|e| format!("读取备份目录失败: {}", e))?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let stamp = name
+                .strip_prefix(&prefix)?
+                .strip_suffix(".dat")?
+                .parse()
+                .ok()?;
+            Some((stamp, entry.path()))
+        })
+        .collect();
+    existing.sort_by_key(|(stamp, _)| std::cmp::Reverse(*stamp));
+    for (_, old) in existing.into_iter().skip(keep) {
+        let _ = std::fs::remove_file(old);
+    }
+    Ok(target)
+}
@@ -0,0 +1,673 @@
+//! Java 版 NBT 编解码。
+//!
+//! 数据一律为大端；文件可能经过 gzip（level.dat、playerdata）、zlib（区域文件中的区块）
+//! 压缩，也可能未压缩。字符串使用 Java 的 Modified UTF-8。
+//! Compound 保留原有的键顺序、List 保留元素类型，读入后原样写出的内容与原文件一致。
+
+use std::io::{Read, Write};
+use std::path::Path;
+
+use flate2::read::{GzDecoder, ZlibDecoder};
+use flate2::write::{GzEncoder, ZlibEncoder};
+
+pub const TAG_END: u8 = 0;
+pub const TAG_BYTE: u8 = 1;
+pub const TAG_SHORT: u8 = 2;
+pub const TAG_INT: u8 = 3;
+pub const TAG_LONG: u8 = 4;
+pub const TAG_FLOAT: u8 = 5;
+pub const TAG_DOUBLE: u8 = 6;
+pub const TAG_BYTE_ARRAY: u8 = 7;
+pub const TAG_STRING: u8 = 8;
+pub const TAG_LIST: u8 = 9;
+pub const TAG_COMPOUND: u8 = 10;
+pub const TAG_INT_ARRAY: u8 = 11;
+pub const TAG_LONG_ARRAY: u8 = 12;
+
+/// 与原版一致的最大嵌套深度
+const MAX_DEPTH: usize = 512;
+/// 解压后的数据上限，防止压缩炸弹
+const MAX_DECOMPRESSED_LEN: u64 = 256 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Tag {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(Vec<i8>),
+    String(String),
+    /// 元素类型 + 元素；空列表的元素类型通常为 `TAG_END`
+    List(u8, Vec<Tag>),
+    Compound(Compound),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+}
+
+impl Tag {
+    /// 以首个元素的类型构造列表，空列表的元素类型为 `TAG_END`。
+    pub fn list(items: Vec<Tag>) -> Tag {
+        let element_type = items.first().map(Tag::id).unwrap_or(TAG_END);
+        Tag::List(element_type, items)
+    }
+
+    pub fn id(&self) -> u8 {
+        match self {
+            Tag::Byte(_) => TAG_BYTE,
+            Tag::Short(_) => TAG_SHORT,
+            Tag::Int(_) => TAG_INT,
+            Tag::Long(_) => TAG_LONG,
+            Tag::Float(_) => TAG_FLOAT,
+            Tag::Double(_) => TAG_DOUBLE,
+            Tag::ByteArray(_) => TAG_BYTE_ARRAY,
+            Tag::String(_) => TAG_STRING,
+            Tag::List(..) => TAG_LIST,
+            Tag::Compound(_) => TAG_COMPOUND,
+            Tag::IntArray(_) => TAG_INT_ARRAY,
+            Tag::LongArray(_) => TAG_LONG_ARRAY,
+        }
+    }
+
+    /// 整数类标签统一转为 i64。
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Tag::Byte(v) => Some(*v as i64),
+            Tag::Short(v) => Some(*v as i64),
+            Tag::Int(v) => Some(*v as i64),
+            Tag::Long(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// 数值类标签统一转为 f64。
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Tag::Float(v) => Some(*v as f64),
+            Tag::Double(v) => Some(*v),
+            other => other.as_i64().map(|v| v as f64),
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Tag::String(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[Tag]> {
+        match self {
+            Tag::List(_, items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn as_compound(&self) -> Option<&Compound> {
+        match self {
+            Tag::Compound(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_compound_mut(&mut self) -> Option<&mut Compound> {
+        match self {
+            Tag::Compound(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// 转为 JSON 便于前端展示；超出 JavaScript 安全整数范围的 Long 以字符串表示。
+    pub fn to_json(&self) -> serde_json::Value {
+        use serde_json::Value;
+        const MAX_SAFE_INTEGER: i64 = (1 << 53) - 1;
+        let long = |v: i64| {
+            if (-MAX_SAFE_INTEGER..=MAX_SAFE_INTEGER).contains(&v) {
+                Value::from(v)
+            } else {
+                Value::from(v.to_string())
+            }
+        };
+        match self {
+            Tag::Byte(v) => Value::from(*v),
+            Tag::Short(v) => Value::from(*v),
+            Tag::Int(v) => Value::from(*v),
+            Tag::Long(v) => long(*v),
+            Tag::Float(v) => Value::from(*v),
+            Tag::Double(v) => Value::from(*v),
+            Tag::ByteArray(values) => Value::from(values.clone()),
+            Tag::String(v) => Value::from(v.as_str()),
+            Tag::List(_, items) => Value::Array(items.iter().map(Tag::to_json).collect()),
+            Tag::Compound(compound) => compound.to_json(),
+            Tag::IntArray(values) => Value::from(values.clone()),
+            Tag::LongArray(values) => Value::Array(values.iter().map(|v| long(*v)).collect()),
+        }
+    }
+}
+
+/// 保持插入顺序的 Compound。
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Compound(Vec<(String, Tag)>);
+
+impl Compound {
+    pub fn new() -> Self {
+        Compound(Vec::new())
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Tag> {
+        self.0.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Tag> {
+        self.0.iter_mut().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    /// 已存在的键原位替换（保持顺序），否则追加到末尾。
+    pub fn insert(&mut self, key: impl Into<String>, tag: Tag) -> Option<Tag> {
+        let key = key.into();
+        match self.get_mut(&key) {
+            Some(existing) => Some(std::mem::replace(existing, tag)),
+            None => {
+                self.0.push((key, tag));
+                None
+            }
+        }
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<Tag> {
+        let index = self.0.iter().position(|(k, _)| k == key)?;
+        Some(self.0.remove(index).1)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Tag)> {
+        self.0.iter().map(|(k, v)| (k.as_str(), v))
+    }
+
+    pub fn get_i64(&self, key: &str) -> Option<i64> {
+        self.get(key).and_then(Tag::as_i64)
+    }
+
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(Tag::as_str)
+    }
+
+    pub fn get_compound(&self, key: &str) -> Option<&Compound> {
+        self.get(key).and_then(Tag::as_compound)
+    }
+
+    pub fn get_compound_mut(&mut self, key: &str) -> Option<&mut Compound> {
+        self.get_mut(key).and_then(Tag::as_compound_mut)
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::Value::Object(
+            self.0
+                .iter()
+                .map(|(k, v)| (k.clone(), v.to_json()))
+                .collect(),
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zlib,
+}
+
+impl Compression {
+    /// 按魔数判断压缩方式：gzip 以 `1f 8b` 开头，zlib 的首字节为 `0x78`。
+    pub fn detect(data: &[u8]) -> Compression {
+        match data {
+            [0x1f, 0x8b, ..] => Compression::Gzip,
+            [0x78, ..] => Compression::Zlib,
+            _ => Compression::None,
+        }
+    }
+}
+
+/// 一个完整的 NBT 文件：根 Compound 及其名称，并记住原来的压缩方式以便原样写回。
+#[derive(Debug, Clone, PartialEq)]
+pub struct NbtDocument {
+    pub name: String,
+    pub root: Compound,
+    pub compression: Compression,
+}
+
+impl NbtDocument {
+    pub fn from_bytes(data: &[u8]) -> Result<Self, String> {
+        let compression = Compression::detect(data);
+        let raw = decompress(data, compression)?;
+        let (name, root) = decode(&raw)?;
+        Ok(NbtDocument { name, root, compression })
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        compress(&encode(&self.name, &self.root)?, self.compression)
+    }
+
+    pub fn read_file(path: &Path) -> Result<Self, String> {
+        let data =
+            std::fs::read(path).map_err(|e| format!("读取 {} 失败: {}", path.display(), e))?;
+        Self::from_bytes(&data).map_err(|e| format!("解析 {} 失败: {}", path.display(), e))
+    }
+
+    /// 先写临时文件再替换，写入中途失败不会损坏原文件。
+    pub fn write_file(&self, path: &Path) -> Result<(), String> {
+        let data = self.to_bytes()?;
+        let tmp_path = path.with_extension("sealantern.tmp");
+        std::fs::write(&tmp_path, data)
+            .map_err(|e| format!("写入 {} 失败: {}", path.display(), e))?;
+        std::fs::rename(&tmp_path, path).map_err(|e| {
+            let _ = std::fs::remove_file(&tmp_path);
+            format!("写入 {} 失败: {}", path.display(), e)
+        })
+    }
+}
+
+pub fn decompress(data: &[u8], compression: Compression) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    let result = match compression {
+        Compression::None => return Ok(data.to_vec()),
+        Compression::Gzip => GzDecoder::new(data)
+            .take(MAX_DECOMPRESSED_LEN)
+            .read_to_end(&mut out),
+        Compression::Zlib => ZlibDecoder::new(data)
+            .take(MAX_DECOMPRESSED_LEN)
+            .read_to_end(&mut out),
+    };
+    result.map_err(|e| format!("解压 NBT 数据失败: {}", e))?;
+    Ok(out)
+}
+
+pub fn compress(data: &[u8], compression: Compression) -> Result<Vec<u8>, String> {
+    let level = flate2::Compression::default();
+    let result = match compression {
+        Compression::None => return Ok(data.to_vec()),
+        Compression::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), level);
+            encoder.write_all(data).and_then(|_| encoder.finish())
+        }
+        Compression::Zlib => {
+            let mut encoder = ZlibEncoder::new(Vec::new(), level);
+            encoder.write_all(data).and_then(|_| encoder.finish())
+        }
+    };
+    result.map_err(|e| format!("压缩 NBT 数据失败: {}", e))
+}
+
+/// 解码未压缩的 NBT，返回根标签的名称与内容。根标签必须是 Compound。
+pub fn decode(data: &[u8]) -> Result<(String, Compound), String> {
+    let mut reader = Reader { data, pos: 0 };
+    let kind = reader.u8()?;
+    if kind != TAG_COMPOUND {
+        return Err(format!("根标签不是 Compound（类型 {}）", kind));
+    }
+    let name = reader.string()?;
+    match reader.payload(TAG_COMPOUND, 0)? {
+        Tag::Compound(root) => Ok((name, root)),
+        _ => unreachable!(),
+    }
+}
+
+/// 编码为未压缩的 NBT。
+pub fn encode(name: &str, root: &Compound) -> Result<Vec<u8>, String> {
+    let mut out = vec![TAG_COMPOUND];
+    write_string(&mut out, name)?;
+    write_compound(&mut out, root)?;
+    Ok(out)
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| "NBT 数据意外结束".to_string())?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        let mut buf = [0u8; N];
+        buf.copy_from_slice(self.take(N)?);
+        Ok(buf)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn i32(&mut self) -> Result<i32, String> {
+        Ok(i32::from_be_bytes(self.array()?))
+    }
+
+    /// 读取数组长度，并确认剩余数据足够，避免按异常长度预分配内存。
+    fn array_len(&mut self, element_size: usize) -> Result<usize, String> {
+        let len = self.i32()?;
+        if len < 0 {
+            return Err(format!("NBT 长度为负数: {}", len));
+        }
+        let len = len as usize;
+        if len.saturating_mul(element_size) > self.data.len() - self.pos {
+            return Err("NBT 数据意外结束".to_string());
+        }
+        Ok(len)
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let len = u16::from_be_bytes(self.array()?) as usize;
+        decode_mutf8(self.take(len)?)
+    }
+
+    fn payload(&mut self, kind: u8, depth: usize) -> Result<Tag, String> {
+        if depth > MAX_DEPTH {
+            return Err(format!("NBT 嵌套层级超过 {}", MAX_DEPTH));
+        }
+        Ok(match kind {
+            TAG_BYTE => Tag::Byte(self.u8()? as i8),
+            TAG_SHORT => Tag::Short(i16::from_be_bytes(self.array()?)),
+            TAG_INT => Tag::Int(self.i32()?),
+            TAG_LONG => Tag::Long(i64::from_be_bytes(self.array()?)),
+            TAG_FLOAT => Tag::Float(f32::from_be_bytes(self.array()?)),
+            TAG_DOUBLE => Tag::Double(f64::from_be_bytes(self.array()?)),
+            TAG_BYTE_ARRAY => {
+                let len = self.array_len(1)?;
+                Tag::ByteArray(self.take(len)?.iter().map(|b| *b as i8).collect())
+            }
+            TAG_STRING => Tag::String(self.string()?),
+            TAG_LIST => {
+                let element_type = self.u8()?;
+                let len = self.array_len(0)?;
+                if element_type == TAG_END && len > 0 {
+                    return Err("NBT 列表缺少元素类型".to_string());
+                }
+                let mut items = Vec::new();
+                for _ in 0..len {
+                    items.push(self.payload(element_type, depth + 1)?);
+                }
+                Tag::List(element_type, items)
+            }
+            TAG_COMPOUND => {
+                let mut compound = Compound::new();
+                loop {
+                    let child = self.u8()?;
+                    if child == TAG_END {
+                        break;
+                    }
+                    let name = self.string()?;
+                    let value = self.payload(child, depth + 1)?;
+                    compound.0.push((name, value));
+                }
+                Tag::Compound(compound)
+            }
+            TAG_INT_ARRAY => {
+                let len = self.array_len(4)?;
+                let mut values = Vec::with_capacity(len);
+                for _ in 0..len {
+                    values.push(self.i32()?);
+                }
+                Tag::IntArray(values)
+            }
+            TAG_LONG_ARRAY => {
+                let len = self.array_len(8)?;
+                let mut values = Vec::with_capacity(len);
+                for _ in 0..len {
+                    values.push(i64::from_be_bytes(self.array()?));
+                }
+                Tag::LongArray(values)
+            }
+            other => return Err(format!("未知的 NBT 标签类型: {}", other)),
+        })
+    }
+}
+
+fn write_len(out: &mut Vec<u8>, len: usize) -> Result<(), String> {
+    let len = i32::try_from(len).map_err(|_| "NBT 数组过长".to_string())?;
+    out.extend_from_slice(&len.to_be_bytes());
+    Ok(())
+}
+
+fn write_string(out: &mut Vec<u8>, value: &str) -> Result<(), String> {
+    let bytes = encode_mutf8(value);
+    let len = u16::try_from(bytes.len()).map_err(|_| "NBT 字符串过长".to_string())?;
+    out.extend_from_slice(&len.to_be_bytes());
+    out.extend_from_slice(&bytes);
+    Ok(())
+}
+
+fn write_compound(out: &mut Vec<u8>, compound: &Compound) -> Result<(), String> {
+    for (name, tag) in &compound.0 {
+        out.push(tag.id());
+        write_string(out, name)?;
+        write_payload(out, tag)?;
+    }
+    out.push(TAG_END);
+    Ok(())
+}
+
+fn write_payload(out: &mut Vec<u8>, tag: &Tag) -> Result<(), String> {
+    match tag {
+        Tag::Byte(v) => out.push(*v as u8),
+        Tag::Short(v) => out.extend_from_slice(&v.to_be_bytes()),
+        Tag::Int(v) => out.extend_from_slice(&v.to_be_bytes()),
+        Tag::Long(v) => out.extend_from_slice(&v.to_be_bytes()),
+        Tag::Float(v) => out.extend_from_slice(&v.to_be_bytes()),
+        Tag::Double(v) => out.extend_from_slice(&v.to_be_bytes()),
+        Tag::ByteArray(values) => {
+            write_len(out, values.len())?;
+            out.extend(values.iter().map(|b| *b as u8));
+        }
+        Tag::String(v) => write_string(out, v)?,
+        Tag::List(element_type, items) => {
+            if let Some(item) = items.iter().find(|item| item.id() != *element_type) {
+                return Err(format!(
+                    "NBT 列表元素类型不一致: 期望 {}，实际 {}",
+                    element_type,
+                    item.id()
+                ));
+            }
+            out.push(*element_type);
+            write_len(out, items.len())?;
+            for item in items {
+                write_payload(out, item)?;
+            }
+        }
+        Tag::Compound(compound) => write_compound(out, compound)?,
+        Tag::IntArray(values) => {
+            write_len(out, values.len())?;
+            for v in values {
+                out.extend_from_slice(&v.to_be_bytes());
+            }
+        }
+        Tag::LongArray(values) => {
+            write_len(out, values.len())?;
+            for v in values {
+                out.extend_from_slice(&v.to_be_bytes());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Modified UTF-8 → String：`\0` 编码为 `C0 80`，补充平面字符编码为两个 3 字节的代理项。
+/// 也接受标准 UTF-8 的 4 字节序列。
+fn decode_mutf8(bytes: &[u8]) -> Result<String, String> {
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return Ok(text.to_string());
+    }
+
+    let invalid = || "NBT 字符串编码无效".to_string();
+    let cont = |i: usize| -> Result<u32, String> {
+        bytes
+            .get(i)
+            .filter(|b| *b & 0xC0 == 0x80)
+            .map(|b| (*b & 0x3F) as u32)
+            .ok_or_else(invalid)
+    };
+
+    let mut units: Vec<u16> = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i] as u32;
+        if b < 0x80 {
+            units.push(b as u16);
+            i += 1;
+        } else if b & 0xE0 == 0xC0 {
+            units.push((((b & 0x1F) << 6) | cont(i + 1)?) as u16);
+            i += 2;
+        } else if b & 0xF0 == 0xE0 {
+            units.push((((b & 0x0F) << 12) | (cont(i + 1)? << 6) | cont(i + 2)?) as u16);
+            i += 3;
+        } else if b & 0xF8 == 0xF0 {
+            let code =
+                ((b & 0x07) << 18) | (cont(i + 1)? << 12) | (cont(i + 2)? << 6) | cont(i + 3)?;
+            let ch = char::from_u32(code).ok_or_else(invalid)?;
+            let mut buf = [0u16; 2];
+            units.extend_from_slice(ch.encode_utf16(&mut buf));
+            i += 4;
+        } else {
+            return Err(invalid());
+        }
+    }
+    Ok(String::from_utf16_lossy(&units))
+}
+
+fn encode_mutf8(value: &str) -> Vec<u8> {
+    if !value.chars().any(|c| c == '\0' || c as u32 > 0xFFFF) {
+        return value.as_bytes().to_vec();
+    }
+
+    let mut out = Vec::with_capacity(value.len() + 8);
+    for unit in value.encode_utf16() {
+        let unit = unit as u32;
+        if unit != 0 && unit < 0x80 {
+            out.push(unit as u8);
+        } else if unit < 0x800 {
+            out.push((0xC0 | (unit >> 6)) as u8);
+            out.push((0x80 | (unit & 0x3F)) as u8);
+        } else {
+            out.push((0xE0 | (unit >> 12)) as u8);
+            out.push((0x80 | ((unit >> 6) & 0x3F)) as u8);
+            out.push((0x80 | (unit & 0x3F)) as u8);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Compound {
+        let mut inner = Compound::new();
+        inner.insert("name", Tag::String("Steve".to_string()));
+        inner.insert("score", Tag::Float(1.5));
+
+        let mut root = Compound::new();
+        root.insert("byte", Tag::Byte(-1));
+        root.insert("short", Tag::Short(300));
+        root.insert("int", Tag::Int(-70000));
+        root.insert("long", Tag::Long(1 << 40));
+        root.insert("double", Tag::Double(0.25));
+        root.insert("bytes", Tag::ByteArray(vec![1, -2, 3]));
+        root.insert("ints", Tag::IntArray(vec![1, -2]));
+        root.insert("longs", Tag::LongArray(vec![i64::MIN, i64::MAX]));
+        root.insert("empty", Tag::List(TAG_END, Vec::new()));
+        root.insert("typed_empty", Tag::List(TAG_COMPOUND, Vec::new()));
+        root.insert("list", Tag::list(vec![Tag::Compound(inner.clone())]));
+        root.insert("inner", Tag::Compound(inner));
+        root
+    }
+
+    #[test]
+    fn round_trips_all_tag_types_and_compressions() {
+        for compression in [Compression::None, Compression::Gzip, Compression::Zlib] {
+            let doc = NbtDocument {
+                name: "Data".to_string(),
+                root: sample(),
+                compression,
+            };
+            let bytes = doc.to_bytes().unwrap();
+            assert_eq!(Compression::detect(&bytes), compression);
+            assert_eq!(NbtDocument::from_bytes(&bytes).unwrap(), doc);
+        }
+    }
+
+    #[test]
+    fn encodes_big_endian_layout() {
+        let mut root = Compound::new();
+        root.insert("a", Tag::Short(1));
+        let bytes = encode("", &root).unwrap();
+        assert_eq!(bytes, vec![10, 0, 0, 2, 0, 1, b'a', 0, 1, 0]);
+    }
+
+    #[test]
+    fn insert_keeps_key_order() {
+        let mut root = sample();
+        root.insert("byte", Tag::Byte(5));
+        assert_eq!(root.iter().next().unwrap(), ("byte", &Tag::Byte(5)));
+        assert_eq!(root.remove("short"), Some(Tag::Short(300)));
+        assert!(root.get("short").is_none());
+    }
+
+    #[test]
+    fn handles_modified_utf8() {
+        let text = "a\0b😀中";
+        let bytes = encode_mutf8(text);
+        assert_eq!(&bytes[1..3], &[0xC0, 0x80]);
+        assert!(std::str::from_utf8(&bytes).is_err());
+        assert_eq!(decode_mutf8(&bytes).unwrap(), text);
+        assert_eq!(decode_mutf8("😀".as_bytes()).unwrap(), "😀");
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        assert!(decode(&[10, 0, 0, 3, 0, 1]).is_err());
+        assert!(decode(&[8, 0, 0]).is_err());
+        // 长度声明远超实际数据
+        assert!(decode(&[10, 0, 0, 11, 0, 1, b'a', 0x7f, 0xff, 0xff, 0xff]).is_err());
+        // 元素类型为 End 却声明了元素
+        assert!(decode(&[10, 0, 0, 9, 0, 1, b'a', 0, 0, 0, 0, 1, 0]).is_err());
+
+        let mut nested = vec![10, 0, 0];
+        for _ in 0..600 {
+            nested.extend_from_slice(&[10, 0, 0]);
+        }
+        assert!(decode(&nested).unwrap_err().contains("嵌套"));
+    }
+
+    #[test]
+    fn converts_to_json() {
+        let json = sample().to_json();
+        assert_eq!(json["byte"], -1);
+        assert_eq!(json["long"], 1i64 << 40);
+        assert_eq!(json["longs"][0], i64::MIN.to_string());
+        assert_eq!(json["list"][0]["name"], "Steve");
+        assert_eq!(json["inner"]["score"], 1.5);
+    }
+
+    #[test]
+    fn rejects_mixed_list_on_write() {
+        let mut root = Compound::new();
+        root.insert("l", Tag::List(TAG_INT, vec![Tag::Int(1), Tag::Byte(2)]));
+        assert!(encode("", &root).is_err());
+    }
+}
@@ -0,0 +1,429 @@
+//! playerdata/<uuid>.dat 玩家数据的查看与物品删除。
+//!
+//! 玩家名来自服务端的 usercache.json，缺失时使用 Bukkit 记录的 `lastKnownName`。
+//! 1.20.5 起物品格式由 `Count` + `tag` 改为 `count` + `components`；1.21.5 起护甲与副手
+//! 移到了 `equipment`，这里映射回旧的 100-103 / -106 槽位，两种格式都能读取和删除。
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use super::copy_with_retention;
+use super::nbt::{Compound, NbtDocument, Tag};
+use crate::models::world::{
+    ItemContainer, ItemRemoval, ItemRemovalResult, ItemStack, PlayerData, PlayerDataSummary,
+};
+use crate::services::server::player::read_usercache;
+
+///此处常量见 utils/constants.rs
+use crate::utils::constants::{PLAYERDATA_BACKUPS_DIR_NAME, PLAYERDATA_BACKUP_KEEP};
+
+/// 1.21.5 起 `equipment` 中的键与旧版背包槽位的对应关系
+const EQUIPMENT_SLOTS: [(&str, i8); 5] =
+    [("feet", 100), ("legs", 101), ("chest", 102), ("head", 103), ("offhand", -106)];
+
+pub fn list_player_data(server_dir: &Path) -> Result<Vec<PlayerDataSummary>, String> {
+    let dir = playerdata_dir(server_dir)?;
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let names = usercache_names(server_dir);
+
+    let mut players: Vec<PlayerDataSummary> = std::fs::read_dir(&dir)
+        .map_err(|e| format!("读取 playerdata 目录失败: {}", e))?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            let stem = path.file_name()?.to_str()?.strip_suffix(".dat")?;
+            let uuid = normalize_uuid(stem).ok()?;
+            let root = NbtDocument::read_file(&path).ok().map(|doc| doc.root);
+            Some(summary(&uuid, &path, root.as_ref(), &names))
+        })
+        .collect();
+    players.sort_by_key(|p| std::cmp::Reverse(p.last_login.unwrap_or(p.modified_at as i64 * 1000)));
+    Ok(players)
+}
+
+pub fn get_player_data(server_dir: &Path, uuid: &str) -> Result<PlayerData, String> {
+    let uuid = normalize_uuid(uuid)?;
+    let path = player_file(server_dir, &uuid)?;
+    let doc = NbtDocument::read_file(&path)?;
+    Ok(player_data(&uuid, &path, &doc.root, &usercache_names(server_dir)))
+}
+
+/// 备份玩家文件后删除指定槽位的物品。
+pub fn remove_items(
+    server_id: &str,
+    server_dir: &Path,
+    uuid: &str,
+    removals: &[ItemRemoval],
+) -> Result<ItemRemovalResult, String> {
+    let uuid = normalize_uuid(uuid)?;
+    let path = player_file(server_dir, &uuid)?;
+    let mut doc = NbtDocument::read_file(&path)?;
+
+    let removed = remove_from_root(&mut doc.root, removals);
+    if removed == 0 {
+        return Err("没有找到要删除的物品".to_string());
+    }
+
+    let backup_dir = PathBuf::from(crate::utils::path::get_or_create_app_data_dir())
+        .join(PLAYERDATA_BACKUPS_DIR_NAME)
+        .join(server_id);
+    let backup_path = copy_with_retention(&path, &backup_dir, &uuid, PLAYERDATA_BACKUP_KEEP)?;
+    doc.write_file(&path)?;
+
+    Ok(ItemRemovalResult {
+        data: player_data(&uuid, &path, &doc.root, &usercache_names(server_dir)),
+        removed,
+        backup_path: backup_path.to_string_lossy().to_string(),
+    })
+}
+
+fn playerdata_dir(server_dir: &Path) -> Result<PathBuf, String> {
+    Ok(super::world_dir(server_dir)?.join("playerdata"))
+}
+
+fn player_file(server_dir: &Path, uuid: &str) -> Result<PathBuf, String> {
+    let path = playerdata_dir(server_dir)?.join(format!("{}.dat", uuid));
+    if !path.is_file() {
+        return Err(format!("未找到玩家数据: {}", uuid));
+    }
+    Ok(path)
+}
+
+/// 校验并统一为带连字符的小写 UUID，同时防止借文件名访问其他路径。
+fn normalize_uuid(raw: &str) -> Result<String, String> {
+    uuid::Uuid::parse_str(raw.trim())
+        .map(|id| id.hyphenated().to_string())
+        .map_err(|_| format!("无效的玩家 UUID: {}", raw))
+}
+
+fn usercache_names(server_dir: &Path) -> HashMap<String, String> {
+    read_usercache(server_dir)
+        .into_iter()
+        .filter_map(|entry| Some((normalize_uuid(&entry.uuid).ok()?, entry.name)))
+        .collect()
+}
+
+fn summary(
+    uuid: &str,
+    path: &Path,
+    root: Option<&Compound>,
+    names: &HashMap<String, String>,
+) -> PlayerDataSummary {
+    let bukkit = root.and_then(|r| r.get_compound("bukkit"));
+    let paper = root.and_then(|r| r.get_compound("Paper"));
+    PlayerDataSummary {
+        uuid: uuid.to_string(),
+        name: names
+            .get(uuid)
+            .cloned()
+            .or_else(|| bukkit?.get_str("lastKnownName").map(str::to_string)),
+        last_login: paper
+            .and_then(|p| p.get_i64("LastLogin"))
+            .or_else(|| bukkit?.get_i64("lastPlayed")),
+        modified_at: std::fs::metadata(path)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+    }
+}
+
+fn player_data(
+    uuid: &str,
+    path: &Path,
+    root: &Compound,
+    names: &HashMap<String, String>,
+) -> PlayerData {
+    let summary = summary(uuid, path, Some(root), names);
+    let int = |key: &str| root.get_i64(key).map(|v| v as i32);
+    let float = |key: &str| root.get(key).and_then(Tag::as_f64).map(|v| v as f32);
+
+    let position = root.get("Pos").and_then(Tag::as_list).and_then(|pos| {
+        let coords: Vec<f64> = pos.iter().filter_map(Tag::as_f64).collect();
+        (coords.len() == 3).then(|| [coords[0], coords[1], coords[2]])
+    });
+
+    let mut inventory = items(root.get("Inventory"));
+    if let Some(equipment) = root.get_compound("equipment") {
+        for (key, slot) in EQUIPMENT_SLOTS {
+            if let Some(item) = equipment.get_compound(key).and_then(item_stack) {
+                inventory.push(ItemStack { slot: Some(slot), ..item });
+            }
+        }
+    }
+
+    PlayerData {
+        uuid: summary.uuid,
+        name: summary.name,
+        last_login: summary.last_login,
+        modified_at: summary.modified_at,
+        dimension: root.get("Dimension").and_then(dimension_name),
+        position,
+        health: float("Health"),
+        food_level: int("foodLevel"),
+        xp_level: int("XpLevel"),
+        xp_total: int("XpTotal"),
+        xp_progress: float("XpP"),
+        game_type: int("playerGameType"),
+        inventory,
+        ender_chest: items(root.get("EnderItems")),
+    }
+}
+
+/// 1.16 起维度为命名空间 ID，更早的版本为数字。
+fn dimension_name(tag: &Tag) -> Option<String> {
+    if let Some(name) = tag.as_str() {
+        return Some(name.to_string());
+    }
+    let name = match tag.as_i64()? {
+        -1 => "minecraft:the_nether",
+        0 => "minecraft:overworld",
+        1 => "minecraft:the_end",
+        _ => return None,
+    };
+    Some(name.to_string())
+}
+
+fn items(list: Option<&Tag>) -> Vec<ItemStack> {
+    list.and_then(Tag::as_list)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(Tag::as_compound)
+        .filter_map(item_stack)
+        .collect()
+}
+
+fn item_stack(item: &Compound) -> Option<ItemStack> {
+    Some(ItemStack {
+        slot: item.get_i64("Slot").map(|v| v as i8),
+        id: item.get_str("id")?.to_string(),
+        count: item
+            .get_i64("count")
+            .or_else(|| item.get_i64("Count"))
+            .unwrap_or(1) as i32,
+        data: item
+            .get("components")
+            .or_else(|| item.get("tag"))
+            .map(Tag::to_json),
+    })
+}
+
+fn remove_from_root(root: &mut Compound, removals: &[ItemRemoval]) -> usize {
+    let slots = |container: ItemContainer| -> HashSet<i8> {
+        removals
+            .iter()
+            .filter(|r| r.container == container)
+            .map(|r| r.slot)
+            .collect()
+    };
+    let inventory_slots = slots(ItemContainer::Inventory);
+    let ender_slots = slots(ItemContainer::EnderChest);
+
+    let mut removed = remove_slots(root.get_mut("Inventory"), &inventory_slots)
+        + remove_slots(root.get_mut("EnderItems"), &ender_slots);
+    if let Some(equipment) = root.get_compound_mut("equipment") {
+        for (key, slot) in EQUIPMENT_SLOTS {
+            if inventory_slots.contains(&slot) && equipment.remove(key).is_some() {
+                removed += 1;
+            }
+        }
+    }
+    removed
+}
+
+fn remove_slots(list: Option<&mut Tag>, slots: &HashSet<i8>) -> usize {
+    let Some(Tag::List(_, items)) = list else {
+        return 0;
+    };
+    let before = items.len();
+    items.retain(|item| {
+        let slot = item
+            .as_compound()
+            .and_then(|c| c.get_i64("Slot"))
+            .map(|v| v as i8);
+        !slot.is_some_and(|slot| slots.contains(&slot))
+    });
+    before - items.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::world::nbt::{Compression, TAG_COMPOUND};
+    use crate::utils::test_utils::temp_dir;
+
+    fn temp_server_dir() -> PathBuf {
+        let dir = temp_dir("playerdata");
+        std::fs::create_dir_all(dir.join("world/playerdata")).unwrap();
+        dir
+    }
+
+    fn legacy_item(slot: i8, id: &str, count: i8) -> Tag {
+        let mut display = Compound::new();
+        display.insert("Name", Tag::String("{\"text\":\"Loot\"}".to_string()));
+        let mut tag = Compound::new();
+        tag.insert("display", Tag::Compound(display));
+
+        let mut item = Compound::new();
+        item.insert("Slot", Tag::Byte(slot));
+        item.insert("id", Tag::String(id.to_string()));
+        item.insert("Count", Tag::Byte(count));
+        item.insert("tag", Tag::Compound(tag));
+        Tag::Compound(item)
+    }
+
+    fn modern_item(slot: Option<i8>, id: &str, count: i32) -> Compound {
+        let mut item = Compound::new();
+        if let Some(slot) = slot {
+            item.insert("Slot", Tag::Byte(slot));
+        }
+        item.insert("id", Tag::String(id.to_string()));
+        item.insert("count", Tag::Int(count));
+        item
+    }
+
+    fn legacy_player() -> Compound {
+        let mut bukkit = Compound::new();
+        bukkit.insert("lastKnownName", Tag::String("Griefer".to_string()));
+        bukkit.insert("lastPlayed", Tag::Long(1_700_000_000_000));
+
+        let mut root = Compound::new();
+        root.insert("Dimension", Tag::Int(-1));
+        root.insert(
+            "Pos",
+            Tag::list(vec![Tag::Double(1.5), Tag::Double(64.0), Tag::Double(-3.25)]),
+        );
+        root.insert("Health", Tag::Float(18.0));
+        root.insert("foodLevel", Tag::Int(20));
+        root.insert("XpLevel", Tag::Int(30));
+        root.insert("XpP", Tag::Float(0.5));
+        root.insert("playerGameType", Tag::Int(0));
+        root.insert(
+            "Inventory",
+            Tag::list(vec![
+                legacy_item(0, "minecraft:tnt", 64),
+                legacy_item(103, "minecraft:diamond_helmet", 1),
+            ]),
+        );
+        root.insert("EnderItems", Tag::list(vec![legacy_item(5, "minecraft:lava_bucket", 1)]));
+        root.insert("bukkit", Tag::Compound(bukkit));
+        root
+    }
+
+    fn modern_player() -> Compound {
+        let mut equipment = Compound::new();
+        equipment.insert("offhand", Tag::Compound(modern_item(None, "minecraft:shield", 1)));
+        let mut root = Compound::new();
+        root.insert("Dimension", Tag::String("minecraft:the_end".to_string()));
+        root.insert(
+            "Inventory",
+            Tag::list(vec![Tag::Compound(modern_item(Some(8), "minecraft:flint_and_steel", 1))]),
+        );
+        root.insert("EnderItems", Tag::List(TAG_COMPOUND, Vec::new()));
+        root.insert("equipment", Tag::Compound(equipment));
+        root
+    }
+
+    fn write_player(server_dir: &Path, uuid: &str, root: Compound) {
+        NbtDocument {
+            name: String::new(),
+            root,
+            compression: Compression::Gzip,
+        }
+        .write_file(&server_dir.join(format!("world/playerdata/{}.dat", uuid)))
+        .unwrap();
+    }
+
+    #[test]
+    fn reads_legacy_player_file() {
+        let root = legacy_player();
+        let data = player_data("u", Path::new("missing"), &root, &HashMap::new());
+        assert_eq!(data.name.as_deref(), Some("Griefer"));
+        assert_eq!(data.last_login, Some(1_700_000_000_000));
+        assert_eq!(data.dimension.as_deref(), Some("minecraft:the_nether"));
+        assert_eq!(data.position, Some([1.5, 64.0, -3.25]));
+        assert_eq!(data.health, Some(18.0));
+        assert_eq!(data.xp_level, Some(30));
+        assert_eq!(data.inventory.len(), 2);
+        assert_eq!(data.inventory[0].id, "minecraft:tnt");
+        assert_eq!(data.inventory[0].count, 64);
+        assert_eq!(
+            data.inventory[0].data.as_ref().unwrap()["display"]["Name"],
+            "{\"text\":\"Loot\"}"
+        );
+        assert_eq!(data.ender_chest[0].slot, Some(5));
+    }
+
+    #[test]
+    fn reads_modern_equipment_as_legacy_slots() {
+        let root = modern_player();
+        let data = player_data("u", Path::new("missing"), &root, &HashMap::new());
+        assert_eq!(data.dimension.as_deref(), Some("minecraft:the_end"));
+        assert_eq!(data.inventory.len(), 2);
+        assert_eq!(data.inventory[1].id, "minecraft:shield");
+        assert_eq!(data.inventory[1].slot, Some(-106));
+        assert!(data.ender_chest.is_empty());
+    }
+
+    #[test]
+    fn removes_items_from_all_containers() {
+        let mut root = legacy_player();
+        let removals = [
+            ItemRemoval {
+                container: ItemContainer::Inventory,
+                slot: 0,
+            },
+            ItemRemoval {
+                container: ItemContainer::EnderChest,
+                slot: 5,
+            },
+            ItemRemoval {
+                container: ItemContainer::EnderChest,
+                slot: 6,
+            },
+        ];
+        assert_eq!(remove_from_root(&mut root, &removals), 2);
+        let data = player_data("u", Path::new("missing"), &root, &HashMap::new());
+        assert_eq!(data.inventory.len(), 1);
+        assert_eq!(data.inventory[0].slot, Some(103));
+        assert!(data.ender_chest.is_empty());
+
+        let mut modern = modern_player();
+        let offhand = [ItemRemoval {
+            container: ItemContainer::Inventory,
+            slot: -106,
+        }];
+        assert_eq!(remove_from_root(&mut modern, &offhand), 1);
+        assert!(modern.get_compound("equipment").unwrap().is_empty());
+    }
+
+    #[test]
+    fn lists_players_with_usercache_names() {
+        let server_dir = temp_server_dir();
+        let griefer = "6b6a6e1c-7c47-4f63-9a3e-2d0d5c2f8a11";
+        let builder = "0f0e8d7c-1b2a-4c3d-8e9f-a0b1c2d3e4f5";
+        write_player(&server_dir, griefer, legacy_player());
+        write_player(&server_dir, builder, modern_player());
+        std::fs::write(server_dir.join("world/playerdata/notes.txt"), "x").unwrap();
+        std::fs::write(
+            server_dir.join("usercache.json"),
+            format!(r#"[{{"name":"Builder","uuid":"{}"}}]"#, builder.to_uppercase()),
+        )
+        .unwrap();
+
+        let players = list_player_data(&server_dir).unwrap();
+        assert_eq!(players.len(), 2);
+        let names: Vec<_> = players.iter().map(|p| p.name.clone().unwrap()).collect();
+        assert!(names.contains(&"Builder".to_string()));
+        assert!(names.contains(&"Griefer".to_string()));
+
+        assert!(get_player_data(&server_dir, "../level").is_err());
+        let data = get_player_data(&server_dir, &griefer.to_uppercase()).unwrap();
+        assert_eq!(data.uuid, griefer);
+
+        let _ = std::fs::remove_dir_all(&server_dir);
+    }
+}
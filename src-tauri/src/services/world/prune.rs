@@ -0,0 +1,298 @@
+//! 按条件删除区块以缩小世界体积。
+//!
+//! 判断依据来自 region 目录中的区块；删除时同坐标的 entities、poi 数据与外置 `.mcc`
+//! 一并删除，区域文件随后紧凑重写，区块全部删除时直接删除文件。
+//! 删除不可恢复，建议先 dry-run 查看报告并做好备份；是否允许操作由调用方判断（服务器必须已停止）。
+
+use std::path::Path;
+
+use super::layout::{self, DIMENSION_NAMES};
+use super::nbt::Compound;
+use super::region::{self, RegionFile};
+use crate::models::world::{PruneCriteria, PruneReport, RegionPruneEntry};
+
+/// 与 region 共用区块坐标、需要同步删除的数据目录
+const CHUNK_DATA_DIRS: [&str; 3] = ["region", "entities", "poi"];
+
+pub fn prune_world(
+    server_dir: &Path,
+    criteria: &PruneCriteria,
+    dry_run: bool,
+) -> Result<PruneReport, String> {
+    validate_criteria(criteria)?;
+    let world = super::level_name(server_dir);
+    if !super::world_dir(server_dir)?.is_dir() {
+        return Err(format!("世界目录不存在: {}", world));
+    }
+
+    // 出生点只影响半径条件；读取失败时按 (0, 0) 计算
+    let spawn = super::level::read_level_info(server_dir)
+        .map(|info| (info.spawn_x.unwrap_or(0) as i64, info.spawn_z.unwrap_or(0) as i64))
+        .unwrap_or((0, 0));
+
+    let mut report = PruneReport {
+        world: world.clone(),
+        dry_run,
+        regions_scanned: 0,
+        chunks_scanned: 0,
+        chunks_deleted: 0,
+        chunks_unreadable: 0,
+        bytes_reclaimed: 0,
+        regions: Vec::new(),
+    };
+
+    for dimension in DIMENSION_NAMES {
+        if !criteria.dimensions.is_empty() && !criteria.dimensions.iter().any(|d| d == dimension) {
+            continue;
+        }
+        let Some(dimension_dir) = layout::dimension_dir(server_dir, &world, dimension) else {
+            continue;
+        };
+        let center = dimension_center(dimension, spawn);
+        let Ok(entries) = std::fs::read_dir(dimension_dir.join("region")) else {
+            continue;
+        };
+        let mut region_paths: Vec<_> = entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|n| n.to_str())
+                    .and_then(region::parse_region_file_name)
+                    .is_some_and(|(x, z)| {
+                        criteria.regions.is_empty() || criteria.regions.contains(&[x, z])
+                    })
+            })
+            .collect();
+        region_paths.sort();
+
+        for path in region_paths {
+            let region = RegionFile::open(&path)?;
+            report.regions_scanned += 1;
+            let chunks = region.chunks();
+            report.chunks_scanned += chunks.len();
+
+            let mut remove = Vec::new();
+            for location in &chunks {
+                match should_delete(&region, location, criteria, center) {
+                    Some(true) => remove.push(location.index),
+                    Some(false) => {}
+                    None => report.chunks_unreadable += 1,
+                }
+            }
+            if remove.is_empty() {
+                continue;
+            }
+
+            let mut bytes_reclaimed = 0;
+            for data_dir in CHUNK_DATA_DIRS {
+                let file = dimension_dir
+                    .join(data_dir)
+                    .join(region::region_file_name(region.region_x, region.region_z));
+                if file.is_file() {
+                    bytes_reclaimed += remove_chunks(&file, &remove, dry_run)?;
+                }
+            }
+
+            report.chunks_deleted += remove.len();
+            report.bytes_reclaimed += bytes_reclaimed;
+            report.regions.push(RegionPruneEntry {
+                dimension: dimension.to_string(),
+                region: [region.region_x, region.region_z],
+                chunks_total: chunks.len(),
+                chunks_deleted: remove.len(),
+                bytes_reclaimed,
+            });
+        }
+    }
+
+    Ok(report)
+}
+
+fn validate_criteria(criteria: &PruneCriteria) -> Result<(), String> {
+    if criteria.max_inhabited_ticks.is_none()
+        && criteria.outside_radius.is_none()
+        && criteria.regions.is_empty()
+    {
+        return Err("请至少指定一个删除条件".to_string());
+    }
+    if criteria.max_inhabited_ticks.is_some_and(|ticks| ticks <= 0) {
+        return Err("InhabitedTime 阈值必须大于 0".to_string());
+    }
+    if let Some(unknown) = criteria
+        .dimensions
+        .iter()
+        .find(|d| !DIMENSION_NAMES.contains(&d.as_str()))
+    {
+        return Err(format!("未知的维度: {}", unknown));
+    }
+    Ok(())
+}
+
+/// 半径条件的圆心：下界按 1:8 换算出生点，末地以主岛 (0, 0) 为中心
+fn dimension_center(dimension: &str, spawn: (i64, i64)) -> (i64, i64) {
+    match dimension {
+        "the_nether" => (spawn.0.div_euclid(8), spawn.1.div_euclid(8)),
+        "the_end" => (0, 0),
+        _ => spawn,
+    }
+}
+
+/// 区块是否满足全部条件；需要读取区块但无法解析时返回 `None`（保留该区块）。
+fn should_delete(
+    region: &RegionFile,
+    location: &region::ChunkLocation,
+    criteria: &PruneCriteria,
+    center: (i64, i64),
+) -> Option<bool> {
+    if let Some(radius) = criteria.outside_radius {
+        let (x, z) = region.chunk_coords(location.index);
+        let dx = x as i64 * 16 + 8 - center.0;
+        let dz = z as i64 * 16 + 8 - center.1;
+        if dx * dx + dz * dz <= radius as i64 * radius as i64 {
+            return Some(false);
+        }
+    }
+    if let Some(max_ticks) = criteria.max_inhabited_ticks {
+        let chunk = region.read_chunk(location).ok()?;
+        return Some(inhabited_time(&chunk) < max_ticks);
+    }
+    Some(true)
+}
+
+/// 1.18 起 InhabitedTime 位于根标签，之前位于 `Level` 下；未记录时视为 0
+fn inhabited_time(chunk: &Compound) -> i64 {
+    chunk
+        .get_i64("InhabitedTime")
+        .or_else(|| {
+            chunk
+                .get_compound("Level")
+                .and_then(|level| level.get_i64("InhabitedTime"))
+        })
+        .unwrap_or(0)
+}
+
+/// 从区域文件中删除区块并返回释放的字节数；`dry_run` 时只计算不写入。
+fn remove_chunks(path: &Path, remove: &[usize], dry_run: bool) -> Result<u64, String> {
+    let region = RegionFile::open(path)?;
+    let external: Vec<_> = region
+        .chunks()
+        .iter()
+        .filter(|location| remove.contains(&location.index))
+        .filter_map(|location| region.external_chunk_path(location))
+        .filter(|path| path.is_file())
+        .collect();
+
+    let rewritten = region.without_chunks(remove);
+    let new_len = rewritten.as_ref().map_or(0, |data| data.len() as u64);
+    let mut reclaimed = region.file_len().saturating_sub(new_len);
+    for path in &external {
+        reclaimed += std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    }
+    if dry_run {
+        return Ok(reclaimed);
+    }
+
+    match rewritten {
+        Some(data) => region::write_region_file(path, &data)?,
+        None => std::fs::remove_file(path)
+            .map_err(|e| format!("删除 {} 失败: {}", path.display(), e))?,
+    }
+    for path in &external {
+        let _ = std::fs::remove_file(path);
+    }
+    Ok(reclaimed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::world::region::tests::{build_region, chunk_nbt};
+    use crate::utils::test_utils::temp_dir;
+    use std::path::PathBuf;
+
+    fn server_with_world(name: &str) -> PathBuf {
+        let server = temp_dir(name);
+        std::fs::create_dir_all(server.join("world/region")).unwrap();
+        std::fs::create_dir_all(server.join("world/entities")).unwrap();
+        // 区域 (0, 0)：序号 0 在出生点旁且有人停留，序号 31 与 1023 无人停留
+        std::fs::write(
+            server.join("world/region/r.0.0.mca"),
+            build_region(&[(0, chunk_nbt(72_000)), (31, chunk_nbt(0)), (1023, chunk_nbt(40))]),
+        )
+        .unwrap();
+        std::fs::write(
+            server.join("world/entities/r.0.0.mca"),
+            build_region(&[(0, chunk_nbt(0)), (31, chunk_nbt(0))]),
+        )
+        .unwrap();
+        std::fs::write(server.join("world/region/r.-1.0.mca"), build_region(&[(5, chunk_nbt(0))]))
+            .unwrap();
+        server
+    }
+
+    #[test]
+    fn dry_run_reports_without_modifying() {
+        let server = server_with_world("dry-run");
+        let before = std::fs::read(server.join("world/region/r.0.0.mca")).unwrap();
+        let criteria = PruneCriteria {
+            max_inhabited_ticks: Some(1200),
+            ..Default::default()
+        };
+
+        let report = prune_world(&server, &criteria, true).unwrap();
+        assert!(report.dry_run);
+        assert_eq!(report.regions_scanned, 2);
+        assert_eq!(report.chunks_scanned, 4);
+        assert_eq!(report.chunks_deleted, 3);
+        assert!(report.bytes_reclaimed > 0);
+        assert_eq!(std::fs::read(server.join("world/region/r.0.0.mca")).unwrap(), before);
+        assert!(server.join("world/region/r.-1.0.mca").is_file());
+
+        let _ = std::fs::remove_dir_all(&server);
+    }
+
+    #[test]
+    fn prunes_by_inhabited_time_and_radius() {
+        let server = server_with_world("apply");
+        let criteria = PruneCriteria {
+            max_inhabited_ticks: Some(1200),
+            outside_radius: Some(300),
+            regions: vec![[0, 0]],
+            dimensions: vec!["overworld".to_string()],
+        };
+
+        let report = prune_world(&server, &criteria, false).unwrap();
+        // 序号 31 的区块中心在 x=504，超出半径；序号 1023 同理；序号 0 有人停留
+        assert_eq!(report.chunks_deleted, 2);
+        assert_eq!(report.regions.len(), 1);
+
+        let region = RegionFile::open(&server.join("world/region/r.0.0.mca")).unwrap();
+        assert_eq!(region.chunks().iter().map(|c| c.index).collect::<Vec<_>>(), vec![0]);
+        let entities = RegionFile::open(&server.join("world/entities/r.0.0.mca")).unwrap();
+        assert_eq!(entities.chunks().len(), 1);
+        assert!(server.join("world/region/r.-1.0.mca").is_file());
+
+        let whole = PruneCriteria {
+            regions: vec![[-1, 0]],
+            ..Default::default()
+        };
+        prune_world(&server, &whole, false).unwrap();
+        assert!(!server.join("world/region/r.-1.0.mca").exists());
+
+        let _ = std::fs::remove_dir_all(&server);
+    }
+
+    #[test]
+    fn rejects_empty_or_invalid_criteria() {
+        let server = server_with_world("invalid");
+        assert!(prune_world(&server, &PruneCriteria::default(), true).is_err());
+        let criteria = PruneCriteria {
+            outside_radius: Some(100),
+            dimensions: vec!["aether".to_string()],
+            ..Default::default()
+        };
+        assert!(prune_world(&server, &criteria, true).is_err());
+        let _ = std::fs::remove_dir_all(&server);
+    }
+}
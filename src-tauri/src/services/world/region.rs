@@ -0,0 +1,281 @@
+//! Anvil 区域文件（`r.<x>.<z>.mca`）的读取与重写。
+//!
+//! 文件头为 1024 个 4 字节位置项（3 字节扇区偏移 + 1 字节扇区数）与 1024 个时间戳，
+//! 之后以 4KiB 扇区存放区块：4 字节长度、1 字节压缩类型、压缩后的 NBT。
+//! 压缩类型最高位为 1 时，区块数据存放在同目录的 `c.<x>.<z>.mcc` 中。
+//! region、entities、poi 三类目录使用相同的格式。
+
+use std::path::{Path, PathBuf};
+
+use super::nbt::{self, Compound, Compression};
+
+pub const SECTOR_BYTES: usize = 4096;
+pub const CHUNKS_PER_REGION: usize = 1024;
+const HEADER_BYTES: usize = SECTOR_BYTES * 2;
+const EXTERNAL_FLAG: u8 = 0x80;
+
+/// 区域文件中一个已存在区块的位置信息
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkLocation {
+    /// 区域内序号，`local_x + local_z * 32`
+    pub index: usize,
+    pub sector_offset: usize,
+    pub sector_count: usize,
+    pub timestamp: u32,
+}
+
+pub struct RegionFile {
+    path: PathBuf,
+    data: Vec<u8>,
+    pub region_x: i32,
+    pub region_z: i32,
+}
+
+impl RegionFile {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let (region_x, region_z) = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(parse_region_file_name)
+            .ok_or_else(|| format!("不是区域文件: {}", path.display()))?;
+        let data = std::fs::read(path)
+            .map_err(|e| format!("读取区域文件 {} 失败: {}", path.display(), e))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            data,
+            region_x,
+            region_z,
+        })
+    }
+
+    pub fn file_len(&self) -> u64 {
+        self.data.len() as u64
+    }
+
+    /// 所有存在的区块；位置超出文件范围的损坏项视为不存在（游戏也会重新生成这些区块）。
+    pub fn chunks(&self) -> Vec<ChunkLocation> {
+        if self.data.len() < HEADER_BYTES {
+            return Vec::new();
+        }
+        let total_sectors = self.data.len().div_ceil(SECTOR_BYTES);
+        (0..CHUNKS_PER_REGION)
+            .filter_map(|index| {
+                let entry = &self.data[index * 4..index * 4 + 4];
+                let sector_offset =
+                    ((entry[0] as usize) << 16) | ((entry[1] as usize) << 8) | entry[2] as usize;
+                let sector_count = entry[3] as usize;
+                if sector_offset < 2
+                    || sector_count == 0
+                    || sector_offset + sector_count > total_sectors
+                {
+                    return None;
+                }
+                let ts = &self.data[SECTOR_BYTES + index * 4..SECTOR_BYTES + index * 4 + 4];
+                Some(ChunkLocation {
+                    index,
+                    sector_offset,
+                    sector_count,
+                    timestamp: u32::from_be_bytes([ts[0], ts[1], ts[2], ts[3]]),
+                })
+            })
+            .collect()
+    }
+
+    /// 区块的世界区块坐标
+    pub fn chunk_coords(&self, index: usize) -> (i32, i32) {
+        (
+            self.region_x * 32 + (index % 32) as i32,
+            self.region_z * 32 + (index / 32) as i32,
+        )
+    }
+
+    /// 外置区块数据文件 `c.<x>.<z>.mcc`；区块未外置时返回 `None`。
+    pub fn external_chunk_path(&self, location: &ChunkLocation) -> Option<PathBuf> {
+        let (compression, _) = self.chunk_payload(location).ok()?;
+        if compression & EXTERNAL_FLAG == 0 {
+            return None;
+        }
+        let (x, z) = self.chunk_coords(location.index);
+        Some(self.path.with_file_name(format!("c.{}.{}.mcc", x, z)))
+    }
+
+    /// 解码区块 NBT。LZ4 等不支持的压缩方式返回错误。
+    pub fn read_chunk(&self, location: &ChunkLocation) -> Result<Compound, String> {
+        let (compression, payload) = self.chunk_payload(location)?;
+        let external;
+        let payload = if compression & EXTERNAL_FLAG != 0 {
+            let path = self
+                .external_chunk_path(location)
+                .ok_or_else(|| "外置区块路径无效".to_string())?;
+            external = std::fs::read(&path)
+                .map_err(|e| format!("读取外置区块 {} 失败: {}", path.display(), e))?;
+            external.as_slice()
+        } else {
+            payload
+        };
+        let raw = match compression & !EXTERNAL_FLAG {
+            1 => nbt::decompress(payload, Compression::Gzip)?,
+            2 => nbt::decompress(payload, Compression::Zlib)?,
+            3 => payload.to_vec(),
+            other => return Err(format!("不支持的区块压缩方式: {}", other)),
+        };
+        Ok(nbt::decode(&raw)?.1)
+    }
+
+    fn chunk_payload(&self, location: &ChunkLocation) -> Result<(u8, &[u8]), String> {
+        let start = location.sector_offset * SECTOR_BYTES;
+        let header = self
+            .data
+            .get(start..start + 5)
+            .ok_or_else(|| "区块数据超出文件范围".to_string())?;
+        let length = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        if length == 0 {
+            return Err("区块长度为 0".to_string());
+        }
+        let payload = self
+            .data
+            .get(start + 5..start + 4 + length)
+            .ok_or_else(|| "区块数据超出文件范围".to_string())?;
+        Ok((header[4], payload))
+    }
+
+    /// 去掉 `remove` 中的区块并紧凑排列剩余区块，返回新文件内容；没有剩余区块时返回 `None`。
+    ///
+    /// 保留的区块数据按扇区原样复制，不重新压缩。
+    pub fn without_chunks(&self, remove: &[usize]) -> Option<Vec<u8>> {
+        let kept: Vec<_> = self
+            .chunks()
+            .into_iter()
+            .filter(|location| !remove.contains(&location.index))
+            .collect();
+        if kept.is_empty() {
+            return None;
+        }
+
+        let mut out = vec![0u8; HEADER_BYTES];
+        for location in kept {
+            let sector_offset = out.len() / SECTOR_BYTES;
+            let start = location.sector_offset * SECTOR_BYTES;
+            let end = (start + location.sector_count * SECTOR_BYTES).min(self.data.len());
+            out.extend_from_slice(&self.data[start..end]);
+            out.resize((sector_offset + location.sector_count) * SECTOR_BYTES, 0);
+
+            let entry = location.index * 4;
+            out[entry] = (sector_offset >> 16) as u8;
+            out[entry + 1] = (sector_offset >> 8) as u8;
+            out[entry + 2] = sector_offset as u8;
+            out[entry + 3] = location.sector_count as u8;
+            out[SECTOR_BYTES + entry..SECTOR_BYTES + entry + 4]
+                .copy_from_slice(&location.timestamp.to_be_bytes());
+        }
+        Some(out)
+    }
+}
+
+/// 解析 `r.<x>.<z>.mca` 中的区域坐标
+pub fn parse_region_file_name(name: &str) -> Option<(i32, i32)> {
+    let mut parts = name.strip_prefix("r.")?.strip_suffix(".mca")?.split('.');
+    let x = parts.next()?.parse().ok()?;
+    let z = parts.next()?.parse().ok()?;
+    parts.next().is_none().then_some((x, z))
+}
+
+pub fn region_file_name(x: i32, z: i32) -> String {
+    format!("r.{}.{}.mca", x, z)
+}
+
+/// 原子地写入区域文件
+pub fn write_region_file(path: &Path, data: &[u8]) -> Result<(), String> {
+    let tmp = path.with_extension("mca.sealantern.tmp");
+    std::fs::write(&tmp, data).map_err(|e| format!("写入 {} 失败: {}", tmp.display(), e))?;
+    std::fs::rename(&tmp, path).map_err(|e| {
+        let _ = std::fs::remove_file(&tmp);
+        format!("替换 {} 失败: {}", path.display(), e)
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::services::world::nbt::Tag;
+    use crate::utils::test_utils::temp_dir;
+
+    /// 构造测试用区域文件：`chunks` 为 (区域内序号, 区块 NBT)
+    pub(crate) fn build_region(chunks: &[(usize, Compound)]) -> Vec<u8> {
+        let mut out = vec![0u8; HEADER_BYTES];
+        for (index, root) in chunks {
+            let raw = nbt::encode("", root).unwrap();
+            let payload = nbt::compress(&raw, Compression::Zlib).unwrap();
+            let sector_offset = out.len() / SECTOR_BYTES;
+            out.extend_from_slice(&((payload.len() + 1) as u32).to_be_bytes());
+            out.push(2);
+            out.extend_from_slice(&payload);
+            let sectors = out.len().div_ceil(SECTOR_BYTES) - sector_offset;
+            out.resize((sector_offset + sectors) * SECTOR_BYTES, 0);
+            out[index * 4] = (sector_offset >> 16) as u8;
+            out[index * 4 + 1] = (sector_offset >> 8) as u8;
+            out[index * 4 + 2] = sector_offset as u8;
+            out[index * 4 + 3] = sectors as u8;
+            out[SECTOR_BYTES + index * 4 + 3] = 1;
+        }
+        out
+    }
+
+    pub(crate) fn chunk_nbt(inhabited: i64) -> Compound {
+        let mut root = Compound::new();
+        root.insert("DataVersion", Tag::Int(3700));
+        root.insert("InhabitedTime", Tag::Long(inhabited));
+        root.insert("Status", Tag::String("minecraft:full".to_string()));
+        root
+    }
+
+    #[test]
+    fn parses_region_file_names() {
+        assert_eq!(parse_region_file_name("r.-1.2.mca"), Some((-1, 2)));
+        assert_eq!(parse_region_file_name("r.0.0.mcr"), None);
+        assert_eq!(parse_region_file_name("r.0.0.1.mca"), None);
+        assert_eq!(region_file_name(3, -4), "r.3.-4.mca");
+    }
+
+    #[test]
+    fn reads_and_compacts_chunks() {
+        let dir = temp_dir("region");
+        let path = dir.join("r.-1.0.mca");
+        std::fs::write(
+            &path,
+            build_region(&[(0, chunk_nbt(5)), (33, chunk_nbt(10)), (1023, chunk_nbt(20))]),
+        )
+        .unwrap();
+
+        let region = RegionFile::open(&path).unwrap();
+        let chunks = region.chunks();
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(region.chunk_coords(33), (-31, 1));
+        assert_eq!(
+            region
+                .read_chunk(&chunks[1])
+                .unwrap()
+                .get_i64("InhabitedTime"),
+            Some(10)
+        );
+
+        let compacted = region.without_chunks(&[0]).unwrap();
+        assert!((compacted.len() as u64) < region.file_len());
+        write_region_file(&path, &compacted).unwrap();
+        let region = RegionFile::open(&path).unwrap();
+        let chunks = region.chunks();
+        assert_eq!(chunks.iter().map(|c| c.index).collect::<Vec<_>>(), vec![33, 1023]);
+        assert_eq!(chunks[0].sector_offset, 2);
+        assert_eq!(chunks[0].timestamp, 1);
+        assert_eq!(
+            region
+                .read_chunk(&chunks[1])
+                .unwrap()
+                .get_i64("InhabitedTime"),
+            Some(20)
+        );
+        assert!(region.without_chunks(&[33, 1023]).is_none());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,440 @@
+//! 服务器目录中的世界管理：列出、上传、单人存档导入、重置与切换。
+//!
+//! 原版把三个维度都放在 `level-name` 目录下；Bukkit 系服务端会把下界与末地拆到
+//! `<level-name>_nether` / `<level-name>_the_end`，这两个目录在这里视为所属世界的一部分，
+//! 重置、上传时一并处理；单人存档导入时按服务器核心转换为对应布局（见 layout.rs）。
+//! 是否允许操作（服务器必须已停止）由调用方判断。
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::models::world::{WorldEntry, WorldLayout};
+use crate::services::server::config_history::ConfigOrigin;
+use crate::services::server::{config, installer};
+
+const DIMENSION_SUFFIXES: [&str; 2] = ["_nether", "_the_end"];
+/// 在上传的压缩包中查找 level.dat 的最大目录深度
+const MAX_ARCHIVE_SEARCH_DEPTH: usize = 3;
+
+pub fn list_worlds(server_dir: &Path) -> Result<Vec<WorldEntry>, String> {
+    let active = super::level_name(server_dir);
+    let mut names: Vec<String> = std::fs::read_dir(server_dir)
+        .map_err(|e| format!("读取服务器目录失败: {}", e))?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+        .filter(|name| name == &active || server_dir.join(name).join("level.dat").is_file())
+        .collect();
+    names.sort();
+
+    let mut worlds = Vec::new();
+    for name in &names {
+        let is_dimension = DIMENSION_SUFFIXES.iter().any(|suffix| {
+            name.strip_suffix(suffix)
+                .is_some_and(|base| names.iter().any(|n| n == base))
+        });
+        if !is_dimension {
+            worlds.push(world_entry(server_dir, name, name == &active));
+        }
+    }
+    Ok(worlds)
+}
+
+/// 删除世界（含 Bukkit 维度目录），下次启动时由服务端重新生成。
+///
+/// `seed` 不为空时写入 server.properties 的 `level-seed`，新世界将使用该种子。
+pub fn reset_world(server_dir: &Path, name: &str, seed: Option<&str>) -> Result<(), String> {
+    super::validate_path_segment(name, "世界名称")?;
+    let world_dir = server_dir.join(name);
+    if !world_dir.is_dir() {
+        return Err(format!("世界不存在: {}", name));
+    }
+
+    if let Some(seed) = seed.map(str::trim).filter(|s| !s.is_empty()) {
+        update_properties(server_dir, &[("level-seed", seed)])?;
+    }
+
+    for dir in std::iter::once(world_dir).chain(dimension_dirs(server_dir, name)) {
+        std::fs::remove_dir_all(&dir).map_err(|e| format!("删除 {} 失败: {}", dir.display(), e))?;
+    }
+    Ok(())
+}
+
+/// 将 `level-name` 切换为另一个已有的世界。
+pub fn switch_world(server_dir: &Path, name: &str) -> Result<(), String> {
+    super::validate_path_segment(name, "世界名称")?;
+    if !server_dir.join(name).join("level.dat").is_file() {
+        return Err(format!("世界不存在或缺少 level.dat: {}", name));
+    }
+    update_properties(server_dir, &[("level-name", name)])
+}
+
+/// 解压世界压缩包（zip / tar / tar.gz）到服务器目录。
+///
+/// 压缩包中最浅的含 level.dat 的目录视为世界根目录，其旁边的 `_nether` / `_the_end`
+/// 目录会一并导入。`name` 为空时使用该目录名（世界直接位于压缩包根部时使用压缩包文件名）。
+pub fn upload_world(
+    server_dir: &Path,
+    archive_path: &Path,
+    name: Option<&str>,
+) -> Result<WorldEntry, String> {
+    let staging =
+        server_dir.join(format!(".sealantern_world_upload-{}", uuid::Uuid::new_v4().simple()));
+    std::fs::create_dir_all(&staging).map_err(|e| format!("创建临时目录失败: {}", e))?;
+    let result = import_from_staging(server_dir, archive_path, &staging, name);
+    let _ = std::fs::remove_dir_all(&staging);
+    result
+}
+
+fn import_from_staging(
+    server_dir: &Path,
+    archive_path: &Path,
+    staging: &Path,
+    name: Option<&str>,
+) -> Result<WorldEntry, String> {
+    installer::extract_modpack_archive(archive_path, staging)?;
+    let root = find_world_root(staging).ok_or_else(|| "压缩包中没有找到 level.dat".to_string())?;
+
+    let source_name = if root == staging {
+        archive_stem(archive_path)
+    } else {
+        root.file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let target_name = name
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .map(str::to_string)
+        .unwrap_or(source_name.clone());
+    super::validate_path_segment(&target_name, "世界名称")?;
+
+    let mut moves = vec![(root.clone(), server_dir.join(&target_name))];
+    if root != staging {
+        if let Some(parent) = root.parent() {
+            for suffix in DIMENSION_SUFFIXES {
+                let dimension = parent.join(format!("{}{}", source_name, suffix));
+                if dimension.is_dir() {
+                    moves.push((dimension, server_dir.join(format!("{}{}", target_name, suffix))));
+                }
+            }
+        }
+    }
+    if let Some((_, existing)) = moves.iter().find(|(_, target)| target.exists()) {
+        return Err(format!("目标目录已存在: {}", existing.display()));
+    }
+
+    for (source, target) in &moves {
+        std::fs::rename(source, target).map_err(|e| format!("移动世界目录失败: {}", e))?;
+    }
+
+    let active = super::level_name(server_dir) == target_name;
+    Ok(world_entry(server_dir, &target_name, active))
+}
+
+/// 从单人存档目录（如 `.minecraft/saves/<world>`）复制世界，并转换为 `layout` 布局。
+///
+/// 单人存档总是原版布局；导入 Bukkit 系服务器时会把 `DIM-1` / `DIM1` 拆到
+/// `<name>_nether` / `<name>_the_end`。源目录中的 `session.lock` 与符号链接不会被复制。
+pub fn import_singleplayer_world(
+    server_dir: &Path,
+    save_dir: &Path,
+    name: Option<&str>,
+    layout: WorldLayout,
+) -> Result<WorldEntry, String> {
+    if !save_dir.join("level.dat").is_file() {
+        return Err(format!("不是有效的存档目录（缺少 level.dat）: {}", save_dir.display()));
+    }
+    let target_name = name
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .map(str::to_string)
+        .or_else(|| save_dir.file_name()?.to_str().map(str::to_string))
+        .ok_or_else(|| "无法确定世界名称".to_string())?;
+    super::validate_path_segment(&target_name, "世界名称")?;
+
+    let target = server_dir.join(&target_name);
+    if let Some(existing) = std::iter::once(target.clone())
+        .chain(
+            DIMENSION_SUFFIXES
+                .iter()
+                .map(|suffix| server_dir.join(format!("{}{}", target_name, suffix))),
+        )
+        .find(|path| path.exists())
+    {
+        return Err(format!("目标目录已存在: {}", existing.display()));
+    }
+
+    // 先复制到临时目录，避免复制失败时留下不完整的世界
+    let staging =
+        server_dir.join(format!(".sealantern_world_import-{}", uuid::Uuid::new_v4().simple()));
+    let copied = super::copy_dir(save_dir, &staging, &["session.lock"])
+        .map_err(|e| format!("复制存档失败: {}", e))
+        .and_then(|_| {
+            std::fs::rename(&staging, &target).map_err(|e| format!("移动世界目录失败: {}", e))
+        });
+    if let Err(e) = copied {
+        let _ = std::fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+
+    super::layout::convert_layout(server_dir, &target_name, layout)?;
+    let active = super::level_name(server_dir) == target_name;
+    Ok(world_entry(server_dir, &target_name, active))
+}
+
+/// 广度优先查找最浅的含 level.dat 的目录。
+fn find_world_root(dir: &Path) -> Option<PathBuf> {
+    let mut level = vec![dir.to_path_buf()];
+    for _ in 0..=MAX_ARCHIVE_SEARCH_DEPTH {
+        if let Some(found) = level.iter().find(|d| d.join("level.dat").is_file()) {
+            return Some(found.clone());
+        }
+        let mut next: Vec<PathBuf> = level
+            .iter()
+            .filter_map(|d| std::fs::read_dir(d).ok())
+            .flat_map(|entries| entries.filter_map(|e| e.ok()))
+            .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+            .map(|e| e.path())
+            .collect();
+        // 同一层有多个候选时按名称排序，保证结果稳定（world 优先于 world_nether）
+        next.sort();
+        level = next;
+    }
+    None
+}
+
+fn archive_stem(archive_path: &Path) -> String {
+    let file_name = archive_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("world");
+    let lower = file_name.to_ascii_lowercase();
+    let stem_len = [".tar.gz", ".tgz", ".tar", ".zip"]
+        .iter()
+        .find(|ext| lower.ends_with(*ext))
+        .map(|ext| file_name.len() - ext.len())
+        .unwrap_or(file_name.len());
+    file_name[..stem_len].to_string()
+}
+
+fn dimension_dirs(server_dir: &Path, name: &str) -> Vec<PathBuf> {
+    DIMENSION_SUFFIXES
+        .iter()
+        .map(|suffix| server_dir.join(format!("{}{}", name, suffix)))
+        .filter(|dir| dir.is_dir())
+        .collect()
+}
+
+fn update_properties(server_dir: &Path, values: &[(&str, &str)]) -> Result<(), String> {
+    let path = server_dir.join("server.properties");
+    let path = path
+        .to_str()
+        .ok_or_else(|| "server.properties 路径无效".to_string())?;
+    let values: HashMap<String, String> = values
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    config::write_properties(path, &values, &ConfigOrigin::System)
+}
+
+fn world_entry(server_dir: &Path, name: &str, active: bool) -> WorldEntry {
+    let dims = dimension_dirs(server_dir, name);
+    let (mut size_bytes, mut last_modified) = (0, 0);
+    for dir in std::iter::once(server_dir.join(name)).chain(dims.iter().cloned()) {
+        let (size, modified) = dir_stats(&dir);
+        size_bytes += size;
+        last_modified = last_modified.max(modified);
+    }
+    let info = super::level::read_level_dat(&server_dir.join(name).join("level.dat"), name).ok();
+
+    WorldEntry {
+        name: name.to_string(),
+        active,
+        dimension_dirs: dims
+            .iter()
+            .filter_map(|d| d.file_name()?.to_str().map(str::to_string))
+            .collect(),
+        size_bytes,
+        last_modified,
+        seed: info.as_ref().and_then(|i| i.seed.clone()),
+        version_name: info.and_then(|i| i.version_name),
+    }
+}
+
+/// 目录总大小与最后修改时间（秒），不跟随符号链接。
+fn dir_stats(dir: &Path) -> (u64, u64) {
+    let mut size = 0;
+    let mut modified = 0;
+    let mut stack = vec![dir.to_path_buf()];
+    while let Some(current) = stack.pop() {
+        let Ok(entries) = std::fs::read_dir(&current) else {
+            continue;
+        };
+        for entry in entries.filter_map(|e| e.ok()) {
+            let Ok(metadata) = std::fs::symlink_metadata(entry.path()) else {
+                continue;
+            };
+            if metadata.is_dir() {
+                stack.push(entry.path());
+            } else if metadata.is_file() {
+                size += metadata.len();
+                let secs = metadata
+                    .modified()
+                    .ok()
+                    .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0);
+                modified = modified.max(secs);
+            }
+        }
+    }
+    (size, modified)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::world::nbt::{Compound, Compression, NbtDocument, Tag};
+    use crate::utils::test_utils::temp_dir;
+    use std::io::Write;
+
+    fn write_level(dir: &Path, seed: i64) {
+        std::fs::create_dir_all(dir).unwrap();
+        let mut data = Compound::new();
+        data.insert("RandomSeed", Tag::Long(seed));
+        let mut root = Compound::new();
+        root.insert("Data", Tag::Compound(data));
+        NbtDocument {
+            name: String::new(),
+            root,
+            compression: Compression::Gzip,
+        }
+        .write_file(&dir.join("level.dat"))
+        .unwrap();
+    }
+
+    #[test]
+    fn lists_worlds_with_bukkit_dimensions() {
+        let server = temp_dir("list");
+        std::fs::write(server.join("server.properties"), "level-name=survival\n").unwrap();
+        write_level(&server.join("survival"), 7);
+        write_level(&server.join("survival_nether"), 7);
+        std::fs::write(server.join("survival_nether/extra.bin"), [0u8; 100]).unwrap();
+        write_level(&server.join("creative"), 8);
+        std::fs::create_dir_all(server.join("plugins")).unwrap();
+
+        let worlds = list_worlds(&server).unwrap();
+        let names: Vec<_> = worlds.iter().map(|w| w.name.as_str()).collect();
+        assert_eq!(names, vec!["creative", "survival"]);
+        let survival = &worlds[1];
+        assert!(survival.active);
+        assert_eq!(survival.dimension_dirs, vec!["survival_nether"]);
+        assert_eq!(survival.seed.as_deref(), Some("7"));
+        assert!(survival.size_bytes >= 100);
+        assert!(!worlds[0].active);
+
+        let _ = std::fs::remove_dir_all(&server);
+    }
+
+    #[test]
+    fn switches_and_resets_worlds() {
+        let server = temp_dir("switch");
+        std::fs::write(server.join("server.properties"), "level-name=world\nlevel-seed=\n")
+            .unwrap();
+        write_level(&server.join("world"), 1);
+        write_level(&server.join("world_the_end"), 1);
+        write_level(&server.join("lobby"), 2);
+
+        assert!(switch_world(&server, "missing").is_err());
+        assert!(switch_world(&server, "../lobby").is_err());
+        switch_world(&server, "lobby").unwrap();
+        assert_eq!(crate::services::world::level_name(&server), "lobby");
+
+        reset_world(&server, "world", Some("12345")).unwrap();
+        assert!(!server.join("world").exists());
+        assert!(!server.join("world_the_end").exists());
+        let props = std::fs::read_to_string(server.join("server.properties")).unwrap();
+        assert!(props.contains("level-seed=12345"));
+        assert!(reset_world(&server, "world", None).is_err());
+
+        let _ = std::fs::remove_dir_all(&server);
+    }
+
+    #[test]
+    fn uploads_nested_world_archive_with_dimensions() {
+        let server = temp_dir("upload");
+        let archive_path = server.join("My Map.zip");
+        {
+            let file = std::fs::File::create(&archive_path).unwrap();
+            let mut zip = zip::ZipWriter::new(file);
+            let options = zip::write::SimpleFileOptions::default();
+            let level = {
+                let doc = NbtDocument {
+                    name: String::new(),
+                    root: Compound::new(),
+                    compression: Compression::Gzip,
+                };
+                doc.to_bytes().unwrap()
+            };
+            for path in ["pack/map/level.dat", "pack/map_nether/level.dat"] {
+                zip.start_file(path, options).unwrap();
+                zip.write_all(&level).unwrap();
+            }
+            zip.start_file("pack/map/region/r.0.0.mca", options)
+                .unwrap();
+            zip.write_all(b"region").unwrap();
+            zip.finish().unwrap();
+        }
+
+        let entry = upload_world(&server, &archive_path, Some("imported")).unwrap();
+        assert_eq!(entry.name, "imported");
+        assert_eq!(entry.dimension_dirs, vec!["imported_nether"]);
+        assert!(server.join("imported/region/r.0.0.mca").is_file());
+        assert!(upload_world(&server, &archive_path, Some("imported")).is_err());
+
+        let leftovers = std::fs::read_dir(&server)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().starts_with(".sealantern"))
+            .count();
+        assert_eq!(leftovers, 0);
+        assert_eq!(archive_stem(Path::new("/tmp/My Map.tar.gz")), "My Map");
+
+        let _ = std::fs::remove_dir_all(&server);
+    }
+
+    #[test]
+    fn imports_singleplayer_save_into_bukkit_layout() {
+        let server = temp_dir("import");
+        let save = server.join("saves/Survival Island");
+        write_level(&save, 42);
+        std::fs::write(save.join("session.lock"), b"lock").unwrap();
+        std::fs::create_dir_all(save.join("DIM-1/region")).unwrap();
+        std::fs::write(save.join("DIM-1/region/r.0.0.mca"), b"nether").unwrap();
+
+        let entry =
+            import_singleplayer_world(&server, &save, Some("island"), WorldLayout::Bukkit).unwrap();
+        assert_eq!(entry.dimension_dirs, vec!["island_nether"]);
+        assert_eq!(entry.seed.as_deref(), Some("42"));
+        assert!(server
+            .join("island_nether/DIM-1/region/r.0.0.mca")
+            .is_file());
+        assert!(!server.join("island/DIM-1").exists());
+        assert!(!server.join("island/session.lock").exists());
+        assert!(save.join("DIM-1/region/r.0.0.mca").is_file());
+
+        let err = import_singleplayer_world(&server, &save, Some("island"), WorldLayout::Vanilla);
+        assert!(err.is_err());
+        assert!(import_singleplayer_world(
+            &server,
+            &server.join("saves"),
+            None,
+            WorldLayout::Vanilla
+        )
+        .is_err());
+
+        let _ = std::fs::remove_dir_all(&server);
+    }
+}
@@ -54,10 +54,141 @@ pub const JAVA_DOWNLOAD_RETRY_LIMIT: usize = 3;
 pub const LATEST_LOG_DB_FILE: &str = "latest_log.db";
 pub const LOG_BATCH_SIZE: usize = 128;
 pub const LOG_FLUSH_INTERVAL_MS: u64 = 50;
+pub const LOG_MIGRATION_CHUNK: usize = 5000;
+
+/// services/server/log_retention.rs
+pub const LOG_ARCHIVE_DIR: &str = "log_archive";
+pub const LOG_RETENTION_INITIAL_DELAY_SECS: u64 = 60;
+pub const LOG_RETENTION_CHECK_INTERVAL_SECS: u64 = 600;
+pub const LOG_RETENTION_DELETE_CHUNK: usize = 5000;
+pub const LOG_RETENTION_SIZE_TARGET_PERCENT: u64 = 80;
+pub const LOG_VACUUM_FREELIST_PERCENT: u64 = 25;
+
+/// services/server/log_alerts.rs
+pub const ALERT_RULES_FILE: &str = "sea_lantern_alert_rules.json";
+pub const ALERT_HISTORY_FILE: &str = "sea_lantern_alert_history.json";
+pub const ALERT_HISTORY_LIMIT: usize = 500;
+pub const ALERT_WEBHOOK_TIMEOUT_SECS: u64 = 10;
+pub const ALERT_MIN_COOLDOWN_SECS: u64 = 30;
+pub const ALERT_ACTION_QUEUE_CAPACITY: usize = 64;
+pub const ALERT_TEST_MAX_LINES: usize = 200_000;
+pub const ALERT_TEST_MAX_FIRINGS: usize = 200;
+
+/// services/server/crash_analyzer.rs
+pub const CRASH_DIAGNOSES_DIR_NAME: &str = "crash_diagnoses";
+pub const CRASH_DIAGNOSES_KEEP: usize = 20;
+pub const CRASH_LOG_TAIL_LINES: usize = 500;
+pub const CRASH_STACK_MAX_LINES: usize = 40;
+/// 服务器没有启动时间记录时，只分析这段时间内修改过的崩溃报告
+pub const CRASH_FILE_MAX_AGE_SECS: u64 = 600;
+/// 这些包属于 JDK、游戏本体或服务端核心，不参与模组 / 插件归因
+pub const CRASH_IGNORED_PACKAGES: &[&str] = &[
+    "java.",
+    "javax.",
+    "jdk.",
+    "sun.",
+    "com.sun.",
+    "net.minecraft.",
+    "com.mojang.",
+    "net.minecraftforge.",
+    "net.neoforged.",
+    "cpw.mods.",
+    "net.fabricmc.",
+    "org.quiltmc.",
+    "org.bukkit.",
+    "org.spigotmc.",
+    "io.papermc.",
+    "com.destroystokyo.paper.",
+    "com.velocitypowered.",
+    "net.md_5.bungee.",
+];
+
+/// services/server/log_search.rs
+pub const LOG_SEARCH_DEFAULT_LIMIT: usize = 100;
+pub const LOG_SEARCH_MAX_LIMIT: usize = 1000;
+pub const LOG_SEARCH_MAX_CONTEXT: usize = 20;
 
 /// services/server/manager.rs
 pub const DATA_FILE: &str = "sea_lantern_servers.json";
 pub const RUN_PATH_MAP_FILE: &str = "sea_lantern_run_path_map.json";
+pub const STOP_DEFAULT_TIMEOUT_SECS: u64 = 10;
+pub const STOP_SAVE_TIMEOUT_SECS: u64 = 30;
+
+/// services/server/scheduler.rs
+pub const SCHEDULED_JOBS_FILE: &str = "sea_lantern_scheduled_jobs.json";
+pub const SCHEDULER_TICK_INTERVAL_MS: u64 = 1000;
+pub const SCHEDULER_MIN_INTERVAL_SECS: u64 = 10;
+
+/// services/server/player_sessions.rs
+pub const PLAYER_SESSIONS_DB_FILE: &str = "player_sessions.db";
+
+/// services/server/player.rs
+pub const MOJANG_PROFILE_API: &str = "https://api.mojang.com/users/profiles/minecraft";
+pub const MOJANG_PROFILE_TIMEOUT_SECS: u64 = 10;
+
+/// services/server/ping.rs
+pub const PING_CONNECT_TIMEOUT_MS: u64 = 1000;
+pub const PING_READ_TIMEOUT_MS: u64 = 3000;
+pub const PING_CACHE_SECS: u64 = 5;
+
+/// services/server/rcon.rs
+pub const RCON_CONNECT_TIMEOUT_MS: u64 = 3000;
+pub const RCON_READ_TIMEOUT_MS: u64 = 5000;
+
+/// services/server/config_history.rs
+pub const CONFIG_HISTORY_DIR_NAME: &str = "config_history";
+pub const CONFIG_HISTORY_KEEP: usize = 50;
+
+/// services/server/config_sync.rs
+pub const CONFIG_PRESETS_FILE: &str = "sea_lantern_config_presets.json";
+
+/// services/server/backup.rs
+pub const BACKUPS_DIR_NAME: &str = "backups";
+pub const BACKUP_INDEX_FILE: &str = "index.json";
+pub const BACKUP_RESTORE_STAGING_DIR: &str = ".sealantern_restore";
+pub const BACKUP_SAVE_TIMEOUT_SECS: u64 = 60;
+pub const BACKUP_SAFETY_KEEP: usize = 3;
+
+/// services/server/backup_store.rs
+pub const BACKUP_STORE_DIR_NAME: &str = "store";
+
+/// services/world/level.rs
+pub const LEVEL_BACKUPS_DIR_NAME: &str = "level_backups";
+pub const LEVEL_BACKUP_KEEP: usize = 10;
+
+/// services/world/playerdata.rs
+pub const PLAYERDATA_BACKUPS_DIR_NAME: &str = "playerdata_backups";
+pub const PLAYERDATA_BACKUP_KEEP: usize = 10;
+
+/// services/world/map.rs
+pub const MAP_TILES_DIR_NAME: &str = "map_tiles";
+pub const MAP_RENDER_STATE_FILE: &str = "render_state.json";
+
+/// services/world/datapacks.rs
+/// (1.x 的次版本号, 起始修订号, 数据包格式)，按版本升序；表中最后一个版本之后的版本视为未知
+pub const DATAPACK_FORMATS: [(u32, u32, u32); 19] = [
+    (13, 0, 4),
+    (15, 0, 5),
+    (16, 2, 6),
+    (17, 0, 7),
+    (18, 0, 8),
+    (18, 2, 9),
+    (19, 0, 10),
+    (19, 4, 12),
+    (20, 0, 15),
+    (20, 2, 18),
+    (20, 3, 26),
+    (20, 5, 41),
+    (21, 0, 48),
+    (21, 2, 57),
+    (21, 4, 61),
+    (21, 5, 71),
+    (21, 6, 80),
+    (21, 7, 81),
+    (21, 9, 88),
+];
+/// DATAPACK_FORMATS 覆盖到的最后一个版本 1.21.10
+pub const DATAPACK_FORMATS_LAST_KNOWN: (u32, u32) = (21, 10);
 
 /// services/server/installer.rs
 pub const STARTER_MC_VERSION_OPTIONS: [&str; 161] = [
@@ -3,3 +3,5 @@ pub mod constants;
 pub mod downloader;
 pub mod logger;
 pub mod path;
+#[cfg(test)]
+pub mod test_utils;
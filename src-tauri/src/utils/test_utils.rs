@@ -0,0 +1,14 @@
+//! 测试公共工具
+
+use std::path::PathBuf;
+
+/// 在系统临时目录下创建一个新的空目录，`name` 仅用于辨认是哪个测试留下的
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "sealantern_test_{}_{}",
+        name,
+        uuid::Uuid::new_v4().simple()
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
@@ -0,0 +1,146 @@
+import { tauriInvoke } from "@api/tauri";
+
+/**
+ * 告警匹配条件，多个条件同时满足才算命中
+ */
+export interface AlertCondition {
+  pattern?: string | null;
+  levels?: string[];
+  logger?: string | null;
+}
+
+/**
+ * 触发阈值：window_secs 秒内命中 count 次才触发
+ */
+export interface AlertThreshold {
+  count: number;
+  window_secs: number;
+}
+
+/**
+ * 告警动作
+ */
+export type AlertAction =
+  | { type: "notify" }
+  | { type: "webhook"; url: string }
+  | { type: "command"; command: string }
+  | { type: "mark_unhealthy" };
+
+/**
+ * 告警规则
+ */
+export interface AlertRule {
+  id: string;
+  server_id: string;
+  name: string;
+  enabled: boolean;
+  condition: AlertCondition;
+  threshold: AlertThreshold;
+  /** 两次触发之间的最短间隔（秒），至少为 30 */
+  cooldown_secs: number;
+  actions: AlertAction[];
+  /** 秒级时间戳 */
+  created_at: number;
+  /** 最近一次触发的秒级时间戳 */
+  last_fired_at: number | null;
+}
+
+/**
+ * 创建 / 更新 / 试运行告警规则时提交的字段
+ */
+export interface AlertRuleRequest {
+  server_id: string;
+  name: string;
+  enabled: boolean;
+  condition: AlertCondition;
+  threshold: AlertThreshold;
+  cooldown_secs: number;
+  actions: AlertAction[];
+}
+
+export interface AlertActionResult {
+  action: string;
+  success: boolean;
+  message: string;
+}
+
+/**
+ * 一次告警触发记录，fired_at 为毫秒时间戳
+ */
+export interface AlertFiring {
+  id: string;
+  rule_id: string;
+  rule_name: string;
+  server_id: string;
+  fired_at: number;
+  match_count: number;
+  line: string;
+  results: AlertActionResult[];
+}
+
+export interface AlertTestFiring {
+  fired_at: number;
+  match_count: number;
+  line_id: number;
+  line: string;
+}
+
+/**
+ * 用历史日志试运行规则的结果
+ */
+export interface AlertTestResult {
+  scanned_lines: number;
+  matched_lines: number;
+  firings: AlertTestFiring[];
+  truncated: boolean;
+}
+
+/**
+ * 日志告警 API
+ */
+export const alertApi = {
+  /**
+   * 获取告警规则列表，传入 serverId 时只返回该服务器的规则
+   */
+  async listRules(serverId?: string): Promise<AlertRule[]> {
+    return tauriInvoke("list_alert_rules", { serverId: serverId ?? null });
+  },
+
+  async createRule(rule: AlertRuleRequest): Promise<AlertRule> {
+    return tauriInvoke("create_alert_rule", { rule });
+  },
+
+  async updateRule(id: string, rule: AlertRuleRequest): Promise<AlertRule> {
+    return tauriInvoke("update_alert_rule", { id, rule });
+  },
+
+  async setRuleEnabled(id: string, enabled: boolean): Promise<AlertRule> {
+    return tauriInvoke("set_alert_rule_enabled", { id, enabled });
+  },
+
+  async deleteRule(id: string): Promise<void> {
+    return tauriInvoke("delete_alert_rule", { id });
+  },
+
+  /**
+   * 用历史日志试运行规则，只统计触发点，不执行动作；since 为毫秒时间戳
+   */
+  async testRule(rule: AlertRuleRequest, since?: number): Promise<AlertTestResult> {
+    return tauriInvoke("test_alert_rule", { rule, since: since ?? null });
+  },
+
+  async listFirings(serverId?: string, ruleId?: string, limit?: number): Promise<AlertFiring[]> {
+    return tauriInvoke("list_alert_firings", {
+      serverId: serverId ?? null,
+      ruleId: ruleId ?? null,
+      limit: limit ?? null,
+    });
+  },
+
+  /**
+   * 清除告警设置的不健康标记
+   */
+  async clearUnhealthy(serverId: string): Promise<void> {
+    return tauriInvoke("clear_server_unhealthy", { serverId });
+  },
+};
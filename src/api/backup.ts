@@ -0,0 +1,158 @@
+import { tauriInvoke } from "@api/tauri";
+
+export type BackupFormat = "zip" | "tar_gz";
+
+/**
+ * 备份来源：手动、定时任务、还原前自动生成的安全快照
+ */
+export type BackupKind = "manual" | "scheduled" | "safety";
+
+/**
+ * 创建备份选项
+ * full 为 false 时只备份世界目录；include / exclude 为相对服务器目录的 glob
+ */
+export interface BackupOptions {
+  format: BackupFormat;
+  full: boolean;
+  include: string[];
+  exclude: string[];
+  note: string | null;
+}
+
+export interface BackupEntry {
+  id: string;
+  server_id: string;
+  file_name: string;
+  format: BackupFormat;
+  kind: BackupKind;
+  full: boolean;
+  roots: string[];
+  size_bytes: number;
+  file_count: number;
+  created_at: number;
+  note: string | null;
+}
+
+/**
+ * 备份保留策略，全部为 0 时保留所有备份
+ */
+export interface BackupRetention {
+  keep_last: number;
+  keep_daily: number;
+  keep_weekly: number;
+}
+
+/**
+ * 去重增量快照选项，含义与 BackupOptions 相同（不区分归档格式）
+ */
+export interface SnapshotOptions {
+  full: boolean;
+  include: string[];
+  exclude: string[];
+  note: string | null;
+}
+
+export interface SnapshotSummary {
+  id: string;
+  server_id: string;
+  created_at: number;
+  full: boolean;
+  roots: string[];
+  file_count: number;
+  total_bytes: number;
+  new_bytes: number;
+  new_objects: number;
+  note: string | null;
+}
+
+export interface SnapshotVerifyReport {
+  snapshot_id: string;
+  ok: boolean;
+  checked_files: number;
+  missing: string[];
+  corrupted: string[];
+}
+
+export interface StoreGcReport {
+  removed_objects: number;
+  freed_bytes: number;
+  remaining_objects: number;
+}
+
+/**
+ * 世界备份 API
+ */
+export const backupApi = {
+  async listBackups(serverId: string): Promise<BackupEntry[]> {
+    return tauriInvoke("list_backups", { serverId });
+  },
+
+  /**
+   * 创建备份，服务器运行中时会自动执行 save-off / save-all / save-on
+   */
+  async createBackup(serverId: string, options?: Partial<BackupOptions>): Promise<BackupEntry> {
+    return tauriInvoke("create_backup", { serverId, options: options ?? null });
+  },
+
+  /**
+   * 还原备份（服务器必须已停止），返回还原前自动生成的安全快照
+   */
+  async restoreBackup(serverId: string, backupId: string): Promise<BackupEntry | null> {
+    return tauriInvoke("restore_backup", { serverId, backupId });
+  },
+
+  async deleteBackup(serverId: string, backupId: string): Promise<void> {
+    return tauriInvoke("delete_backup", { serverId, backupId });
+  },
+
+  async getRetention(serverId: string): Promise<BackupRetention> {
+    return tauriInvoke("get_backup_retention", { serverId });
+  },
+
+  /**
+   * 更新保留策略并立即清理，返回清理后的备份列表
+   */
+  async updateRetention(serverId: string, retention: BackupRetention): Promise<BackupEntry[]> {
+    return tauriInvoke("update_backup_retention", { serverId, retention });
+  },
+
+  async listSnapshots(serverId: string): Promise<SnapshotSummary[]> {
+    return tauriInvoke("list_snapshots", { serverId });
+  },
+
+  /**
+   * 创建去重增量快照，只存储内容发生变化的文件
+   */
+  async createSnapshot(
+    serverId: string,
+    options?: Partial<SnapshotOptions>,
+  ): Promise<SnapshotSummary> {
+    return tauriInvoke("create_snapshot", { serverId, options: options ?? null });
+  },
+
+  /**
+   * 删除快照清单，释放空间需再调用 gcStore
+   */
+  async deleteSnapshot(serverId: string, snapshotId: string): Promise<void> {
+    return tauriInvoke("delete_snapshot", { serverId, snapshotId });
+  },
+
+  async verifySnapshot(serverId: string, snapshotId: string): Promise<SnapshotVerifyReport> {
+    return tauriInvoke("verify_backup", { serverId, snapshotId });
+  },
+
+  async gcStore(serverId: string): Promise<StoreGcReport> {
+    return tauriInvoke("gc_backup_store", { serverId });
+  },
+
+  /**
+   * 将快照还原到指定目录（目录必须不存在或为空）
+   */
+  async restoreSnapshot(
+    serverId: string,
+    snapshotId: string,
+    targetDir: string,
+  ): Promise<SnapshotSummary> {
+    return tauriInvoke("restore_snapshot", { serverId, snapshotId, targetDir });
+  },
+};
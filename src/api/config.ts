@@ -10,6 +10,23 @@ export interface ConfigEntry {
   value_type: string;
   default_value: string;
   category: string;
+  /** 数值类型的取值范围 */
+  min: number | null;
+  max: number | null;
+  /** select 类型的可选值 */
+  options: string[];
+  /** 引入 / 移除该配置项的游戏版本 */
+  since: string | null;
+  removed_in: string | null;
+}
+
+/**
+ * server.properties 校验问题；error 会阻止写入，warning 仅提示
+ */
+export interface PropertyIssue {
+  key: string;
+  severity: "error" | "warning";
+  message: string;
 }
 
 /**
@@ -18,6 +35,76 @@ export interface ConfigEntry {
 export interface ServerProperties {
   entries: ConfigEntry[];
   raw: Record<string, string>;
+  issues: PropertyIssue[];
+}
+
+/**
+ * 配置写入结果；server.properties 存在错误时 written 为 false，文件保持不变
+ */
+export interface PropertyWriteResult {
+  written: boolean;
+  issues: PropertyIssue[];
+}
+
+/**
+ * 配置文件写入前保存的修订版本
+ */
+export interface ConfigRevision {
+  id: string;
+  /** 相对服务器目录的路径 */
+  file: string;
+  created_at: number;
+  /** 写入来源：ui、http、plugin:<插件 ID> 或 system */
+  origin: string;
+  size_bytes: number;
+}
+
+/**
+ * 修订版本与当前文件内容的差异
+ */
+export interface ConfigRevisionDiff {
+  revision: ConfigRevision;
+  added: number;
+  removed: number;
+  /** unified diff 文本，- 为修订版本中的行，+ 为当前文件中的行；无差异时为空 */
+  unified: string;
+}
+
+/**
+ * 多台服务器配置中的一个键；values 与 ConfigComparison.server_ids 一一对应，缺失为 null
+ */
+export interface ConfigComparisonEntry {
+  /** 配置文件相对路径，或表示服务器实例设置的 @instance */
+  section: string;
+  key: string;
+  values: (string | null)[];
+  differs: boolean;
+}
+
+export interface ConfigComparison {
+  server_ids: string[];
+  sections: string[];
+  entries: ConfigComparisonEntry[];
+}
+
+/**
+ * 单台服务器的同步结果；error 不为空时该服务器未做任何修改
+ */
+export interface ConfigSyncResult {
+  server_id: string;
+  applied: number;
+  issues: PropertyIssue[];
+  error: string | null;
+}
+
+/**
+ * 命名的配置预设：section 到键值的映射
+ */
+export interface ConfigPreset {
+  name: string;
+  description: string;
+  sections: Record<string, Record<string, string>>;
+  updated_at: number;
 }
 
 /**
@@ -34,9 +121,12 @@ export const configApi = {
   },
 
   /**
-   * 写入服务器配置文件
+   * 校验并写入服务器配置文件
    */
-  async writeServerProperties(serverPath: string, values: Record<string, string>): Promise<void> {
+  async writeServerProperties(
+    serverPath: string,
+    values: Record<string, string>,
+  ): Promise<PropertyWriteResult> {
     return tauriInvoke("write_server_properties", {
       serverPath,
       values,
@@ -53,9 +143,12 @@ export const configApi = {
   },
 
   /**
-   * 直接写入 server.properties 原始文本
+   * 校验并直接写入 server.properties 原始文本
    */
-  async writeServerPropertiesSource(serverPath: string, source: string): Promise<void> {
+  async writeServerPropertiesSource(
+    serverPath: string,
+    source: string,
+  ): Promise<PropertyWriteResult> {
     return tauriInvoke("write_server_properties_source", {
       serverPath,
       source,
@@ -63,11 +156,15 @@ export const configApi = {
   },
 
   /**
-   * 将原始文本解析为可视化配置结构
+   * 将原始文本解析为可视化配置结构；传入 serverPath 时按该服务器的版本校验
    */
-  async parseServerPropertiesSource(source: string): Promise<ServerProperties> {
+  async parseServerPropertiesSource(
+    source: string,
+    serverPath?: string,
+  ): Promise<ServerProperties> {
     return tauriInvoke("parse_server_properties_source", {
       source,
+      serverPath,
     });
   },
 
@@ -98,20 +195,92 @@ export const configApi = {
   },
 
   /**
-   * 读取通用配置文件
+   * 读取通用配置文件（.properties / .yml / .yaml / .toml）
+   *
+   * YAML / TOML 的嵌套键以点号路径表示，如 settings.debug；含点号的键用双引号包裹，
+   * 如 permissions."essentials.home"。数组以 JSON 文本表示。
    */
   async readConfig(serverPath: string, path: string): Promise<Record<string, string>> {
     return tauriInvoke("read_config", { serverPath, path });
   },
 
   /**
-   * 写入通用配置文件
+   * 按点号路径写入通用配置文件，保留注释与键顺序；已有键沿用原值类型
+   *
+   * 目标为服务器的 server.properties 时先校验，存在错误则不写入
    */
   async writeConfig(
     serverPath: string,
     path: string,
     values: Record<string, string>,
-  ): Promise<void> {
+  ): Promise<PropertyWriteResult> {
     return tauriInvoke("write_config", { serverPath, path, values });
   },
+
+  /**
+   * 预览通用配置文件写入后的最终文本
+   */
+  async previewConfigWrite(
+    serverPath: string,
+    path: string,
+    values: Record<string, string>,
+  ): Promise<string> {
+    return tauriInvoke("preview_config_write", { serverPath, path, values });
+  },
+
+  /**
+   * 按时间倒序列出配置修改历史，file 为相对服务器目录的路径
+   */
+  async listConfigHistory(serverId: string, file?: string): Promise<ConfigRevision[]> {
+    return tauriInvoke("list_config_history", { serverId, file });
+  },
+
+  async diffConfigRevision(serverId: string, revisionId: string): Promise<ConfigRevisionDiff> {
+    return tauriInvoke("diff_config_revision", { serverId, revisionId });
+  },
+
+  /**
+   * 恢复到修订版本的内容；恢复前的内容也会记入历史
+   */
+  async revertConfigRevision(serverId: string, revisionId: string): Promise<ConfigRevision> {
+    return tauriInvoke("revert_config_revision", { serverId, revisionId });
+  },
+
+  /**
+   * 对比多台服务器的配置；sections 留空时比较 server.properties 与实例设置（@instance）
+   */
+  async compareServerConfigs(serverIds: string[], sections?: string[]): Promise<ConfigComparison> {
+    return tauriInvoke("compare_server_configs", { serverIds, sections });
+  },
+
+  /**
+   * 把源服务器某个 section 中选定的键同步到其他服务器
+   */
+  async syncServerConfig(
+    sourceId: string,
+    targetIds: string[],
+    section: string,
+    keys: string[],
+  ): Promise<ConfigSyncResult[]> {
+    return tauriInvoke("sync_server_config", { sourceId, targetIds, section, keys });
+  },
+
+  async listConfigPresets(): Promise<ConfigPreset[]> {
+    return tauriInvoke("list_config_presets");
+  },
+
+  /**
+   * 新建或覆盖同名预设
+   */
+  async saveConfigPreset(preset: ConfigPreset): Promise<ConfigPreset> {
+    return tauriInvoke("save_config_preset", { preset });
+  },
+
+  async deleteConfigPreset(name: string): Promise<void> {
+    return tauriInvoke("delete_config_preset", { name });
+  },
+
+  async applyConfigPreset(name: string, serverIds: string[]): Promise<ConfigSyncResult[]> {
+    return tauriInvoke("apply_config_preset", { name, serverIds });
+  },
 };
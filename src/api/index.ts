@@ -7,16 +7,103 @@ export { tauriInvoke, tauriInvokeAll, createCachedInvoke } from "@api/tauri";
 export type { InvokeOptions } from "@api/tauri";
 
 export { serverApi } from "@api/server";
-export type { ServerStatusInfo } from "@api/server";
+export type {
+  ServerStatusInfo,
+  LogPage,
+  LogSearchQuery,
+  LogLine,
+  LogSearchMatch,
+  LogSearchPage,
+  LogRetentionPolicy,
+  LogStorageStatus,
+  CrashCause,
+  CrashSuspect,
+  CrashReport,
+} from "@api/server";
 
 export { javaApi } from "@api/java";
 export type { JavaInfo } from "@api/java";
 
 export { configApi } from "@api/config";
-export type { ConfigEntry, ServerProperties } from "@api/config";
+export type {
+  ConfigEntry,
+  ServerProperties,
+  PropertyIssue,
+  PropertyWriteResult,
+  ConfigRevision,
+  ConfigRevisionDiff,
+  ConfigComparison,
+  ConfigComparisonEntry,
+  ConfigSyncResult,
+  ConfigPreset,
+} from "@api/config";
 
 export { playerApi } from "@api/player";
-export type { PlayerEntry, BanEntry, OpEntry } from "@api/player";
+export type {
+  PlayerEntry,
+  BanEntry,
+  BannedIpEntry,
+  OpEntry,
+  OnlinePlayer,
+  PlayerSession,
+  PlayerDataSummary,
+  ItemStack,
+  PlayerData,
+  ItemContainer,
+  ItemRemoval,
+  ItemRemovalResult,
+} from "@api/player";
+
+export { backupApi } from "@api/backup";
+export type {
+  BackupFormat,
+  BackupKind,
+  BackupOptions,
+  BackupEntry,
+  BackupRetention,
+  SnapshotOptions,
+  SnapshotSummary,
+  SnapshotVerifyReport,
+  StoreGcReport,
+} from "@api/backup";
+
+export { worldApi } from "@api/world";
+export type {
+  LevelInfo,
+  LevelUpdate,
+  LevelUpdateResult,
+  WorldEntry,
+  WorldLayout,
+  LayoutConversion,
+  PruneCriteria,
+  RegionPruneEntry,
+  PruneReport,
+  WorldDimension,
+  MapRenderReport,
+  DatapackInfo,
+} from "@api/world";
+
+export { schedulerApi } from "@api/scheduler";
+export type {
+  JobSchedule,
+  JobAction,
+  JobRunResult,
+  ScheduledJob,
+  ScheduledJobRequest,
+} from "@api/scheduler";
+
+export { alertApi } from "@api/alert";
+export type {
+  AlertCondition,
+  AlertThreshold,
+  AlertAction,
+  AlertRule,
+  AlertRuleRequest,
+  AlertActionResult,
+  AlertFiring,
+  AlertTestFiring,
+  AlertTestResult,
+} from "@api/alert";
 
 export { settingsApi, getSystemFonts } from "@api/settings";
 export type { AppSettings } from "@api/settings";
@@ -20,6 +20,17 @@ export interface BanEntry {
   expires: string;
 }
 
+/**
+ * IP 封禁条目
+ */
+export interface BannedIpEntry {
+  ip: string;
+  reason: string;
+  source: string;
+  created: string;
+  expires: string;
+}
+
 /**
  * OP (管理员) 条目
  */
@@ -30,6 +41,79 @@ export interface OpEntry {
   bypasses_player_limit: boolean;
 }
 
+/**
+ * 在线玩家 (由服务器日志解析)
+ */
+export interface OnlinePlayer {
+  name: string;
+  uuid: string | null;
+  ip: string | null;
+  joined_at: number;
+}
+
+/**
+ * 玩家会话记录，left_at 为 null 表示仍在线
+ */
+export interface PlayerSession {
+  id: number;
+  player: string;
+  uuid: string | null;
+  ip: string | null;
+  joined_at: number;
+  left_at: number | null;
+  reason: string | null;
+}
+
+/**
+ * playerdata 目录中的玩家 (name 来自 usercache.json)
+ */
+export interface PlayerDataSummary {
+  uuid: string;
+  name: string | null;
+  /** 最后登录时间 (毫秒)，仅 Paper / Bukkit 服务端会记录 */
+  last_login: number | null;
+  /** 文件修改时间 (秒) */
+  modified_at: number;
+}
+
+/**
+ * 物品，slot：0-8 快捷栏，9-35 背包，100-103 护甲，-106 副手
+ */
+export interface ItemStack {
+  slot: number | null;
+  id: string;
+  count: number;
+  /** 1.20.5 起的 components，或更早版本的 tag */
+  data: Record<string, unknown> | null;
+}
+
+export interface PlayerData extends PlayerDataSummary {
+  dimension: string | null;
+  position: [number, number, number] | null;
+  health: number | null;
+  food_level: number | null;
+  xp_level: number | null;
+  xp_total: number | null;
+  xp_progress: number | null;
+  game_type: number | null;
+  inventory: ItemStack[];
+  ender_chest: ItemStack[];
+}
+
+export type ItemContainer = "inventory" | "ender_chest";
+
+export interface ItemRemoval {
+  container: ItemContainer;
+  slot: number;
+}
+
+export interface ItemRemovalResult {
+  data: PlayerData;
+  removed: number;
+  /** 修改前的玩家文件备份路径 */
+  backup_path: string;
+}
+
 /**
  * 玩家管理 API
  */
@@ -56,7 +140,54 @@ export const playerApi = {
   },
 
   /**
-   * 添加玩家到白名单 (向运行中的服务器发送命令)
+   * 获取当前在线玩家
+   */
+  async getOnlinePlayers(serverId: string): Promise<OnlinePlayer[]> {
+    return tauriInvoke("get_online_players", { serverId });
+  },
+
+  /**
+   * 获取玩家会话记录 (按加入时间倒序)，可按玩家名筛选
+   */
+  async getPlayerSessions(
+    serverId: string,
+    player?: string,
+    limit?: number,
+  ): Promise<PlayerSession[]> {
+    return tauriInvoke("get_player_sessions", {
+      serverId,
+      player: player ?? null,
+      limit: limit ?? null,
+    });
+  },
+
+  /**
+   * 列出有存档数据的玩家 (按最后登录时间倒序)
+   */
+  async listPlayerData(serverId: string): Promise<PlayerDataSummary[]> {
+    return tauriInvoke("list_player_data", { serverId });
+  },
+
+  /**
+   * 读取玩家存档：背包、末影箱、位置、生命值、经验等
+   */
+  async getPlayerData(serverId: string, uuid: string): Promise<PlayerData> {
+    return tauriInvoke("get_player_data", { serverId, uuid });
+  },
+
+  /**
+   * 删除玩家物品 (服务器必须已停止)，修改前会自动备份玩家文件
+   */
+  async removePlayerItems(
+    serverId: string,
+    uuid: string,
+    removals: ItemRemoval[],
+  ): Promise<ItemRemovalResult> {
+    return tauriInvoke("remove_player_items", { serverId, uuid, removals });
+  },
+
+  /**
+   * 添加玩家到白名单 (运行中时发送命令，已停止时直接修改 whitelist.json)
    */
   async addToWhitelist(serverId: string, name: string): Promise<string> {
     return tauriInvoke("add_to_whitelist", { serverId, name });
@@ -83,6 +214,27 @@ export const playerApi = {
     return tauriInvoke("unban_player", { serverId, name });
   },
 
+  /**
+   * 获取 IP 封禁列表
+   */
+  async getBannedIps(serverPath: string): Promise<BannedIpEntry[]> {
+    return tauriInvoke("get_banned_ips", { serverPath });
+  },
+
+  /**
+   * 封禁 IP
+   */
+  async banIp(serverId: string, ip: string, reason: string = ""): Promise<string> {
+    return tauriInvoke("ban_ip", { serverId, ip, reason });
+  },
+
+  /**
+   * 解封 IP
+   */
+  async unbanIp(serverId: string, ip: string): Promise<string> {
+    return tauriInvoke("unban_ip", { serverId, ip });
+  },
+
   /**
    * 添加 OP
    */
@@ -0,0 +1,88 @@
+import { tauriInvoke } from "@api/tauri";
+import type { BackupOptions } from "@api/backup";
+import type { StopCountdown } from "@type/server";
+
+/**
+ * 定时任务触发方式：cron 表达式（本地时区）或固定间隔（秒）
+ */
+export type JobSchedule =
+  | { type: "cron"; expression: string }
+  | { type: "interval"; seconds: number };
+
+/**
+ * 定时任务动作
+ */
+export type JobAction =
+  | { type: "start" }
+  | { type: "stop"; countdown?: StopCountdown | null }
+  | { type: "restart"; countdown?: StopCountdown | null }
+  | { type: "command"; command: string }
+  | { type: "backup"; options?: Partial<BackupOptions> };
+
+/**
+ * 单次执行结果
+ */
+export interface JobRunResult {
+  success: boolean;
+  message: string;
+  started_at: number;
+  finished_at: number;
+}
+
+/**
+ * 定时任务
+ */
+export interface ScheduledJob {
+  id: string;
+  server_id: string;
+  name: string;
+  enabled: boolean;
+  schedule: JobSchedule;
+  action: JobAction;
+  created_at: number;
+  last_run_at: number | null;
+  last_result: JobRunResult | null;
+  next_run_at: number | null;
+}
+
+/**
+ * 创建 / 更新定时任务时提交的字段
+ */
+export interface ScheduledJobRequest {
+  server_id: string;
+  name: string;
+  enabled: boolean;
+  schedule: JobSchedule;
+  action: JobAction;
+}
+
+/**
+ * 定时任务 API
+ */
+export const schedulerApi = {
+  /**
+   * 获取定时任务列表，传入 serverId 时只返回该服务器的任务
+   */
+  async listJobs(serverId?: string): Promise<ScheduledJob[]> {
+    return tauriInvoke("list_scheduled_jobs", { serverId: serverId ?? null });
+  },
+
+  async createJob(job: ScheduledJobRequest): Promise<ScheduledJob> {
+    return tauriInvoke("create_scheduled_job", { job });
+  },
+
+  async updateJob(id: string, job: ScheduledJobRequest): Promise<ScheduledJob> {
+    return tauriInvoke("update_scheduled_job", { id, job });
+  },
+
+  async deleteJob(id: string): Promise<void> {
+    return tauriInvoke("delete_scheduled_job", { id });
+  },
+
+  /**
+   * 立即执行一次任务，不影响原有的触发计划
+   */
+  async runJob(id: string): Promise<JobRunResult> {
+    return tauriInvoke("run_scheduled_job", { id });
+  },
+};
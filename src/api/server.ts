@@ -1,5 +1,5 @@
 import { tauriInvoke, isBrowserEnv, HTTP_API_BASE } from "@api/tauri";
-import type { ServerInstance } from "@type/server";
+import type { RestartPolicy, ServerInstance, StopCountdown } from "@type/server";
 import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 
 export interface ServerStatusInfo {
@@ -7,6 +7,28 @@ export interface ServerStatusInfo {
   status: "Stopped" | "Starting" | "Running" | "Stopping" | "Error";
   pid: number | null;
   uptime: number | null;
+  error_message?: string | null;
+  exit_code?: number | null;
+  restart_attempts?: number;
+  next_restart_at?: number | null;
+  ping?: ServerPingInfo | null;
+  /** 被告警规则标记为不健康时的原因 */
+  unhealthy_reason?: string | null;
+}
+
+/**
+ * Server List Ping 查询结果
+ */
+export interface ServerPingInfo {
+  online_players: number;
+  max_players: number;
+  sample: string[];
+  version: string;
+  protocol: number;
+  motd: string;
+  latency_ms: number;
+  favicon: string | null;
+  legacy: boolean;
 }
 
 export interface ParsedServerCoreInfo {
@@ -20,6 +42,109 @@ export interface ServerLogLineEvent {
   line: string;
 }
 
+/**
+ * 日志检索条件，字段均可省略
+ */
+export interface LogSearchQuery {
+  /** 关键词，不区分大小写的子串匹配；regex 为 true 时按正则表达式匹配 */
+  text?: string;
+  regex?: boolean;
+  /** 毫秒时间戳，闭区间 */
+  since?: number;
+  until?: number;
+  source?: "sealantern" | "server";
+  /** 如 WARN、ERROR */
+  levels?: string[];
+  /** logger 名称（Forge 类名、插件名等），不区分大小写 */
+  logger?: string;
+  /** 分页游标，取上一页的 next_before_id */
+  before_id?: number;
+  limit?: number;
+  /** 每条结果前后附带的上下文行数 */
+  context?: number;
+}
+
+export interface LogLine {
+  id: number;
+  timestamp: number;
+  source: "sealantern" | "server";
+  level: string | null;
+  thread: string | null;
+  logger: string | null;
+  /** 去掉时间、线程与级别前缀后的正文 */
+  message: string;
+  line: string;
+}
+
+export interface LogSearchMatch extends LogLine {
+  before: LogLine[];
+  after: LogLine[];
+}
+
+/** 增量读取的日志，next_since 为最后一行的 ID，没有新日志时等于传入的 since */
+export interface LogPage {
+  lines: string[];
+  next_since: number;
+}
+
+export interface LogSearchPage {
+  matches: LogSearchMatch[];
+  /** 还有更早的结果时为下一页的 before_id */
+  next_before_id: number | null;
+}
+
+/** 日志保留上限，0 表示不限制 */
+export interface LogRetentionPolicy {
+  max_age_days: number;
+  max_rows: number;
+  max_db_bytes: number;
+}
+
+export interface LogStorageStatus {
+  /** 数据库与 WAL 文件总大小（字节） */
+  db_size_bytes: number;
+  row_count: number;
+  /** 最旧 / 最新日志的毫秒时间戳，没有日志时为 null */
+  oldest_timestamp: number | null;
+  newest_timestamp: number | null;
+  archive_count: number;
+  archive_size_bytes: number;
+  policy: LogRetentionPolicy;
+}
+
+export type CrashCause = "out_of_memory" | "native_crash" | "java_exception" | "unknown";
+
+/** 疑似引起崩溃的模组 / 插件 */
+export interface CrashSuspect {
+  name: string;
+  /** 相对服务器目录的 jar 路径，仅出现在加载器报告中的模组为 null */
+  jar: string | null;
+  /** 异常堆栈中属于该 jar 的帧数 */
+  frame_count: number;
+  reported_by_loader: boolean;
+}
+
+/** 一次异常退出的诊断结果 */
+export interface CrashReport {
+  id: string;
+  server_id: string;
+  /** 秒级时间戳 */
+  created_at: number;
+  exit_code: number | null;
+  cause: CrashCause;
+  summary: string;
+  description: string | null;
+  exception: string | null;
+  exception_message: string | null;
+  /** JVM 层面的原因，如 Java heap space、SIGSEGV */
+  jvm_cause: string | null;
+  problematic_frame: string | null;
+  suspects: CrashSuspect[];
+  stack_trace: string[];
+  crash_report_file: string | null;
+  hs_err_file: string | null;
+}
+
 export interface ForceStopPreparation {
   token: string;
   expiresAt: number;
@@ -224,8 +349,15 @@ export const serverApi = {
     return tauriInvoke("start_server", { id });
   },
 
-  async stop(id: string): Promise<void> {
-    return tauriInvoke("stop_server", { id });
+  /**
+   * 停止服务器，传入 countdown 时先向玩家广播倒计时
+   */
+  async stop(id: string, countdown?: StopCountdown): Promise<void> {
+    return tauriInvoke("stop_server", { id, countdown: countdown ?? null });
+  },
+
+  async stopAll(countdown?: StopCountdown): Promise<void> {
+    return tauriInvoke("stop_all_servers", { countdown: countdown ?? null });
   },
 
   async prepareForceStop(id: string): Promise<ForceStopPreparation> {
@@ -236,7 +368,10 @@ export const serverApi = {
     return tauriInvoke("force_stop_server", { id, confirmationToken });
   },
 
-  async sendCommand(id: string, command: string): Promise<void> {
+  /**
+   * 发送控制台命令；服务器不是由 SeaLantern 启动时通过 RCON 执行并返回输出
+   */
+  async sendCommand(id: string, command: string): Promise<string | null> {
     return tauriInvoke("send_command", { id, command });
   },
 
@@ -248,14 +383,43 @@ export const serverApi = {
     return tauriInvoke("get_server_status", { id });
   },
 
+  async updateRestartPolicy(id: string, policy: RestartPolicy): Promise<ServerInstance> {
+    return tauriInvoke("update_restart_policy", { id, policy });
+  },
+
   async deleteServer(id: string): Promise<void> {
     return tauriInvoke("delete_server", { id });
   },
 
-  async getLogs(id: string, since: number, maxLines?: number): Promise<string[]> {
+  /**
+   * 读取日志行 ID 大于 since 的日志，since 为 0 时从头读取；maxLines 限制为其中最新的若干行。
+   * 返回的 next_since 作为下一次读取的 since
+   */
+  async getLogs(id: string, since: number, maxLines?: number): Promise<LogPage> {
     return tauriInvoke("get_server_logs", { id, since, maxLines });
   },
 
+  /**
+   * 检索日志，结果按时间倒序分页
+   */
+  async searchLogs(id: string, query: LogSearchQuery): Promise<LogSearchPage> {
+    return tauriInvoke("search_server_logs", { id, query });
+  },
+
+  /**
+   * 日志数据库大小、保留范围与归档情况
+   */
+  async getLogStorageStatus(id: string): Promise<LogStorageStatus> {
+    return tauriInvoke("get_log_storage_status", { id });
+  },
+
+  /**
+   * 异常退出后的崩溃诊断记录，最新的在前
+   */
+  async getCrashReports(id: string): Promise<CrashReport[]> {
+    return tauriInvoke("get_crash_reports", { id });
+  },
+
   onLogLine(callback: (payload: ServerLogLineEvent) => void): Promise<UnlistenFn> {
     // 浏览器环境使用 SSE
     if (isBrowserEnv()) {
@@ -22,6 +22,9 @@ export interface AppSettings {
   console_font_family: string;
   console_letter_spacing: number;
   max_log_lines: number;
+  log_retention_days: number;
+  log_retention_max_rows: number;
+  log_retention_max_db_mb: number;
   cached_java_list: JavaInfo[];
   background_image: string;
   background_opacity: number;
@@ -60,6 +63,9 @@ export interface PartialSettings {
   console_font_family?: string;
   console_letter_spacing?: number;
   max_log_lines?: number;
+  log_retention_days?: number;
+  log_retention_max_rows?: number;
+  log_retention_max_db_mb?: number;
   cached_java_list?: JavaInfo[];
   background_image?: string;
   background_opacity?: number;
@@ -1,5 +1,6 @@
 import { tauriInvoke } from "@api/tauri";
 import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type { StopCountdown } from "@type/server";
 
 export interface UpdateInfo {
   has_update: boolean;
@@ -40,8 +41,19 @@ export async function downloadUpdate(
   return tauriInvoke<string>("download_update", { url, expectedHash, version });
 }
 
-export async function installUpdate(filePath: string, version: string): Promise<void> {
-  return tauriInvoke<void>("install_update", { filePath, version });
+/**
+ * 安装更新；开启“更新前关闭服务器”时，传入 stopCountdown 会先向玩家广播倒计时
+ */
+export async function installUpdate(
+  filePath: string,
+  version: string,
+  stopCountdown?: StopCountdown,
+): Promise<void> {
+  return tauriInvoke<void>("install_update", {
+    filePath,
+    version,
+    stopCountdown: stopCountdown ?? null,
+  });
 }
 
 export async function checkPendingUpdate(): Promise<PendingUpdate | null> {
@@ -0,0 +1,267 @@
+import { tauriInvoke } from "@api/tauri";
+
+/**
+ * 从 level.dat 读取的世界信息，文件中不存在的字段为 null
+ */
+export interface LevelInfo {
+  world: string;
+  level_name: string | null;
+  /** 64 位种子，以字符串传递以免丢失精度 */
+  seed: string | null;
+  spawn_x: number | null;
+  spawn_y: number | null;
+  spawn_z: number | null;
+  /** 0 和平 / 1 简单 / 2 普通 / 3 困难 */
+  difficulty: number | null;
+  difficulty_locked: boolean;
+  hardcore: boolean;
+  /** 0 生存 / 1 创造 / 2 冒险 / 3 旁观 */
+  game_type: number | null;
+  game_rules: Record<string, string>;
+  enabled_datapacks: string[];
+  disabled_datapacks: string[];
+  last_played: number | null;
+  version_name: string | null;
+  data_version: number | null;
+}
+
+/**
+ * 修改 level.dat 的字段，未提供的字段保持不变；game_rules 只需包含要修改的规则
+ */
+export interface LevelUpdate {
+  level_name?: string;
+  seed?: string;
+  spawn_x?: number;
+  spawn_y?: number;
+  spawn_z?: number;
+  difficulty?: number;
+  difficulty_locked?: boolean;
+  hardcore?: boolean;
+  game_type?: number;
+  game_rules?: Record<string, string>;
+}
+
+export interface LevelUpdateResult {
+  info: LevelInfo;
+  backup_path: string;
+}
+
+/**
+ * 服务器目录中的世界；Bukkit 布局的下界 / 末地目录列在 dimension_dirs 中
+ */
+export interface WorldEntry {
+  name: string;
+  /** 是否为 server.properties 中 level-name 指向的世界 */
+  active: boolean;
+  dimension_dirs: string[];
+  size_bytes: number;
+  /** Unix 时间戳（秒） */
+  last_modified: number;
+  seed: string | null;
+  version_name: string | null;
+}
+
+/**
+ * 维度目录布局：vanilla 为 <world>/DIM-1、<world>/DIM1；
+ * bukkit 为 <world>_nether/DIM-1、<world>_the_end/DIM1
+ */
+export type WorldLayout = "vanilla" | "bukkit";
+
+export interface LayoutConversion {
+  world: string;
+  layout: WorldLayout;
+  /** 实际移动的目录，形如 "world/DIM-1 -> world_nether/DIM-1" */
+  moved: string[];
+}
+
+/**
+ * 区块删除条件：已设置的条件需同时满足，且至少设置一项
+ */
+export interface PruneCriteria {
+  /** 只删除 InhabitedTime（tick，20 tick = 1 秒）低于该值的区块 */
+  max_inhabited_ticks?: number;
+  /** 只删除距出生点超过该半径（方块）的区块；下界按 1:8 换算，末地以 (0, 0) 为中心 */
+  outside_radius?: number;
+  /** 只处理这些区域文件，[x, z] 为 r.<x>.<z>.mca 中的坐标 */
+  regions?: [number, number][];
+  /** 只处理这些维度（overworld / the_nether / the_end），为空时处理全部 */
+  dimensions?: string[];
+}
+
+export interface RegionPruneEntry {
+  dimension: string;
+  region: [number, number];
+  chunks_total: number;
+  chunks_deleted: number;
+  bytes_reclaimed: number;
+}
+
+export interface PruneReport {
+  world: string;
+  dry_run: boolean;
+  regions_scanned: number;
+  chunks_scanned: number;
+  chunks_deleted: number;
+  /** 无法解析而被保留的区块数 */
+  chunks_unreadable: number;
+  bytes_reclaimed: number;
+  regions: RegionPruneEntry[];
+}
+
+export type WorldDimension = "overworld" | "the_nether" | "the_end";
+
+export interface MapRenderReport {
+  world: string;
+  dimension: WorldDimension;
+  /** 本次重新渲染的瓦片数 */
+  rendered: number;
+  /** 区域文件未变化而跳过的瓦片数 */
+  unchanged: number;
+  /** 区域文件已不存在而删除的瓦片数 */
+  removed: number;
+  /** 渲染失败的区域文件名，下次渲染时会重试 */
+  failed: string[];
+  /** 所有瓦片的区域坐标 [x, z]，每张瓦片 512×512 像素，1 像素 = 1 方块 */
+  tiles: [number, number][];
+}
+
+export interface DatapackInfo {
+  /** 文件或目录名 */
+  name: string;
+  /** level.dat 与 /datapack 命令中使用的 ID，形如 file/<name> */
+  id: string;
+  is_archive: boolean;
+  description: string | null;
+  pack_format: number | null;
+  /** pack.mcmeta 声明的兼容格式范围 [min, max] */
+  supported_formats: [number, number] | null;
+  /** null 表示服务端尚未加载过该数据包 */
+  enabled: boolean | null;
+  size_bytes: number;
+  /** 数据包格式与服务器版本不匹配等提示 */
+  warning: string | null;
+}
+
+/**
+ * 世界存档 API
+ */
+export const worldApi = {
+  async getLevelInfo(serverId: string): Promise<LevelInfo> {
+    return tauriInvoke("get_level_info", { serverId });
+  },
+
+  /**
+   * 修改 level.dat（服务器必须已停止），修改前会自动备份原文件
+   */
+  async updateLevelInfo(serverId: string, update: LevelUpdate): Promise<LevelUpdateResult> {
+    return tauriInvoke("update_level_info", { serverId, update });
+  },
+
+  async listWorlds(serverId: string): Promise<WorldEntry[]> {
+    return tauriInvoke("list_worlds", { serverId });
+  },
+
+  /**
+   * 导入世界压缩包（zip / tar / tar.gz），name 为空时使用压缩包中的目录名
+   */
+  async uploadWorld(
+    serverId: string,
+    archivePath: string,
+    name?: string,
+    activate = false,
+  ): Promise<WorldEntry> {
+    return tauriInvoke("upload_world", { serverId, archivePath, name, activate });
+  },
+
+  /**
+   * 导入单人存档目录（如 .minecraft/saves/<world>），按服务器核心自动转换维度布局
+   */
+  async importSingleplayerWorld(
+    serverId: string,
+    savePath: string,
+    name?: string,
+    activate = false,
+  ): Promise<WorldEntry> {
+    return tauriInvoke("import_singleplayer_world", { serverId, savePath, name, activate });
+  },
+
+  async convertWorldLayout(
+    serverId: string,
+    name: string,
+    layout: WorldLayout,
+  ): Promise<LayoutConversion> {
+    return tauriInvoke("convert_world_layout", { serverId, name, layout });
+  },
+
+  /**
+   * 删除世界，下次启动时重新生成；提供 seed 时会写入 level-seed
+   */
+  async resetWorld(serverId: string, name: string, seed?: string): Promise<void> {
+    return tauriInvoke("reset_world", { serverId, name, seed });
+  },
+
+  async switchWorld(serverId: string, name: string): Promise<void> {
+    return tauriInvoke("switch_world", { serverId, name });
+  },
+
+  /**
+   * 按条件删除主世界中的区块（服务器必须已停止）；dryRun 为 true 时只返回报告，不修改文件
+   */
+  async pruneWorldChunks(
+    serverId: string,
+    criteria: PruneCriteria,
+    dryRun: boolean,
+  ): Promise<PruneReport> {
+    return tauriInvoke("prune_world_chunks", { serverId, criteria, dryRun });
+  },
+
+  /**
+   * 增量渲染俯视地图瓦片，force 为 true 时全部重绘；Docker 模式下瓦片同时通过
+   * GET /map/<serverId>/<dimension>/r.<x>.<z>.png 提供
+   */
+  async renderWorldMap(
+    serverId: string,
+    dimension: WorldDimension,
+    force = false,
+  ): Promise<MapRenderReport> {
+    return tauriInvoke("render_world_map", { serverId, dimension, force });
+  },
+
+  async listMapTiles(serverId: string, dimension: WorldDimension): Promise<[number, number][]> {
+    return tauriInvoke("list_map_tiles", { serverId, dimension });
+  },
+
+  /**
+   * 返回 data:image/png;base64,... 形式的瓦片
+   */
+  async getMapTile(
+    serverId: string,
+    dimension: WorldDimension,
+    x: number,
+    z: number,
+  ): Promise<string> {
+    return tauriInvoke("get_map_tile", { serverId, dimension, x, z });
+  },
+
+  async listDatapacks(serverId: string): Promise<DatapackInfo[]> {
+    return tauriInvoke("list_datapacks", { serverId });
+  },
+
+  /**
+   * 安装 zip 或目录形式的数据包；服务器运行时需 /reload 后生效
+   */
+  async installDatapack(serverId: string, sourcePath: string): Promise<DatapackInfo> {
+    return tauriInvoke("install_datapack", { serverId, sourcePath });
+  },
+
+  /**
+   * 服务器运行时下发 /datapack enable|disable，否则修改 level.dat
+   */
+  async setDatapackEnabled(serverId: string, name: string, enabled: boolean): Promise<void> {
+    return tauriInvoke("set_datapack_enabled", { serverId, name, enabled });
+  },
+
+  async removeDatapack(serverId: string, name: string): Promise<void> {
+    return tauriInvoke("remove_datapack", { serverId, name });
+  },
+};
@@ -54,6 +54,9 @@ const defaultSettings: AppSettings = {
   console_font_family: "",
   console_letter_spacing: 0,
   max_log_lines: 1000,
+  log_retention_days: 30,
+  log_retention_max_rows: 1000000,
+  log_retention_max_db_mb: 512,
   cached_java_list: [],
   background_image: "",
   background_opacity: 0.3,
@@ -1,3 +1,26 @@
+/**
+ * 崩溃自动重启策略
+ */
+export interface RestartPolicy {
+  enabled: boolean;
+  max_restarts: number;
+  window_secs: number;
+  backoff_initial_secs: number;
+  backoff_max_secs: number;
+  give_up_after: number;
+}
+
+/**
+ * 倒计时停服选项，`message` 中的 `{time}` 会替换为剩余时间
+ */
+export interface StopCountdown {
+  warnings?: number[];
+  message?: string;
+  use_tellraw?: boolean;
+  force_stop_after_secs?: number;
+  reason?: string | null;
+}
+
 /**
  * 服务器实例类型
  */
@@ -18,6 +41,7 @@ export interface ServerInstance {
   port: number;
   created_at: number;
   last_started_at: number | null;
+  restart_policy?: RestartPolicy;
 }
 
 /**
@@ -250,7 +250,10 @@ export function useConfigPropertiesEditor(options: UseConfigPropertiesEditorOpti
     sourceText: string,
     targetMode: "visual" | "source" = "visual",
   ) {
-    const parsed = await configApi.parseServerPropertiesSource(sourceText);
+    const parsed = await configApi.parseServerPropertiesSource(
+      sourceText,
+      options.serverPath.value,
+    );
     entries.value = parsed.entries as ConfigEntryType[];
     editValues.value = { ...parsed.raw };
     loadedValues.value = { ...parsed.raw };
@@ -465,7 +468,10 @@ export function useConfigPropertiesEditor(options: UseConfigPropertiesEditorOpti
         return;
       }
 
-      const parsed = await configApi.parseServerPropertiesSource(sourceDraftText.value);
+      const parsed = await configApi.parseServerPropertiesSource(
+        sourceDraftText.value,
+        options.serverPath.value,
+      );
       entries.value = parsed.entries as ConfigEntryType[];
       editValues.value = { ...parsed.raw };
       visualModeBaseValues.value = { ...parsed.raw };
@@ -494,11 +500,18 @@ export function useConfigPropertiesEditor(options: UseConfigPropertiesEditorOpti
     options.setSuccess(null);
 
     try {
-      await Promise.all(
+      const results = await Promise.all(
         pendingSaveItems.value.map((item) =>
           configApi.writeServerPropertiesSource(item.serverPath, item.modifiedText),
         ),
       );
+      const rejected = results.flatMap((result) =>
+        result.written ? [] : result.issues.filter((issue) => issue.severity === "error"),
+      );
+      if (rejected.length > 0) {
+        options.setError(rejected.map((issue) => `${issue.key}: ${issue.message}`).join("\n"));
+        return;
+      }
 
       const savedCurrent = pendingSaveItems.value.find(
         (item) => item.serverId === options.currentServerId.value,